@@ -0,0 +1,36 @@
+//! Benchmarks `find_references` walking the crate's own `src/` tree, to
+//! track the win from fanning `search_rust_files`'s reads/parses out across
+//! worker threads instead of walking them on a single thread.
+//!
+//! Requires `criterion` as a dev-dependency and a matching `[[bench]]` entry
+//! in Cargo.toml:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! criterion = "0.5"
+//!
+//! [[bench]]
+//! name = "search_rust_files"
+//! harness = false
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gemini_repl::tools::code_analysis::FindReferencesTool;
+use gemini_repl::tools::Tool;
+use serde_json::json;
+use std::path::PathBuf;
+
+fn bench_find_references(c: &mut Criterion) {
+    let workspace = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let tool = FindReferencesTool::new(workspace);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    c.bench_function("find_references across src/", |b| {
+        b.iter(|| {
+            runtime.block_on(tool.execute(json!({ "name": "execute" }))).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_find_references);
+criterion_main!(benches);
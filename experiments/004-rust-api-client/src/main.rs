@@ -2,19 +2,26 @@
 
 use anyhow::Result;
 use clap::Parser;
+use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::io::Write;
 
 #[derive(Parser)]
 struct Args {
     /// API key (or use GEMINI_API_KEY env var)
     #[arg(short, long, env = "GEMINI_API_KEY")]
     api_key: Option<String>,
-    
+
     /// Use proxy
     #[arg(short, long)]
     proxy: bool,
+
+    /// Stream the response token-by-token via `streamGenerateContent?alt=sse`
+    /// instead of blocking for the full, buffered response.
+    #[arg(long)]
+    stream: bool,
 }
 
 #[derive(Serialize)]
@@ -94,36 +101,93 @@ async fn main() -> Result<()> {
         }],
     };
     
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash:generateContent?key={}",
-        api_key
-    );
-    
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await?;
-    
-    let status = response.status();
-    println!("Status: {}", status);
-    
-    let response_text = response.text().await?;
-    let parsed: GenerateResponse = serde_json::from_str(&response_text)?;
-    
-    if let Some(error) = parsed.error {
-        println!("✗ API Error: {} ({})", error.message, error.code);
-    } else if let Some(candidates) = parsed.candidates {
-        if let Some(candidate) = candidates.first() {
-            let answer = &candidate.content.parts[0].text;
-            println!("✓ API Response: {}", answer.trim());
-            
-            if answer.contains("42") {
-                println!("✓ Correct answer received!");
+    if args.stream {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash:streamGenerateContent?alt=sse&key={}",
+            api_key
+        );
+        let answer = stream_generate_content(&client, &url, &request).await?;
+        println!();
+        if answer.contains("42") {
+            println!("✓ Correct answer received!");
+        }
+    } else {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash:generateContent?key={}",
+            api_key
+        );
+
+        let response = client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        println!("Status: {}", status);
+
+        let response_text = response.text().await?;
+        let parsed: GenerateResponse = serde_json::from_str(&response_text)?;
+
+        if let Some(error) = parsed.error {
+            println!("✗ API Error: {} ({})", error.message, error.code);
+        } else if let Some(candidates) = parsed.candidates {
+            if let Some(candidate) = candidates.first() {
+                let answer = &candidate.content.parts[0].text;
+                println!("✓ API Response: {}", answer.trim());
+
+                if answer.contains("42") {
+                    println!("✓ Correct answer received!");
+                }
             }
         }
     }
-    
+
     println!("\n=== Test Complete ===");
     Ok(())
+}
+
+/// Posts `request` to a `streamGenerateContent?alt=sse` endpoint and prints
+/// each partial `PartResponse.text` chunk as it arrives, rather than
+/// blocking on the whole response like the buffered path above. Returns the
+/// concatenated text once the stream ends.
+async fn stream_generate_content(client: &Client, url: &str, request: &GenerateRequest) -> Result<String> {
+    let response = client.post(url).json(request).send().await?;
+    println!("Status: {}", response.status());
+
+    let mut answer = String::new();
+    let mut buffered = String::new();
+    let mut bytes = response.bytes_stream();
+
+    while let Some(chunk) = bytes.next().await {
+        buffered.push_str(&String::from_utf8_lossy(&chunk?));
+
+        // SSE frames are separated by a blank line; only process complete
+        // frames, leaving any trailing partial frame in `buffered`.
+        while let Some(frame_end) = buffered.find("\n\n") {
+            let frame = buffered[..frame_end].to_string();
+            buffered.drain(..frame_end + 2);
+
+            for line in frame.lines() {
+                let Some(data) = line.strip_prefix("data: ") else {
+                    continue;
+                };
+                let parsed: GenerateResponse = serde_json::from_str(data)?;
+                if let Some(error) = parsed.error {
+                    println!("✗ API Error: {} ({})", error.message, error.code);
+                    continue;
+                }
+                let Some(candidate) = parsed.candidates.and_then(|c| c.into_iter().next()) else {
+                    continue;
+                };
+                if let Some(part) = candidate.content.parts.first() {
+                    print!("{}", part.text);
+                    std::io::stdout().flush()?;
+                    answer.push_str(&part.text);
+                }
+            }
+        }
+    }
+
+    Ok(answer)
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 //! Command Parser Experiment
 
 use anyhow::Result;
+use std::ops::Range;
 
 #[derive(Debug, PartialEq)]
 pub enum Command {
@@ -13,43 +14,188 @@ pub enum Command {
     Chat { message: String },
 }
 
-pub fn parse_command(input: &str) -> Result<Command> {
+/// Byte-offset span into the source string a [`ParseError`] was raised
+/// against, so a caller can point at exactly where parsing went wrong
+/// instead of just printing a message.
+pub type Span = Range<usize>;
+
+/// Structured replacement for the ad-hoc `anyhow!` strings `parse_command`
+/// used to return. Every variant carries enough of a [`Span`] to render a
+/// caret-underlined diagnostic via [`Loader::render`].
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The input was empty (or all whitespace).
+    EmptyInput,
+    /// `/name` didn't match any known command.
+    UnknownCommand { name: String, span: Span },
+    /// A known command got the wrong number of arguments.
+    BadArity { command: String, expected: usize, got: usize, span: Span },
+    /// A `"` was opened but never closed.
+    UnterminatedQuote { span: Span },
+}
+
+impl ParseError {
+    /// The span this error points at, if any (`EmptyInput` has none: there's
+    /// no input to point into).
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParseError::EmptyInput => None,
+            ParseError::UnknownCommand { span, .. }
+            | ParseError::BadArity { span, .. }
+            | ParseError::UnterminatedQuote { span } => Some(span.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::EmptyInput => write!(f, "empty input"),
+            ParseError::UnknownCommand { name, .. } => write!(f, "unknown command: /{name}"),
+            ParseError::BadArity { command, expected, got, .. } => {
+                write!(f, "/{command}: expected {expected} argument(s), got {got}")
+            }
+            ParseError::UnterminatedQuote { .. } => write!(f, "unterminated quote"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Holds multiple named source strings (e.g. the lines of a multi-line
+/// command script) so a [`ParseError`]'s span can be rendered back into a
+/// caret-underlined diagnostic without the caller threading the original
+/// text through separately.
+#[derive(Debug, Default)]
+pub struct Loader {
+    sources: Vec<(String, String)>,
+}
+
+impl Loader {
+    pub fn new() -> Self {
+        Self { sources: Vec::new() }
+    }
+
+    /// Registers `content` under `name`, returning its index for later
+    /// lookup via [`Self::source`].
+    pub fn add(&mut self, name: impl Into<String>, content: impl Into<String>) -> usize {
+        self.sources.push((name.into(), content.into()));
+        self.sources.len() - 1
+    }
+
+    pub fn source(&self, name: &str) -> Option<&str> {
+        self.sources.iter().find(|(n, _)| n == name).map(|(_, content)| content.as_str())
+    }
+
+    /// Renders `error` against the source registered as `name` as a
+    /// caret-underlined diagnostic. Falls back to `error`'s `Display` if
+    /// `name` isn't registered or the error carries no span.
+    pub fn render(&self, name: &str, error: &ParseError) -> String {
+        let (Some(content), Some(span)) = (self.source(name), error.span()) else {
+            return error.to_string();
+        };
+        let start = span.start.min(content.len());
+        let width = span.len().max(1);
+        let caret_line = format!("{}{}", " ".repeat(start), "^".repeat(width));
+        format!("{error}\n  --> {name}\n    | {content}\n    | {caret_line}")
+    }
+}
+
+/// Splits `body` into whitespace-separated tokens, treating a `"..."` run
+/// as a single token, and returns each token alongside its absolute byte
+/// offset into the original input (`offset` shifts every position by the
+/// length of whatever precedes `body` there, e.g. the leading `/`).
+fn tokenize(body: &str, offset: usize) -> Result<Vec<(usize, &str)>, ParseError> {
+    let bytes = body.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        if bytes[i] == b'"' {
+            i += 1;
+            let mut closed = false;
+            while i < bytes.len() {
+                if bytes[i] == b'"' {
+                    i += 1;
+                    closed = true;
+                    break;
+                }
+                i += 1;
+            }
+            if !closed {
+                return Err(ParseError::UnterminatedQuote { span: (offset + start)..(offset + body.len()) });
+            }
+        } else {
+            while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+        }
+
+        tokens.push((offset + start, &body[start..i]));
+    }
+
+    Ok(tokens)
+}
+
+pub fn parse_command(input: &str) -> Result<Command, ParseError> {
     let trimmed = input.trim();
-    
+
     if trimmed.is_empty() {
-        return Err(anyhow::anyhow!("Empty input"));
+        return Err(ParseError::EmptyInput);
     }
-    
+
     if !trimmed.starts_with('/') {
         // Regular chat message
         return Ok(Command::Chat {
             message: trimmed.to_string(),
         });
     }
-    
-    // Parse command (starts with /)
-    let parts: Vec<&str> = trimmed[1..].split_whitespace().collect();
-    
-    if parts.is_empty() {
-        return Err(anyhow::anyhow!("Empty command"));
-    }
-    
-    match parts[0] {
+
+    let slash_offset = input.len() - input.trim_start().len();
+    let body = &trimmed[1..];
+    let body_offset = slash_offset + 1;
+    let tokens = tokenize(body, body_offset)?;
+
+    let Some(&(name_start, name)) = tokens.first() else {
+        return Err(ParseError::UnknownCommand {
+            name: String::new(),
+            span: slash_offset..slash_offset + 1,
+        });
+    };
+    let name_span = name_start..(name_start + name.len());
+
+    match name {
         "help" | "h" => Ok(Command::Help),
         "exit" | "quit" | "q" => Ok(Command::Exit),
         "model" | "m" => Ok(Command::Model),
         "clear" | "cls" => Ok(Command::Clear),
         "history" | "hist" => Ok(Command::History),
         "set" => {
-            if parts.len() != 3 {
-                return Err(anyhow::anyhow!("Usage: /set <key> <value>"));
+            let args = &tokens[1..];
+            if args.len() != 2 {
+                let span_end = tokens.last().map_or(name_span.end, |(start, tok)| start + tok.len());
+                return Err(ParseError::BadArity {
+                    command: "set".to_string(),
+                    expected: 2,
+                    got: args.len(),
+                    span: name_span.start..span_end,
+                });
             }
             Ok(Command::Set {
-                key: parts[1].to_string(),
-                value: parts[2].to_string(),
+                key: args[0].1.to_string(),
+                value: args[1].1.to_string(),
             })
         }
-        cmd => Err(anyhow::anyhow!("Unknown command: /{}", cmd)),
+        other => Err(ParseError::UnknownCommand {
+            name: other.to_string(),
+            span: name_span,
+        }),
     }
 }
 
@@ -58,7 +204,7 @@ fn test_commands() -> Result<()> {
         // Chat messages
         ("Hello, how are you?", Command::Chat { message: "Hello, how are you?".to_string() }),
         ("What is 2 + 40?", Command::Chat { message: "What is 2 + 40?".to_string() }),
-        
+
         // Commands
         ("/help", Command::Help),
         ("/h", Command::Help),
@@ -71,17 +217,17 @@ fn test_commands() -> Result<()> {
         ("/cls", Command::Clear),
         ("/history", Command::History),
         ("/hist", Command::History),
-        ("/set debug true", Command::Set { 
-            key: "debug".to_string(), 
-            value: "true".to_string() 
+        ("/set debug true", Command::Set {
+            key: "debug".to_string(),
+            value: "true".to_string()
         }),
     ];
-    
+
     println!("=== Command Parser Tests ===");
-    
+
     let mut passed = 0;
     let mut failed = 0;
-    
+
     for (input, expected) in test_cases {
         match parse_command(input) {
             Ok(parsed) => {
@@ -99,7 +245,7 @@ fn test_commands() -> Result<()> {
             }
         }
     }
-    
+
     // Test error cases
     println!("\n--- Error Cases ---");
     let error_cases = vec![
@@ -109,7 +255,7 @@ fn test_commands() -> Result<()> {
         ("/set", "Usage: /set <key> <value>"),
         ("/set key", "Usage: /set <key> <value>"),
     ];
-    
+
     for (input, _expected_error) in error_cases {
         match parse_command(input) {
             Ok(parsed) => {
@@ -122,35 +268,38 @@ fn test_commands() -> Result<()> {
             }
         }
     }
-    
+
     println!("\n--- Test Results ---");
     println!("Passed: {}", passed);
     println!("Failed: {}", failed);
-    
+
     if failed == 0 {
         println!("✓ All tests passed!");
     } else {
         println!("✗ {} tests failed", failed);
     }
-    
+
     Ok(())
 }
 
 fn main() -> Result<()> {
     test_commands()?;
-    
+
     println!("\n=== Interactive Test ===");
     println!("Enter commands to test the parser (type '/exit' to quit):");
-    
+
     loop {
         use std::io::{self, Write};
-        
+
         print!("parser-test> ");
         io::stdout().flush()?;
-        
+
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
-        
+
+        let mut loader = Loader::new();
+        loader.add("stdin", input.trim_end_matches('\n'));
+
         match parse_command(&input) {
             Ok(Command::Exit) => {
                 println!("Goodbye!");
@@ -160,11 +309,67 @@ fn main() -> Result<()> {
                 println!("Parsed: {:?}", cmd);
             }
             Err(e) => {
-                println!("Error: {}", e);
+                println!("{}", loader.render("stdin", &e));
             }
         }
     }
-    
+
     println!("\n=== Command Parser Test Complete ===");
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_input_has_no_span() {
+        assert_eq!(parse_command(""), Err(ParseError::EmptyInput));
+        assert_eq!(parse_command("   "), Err(ParseError::EmptyInput));
+    }
+
+    #[test]
+    fn test_unknown_command_span_covers_the_name() {
+        let err = parse_command("/bogus arg").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnknownCommand { name: "bogus".to_string(), span: 1..6 }
+        );
+    }
+
+    #[test]
+    fn test_bad_arity_span_covers_the_whole_invocation() {
+        let err = parse_command("/set key").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::BadArity { command: "set".to_string(), expected: 2, got: 1, span: 1..8 }
+        );
+    }
+
+    #[test]
+    fn test_unterminated_quote_is_reported() {
+        let err = parse_command(r#"/set key "unterminated"#).unwrap_err();
+        assert!(matches!(err, ParseError::UnterminatedQuote { .. }));
+    }
+
+    #[test]
+    fn test_quoted_argument_is_one_token() {
+        let cmd = parse_command(r#"/set path "my file.txt""#).unwrap();
+        assert_eq!(
+            cmd,
+            Command::Set { key: "path".to_string(), value: "\"my file.txt\"".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_loader_renders_a_caret_diagnostic() {
+        let mut loader = Loader::new();
+        loader.add("line1", "/bogus arg");
+
+        let err = parse_command("/bogus arg").unwrap_err();
+        let rendered = loader.render("line1", &err);
+
+        assert!(rendered.contains("unknown command: /bogus"));
+        assert!(rendered.contains("^^^^^"));
+    }
+}
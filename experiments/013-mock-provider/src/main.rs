@@ -23,35 +23,106 @@ struct MockResponse {
 struct Recording {
     request: MockRequest,
     response: MockResponse,
+    /// How many times this recording has satisfied a request. Used to skip
+    /// it once played when `MatcherOptions::allow_reuse` is `false`.
+    #[serde(skip)]
+    play_count: usize,
+}
+
+/// How `MockProvider::find_matching` picks a recording for an incoming
+/// request.
+#[derive(Debug, Clone)]
+enum MatchStrategy {
+    /// Replay recordings in original order, matching only HTTP method —
+    /// the original behavior. Breaks as soon as requests arrive out of
+    /// recorded order.
+    Sequential,
+    /// Match on method + URL + canonicalized JSON body equality.
+    ExactBody,
+    /// Match on method + URL + a configurable subset of body fields (e.g.
+    /// only `prompt`), ignoring everything else in the body.
+    FuzzyBody { fields: Vec<String> },
+}
+
+/// Tunables for [`MockProvider::find_matching`].
+#[derive(Debug, Clone)]
+struct MatcherOptions {
+    strategy: MatchStrategy,
+    /// JSON pointer paths (e.g. `/headers/authorization`) stripped from
+    /// both sides before comparing bodies, so volatile fields like auth
+    /// tokens or timestamps don't break a match.
+    ignore_paths: Vec<String>,
+    /// If `false` (the default), a recording that already satisfied a
+    /// request is skipped on subsequent lookups.
+    allow_reuse: bool,
+}
+
+impl Default for MatcherOptions {
+    fn default() -> Self {
+        Self {
+            strategy: MatchStrategy::Sequential,
+            ignore_paths: Vec::new(),
+            allow_reuse: false,
+        }
+    }
 }
 
 struct MockProvider {
     recordings: Vec<Recording>,
     current_index: usize,
+    options: MatcherOptions,
 }
 
 impl MockProvider {
     fn load_from_jsonl(req_file: &Path, resp_file: &Path) -> Result<Self> {
-        let mut recordings = Vec::new();
-        
+        Self::load_from_jsonl_with_options(req_file, resp_file, MatcherOptions::default())
+    }
+
+    fn with_options(mut self, options: MatcherOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn load_from_jsonl_with_options(
+        req_file: &Path,
+        resp_file: &Path,
+        options: MatcherOptions,
+    ) -> Result<Self> {
         // Read request logs
         let req_contents = fs::read_to_string(req_file)?;
         let resp_contents = fs::read_to_string(resp_file)?;
-        
+
         let requests: Vec<serde_json::Value> = req_contents
             .lines()
             .filter(|l| !l.is_empty())
             .map(|line| serde_json::from_str(line))
             .collect::<Result<Vec<_>, _>>()?;
-        
+
         let responses: Vec<serde_json::Value> = resp_contents
             .lines()
             .filter(|l| !l.is_empty())
             .map(|line| serde_json::from_str(line))
             .collect::<Result<Vec<_>, _>>()?;
-        
-        // Match requests with responses by ID
-        for (req, resp) in requests.iter().zip(responses.iter()) {
+
+        // Pair by the `id`/`request_id` fields already present in the data
+        // instead of a positional zip, so a mismatched-length file (a
+        // dropped request, a response logged out of order) doesn't
+        // silently drop or misalign recordings.
+        let responses_by_id: HashMap<&str, &serde_json::Value> = responses
+            .iter()
+            .filter_map(|resp| {
+                resp["request_id"]
+                    .as_str()
+                    .or_else(|| resp["id"].as_str())
+                    .map(|id| (id, resp))
+            })
+            .collect();
+
+        let mut recordings = Vec::new();
+        for req in &requests {
+            let Some(id) = req["id"].as_str() else { continue };
+            let Some(resp) = responses_by_id.get(id) else { continue };
+
             recordings.push(Recording {
                 request: MockRequest {
                     method: req["method"].as_str().unwrap_or("GET").to_string(),
@@ -62,34 +133,52 @@ impl MockProvider {
                     status: resp["status"].as_u64().unwrap_or(200) as u16,
                     body: resp["body"].clone(),
                 },
+                play_count: 0,
             });
         }
-        
+
         Ok(Self {
             recordings,
             current_index: 0,
+            options,
         })
     }
-    
-    fn find_matching(&mut self, method: &str, body: &serde_json::Value) -> Option<&Recording> {
-        // Simple matching: find first unused recording with matching method
-        // In real implementation, would match on URL and body content
-        for (i, recording) in self.recordings.iter().enumerate() {
-            if i >= self.current_index && recording.request.method == method {
-                self.current_index = i + 1;
-                return Some(recording);
-            }
+
+    fn find_matching(&mut self, method: &str, url: &str, body: &serde_json::Value) -> Option<&Recording> {
+        let ignore_paths = &self.options.ignore_paths;
+        let allow_reuse = self.options.allow_reuse;
+
+        let index = match &self.options.strategy {
+            MatchStrategy::Sequential => (self.current_index..self.recordings.len())
+                .find(|&i| self.recordings[i].request.method == method),
+            MatchStrategy::ExactBody => self.recordings.iter().position(|r| {
+                (allow_reuse || r.play_count == 0)
+                    && r.request.method == method
+                    && r.request.url == url
+                    && strip_ignored(&r.request.body, ignore_paths) == strip_ignored(body, ignore_paths)
+            }),
+            MatchStrategy::FuzzyBody { fields } => self.recordings.iter().position(|r| {
+                (allow_reuse || r.play_count == 0)
+                    && r.request.method == method
+                    && r.request.url == url
+                    && fields.iter().all(|field| r.request.body.get(field) == body.get(field))
+            }),
+        }?;
+
+        self.recordings[index].play_count += 1;
+        if matches!(self.options.strategy, MatchStrategy::Sequential) {
+            self.current_index = index + 1;
         }
-        None
+        Some(&self.recordings[index])
     }
-    
+
     async fn handle_request(
         &mut self,
         method: &str,
-        _url: &str,
+        url: &str,
         body: &serde_json::Value,
     ) -> Result<serde_json::Value> {
-        if let Some(recording) = self.find_matching(method, body) {
+        if let Some(recording) = self.find_matching(method, url, body) {
             println!("Mock: Found matching recording");
             Ok(recording.response.body.clone())
         } else {
@@ -102,6 +191,19 @@ impl MockProvider {
     }
 }
 
+/// Returns a clone of `value` with every JSON pointer in `paths` removed,
+/// so volatile fields (auth tokens, timestamps) don't break a body match.
+fn strip_ignored(value: &serde_json::Value, paths: &[String]) -> serde_json::Value {
+    let mut value = value.clone();
+    for path in paths {
+        let Some((parent_pointer, key)) = path.rsplit_once('/') else { continue };
+        if let Some(parent) = value.pointer_mut(parent_pointer).and_then(|p| p.as_object_mut()) {
+            parent.remove(key);
+        }
+    }
+    value
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // First, create some mock recordings
@@ -159,14 +261,24 @@ async fn main() -> Result<()> {
     
     println!("Created mock data files");
     
-    // Load and test the mock provider
-    let mut provider = MockProvider::load_from_jsonl(&req_file, &resp_file)?;
+    // Load and test the mock provider. ExactBody matching (rather than the
+    // original method-only Sequential strategy) means the requests below
+    // can arrive out of recorded order and still find their recording.
+    let mut provider = MockProvider::load_from_jsonl_with_options(
+        &req_file,
+        &resp_file,
+        MatcherOptions {
+            strategy: MatchStrategy::ExactBody,
+            ..MatcherOptions::default()
+        },
+    )?;
     println!("\nLoaded {} recordings", provider.recordings.len());
-    
-    // Test requests
+
+    // Test requests, including the second recording played before the
+    // first to demonstrate order-independent matching.
     let test_requests = vec![
-        ("POST", "https://api.example.com/generate", json!({"prompt": "Hello"})),
         ("POST", "https://api.example.com/generate", json!({"prompt": "What is 2+2?"})),
+        ("POST", "https://api.example.com/generate", json!({"prompt": "Hello"})),
         ("POST", "https://api.example.com/generate", json!({"prompt": "Unknown prompt"})),
     ];
     
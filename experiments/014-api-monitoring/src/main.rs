@@ -1,15 +1,161 @@
 //! Experiment: Monitor and validate actual Gemini API calls
 //! This creates observability for our REPL without external dependencies
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use chrono::Utc;
+use futures::{Stream, StreamExt};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::VecDeque;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::time::Instant;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const TOKEN_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
+/// Refresh this long before the token's actual expiry so an in-flight
+/// call never races a token that goes stale mid-request.
+const EXPIRY_SLACK: Duration = Duration::from_secs(60);
+
+/// Which Gemini deployment to call, and how to authenticate to it.
+/// Selected via `GEMINI_BACKEND` (`public` or `vertex`), mirroring the
+/// REPL's own `--backend` flag.
+enum Backend {
+    /// `generativelanguage.googleapis.com`, authenticated with an API key
+    /// query parameter.
+    GenerativeLanguage { api_key: String },
+    /// `{location}-aiplatform.googleapis.com`, authenticated with an
+    /// OAuth bearer token minted from Application Default Credentials.
+    VertexAI {
+        project_id: String,
+        location: String,
+        adc_file: Option<String>,
+    },
+}
+
+/// The two ADC shapes `gcloud` writes to disk: a service-account key (from
+/// `GOOGLE_APPLICATION_CREDENTIALS`) or a user's refresh token (from
+/// `gcloud auth application-default login`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AdcFile {
+    ServiceAccount {
+        client_email: String,
+        private_key: String,
+        #[serde(default = "default_token_uri")]
+        token_uri: String,
+    },
+    AuthorizedUser {
+        client_id: String,
+        client_secret: String,
+        refresh_token: String,
+    },
+}
+
+fn default_token_uri() -> String {
+    TOKEN_ENDPOINT.to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Locate and parse ADC: `adc_file` if given, otherwise
+/// `GOOGLE_APPLICATION_CREDENTIALS`, otherwise the well-known path under
+/// the user's gcloud config directory.
+fn load_adc_file(adc_file: Option<&str>) -> Result<AdcFile> {
+    let path = match adc_file {
+        Some(path) => path.to_string(),
+        None => match std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+            Ok(path) => path,
+            Err(_) => {
+                let home = std::env::var("HOME").context("no GOOGLE_APPLICATION_CREDENTIALS and $HOME unset")?;
+                format!("{home}/.config/gcloud/application_default_credentials.json")
+            }
+        },
+    };
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("reading Application Default Credentials from {path}"))?;
+    serde_json::from_str(&contents).with_context(|| format!("parsing Application Default Credentials at {path}"))
+}
+
+/// Mint a fresh Vertex AI access token via the self-signed JWT-bearer flow
+/// (service accounts) or the refresh-token flow (`gcloud auth
+/// application-default login` users).
+async fn mint_access_token(http: &Client, credentials: &AdcFile) -> Result<TokenResponse> {
+    match credentials {
+        AdcFile::ServiceAccount { client_email, private_key, token_uri } => {
+            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+            let claims = json!({
+                "iss": client_email,
+                "scope": CLOUD_PLATFORM_SCOPE,
+                "aud": token_uri,
+                "iat": now,
+                "exp": now + 3600,
+            });
+            let key = EncodingKey::from_rsa_pem(private_key.as_bytes()).context("parsing service account private key")?;
+            let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+                .context("signing service account JWT")?;
+
+            let response = http
+                .post(token_uri)
+                .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", &assertion)])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                bail!("token exchange failed with status {status}: {}", response.text().await?);
+            }
+            Ok(response.json().await?)
+        }
+        AdcFile::AuthorizedUser { client_id, client_secret, refresh_token } => {
+            let response = http
+                .post(TOKEN_ENDPOINT)
+                .form(&[
+                    ("client_id", client_id.as_str()),
+                    ("client_secret", client_secret.as_str()),
+                    ("refresh_token", refresh_token.as_str()),
+                    ("grant_type", "refresh_token"),
+                ])
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                bail!("token refresh failed with status {status}: {}", response.text().await?);
+            }
+            Ok(response.json().await?)
+        }
+    }
+}
+
+/// Pull complete `data: {...}` JSON payloads out of `buffer`, in the order
+/// they appear, removing each one (plus its SSE framing) once extracted.
+/// An object split across network reads is left in the buffer until a
+/// later call completes it.
+fn drain_sse_objects(buffer: &mut String) -> Vec<String> {
+    let mut objects = Vec::new();
+    while let Some(newline) = buffer.find('\n') {
+        let line = buffer[..newline].trim_end_matches('\r').to_string();
+        buffer.drain(..=newline);
+        if let Some(data) = line.strip_prefix("data: ") {
+            objects.push(data.to_string());
+        }
+    }
+    objects
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ApiObservation {
     timestamp: chrono::DateTime<Utc>,
@@ -37,11 +183,31 @@ struct ResponseDetails {
 #[derive(Debug, Serialize, Deserialize)]
 struct Metrics {
     duration_ms: u64,
+    /// Time from request start to the first streamed token, for
+    /// `call_api_streaming`. `None` for non-streaming calls, which only
+    /// ever see the response all at once.
+    duration_to_first_token_ms: Option<u64>,
     prompt_tokens: Option<u32>,
     completion_tokens: Option<u32>,
     total_tokens: Option<u32>,
 }
 
+/// An incremental piece of a `streamGenerateContent` response, yielded as
+/// soon as each SSE event arrives instead of after the whole body has
+/// been read.
+#[derive(Debug, Clone)]
+enum StreamEvent {
+    TextDelta(String),
+    FunctionCall(FunctionCall),
+    UsageMetadata(Metrics),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    args: serde_json::Value,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ValidationResult {
     is_valid: bool,
@@ -49,36 +215,364 @@ struct ValidationResult {
     warnings: Vec<String>,
 }
 
+/// A workload file: a named batch of requests to replay against the
+/// configured backend, each repeated `repetitions` times with up to
+/// `concurrency` in flight at once. Committing one of these to the repo
+/// and diffing its `WorkloadReport` between runs is how latency/accuracy
+/// regressions get caught across model or prompt changes.
+#[derive(Debug, Deserialize)]
+struct Workload {
+    name: String,
+    #[serde(default = "default_repetitions")]
+    repetitions: usize,
+    #[serde(default = "default_concurrency")]
+    concurrency: usize,
+    requests: Vec<WorkloadRequest>,
+}
+
+fn default_repetitions() -> usize {
+    1
+}
+
+fn default_concurrency() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WorkloadRequest {
+    name: String,
+    prompt: String,
+    #[serde(default)]
+    tools: Option<serde_json::Value>,
+    /// Passes if any returned part is a `functionCall` with this name.
+    #[serde(default)]
+    expected_function: Option<String>,
+    /// Passes if any returned text part contains this substring.
+    #[serde(default)]
+    expected_answer: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RequestOutcome {
+    request: String,
+    run: usize,
+    success: bool,
+    duration_ms: u64,
+    total_tokens: Option<u32>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkloadReport {
+    workload: String,
+    total_requests: usize,
+    successes: usize,
+    success_rate: f64,
+    p50_duration_ms: u64,
+    p90_duration_ms: u64,
+    p99_duration_ms: u64,
+    total_tokens: u64,
+    outcomes: Vec<RequestOutcome>,
+}
+
+/// Nearest-rank percentile over already-sorted values.
+fn percentile(sorted_values: &[u64], pct: f64) -> u64 {
+    if sorted_values.is_empty() {
+        return 0;
+    }
+    let rank = ((pct / 100.0) * (sorted_values.len() - 1) as f64).round() as usize;
+    sorted_values[rank.min(sorted_values.len() - 1)]
+}
+
+fn build_request_body(request: &WorkloadRequest) -> serde_json::Value {
+    let mut body = json!({
+        "contents": [{ "role": "user", "parts": [{ "text": request.prompt }] }]
+    });
+    if let Some(tools) = &request.tools {
+        body["tools"] = tools.clone();
+    }
+    body
+}
+
+/// Whether `observation` satisfies `request`'s expectation. A request with
+/// neither `expected_function` nor `expected_answer` only checks that the
+/// call itself validated cleanly.
+fn evaluate_outcome(request: &WorkloadRequest, observation: &ApiObservation) -> bool {
+    if !observation.validation.is_valid {
+        return false;
+    }
+    let parts = observation.response.body["candidates"][0]["content"]["parts"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    if let Some(expected_function) = &request.expected_function {
+        return parts
+            .iter()
+            .any(|part| part.get("functionCall").and_then(|c| c.get("name")).and_then(|n| n.as_str()) == Some(expected_function.as_str()));
+    }
+    if let Some(expected_answer) = &request.expected_answer {
+        return parts
+            .iter()
+            .any(|part| part.get("text").and_then(|t| t.as_str()).is_some_and(|text| text.contains(expected_answer.as_str())));
+    }
+    true
+}
+
+/// Run every request in `workload`, `repetitions` times each, with up to
+/// `concurrency` in flight at once, and aggregate the results.
+async fn run_workload(monitor: &GeminiMonitor, workload: &Workload) -> Result<WorkloadReport> {
+    let jobs = workload
+        .requests
+        .iter()
+        .flat_map(|request| (0..workload.repetitions).map(move |run| (request, run)));
+
+    let outcomes: Vec<RequestOutcome> = futures::stream::iter(jobs.map(|(request, run)| async move {
+        let body = build_request_body(request);
+        let start = Instant::now();
+        match monitor.call_api(body).await {
+            Ok(observation) => RequestOutcome {
+                request: request.name.clone(),
+                run,
+                success: evaluate_outcome(request, &observation),
+                duration_ms: observation.metrics.duration_ms,
+                total_tokens: observation.metrics.total_tokens,
+                error: None,
+            },
+            Err(e) => RequestOutcome {
+                request: request.name.clone(),
+                run,
+                success: false,
+                duration_ms: start.elapsed().as_millis() as u64,
+                total_tokens: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }))
+    .buffer_unordered(workload.concurrency.max(1))
+    .collect()
+    .await;
+
+    let mut durations: Vec<u64> = outcomes.iter().map(|o| o.duration_ms).collect();
+    durations.sort_unstable();
+    let successes = outcomes.iter().filter(|o| o.success).count();
+    let total_tokens: u64 = outcomes.iter().filter_map(|o| o.total_tokens).map(u64::from).sum();
+
+    Ok(WorkloadReport {
+        workload: workload.name.clone(),
+        total_requests: outcomes.len(),
+        success_rate: if outcomes.is_empty() { 0.0 } else { successes as f64 / outcomes.len() as f64 },
+        successes,
+        p50_duration_ms: percentile(&durations, 50.0),
+        p90_duration_ms: percentile(&durations, 90.0),
+        p99_duration_ms: percentile(&durations, 99.0),
+        total_tokens,
+        outcomes,
+    })
+}
+
 struct GeminiMonitor {
     client: Client,
-    api_key: String,
+    backend: Backend,
+    token_cache: Mutex<Option<CachedToken>>,
     log_dir: String,
 }
 
 impl GeminiMonitor {
-    fn new(api_key: String) -> Result<Self> {
+    fn new(backend: Backend) -> Result<Self> {
         let log_dir = format!("logs/gemini/{}", Utc::now().format("%Y-%m-%d"));
         fs::create_dir_all(&log_dir)?;
-        
+
         Ok(Self {
             client: Client::new(),
-            api_key,
+            backend,
+            token_cache: Mutex::new(None),
             log_dir,
         })
     }
-    
+
+    /// Return a valid Vertex AI bearer token, minting or refreshing one if
+    /// the cached token is missing or close to expiry.
+    async fn access_token(&self, adc_file: Option<&str>) -> Result<String> {
+        if let Some(cached) = self.token_cache.lock().unwrap().as_ref() {
+            if cached.expires_at > Instant::now() {
+                return Ok(cached.access_token.clone());
+            }
+        }
+
+        let credentials = load_adc_file(adc_file)?;
+        let token = mint_access_token(&self.client, &credentials).await?;
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in).saturating_sub(EXPIRY_SLACK);
+        *self.token_cache.lock().unwrap() = Some(CachedToken { access_token: token.access_token.clone(), expires_at });
+        Ok(token.access_token)
+    }
+
+    /// Resolve this call's URL and the request builder's auth, depending
+    /// on which `Backend` is configured.
+    async fn request_builder(&self, model: &str) -> Result<(String, reqwest::RequestBuilder)> {
+        match &self.backend {
+            Backend::GenerativeLanguage { api_key } => {
+                let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent?key={api_key}");
+                Ok((url.clone(), self.client.post(url)))
+            }
+            Backend::VertexAI { project_id, location, adc_file } => {
+                let token = self.access_token(adc_file.as_deref()).await?;
+                let url = format!(
+                    "https://{location}-aiplatform.googleapis.com/v1/projects/{project_id}/locations/{location}/publishers/google/models/{model}:generateContent"
+                );
+                Ok((url.clone(), self.client.post(&url).bearer_auth(token)))
+            }
+        }
+    }
+
+    /// Like [`Self::request_builder`], but hits `:streamGenerateContent`
+    /// with `alt=sse` instead of the blocking `:generateContent` endpoint.
+    async fn request_builder_streaming(&self, model: &str) -> Result<reqwest::RequestBuilder> {
+        let (url, request) = self.request_builder(model).await?;
+        let url = url.replacen(":generateContent", ":streamGenerateContent", 1);
+        let url = if url.contains('?') { format!("{url}&alt=sse") } else { format!("{url}?alt=sse") };
+        Ok(match &self.backend {
+            Backend::GenerativeLanguage { .. } => self.client.post(url),
+            Backend::VertexAI { adc_file, .. } => self.client.post(url).bearer_auth(self.access_token(adc_file.as_deref()).await?),
+        })
+    }
+
+    /// Hit `:streamGenerateContent?alt=sse` and yield incremental
+    /// `StreamEvent`s as SSE chunks arrive, instead of waiting for the
+    /// whole body the way [`Self::call_api`] does.
+    async fn send_request_streaming(&self, model: &str, body: serde_json::Value) -> Result<impl Stream<Item = Result<StreamEvent>>> {
+        let response = self.request_builder_streaming(model).await?.json(&body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("stream request failed with status {status}: {}", response.text().await?);
+        }
+
+        let state = (response.bytes_stream(), Vec::<u8>::new(), String::new(), VecDeque::<StreamEvent>::new());
+
+        Ok(futures::stream::unfold(state, |(mut bytes, mut pending_bytes, mut buffer, mut queue)| async move {
+            loop {
+                if let Some(event) = queue.pop_front() {
+                    return Some((Ok(event), (bytes, pending_bytes, buffer, queue)));
+                }
+
+                let chunk = match bytes.next().await {
+                    Some(Ok(chunk)) => chunk,
+                    Some(Err(e)) => return Some((Err(e.into()), (bytes, pending_bytes, buffer, queue))),
+                    None => return None,
+                };
+                pending_bytes.extend_from_slice(&chunk);
+
+                // A single SSE event's bytes can split a multi-byte UTF-8
+                // sequence across two network reads; only decode the
+                // longest valid prefix and leave the rest for later.
+                let valid_len = match std::str::from_utf8(&pending_bytes) {
+                    Ok(s) => s.len(),
+                    Err(e) => e.valid_up_to(),
+                };
+                buffer.push_str(std::str::from_utf8(&pending_bytes[..valid_len]).expect("validated above"));
+                pending_bytes.drain(..valid_len);
+
+                for object in drain_sse_objects(&mut buffer) {
+                    let parsed: serde_json::Value = match serde_json::from_str(&object) {
+                        Ok(v) => v,
+                        Err(e) => return Some((Err(e.into()), (bytes, pending_bytes, buffer, queue))),
+                    };
+                    if let Some(message) = parsed.get("error").and_then(|e| e.get("message")).and_then(|m| m.as_str()) {
+                        return Some((Err(anyhow::anyhow!("Gemini API error during stream: {message}")), (bytes, pending_bytes, buffer, queue)));
+                    }
+                    for candidate in parsed["candidates"].as_array().cloned().unwrap_or_default() {
+                        for part in candidate["content"]["parts"].as_array().cloned().unwrap_or_default() {
+                            if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
+                                queue.push_back(StreamEvent::TextDelta(text.to_string()));
+                            }
+                            if let Some(call) = part.get("functionCall") {
+                                queue.push_back(StreamEvent::FunctionCall(FunctionCall {
+                                    name: call["name"].as_str().unwrap_or_default().to_string(),
+                                    args: call["args"].clone(),
+                                }));
+                            }
+                        }
+                    }
+                    if let Some(usage) = parsed.get("usageMetadata") {
+                        queue.push_back(StreamEvent::UsageMetadata(Metrics {
+                            duration_ms: 0,
+                            duration_to_first_token_ms: None,
+                            prompt_tokens: usage.get("promptTokenCount").and_then(|v| v.as_u64()).map(|v| v as u32),
+                            completion_tokens: usage.get("candidatesTokenCount").and_then(|v| v.as_u64()).map(|v| v as u32),
+                            total_tokens: usage.get("totalTokenCount").and_then(|v| v.as_u64()).map(|v| v as u32),
+                        }));
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Like [`Self::call_api`], but drives [`Self::send_request_streaming`]
+    /// and accumulates its deltas into the same `ApiObservation` shape, so
+    /// streamed and non-streamed calls log identically. Tracks duration to
+    /// the first token separately from the call's total duration.
+    async fn call_api_streaming(&self, model: &str, body: serde_json::Value) -> Result<ApiObservation> {
+        let start = Instant::now();
+        let mut stream = Box::pin(self.send_request_streaming(model, body.clone()).await?);
+
+        let mut text = String::new();
+        let mut function_calls = Vec::new();
+        let mut usage: Option<Metrics> = None;
+        let mut duration_to_first_token_ms = None;
+
+        while let Some(event) = stream.next().await {
+            if duration_to_first_token_ms.is_none() {
+                duration_to_first_token_ms = Some(start.elapsed().as_millis() as u64);
+            }
+            match event? {
+                StreamEvent::TextDelta(delta) => text.push_str(&delta),
+                StreamEvent::FunctionCall(call) => function_calls.push(call),
+                StreamEvent::UsageMetadata(metrics) => usage = Some(metrics),
+            }
+        }
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let parts: Vec<serde_json::Value> = std::iter::once(json!({ "text": text }))
+            .chain(function_calls.into_iter().map(|call| json!({ "functionCall": { "name": call.name, "args": call.args } })))
+            .collect();
+        let response_body = json!({
+            "candidates": [{ "content": { "parts": parts } }],
+        });
+
+        let validation = self.validate_response(&body, &response_body, 200);
+        let mut metrics = self.extract_metrics(&response_body, duration_ms);
+        metrics.duration_to_first_token_ms = duration_to_first_token_ms;
+        if let Some(usage) = usage {
+            metrics.prompt_tokens = usage.prompt_tokens;
+            metrics.completion_tokens = usage.completion_tokens;
+            metrics.total_tokens = usage.total_tokens;
+        }
+
+        let observation = ApiObservation {
+            timestamp: Utc::now(),
+            request: RequestDetails {
+                method: "POST".to_string(),
+                endpoint: format!("models/{model}:streamGenerateContent"),
+                body: body.clone(),
+                model: model.to_string(),
+            },
+            response: ResponseDetails { status: 200, body: response_body, headers: std::collections::HashMap::new() },
+            metrics,
+            validation,
+        };
+
+        self.log_observation(&observation)?;
+        Ok(observation)
+    }
+
     async fn call_api(&self, body: serde_json::Value) -> Result<ApiObservation> {
         let model = "gemini-1.5-flash";
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            model, self.api_key
-        );
-        
+        let (url, request) = self.request_builder(model).await?;
+
         let start = Instant::now();
-        
+
         // Make the actual API call
-        let response = self.client
-            .post(&url)
+        let response = request
             .json(&body)
             .send()
             .await?;
@@ -183,6 +677,7 @@ impl GeminiMonitor {
     fn extract_metrics(&self, response: &serde_json::Value, duration_ms: u64) -> Metrics {
         let mut metrics = Metrics {
             duration_ms,
+            duration_to_first_token_ms: None,
             prompt_tokens: None,
             completion_tokens: None,
             total_tokens: None,
@@ -229,19 +724,410 @@ impl GeminiMonitor {
     }
 }
 
+/// Token-bucket-style rate limiter: hands out evenly-spaced time slots at
+/// `rate_per_minute` per minute, so a concurrent sweep stays within the
+/// API's requests-per-minute quota instead of relying on a blanket
+/// `sleep` between every call.
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn per_minute(rate_per_minute: u32) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(60.0 / rate_per_minute.max(1) as f64),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        let now = Instant::now();
+        if slot > now {
+            tokio::time::sleep(slot - now).await;
+        }
+    }
+}
+
+/// Default worker-pool size for a concurrent sweep: `--concurrency N`/
+/// `CONCURRENCY` if set, otherwise the number of available CPUs.
+fn default_concurrency_limit() -> usize {
+    std::env::var("CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| std::thread::available_parallelism().map(std::num::NonZeroUsize::get).unwrap_or(4))
+}
+
+/// Pick the backend the same way the REPL's `--backend` flag does:
+/// `public` (the default) needs just an API key, `vertex` needs a GCP
+/// project/region and optionally an explicit ADC file path.
+fn resolve_backend() -> Backend {
+    match std::env::var("GEMINI_BACKEND").as_deref() {
+        Ok("vertex") => Backend::VertexAI {
+            project_id: std::env::var("GOOGLE_CLOUD_PROJECT").expect("GOOGLE_CLOUD_PROJECT must be set for GEMINI_BACKEND=vertex"),
+            location: std::env::var("GOOGLE_CLOUD_REGION").unwrap_or_else(|_| "us-central1".to_string()),
+            adc_file: std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok(),
+        },
+        _ => Backend::GenerativeLanguage {
+            api_key: std::env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set"),
+        },
+    }
+}
+
+// --- OpenAI-compatible `/v1/chat/completions` proxy -----------------------
+//
+// Lets any client that only speaks the OpenAI protocol point at this
+// crate's Gemini integration as a drop-in shim, the same way `OpenAiBackend`
+// in the main REPL crate lets this crate point *at* an OpenAI-compatible
+// server. Every proxied call is logged through the same `GeminiMonitor`
+// used everywhere else in this experiment, so proxy traffic shows up in
+// `observations.jsonl` identically to a direct `call_api` call.
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    #[allow(dead_code)] // accepted for OpenAI-schema compatibility; Gemini has no forcing equivalent we translate to yet
+    tool_choice: Option<serde_json::Value>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    role: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<serde_json::Value>>,
+}
+
+/// Monotonically-increasing suffix for generated `tool_calls[].id`s, since
+/// Gemini's `functionCall` parts don't carry one of their own.
+static TOOL_CALL_ID_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+fn next_tool_call_id() -> String {
+    format!("call_{}", TOOL_CALL_ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+}
+
+/// Translate OpenAI `messages` into a Gemini `generateContent` request
+/// body's `contents` (and `tools`, if any were given).
+fn openai_request_to_gemini_body(request: &ChatCompletionRequest) -> serde_json::Value {
+    let contents: Vec<serde_json::Value> = request
+        .messages
+        .iter()
+        .map(|message| {
+            let role = match message.role.as_str() {
+                "assistant" => "model",
+                "tool" => "user",
+                other => other,
+            };
+            let mut parts = Vec::new();
+            if let Some(content) = &message.content {
+                parts.push(json!({ "text": content }));
+            }
+            for call in message.tool_calls.iter().flatten() {
+                let name = call.pointer("/function/name").and_then(serde_json::Value::as_str).unwrap_or_default();
+                let args_raw = call.pointer("/function/arguments").and_then(serde_json::Value::as_str).unwrap_or("{}");
+                let args: serde_json::Value = serde_json::from_str(args_raw).unwrap_or(serde_json::Value::Null);
+                parts.push(json!({ "functionCall": { "name": name, "args": args } }));
+            }
+            json!({ "role": role, "parts": parts })
+        })
+        .collect();
+
+    let tools = request.tools.as_ref().map(|tools| {
+        let declarations: Vec<serde_json::Value> = tools
+            .iter()
+            .filter_map(|tool| {
+                let function = tool.get("function")?;
+                Some(json!({
+                    "name": function.get("name")?.as_str()?,
+                    "description": function.get("description").and_then(serde_json::Value::as_str).unwrap_or_default(),
+                    "parameters": function.get("parameters").cloned().unwrap_or(serde_json::Value::Null),
+                }))
+            })
+            .collect();
+        json!([{ "functionDeclarations": declarations }])
+    });
+
+    let mut body = json!({ "contents": contents });
+    if let Some(tools) = tools {
+        body["tools"] = tools;
+    }
+    body
+}
+
+/// Translate a buffered Gemini `generateContent` response body into an
+/// OpenAI `chat.completion` response.
+fn gemini_response_to_openai(model: &str, response_body: &serde_json::Value, metrics: &Metrics) -> serde_json::Value {
+    let parts = response_body["candidates"][0]["content"]["parts"].as_array().cloned().unwrap_or_default();
+
+    let mut content = String::new();
+    let mut tool_calls = Vec::new();
+    for part in &parts {
+        if let Some(text) = part.get("text").and_then(serde_json::Value::as_str) {
+            content.push_str(text);
+        }
+        if let Some(call) = part.get("functionCall") {
+            tool_calls.push(json!({
+                "id": next_tool_call_id(),
+                "type": "function",
+                "function": {
+                    "name": call["name"].as_str().unwrap_or_default(),
+                    "arguments": serde_json::to_string(&call["args"]).unwrap_or_else(|_| "{}".to_string()),
+                }
+            }));
+        }
+    }
+
+    json!({
+        "id": format!("chatcmpl-{}", next_tool_call_id()),
+        "object": "chat.completion",
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": if content.is_empty() { serde_json::Value::Null } else { serde_json::Value::String(content) },
+                "tool_calls": if tool_calls.is_empty() { serde_json::Value::Null } else { serde_json::Value::Array(tool_calls) },
+            },
+            "finish_reason": "stop",
+        }],
+        "usage": {
+            "prompt_tokens": metrics.prompt_tokens,
+            "completion_tokens": metrics.completion_tokens,
+            "total_tokens": metrics.total_tokens,
+        },
+    })
+}
+
+/// Translate one [`StreamEvent`] into an OpenAI `chat.completion.chunk`.
+fn stream_event_to_chunk(model: &str, id: &str, event: &StreamEvent) -> serde_json::Value {
+    let delta = match event {
+        StreamEvent::TextDelta(text) => json!({ "content": text }),
+        StreamEvent::FunctionCall(call) => json!({
+            "tool_calls": [{
+                "index": 0,
+                "id": next_tool_call_id(),
+                "type": "function",
+                "function": { "name": call.name, "arguments": serde_json::to_string(&call.args).unwrap_or_else(|_| "{}".to_string()) },
+            }]
+        }),
+        StreamEvent::UsageMetadata(_) => json!({}),
+    };
+
+    json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": serde_json::Value::Null }],
+    })
+}
+
+/// `POST /v1/chat/completions`, buffered path: run the whole request
+/// through [`GeminiMonitor::call_api`] and translate the result.
+async fn handle_chat_completions_buffered(monitor: &GeminiMonitor, request: &ChatCompletionRequest) -> Result<serde_json::Value> {
+    let body = openai_request_to_gemini_body(request);
+    let observation = monitor.call_api(body).await?;
+    Ok(gemini_response_to_openai(&request.model, &observation.response.body, &observation.metrics))
+}
+
+/// `POST /v1/chat/completions`, `stream: true` path: drive
+/// [`GeminiMonitor::send_request_streaming`], write each translated
+/// `chat.completion.chunk` to `out` as an SSE event, and log the
+/// accumulated call through `GeminiMonitor` once the stream ends, the same
+/// way the buffered path logs through `call_api`.
+async fn handle_chat_completions_streaming(
+    monitor: &GeminiMonitor,
+    request: &ChatCompletionRequest,
+    out: &mut (impl tokio::io::AsyncWrite + Unpin),
+) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let start = Instant::now();
+    let body = openai_request_to_gemini_body(request);
+    let id = format!("chatcmpl-{}", next_tool_call_id());
+    let mut stream = Box::pin(monitor.send_request_streaming(&request.model, body.clone()).await?);
+
+    let mut text = String::new();
+    let mut function_calls = Vec::new();
+    while let Some(event) = stream.next().await {
+        let event = event?;
+        let chunk = stream_event_to_chunk(&request.model, &id, &event);
+        out.write_all(format!("data: {}\n\n", serde_json::to_string(&chunk)?).as_bytes()).await?;
+        match event {
+            StreamEvent::TextDelta(delta) => text.push_str(&delta),
+            StreamEvent::FunctionCall(call) => function_calls.push(call),
+            StreamEvent::UsageMetadata(_) => {}
+        }
+    }
+    out.write_all(b"data: [DONE]\n\n").await?;
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let parts: Vec<serde_json::Value> = std::iter::once(json!({ "text": text }))
+        .chain(function_calls.iter().map(|call| json!({ "functionCall": { "name": call.name, "args": call.args } })))
+        .collect();
+    let response_body = json!({ "candidates": [{ "content": { "parts": parts } }] });
+    let validation = monitor.validate_response(&body, &response_body, 200);
+    let mut metrics = monitor.extract_metrics(&response_body, duration_ms);
+    metrics.duration_to_first_token_ms = None;
+
+    monitor.log_observation(&ApiObservation {
+        timestamp: Utc::now(),
+        request: RequestDetails {
+            method: "POST".to_string(),
+            endpoint: "v1/chat/completions (proxied, streamed)".to_string(),
+            body,
+            model: request.model.clone(),
+        },
+        response: ResponseDetails { status: 200, body: response_body, headers: std::collections::HashMap::new() },
+        metrics,
+        validation,
+    })?;
+
+    Ok(())
+}
+
+/// `api-monitoring serve [addr]`: run a minimal HTTP server implementing
+/// the OpenAI `/v1/chat/completions` schema in front of the configured
+/// Gemini backend, so clients that only speak the OpenAI protocol can use
+/// this crate as a drop-in shim.
+async fn run_serve_command(addr: &str) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    let monitor = std::sync::Arc::new(GeminiMonitor::new(resolve_backend())?);
+    let listener = tokio::net::TcpListener::bind(addr).await.with_context(|| format!("binding {addr}"))?;
+    info!("OpenAI-compatible proxy listening on http://{addr}/v1/chat/completions");
+
+    loop {
+        let (socket, _) = listener.accept().await?;
+        let monitor = monitor.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = socket.into_split();
+            let mut reader = BufReader::new(reader);
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+                return;
+            }
+
+            let mut content_length = 0usize;
+            loop {
+                let mut header = String::new();
+                if reader.read_line(&mut header).await.unwrap_or(0) == 0 || header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+
+            let mut body_bytes = vec![0u8; content_length];
+            if content_length > 0 && reader.read_exact(&mut body_bytes).await.is_err() {
+                return;
+            }
+
+            let request: ChatCompletionRequest = match serde_json::from_slice(&body_bytes) {
+                Ok(r) => r,
+                Err(e) => {
+                    let body = format!("{{\"error\":\"invalid request: {e}\"}}");
+                    let _ = writer
+                        .write_all(format!("HTTP/1.1 400 Bad Request\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+                        .await;
+                    return;
+                }
+            };
+
+            if request.stream {
+                let header = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncache-control: no-cache\r\n\r\n";
+                if writer.write_all(header.as_bytes()).await.is_err() {
+                    return;
+                }
+                if let Err(e) = handle_chat_completions_streaming(&monitor, &request, &mut writer).await {
+                    warn!("streaming proxy call failed: {e}");
+                }
+            } else {
+                match handle_chat_completions_buffered(&monitor, &request).await {
+                    Ok(response) => {
+                        let body = serde_json::to_string(&response).unwrap_or_default();
+                        let _ = writer
+                            .write_all(format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+                            .await;
+                    }
+                    Err(e) => {
+                        let body = format!("{{\"error\":\"{e}\"}}");
+                        let _ = writer
+                            .write_all(format!("HTTP/1.1 502 Bad Gateway\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}", body.len(), body).as_bytes())
+                            .await;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// `api-monitoring benchmark <workload.json> [report.json]`: run a
+/// workload file's requests through [`GeminiMonitor::call_api`], write the
+/// aggregated [`WorkloadReport`] to `report.json`, and print a one-line
+/// summary.
+async fn run_benchmark_command(workload_path: &str, report_path: &str) -> Result<()> {
+    let workload: Workload = serde_json::from_str(
+        &fs::read_to_string(workload_path).with_context(|| format!("reading workload file {workload_path}"))?,
+    )
+    .with_context(|| format!("parsing workload file {workload_path}"))?;
+
+    let monitor = GeminiMonitor::new(resolve_backend())?;
+    let report = run_workload(&monitor, &workload).await?;
+
+    fs::write(report_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("writing benchmark report to {report_path}"))?;
+
+    println!(
+        "Workload '{}': {}/{} succeeded ({:.1}%), p50={}ms p90={}ms p99={}ms, report written to {}",
+        report.workload,
+        report.successes,
+        report.total_requests,
+        report.success_rate * 100.0,
+        report.p50_duration_ms,
+        report.p90_duration_ms,
+        report.p99_duration_ms,
+        report_path,
+    );
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
     tracing_subscriber::fmt()
         .with_env_filter("debug")
         .init();
-    
-    // Load API key
-    let api_key = std::env::var("GEMINI_API_KEY")
-        .expect("GEMINI_API_KEY must be set");
-    
-    let monitor = GeminiMonitor::new(api_key)?;
-    
+
+    let cli_args: Vec<String> = std::env::args().collect();
+    if cli_args.get(1).map(String::as_str) == Some("benchmark") {
+        let workload_path = cli_args
+            .get(2)
+            .context("usage: api-monitoring benchmark <workload.json> [report.json]")?;
+        let report_path = cli_args.get(3).map_or("benchmark_report.json", String::as_str);
+        return run_benchmark_command(workload_path, report_path).await;
+    }
+    if cli_args.get(1).map(String::as_str) == Some("serve") {
+        let addr = cli_args.get(2).map_or("127.0.0.1:8081", String::as_str);
+        return run_serve_command(addr).await;
+    }
+
+    let monitor = GeminiMonitor::new(resolve_backend())?;
+
     // Test different request patterns
     let test_requests = vec![
         // Valid request
@@ -278,11 +1164,28 @@ async fn main() -> Result<()> {
         }),
     ];
     
-    for (i, request) in test_requests.into_iter().enumerate() {
+    // Run the sweep across a bounded worker pool instead of serially, with
+    // a rate limiter standing in for the old blanket per-call sleep.
+    let concurrency = default_concurrency_limit();
+    let rate_limiter = RateLimiter::per_minute(60);
+
+    let mut results: Vec<(usize, Result<ApiObservation>)> = futures::stream::iter(test_requests.into_iter().enumerate().map(|(i, request)| {
+        let monitor = &monitor;
+        let rate_limiter = &rate_limiter;
+        async move {
+            rate_limiter.acquire().await;
+            debug!("Request {}: {}", i + 1, serde_json::to_string_pretty(&request).unwrap_or_default());
+            (i, monitor.call_api(request).await)
+        }
+    }))
+    .buffer_unordered(concurrency)
+    .collect()
+    .await;
+    results.sort_by_key(|(i, _)| *i);
+
+    for (i, result) in results {
         println!("\n=== Test {} ===", i + 1);
-        debug!("Request: {}", serde_json::to_string_pretty(&request)?);
-        
-        match monitor.call_api(request).await {
+        match result {
             Ok(observation) => {
                 println!("Status: {}", observation.response.status);
                 println!("Valid: {}", observation.validation.is_valid);
@@ -298,11 +1201,8 @@ async fn main() -> Result<()> {
                 println!("Request failed: {}", e);
             }
         }
-        
-        // Rate limit courtesy delay
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     }
-    
+
     println!("\n=== Summary ===");
     println!("Observations logged to: {}/observations.jsonl", monitor.log_dir);
     
@@ -79,6 +79,109 @@ struct FunctionResponse {
     response: Value,
 }
 
+/// A tool executor, keyed by function name in [`build_tool_registry`] —
+/// the minimal stand-in for the main crate's `ToolRegistry::execute()`
+/// this self-contained experiment can have without a shared `lib.rs`.
+type ToolFn = fn(&Value) -> Result<Value>;
+
+fn build_tool_registry() -> HashMap<&'static str, ToolFn> {
+    HashMap::from([("read_file", execute_read_file as ToolFn)])
+}
+
+fn execute_read_file(args: &Value) -> Result<Value> {
+    let file_path = args["file_path"].as_str().ok_or_else(|| anyhow::anyhow!("Missing file_path parameter"))?;
+    match fs::read_to_string(file_path) {
+        Ok(content) => Ok(json!({ "exists": true, "file_path": file_path, "content": content })),
+        Err(e) => Ok(json!({ "exists": false, "file_path": file_path, "error": e.to_string() })),
+    }
+}
+
+/// Canonical cache key for `(name, args)`: sorting the JSON text isn't
+/// needed here since every tool call has exactly one argument, but the
+/// raw serialized args are still a stable enough key for exact repeats.
+fn call_cache_key(function_call: &FunctionCall) -> (String, String) {
+    (function_call.name.clone(), function_call.args.to_string())
+}
+
+/// Drive the full multi-step tool-calling loop: send the request, dispatch
+/// every `functionCall` part in the response to the matching entry in
+/// `registry`, append a `model` turn with the original calls and a
+/// `user` turn with the corresponding `FunctionResponse`s, and re-send —
+/// until a response carries no function calls or `max_steps` is reached.
+/// Identical `(name, args)` calls are only executed once per loop; later
+/// repeats reuse the cached result instead of re-running a
+/// potentially side-effecting tool.
+async fn run_tool_loop(
+    client: &Client,
+    model: &str,
+    api_key: &str,
+    mut contents: Vec<Content>,
+    tools: Vec<Tool>,
+    registry: &HashMap<&str, ToolFn>,
+    max_steps: usize,
+) -> Result<(String, Vec<String>)> {
+    let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}", model, api_key);
+    let mut called = Vec::new();
+    let mut cache: HashMap<(String, String), Value> = HashMap::new();
+
+    for step in 0..max_steps {
+        let request = GenerateRequest { contents: contents.clone(), tools: Some(tools.clone()) };
+        let response: Value = client.post(&url).json(&request).send().await?.json().await?;
+
+        let Some(parts) = response["candidates"][0]["content"]["parts"].as_array().cloned() else {
+            warn!("No candidates in response");
+            return Ok((String::new(), called));
+        };
+
+        let mut function_calls = Vec::new();
+        let mut text_parts = Vec::new();
+        for part in &parts {
+            if let Some(func_call) = part.get("functionCall") {
+                function_calls.push(FunctionCall {
+                    name: func_call["name"].as_str().unwrap_or("").to_string(),
+                    args: func_call["args"].clone(),
+                });
+            } else if let Some(text) = part.get("text").and_then(Value::as_str) {
+                text_parts.push(text.to_string());
+            }
+        }
+
+        if function_calls.is_empty() {
+            return Ok((text_parts.join("\n"), called));
+        }
+
+        debug!("Step {}/{}: model requested {} function call(s)", step + 1, max_steps, function_calls.len());
+        contents.push(Content {
+            role: "model".to_string(),
+            parts: function_calls.iter().map(|fc| Part::FunctionCall { function_call: fc.clone() }).collect(),
+        });
+
+        let mut response_parts = Vec::with_capacity(function_calls.len());
+        for function_call in &function_calls {
+            let key = call_cache_key(function_call);
+            let result = if let Some(cached) = cache.get(&key) {
+                debug!("Reusing cached result for {}({})", function_call.name, function_call.args);
+                cached.clone()
+            } else {
+                let result = match registry.get(function_call.name.as_str()) {
+                    Some(tool) => tool(&function_call.args).unwrap_or_else(|e| json!({ "error": e.to_string() })),
+                    None => json!({ "error": format!("Unknown function: {}", function_call.name) }),
+                };
+                cache.insert(key, result.clone());
+                result
+            };
+            called.push(function_call.name.clone());
+            response_parts.push(Part::FunctionResponse {
+                function_response: FunctionResponse { name: function_call.name.clone(), response: result },
+            });
+        }
+
+        contents.push(Content { role: "user".to_string(), parts: response_parts });
+    }
+
+    Err(anyhow::anyhow!("exceeded max_steps ({max_steps}) without reaching a final text answer"))
+}
+
 fn create_aggressive_tools() -> Vec<Tool> {
     vec![Tool {
         function_declarations: vec![
@@ -117,102 +220,48 @@ async fn main() -> Result<()> {
     info!("Testing with VERY explicit instructions...");
     
     let tools = create_aggressive_tools();
+    let registry = build_tool_registry();
     let client = Client::new();
-    
+
     // Ultra-explicit test case
-    let request = GenerateRequest {
-        contents: vec![
-            Content {
-                role: "user".to_string(),
-                parts: vec![Part::Text { 
-                    text: "SYSTEM: You are a file system assistant. You have a read_file tool available. When I ask about file contents, you MUST use the read_file tool. Do NOT say you cannot access files. Use the tools provided.".to_string() 
-                }],
-            },
-            Content {
-                role: "model".to_string(),
-                parts: vec![Part::Text { 
-                    text: "Understood. I have the read_file tool and will use it to read files when requested. I will not claim I cannot access files.".to_string() 
-                }],
-            },
-            Content {
-                role: "user".to_string(),
-                parts: vec![Part::Text { 
-                    text: "Use the read_file tool to read the Makefile. The file_path parameter should be 'Makefile'.".to_string() 
-                }],
-            }
-        ],
-        tools: Some(tools),
-    };
-    
+    let contents = vec![
+        Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text {
+                text: "SYSTEM: You are a file system assistant. You have a read_file tool available. When I ask about file contents, you MUST use the read_file tool. Do NOT say you cannot access files. Use the tools provided.".to_string()
+            }],
+        },
+        Content {
+            role: "model".to_string(),
+            parts: vec![Part::Text {
+                text: "Understood. I have the read_file tool and will use it to read files when requested. I will not claim I cannot access files.".to_string()
+            }],
+        },
+        Content {
+            role: "user".to_string(),
+            parts: vec![Part::Text {
+                text: "Use the read_file tool to read the Makefile. The file_path parameter should be 'Makefile'.".to_string()
+            }],
+        }
+    ];
+
     info!("Making API call with ultra-explicit instructions...");
-    
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-        model, api_key
-    );
-    
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await?;
-    
-    let status = response.status();
-    let text = response.text().await?;
-    
-    info!("Response status: {}", status);
-    
-    if status.is_success() {
-        if let Ok(response_json) = serde_json::from_str::<Value>(&text) {
-            info!("✅ API call successful!");
-            
-            // Check for function calls
-            if let Some(candidates) = response_json["candidates"].as_array() {
-                for candidate in candidates {
-                    if let Some(parts) = candidate["content"]["parts"].as_array() {
-                        for part in parts {
-                            if let Some(func_call) = part.get("functionCall") {
-                                info!("🎉 FUNCTION CALL DETECTED!");
-                                info!("Function: {}", func_call["name"]);
-                                info!("Args: {}", func_call["args"]);
-                                
-                                if func_call["name"] == "read_file" {
-                                    info!("✅ CORRECT: read_file function called!");
-                                    
-                                    if let Some(file_path) = func_call["args"]["file_path"].as_str() {
-                                        info!("File path: {}", file_path);
-                                        
-                                        // Execute the function
-                                        match fs::read_to_string(file_path) {
-                                            Ok(content) => {
-                                                info!("✅ File read successfully: {} bytes", content.len());
-                                                info!("First 100 chars: {}", &content[..content.len().min(100)]);
-                                            },
-                                            Err(e) => {
-                                                warn!("File read error: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                                return Ok(());
-                            } else if let Some(text) = part.get("text") {
-                                info!("Text response: {}", text.as_str().unwrap_or(""));
-                            }
-                        }
-                    }
-                }
+
+    match run_tool_loop(&client, model, &api_key, contents, tools, &registry, 5).await {
+        Ok((final_text, called)) => {
+            if called.is_empty() {
+                warn!("❌ NO FUNCTION CALLS DETECTED");
+                info!("Final response: {}", final_text);
+            } else {
+                info!("🎉 FUNCTION CALL(S) DETECTED: {:?}", called);
+                info!("✅ Reached final answer after {} tool call(s)", called.len());
+                info!("Final response: {}", final_text);
             }
-            
-            warn!("❌ NO FUNCTION CALLS DETECTED");
-            info!("Full response:");
-            println!("{}", serde_json::to_string_pretty(&response_json)?);
-        } else {
-            warn!("❌ Invalid JSON response");
-            info!("Raw response: {}", text);
         }
-    } else {
-        warn!("❌ API error {}: {}", status, text);
+        Err(e) => {
+            warn!("❌ Tool loop failed: {}", e);
+        }
     }
-    
+
     Ok(())
 }
\ No newline at end of file
@@ -11,6 +11,8 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::sync::mpsc;
+use threadpool::ThreadPool;
 use tracing::{info, warn, debug};
 
 // === Gemini API Types ===
@@ -20,6 +22,69 @@ struct GenerateRequest {
     contents: Vec<Content>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<Tool>>,
+    #[serde(rename = "toolConfig", skip_serializing_if = "Option::is_none")]
+    tool_config: Option<ToolConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ToolConfig {
+    #[serde(rename = "functionCallingConfig")]
+    function_calling_config: FunctionCallingConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct FunctionCallingConfig {
+    mode: String,
+    #[serde(rename = "allowedFunctionNames", skip_serializing_if = "Option::is_none")]
+    allowed_function_names: Option<Vec<String>>,
+}
+
+/// How strongly the model should be steered towards calling a function,
+/// mirroring Gemini's `toolConfig.functionCallingConfig`. `Auto` leaves the
+/// choice to the model (the default), `None` forbids tool calls, `Any` forces
+/// one of the advertised functions to be called, and `Function(name)` forces
+/// that specific function and strips the others from the advertised tools.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+enum ToolChoice {
+    #[default]
+    Auto,
+    None,
+    Any,
+    Function(String),
+}
+
+impl ToolChoice {
+    fn to_tool_config(&self) -> ToolConfig {
+        let (mode, allowed_function_names) = match self {
+            ToolChoice::Auto => ("AUTO".to_string(), None),
+            ToolChoice::None => ("NONE".to_string(), None),
+            ToolChoice::Any => ("ANY".to_string(), None),
+            ToolChoice::Function(name) => ("ANY".to_string(), Some(vec![name.clone()])),
+        };
+        ToolConfig {
+            function_calling_config: FunctionCallingConfig { mode, allowed_function_names },
+        }
+    }
+
+    /// When a specific function is chosen, only that declaration should be
+    /// advertised as callable, so the model has nothing else to pick from.
+    fn filter_tools(&self, tools: &[Tool]) -> Vec<Tool> {
+        match self {
+            ToolChoice::Function(name) => tools
+                .iter()
+                .map(|tool| Tool {
+                    function_declarations: tool
+                        .function_declarations
+                        .iter()
+                        .filter(|decl| &decl.name == name)
+                        .cloned()
+                        .collect(),
+                })
+                .filter(|tool| !tool.function_declarations.is_empty())
+                .collect(),
+            _ => tools.to_vec(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -326,6 +391,38 @@ fn execute_function(function_call: &FunctionCall) -> Result<Value> {
     }
 }
 
+/// Runs every `FunctionCall` from one model turn concurrently on a
+/// `threadpool::ThreadPool` sized to `num_cpus::get()`. The file tools in
+/// `execute_function` are blocking `std::fs` calls, so dispatching them onto
+/// worker threads and joining via a channel keeps the tokio runtime
+/// unblocked and cuts latency when the model fans out several calls at
+/// once. Results are collected back in the same order as `function_calls`
+/// so the returned `FunctionResponse`s line up with what the model asked
+/// for, regardless of which call finishes first.
+fn execute_functions_parallel(function_calls: &[FunctionCall]) -> Vec<Result<Value>> {
+    let pool = ThreadPool::new(num_cpus::get());
+    let (tx, rx) = mpsc::channel();
+
+    for (index, function_call) in function_calls.iter().enumerate() {
+        let tx = tx.clone();
+        let function_call = function_call.clone();
+        pool.execute(move || {
+            let result = execute_function(&function_call);
+            tx.send((index, result)).expect("receiver dropped before all results were sent");
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<Option<Result<Value>>> = (0..function_calls.len()).map(|_| None).collect();
+    for (index, result) in rx {
+        results[index] = Some(result);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every function call index is sent exactly once"))
+        .collect()
+}
+
 // === API Testing ===
 
 async fn test_function_calling_flow() -> Result<()> {
@@ -374,6 +471,7 @@ async fn test_function_calling_flow() -> Result<()> {
     let request = GenerateRequest {
         contents: conversation.clone(),
         tools: Some(tools.clone()),
+        tool_config: None,
     };
     
     info!("\n--- Request Structure ---");
@@ -438,19 +536,22 @@ async fn test_function_calling_flow() -> Result<()> {
     let full_request = GenerateRequest {
         contents: conversation.clone(),
         tools: Some(tools.clone()),
+        tool_config: None,
     };
     info!("Request JSON:");
     println!("{}", serde_json::to_string_pretty(&full_request)?);
     
-    // Try actual API call if key is available
+    // Try the real agentic loop if a key is available: this re-sends the
+    // conversation after every function call until the model settles on a
+    // text-only answer, rather than stopping after the one simulated call.
     if api_key != "mock-api-key" {
-        info!("\n--- Making Real API Call ---");
-        match make_api_call(&client, &model, &api_key, &full_request).await {
-            Ok(response) => {
-                info!("API Response: {:?}", response);
+        info!("\n--- Running Agentic Function-Calling Loop ---");
+        match run_function_loop(&client, &model, &api_key, conversation.clone(), &tools, ToolChoice::Auto, DEFAULT_MAX_STEPS).await {
+            Ok(answer) => {
+                info!("Final answer: {}", answer);
             }
             Err(e) => {
-                warn!("API call failed (expected in test): {}", e);
+                warn!("Function-calling loop failed (expected in test): {}", e);
             }
         }
     }
@@ -458,6 +559,80 @@ async fn test_function_calling_flow() -> Result<()> {
     Ok(())
 }
 
+/// Upper bound on how many agentic turns `run_function_loop` takes before
+/// giving up, so a model that keeps calling functions forever can't hang
+/// the experiment.
+const DEFAULT_MAX_STEPS: usize = 10;
+
+/// Drives `conversation` through the real Gemini endpoint to completion:
+/// each turn calls `make_api_call`, executes every `Part::FunctionCall`
+/// the model returned via `execute_function`, appends a
+/// `Content { role: "function", .. }` turn with the results, and resends
+/// the whole conversation. Stops once a turn's parts are all
+/// `Part::Text`, returning that text as the final answer, or once
+/// `max_steps` turns have elapsed without one. `tool_choice` is applied to
+/// every turn's request, so e.g. `ToolChoice::Function("read_file")` forces
+/// the model to call that function on the first turn.
+async fn run_function_loop(
+    client: &Client,
+    model: &str,
+    api_key: &str,
+    mut conversation: Vec<Content>,
+    tools: &[Tool],
+    tool_choice: ToolChoice,
+    max_steps: usize,
+) -> Result<String> {
+    let advertised_tools = tool_choice.filter_tools(tools);
+    for step in 0..max_steps {
+        let request = GenerateRequest {
+            contents: conversation.clone(),
+            tools: Some(advertised_tools.clone()),
+            tool_config: Some(tool_choice.to_tool_config()),
+        };
+
+        let response = make_api_call(client, model, api_key, &request).await?;
+        let candidate = response
+            .candidates
+            .and_then(|mut candidates| (!candidates.is_empty()).then(|| candidates.remove(0)))
+            .ok_or_else(|| anyhow::anyhow!("no candidates in response"))?;
+
+        let mut function_calls = Vec::new();
+        let mut text_parts = Vec::new();
+        for part in &candidate.content.parts {
+            match part {
+                Part::FunctionCall { function_call } => function_calls.push(function_call.clone()),
+                Part::Text { text } => text_parts.push(text.clone()),
+                Part::FunctionResponse { .. } => {}
+            }
+        }
+
+        if function_calls.is_empty() {
+            return Ok(text_parts.join("\n"));
+        }
+
+        debug!("Step {}/{}: model requested {} function call(s)", step + 1, max_steps, function_calls.len());
+        conversation.push(candidate.content);
+
+        let results = execute_functions_parallel(&function_calls);
+        let mut response_parts = Vec::with_capacity(function_calls.len());
+        for (function_call, result) in function_calls.iter().zip(results) {
+            response_parts.push(Part::FunctionResponse {
+                function_response: FunctionResponse {
+                    name: function_call.name.clone(),
+                    response: result?,
+                },
+            });
+        }
+
+        conversation.push(Content {
+            role: "function".to_string(),
+            parts: response_parts,
+        });
+    }
+
+    Err(anyhow::anyhow!("exceeded max_steps ({}) without reaching a final text answer", max_steps))
+}
+
 async fn make_api_call(
     client: &Client,
     model: &str,
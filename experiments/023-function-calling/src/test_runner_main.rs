@@ -1,18 +1,124 @@
 //! Main entry point for the test runner
 
-use anyhow::Result;
-use function_calling::test_runner::{run_test_suite, print_summary};
+use anyhow::{bail, Result};
+use function_calling::test_runner::{
+    collect_test_files, reporter_from_name, run_test_suite, run_test_suite_watch, RunOptions, TestMode,
+    DEFAULT_TEST_EXTENSIONS, DEFAULT_TEST_IGNORE,
+};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
 use tracing_subscriber;
 
+/// Parsed command-line flags for this binary. There's no clap dependency in
+/// this crate yet and the flag set is small, so hand-rolled parsing keeps
+/// things simple: one or more positional test path/directory arguments,
+/// `--concurrency N`, `--seed N`, `--shard i/n`, `--filter <substring>`,
+/// `--bless`, `--reporter <pretty|json|junit>`, `--report-path <path>`,
+/// `--watch`.
+struct Args {
+    roots: Vec<PathBuf>,
+    options: RunOptions,
+    watch: bool,
+    reporter: String,
+    report_path: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut roots = Vec::new();
+    let mut options = RunOptions {
+        concurrency: 4,
+        seed: None,
+        shard: None,
+        filter: None,
+        bless: false,
+    };
+    let mut watch = false;
+    let mut reporter = "pretty".to_string();
+    let mut report_path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--concurrency" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--concurrency requires a value"))?;
+                options.concurrency = value.parse()?;
+            }
+            "--seed" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--seed requires a value"))?;
+                options.seed = Some(value.parse()?);
+            }
+            "--shard" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--shard requires an i/n value"))?;
+                let (i, n) = value
+                    .split_once('/')
+                    .ok_or_else(|| anyhow::anyhow!("--shard expects the form i/n, got '{}'", value))?;
+                let (i, n): (u32, u32) = (i.parse()?, n.parse()?);
+                if n == 0 || i >= n {
+                    bail!("--shard {} is out of range: i must be < n and n must be > 0", value);
+                }
+                options.shard = Some((i, n));
+            }
+            "--filter" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--filter requires a value"))?;
+                options.filter = Some(value);
+            }
+            "--bless" => options.bless = true,
+            "--reporter" => {
+                reporter = args.next().ok_or_else(|| anyhow::anyhow!("--reporter requires a value"))?;
+            }
+            "--report-path" => {
+                let value = args.next().ok_or_else(|| anyhow::anyhow!("--report-path requires a value"))?;
+                report_path = Some(PathBuf::from(value));
+            }
+            "--watch" => watch = true,
+            other if other.starts_with("--") => bail!("unrecognized argument: {}", other),
+            path => roots.push(PathBuf::from(path)),
+        }
+    }
+
+    if roots.is_empty() {
+        roots.push(PathBuf::from("tests"));
+    }
+
+    Ok(Args { roots, options, watch, reporter, report_path })
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt()
         .with_target(false)
         .init();
 
-    let test_file = "tests/test_cases.json";
-    let results = run_test_suite(test_file)?;
-    print_summary(&results);
-    
+    // Live mode needs a real GEMINI_API_KEY and hits the actual endpoint;
+    // Mock stays the default so CI can run this without one.
+    let mode = match env::var("GEMINI_TEST_MODE") {
+        Ok(value) if value.eq_ignore_ascii_case("live") => TestMode::Live,
+        _ => TestMode::Mock,
+    };
+
+    let args = parse_args()?;
+
+    let include: Vec<String> = DEFAULT_TEST_EXTENSIONS.iter().map(|ext| ext.to_string()).collect();
+    let ignore: Vec<String> = DEFAULT_TEST_IGNORE.iter().map(|pattern| pattern.to_string()).collect();
+    let test_files = collect_test_files(&args.roots, &include, &ignore)?;
+    if test_files.is_empty() {
+        bail!("no test files found under {:?}", args.roots);
+    }
+
+    if args.watch {
+        return run_test_suite_watch(&test_files, &[PathBuf::from("src")], mode, args.options).await;
+    }
+
+    let results = run_test_suite(&test_files, mode, args.options).await?;
+
+    let reporter = reporter_from_name(&args.reporter)?;
+    if let Some(report) = reporter.report(&results) {
+        let path = args
+            .report_path
+            .ok_or_else(|| anyhow::anyhow!("--reporter {} requires --report-path", args.reporter))?;
+        fs::write(&path, report)?;
+    }
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,55 @@
+//! honggfuzz target for the proportion-based `StatisticalAnalyzer` methods.
+//!
+//! Feeds arbitrary `(successes, total)` pairs decoded from raw fuzz bytes
+//! into `analyze_results`, `two_proportion_test`, and `compare_groups`,
+//! deliberately including the degenerate shapes those methods are now
+//! hardened against: `total == 0`, `successes > total`, and groups that are
+//! entirely successes or entirely failures. Run with `cargo hfuzz run
+//! statistical_fuzz` from this directory.
+
+use honggfuzz::fuzz;
+use statistical_evaluation::statistical_framework::{ExperimentConfig, StatisticalAnalyzer};
+
+/// Reads 8 little-endian bytes starting at `offset` (zero-padding past the
+/// end of `data`) and reduces them to a small `usize` so most fuzz inputs
+/// land in the interesting total=0..~1000 range rather than overflowing.
+fn take_usize(data: &[u8], offset: usize) -> usize {
+    let mut buf = [0u8; 8];
+    if let Some(slice) = data.get(offset..offset + 8) {
+        buf.copy_from_slice(slice);
+    }
+    usize::from_le_bytes(buf) % 1000
+}
+
+fn main() {
+    let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+
+    loop {
+        fuzz!(|data: &[u8]| {
+            if data.len() < 32 {
+                return;
+            }
+
+            let successes1 = take_usize(data, 0);
+            let total1 = take_usize(data, 8);
+            let successes2 = take_usize(data, 16);
+            let total2 = take_usize(data, 24);
+
+            let result = analyzer.analyze_results(successes1, total1);
+            assert!(result.success_rate.is_finite());
+            assert!(result.z_score.is_finite());
+            assert!(result.p_value.is_finite());
+            assert!(result.effect_size.is_finite());
+            assert!(result.standard_error.is_finite());
+
+            let (z, p, _significant) = analyzer.two_proportion_test(successes1, total1, successes2, total2);
+            assert!(z.is_finite());
+            assert!(p.is_finite());
+
+            let comparison = analyzer.compare_groups(successes1, total1, successes2, total2);
+            assert!(comparison.odds_ratio.is_finite());
+            assert!(comparison.chi_squared.is_finite());
+            assert!(comparison.chi_squared_p_value.is_finite());
+        });
+    }
+}
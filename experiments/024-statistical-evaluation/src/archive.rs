@@ -0,0 +1,99 @@
+//! Zero-copy archival of raw trial data via rkyv.
+//!
+//! `StatisticalAnalyzer::analyze_results`/`bootstrap_confidence_interval`
+//! only ever see summary counts, so re-analyzing a finished experiment with
+//! a different confidence level or more bootstrap iterations normally means
+//! re-running it. `archive_trials` keeps the raw per-trial outcomes (plus
+//! the `ExperimentConfig` that produced them) on disk instead, and
+//! `load_archived` memory-maps that file back so a huge trial vector can be
+//! re-analyzed without a full deserialization pass.
+
+use anyhow::{Context, Result};
+use rkyv::Deserialize as _;
+use std::fs;
+use std::path::Path;
+
+use crate::statistical_framework::ExperimentConfig;
+
+/// On-disk rkyv archive of one experiment's raw boolean trial outcomes
+/// plus the `ExperimentConfig` that produced them.
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Debug, Clone)]
+#[archive(check_bytes)]
+pub struct TrialArchive {
+    pub config: ExperimentConfig,
+    pub outcomes: Vec<bool>,
+}
+
+/// Serializes `config` and `outcomes` to `path` as a single rkyv-encoded
+/// `TrialArchive`.
+pub fn archive_trials(path: &Path, config: &ExperimentConfig, outcomes: &[bool]) -> Result<()> {
+    let archive = TrialArchive { config: config.clone(), outcomes: outcomes.to_vec() };
+    let bytes = rkyv::to_bytes::<_, 4096>(&archive).map_err(|e| anyhow::anyhow!("serializing trial archive: {e}"))?;
+    fs::write(path, &bytes).with_context(|| format!("writing {}", path.display()))
+}
+
+/// A memory-mapped, validated view of a `TrialArchive` on disk. Reading
+/// `outcomes()` doesn't deserialize the trial vector at all; only
+/// `config()` (small and read once per load) pays a deserialization cost.
+pub struct ArchivedTrials {
+    mmap: memmap2::Mmap,
+}
+
+impl ArchivedTrials {
+    fn archive(&self) -> &ArchivedTrialArchive {
+        rkyv::check_archived_root::<TrialArchive>(&self.mmap[..]).expect("corrupt trial archive")
+    }
+
+    /// The raw per-trial outcomes, read directly out of the mapped file.
+    #[must_use]
+    pub fn outcomes(&self) -> &[bool] {
+        self.archive().outcomes.as_slice()
+    }
+
+    /// The `ExperimentConfig` the trials were run under.
+    #[must_use]
+    pub fn config(&self) -> ExperimentConfig {
+        self.archive().config.deserialize(&mut rkyv::Infallible).expect("deserializing archived config")
+    }
+}
+
+/// Memory-maps `path` and returns a zero-copy, `check_bytes`-validated view
+/// of the `TrialArchive` written there by `archive_trials`.
+///
+/// # Errors
+///
+/// Returns an error if `path` can't be opened or mapped.
+pub fn load_archived(path: &Path) -> Result<ArchivedTrials> {
+    let file = fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mmap = unsafe { memmap2::Mmap::map(&file) }.with_context(|| format!("mapping {}", path.display()))?;
+    Ok(ArchivedTrials { mmap })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statistical_framework::StatisticalAnalyzer;
+
+    #[test]
+    fn archived_trials_round_trip_into_analyze_results() {
+        let config = ExperimentConfig::default();
+        let outcomes = vec![true, true, false, true, true, false, true, true, true, false];
+
+        let dir = std::env::temp_dir().join(format!("trial-archive-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trials.rkyv");
+
+        archive_trials(&path, &config, &outcomes).unwrap();
+        let loaded = load_archived(&path).unwrap();
+
+        assert_eq!(loaded.outcomes(), outcomes.as_slice());
+        assert_eq!(loaded.config().min_sample_size, config.min_sample_size);
+
+        let successes = loaded.outcomes().iter().filter(|&&ok| ok).count();
+        let analyzer = StatisticalAnalyzer::new(loaded.config());
+        let result = analyzer.analyze_results(successes, loaded.outcomes().len());
+        assert_eq!(result.sample_size, outcomes.len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,9 @@
 //! and temporal logic properties
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use serde::{Serialize, Deserialize};
 
 /// REPL States
@@ -50,131 +53,404 @@ pub enum ParseResult {
     Invalid,
 }
 
+/// A [`ReplState`]/[`Event`]/[`ReplState`] edge stripped of any payload
+/// the event or states carry, so ten different user inputs (or ten
+/// different function names) all count as exercising the same edge.
+/// Coverage is tracked at this granularity rather than on the full
+/// `transitions` keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    Start,
+    UserInput,
+    ParseComplete,
+    FunctionCallRequired,
+    FunctionCallComplete,
+    ModelQueryRequired,
+    ModelResponseReceived,
+    OutputReady,
+    ErrorOccurred,
+    Reset,
+}
+
+impl From<&Event> for EventKind {
+    fn from(event: &Event) -> Self {
+        match event {
+            Event::Start => EventKind::Start,
+            Event::UserInput(_) => EventKind::UserInput,
+            Event::ParseComplete(_) => EventKind::ParseComplete,
+            Event::FunctionCallRequired(_) => EventKind::FunctionCallRequired,
+            Event::FunctionCallComplete(_) => EventKind::FunctionCallComplete,
+            Event::ModelQueryRequired => EventKind::ModelQueryRequired,
+            Event::ModelResponseReceived(_) => EventKind::ModelResponseReceived,
+            Event::OutputReady(_) => EventKind::OutputReady,
+            Event::ErrorOccurred(_) => EventKind::ErrorOccurred,
+            Event::Reset => EventKind::Reset,
+        }
+    }
+}
+
+/// [`ReplState`] stripped of the payload `transition` shouldn't key on: a
+/// `CallingFunction("foo")` and a `CallingFunction("bar")` are the same
+/// place in the state graph, so they collapse to one `StateKind` instead
+/// of needing one transition-table entry per function name. `Error`
+/// keeps its [`ErrorState`], since that's already a plain discriminant
+/// and different error states do mean different places in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum StateKind {
+    Init,
+    AwaitingInput,
+    ParsingCommand,
+    ProcessingCommand,
+    CallingFunction,
+    AwaitingModel,
+    GeneratingResponse,
+    DisplayingOutput,
+    Error(ErrorState),
+}
+
+impl From<&ReplState> for StateKind {
+    fn from(state: &ReplState) -> Self {
+        match state {
+            ReplState::Init => StateKind::Init,
+            ReplState::AwaitingInput => StateKind::AwaitingInput,
+            ReplState::ParsingCommand => StateKind::ParsingCommand,
+            ReplState::ProcessingCommand => StateKind::ProcessingCommand,
+            ReplState::CallingFunction(_) => StateKind::CallingFunction,
+            ReplState::AwaitingModel => StateKind::AwaitingModel,
+            ReplState::GeneratingResponse => StateKind::GeneratingResponse,
+            ReplState::DisplayingOutput => StateKind::DisplayingOutput,
+            ReplState::Error(error) => StateKind::Error(error.clone()),
+        }
+    }
+}
+
+/// Borrowed from the idea behind Deno's `CoverageCollector`: records every
+/// `(ReplState, EventKind) -> ReplState` edge a running
+/// [`ReplStateMachine`] actually takes, so real traffic can later be
+/// diffed against the fully-modeled `transitions` graph to find dead or
+/// unreachable edges.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageCollector {
+    edges: HashSet<(ReplState, EventKind, ReplState)>,
+}
+
+impl CoverageCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, from: ReplState, event: &Event, to: ReplState) {
+        self.edges.insert((from, EventKind::from(event), to));
+    }
+
+    /// Folds another collector's edges into this one, e.g. to combine
+    /// several sessions' serialized coverage into one batch report.
+    pub fn merge(&mut self, other: &CoverageCollector) {
+        self.edges.extend(other.edges.iter().cloned());
+    }
+
+    /// Diffs the recorded edges against every edge `checker` can reach
+    /// within `max_depth`. The transition table is keyed on
+    /// [`StateKind`]/[`EventKind`] pairs rather than concrete states, so
+    /// walking it directly can no longer enumerate concrete edges; the
+    /// model checker's own reachability search is the source of truth.
+    pub fn report(&self, checker: &ModelChecker, max_depth: usize) -> CoverageReport {
+        let all_edges = checker.all_edges(max_depth);
+
+        let dead_edges: Vec<_> = all_edges
+            .iter()
+            .filter(|edge| !self.edges.contains(*edge))
+            .cloned()
+            .collect();
+
+        let all_states: HashSet<ReplState> = all_edges
+            .iter()
+            .flat_map(|(from, _, to)| [from.clone(), to.clone()])
+            .collect();
+        let covered_states: HashSet<ReplState> = self
+            .edges
+            .iter()
+            .flat_map(|(from, _, to)| [from.clone(), to.clone()])
+            .collect();
+
+        CoverageReport {
+            states_covered: covered_states.len(),
+            total_states: all_states.len(),
+            edges_covered: all_edges.len() - dead_edges.len(),
+            total_edges: all_edges.len(),
+            dead_edges,
+        }
+    }
+}
+
+/// Summary produced by [`CoverageCollector::report`]: how much of the
+/// formally-modeled state graph a session (or a batch of merged sessions)
+/// actually exercised, plus the edges that were never hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub states_covered: usize,
+    pub total_states: usize,
+    pub edges_covered: usize,
+    pub total_edges: usize,
+    pub dead_edges: Vec<(ReplState, EventKind, ReplState)>,
+}
+
+impl CoverageReport {
+    pub fn state_coverage_percent(&self) -> f64 {
+        if self.total_states == 0 {
+            100.0
+        } else {
+            self.states_covered as f64 / self.total_states as f64 * 100.0
+        }
+    }
+
+    pub fn edge_coverage_percent(&self) -> f64 {
+        if self.total_edges == 0 {
+            100.0
+        } else {
+            self.edges_covered as f64 / self.total_edges as f64 * 100.0
+        }
+    }
+}
+
+impl std::fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "States covered: {}/{} ({:.1}%)",
+            self.states_covered,
+            self.total_states,
+            self.state_coverage_percent()
+        )?;
+        writeln!(
+            f,
+            "Edges covered: {}/{} ({:.1}%)",
+            self.edges_covered,
+            self.total_edges,
+            self.edge_coverage_percent()
+        )?;
+        if !self.dead_edges.is_empty() {
+            writeln!(f, "Dead edges:")?;
+            for (from, event, to) in &self.dead_edges {
+                writeln!(f, "  {:?} --{:?}--> {:?}", from, event, to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One entry in the transition table. `guard`, when present, inspects
+/// the triggering event's payload to disambiguate cases [`EventKind`]
+/// has already collapsed together (e.g. telling `FunctionCallComplete(Ok(_))`
+/// from `FunctionCallComplete(Err(_))`); the first rule for a key whose
+/// guard passes (or has none) wins. `target` builds the resulting
+/// [`ReplState`] from the event, so payload data the key threw away (a
+/// function name, say) can still reach the next state.
+struct TransitionRule {
+    guard: Option<fn(&Event) -> bool>,
+    target: fn(&Event) -> ReplState,
+}
+
+impl TransitionRule {
+    fn matches(&self, event: &Event) -> bool {
+        self.guard.is_none_or(|guard| guard(event))
+    }
+}
+
+/// Builds the `CallingFunction` target from whichever event triggered
+/// it, carrying the real function name forward instead of a fixed
+/// placeholder.
+fn calling_function_target(event: &Event) -> ReplState {
+    match event {
+        Event::FunctionCallRequired(name) => ReplState::CallingFunction(name.clone()),
+        _ => ReplState::CallingFunction(String::new()),
+    }
+}
+
 /// State machine definition
 pub struct ReplStateMachine {
     current_state: ReplState,
     history: Vec<(ReplState, Event)>,
-    transitions: HashMap<(ReplState, Event), ReplState>,
+    transitions: HashMap<(StateKind, EventKind), Vec<TransitionRule>>,
+    coverage: Option<CoverageCollector>,
 }
 
 impl ReplStateMachine {
+    fn insert_rule(
+        transitions: &mut HashMap<(StateKind, EventKind), Vec<TransitionRule>>,
+        state_kind: StateKind,
+        event_kind: EventKind,
+        guard: Option<fn(&Event) -> bool>,
+        target: fn(&Event) -> ReplState,
+    ) {
+        transitions
+            .entry((state_kind, event_kind))
+            .or_default()
+            .push(TransitionRule { guard, target });
+    }
+
     pub fn new() -> Self {
         let mut transitions = HashMap::new();
-        
-        // Define all valid state transitions
+
         // Init transitions
-        transitions.insert(
-            (ReplState::Init, Event::Start),
+        Self::insert_rule(&mut transitions, StateKind::Init, EventKind::Start, None, |_| {
             ReplState::AwaitingInput
-        );
-        
+        });
+
         // AwaitingInput transitions
-        transitions.insert(
-            (ReplState::AwaitingInput, Event::UserInput(_)),
+        Self::insert_rule(&mut transitions, StateKind::AwaitingInput, EventKind::UserInput, None, |_| {
             ReplState::ParsingCommand
+        });
+
+        // ParsingCommand transitions: a valid parse and an invalid one
+        // share an `EventKind`, so they're disambiguated by guard.
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::ParsingCommand,
+            EventKind::ParseComplete,
+            Some(|event| {
+                matches!(
+                    event,
+                    Event::ParseComplete(ParseResult::Command(_)) | Event::ParseComplete(ParseResult::Query(_))
+                )
+            }),
+            |_| ReplState::ProcessingCommand,
         );
-        
-        // ParsingCommand transitions
-        transitions.insert(
-            (ReplState::ParsingCommand, Event::ParseComplete(ParseResult::Command(_))),
-            ReplState::ProcessingCommand
-        );
-        transitions.insert(
-            (ReplState::ParsingCommand, Event::ParseComplete(ParseResult::Query(_))),
-            ReplState::ProcessingCommand
-        );
-        transitions.insert(
-            (ReplState::ParsingCommand, Event::ParseComplete(ParseResult::Invalid)),
-            ReplState::Error(ErrorState::ParseError)
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::ParsingCommand,
+            EventKind::ParseComplete,
+            Some(|event| matches!(event, Event::ParseComplete(ParseResult::Invalid))),
+            |_| ReplState::Error(ErrorState::ParseError),
         );
-        
+
         // ProcessingCommand transitions
-        transitions.insert(
-            (ReplState::ProcessingCommand, Event::FunctionCallRequired(_)),
-            ReplState::CallingFunction("".to_string())
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::ProcessingCommand,
+            EventKind::FunctionCallRequired,
+            None,
+            calling_function_target,
         );
-        transitions.insert(
-            (ReplState::ProcessingCommand, Event::ModelQueryRequired),
+        Self::insert_rule(&mut transitions, StateKind::ProcessingCommand, EventKind::ModelQueryRequired, None, |_| {
             ReplState::AwaitingModel
-        );
-        transitions.insert(
-            (ReplState::ProcessingCommand, Event::OutputReady(_)),
+        });
+        Self::insert_rule(&mut transitions, StateKind::ProcessingCommand, EventKind::OutputReady, None, |_| {
             ReplState::DisplayingOutput
+        });
+
+        // CallingFunction transitions: Ok/Err share an `EventKind` too.
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::CallingFunction,
+            EventKind::FunctionCallComplete,
+            Some(|event| matches!(event, Event::FunctionCallComplete(Ok(_)))),
+            |_| ReplState::GeneratingResponse,
         );
-        
-        // CallingFunction transitions
-        transitions.insert(
-            (ReplState::CallingFunction(_), Event::FunctionCallComplete(Ok(_))),
-            ReplState::GeneratingResponse
-        );
-        transitions.insert(
-            (ReplState::CallingFunction(_), Event::FunctionCallComplete(Err(_))),
-            ReplState::Error(ErrorState::FunctionCallError)
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::CallingFunction,
+            EventKind::FunctionCallComplete,
+            Some(|event| matches!(event, Event::FunctionCallComplete(Err(_)))),
+            |_| ReplState::Error(ErrorState::FunctionCallError),
         );
-        
+
         // AwaitingModel transitions
-        transitions.insert(
-            (ReplState::AwaitingModel, Event::ModelResponseReceived(_)),
+        Self::insert_rule(&mut transitions, StateKind::AwaitingModel, EventKind::ModelResponseReceived, None, |_| {
             ReplState::GeneratingResponse
+        });
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::AwaitingModel,
+            EventKind::ErrorOccurred,
+            Some(|event| matches!(event, Event::ErrorOccurred(ErrorState::TimeoutError))),
+            |_| ReplState::Error(ErrorState::TimeoutError),
         );
-        transitions.insert(
-            (ReplState::AwaitingModel, Event::ErrorOccurred(ErrorState::TimeoutError)),
-            ReplState::Error(ErrorState::TimeoutError)
-        );
-        
+
         // GeneratingResponse transitions
-        transitions.insert(
-            (ReplState::GeneratingResponse, Event::OutputReady(_)),
+        Self::insert_rule(&mut transitions, StateKind::GeneratingResponse, EventKind::OutputReady, None, |_| {
             ReplState::DisplayingOutput
+        });
+        Self::insert_rule(
+            &mut transitions,
+            StateKind::GeneratingResponse,
+            EventKind::FunctionCallRequired,
+            None,
+            calling_function_target,
         );
-        transitions.insert(
-            (ReplState::GeneratingResponse, Event::FunctionCallRequired(_)),
-            ReplState::CallingFunction("".to_string())
-        );
-        
+
         // DisplayingOutput transitions
-        transitions.insert(
-            (ReplState::DisplayingOutput, Event::Reset),
+        Self::insert_rule(&mut transitions, StateKind::DisplayingOutput, EventKind::Reset, None, |_| {
             ReplState::AwaitingInput
-        );
-        
+        });
+
         // Error state transitions
-        for error in vec![
+        for error in [
             ErrorState::ParseError,
             ErrorState::FunctionCallError,
             ErrorState::ModelError,
             ErrorState::TimeoutError,
         ] {
-            transitions.insert(
-                (ReplState::Error(error), Event::Reset),
+            Self::insert_rule(&mut transitions, StateKind::Error(error), EventKind::Reset, None, |_| {
                 ReplState::AwaitingInput
-            );
+            });
         }
-        
+
         Self {
             current_state: ReplState::Init,
             history: Vec::new(),
             transitions,
+            coverage: None,
         }
     }
-    
+
+    /// Looks up the next state for `event` fired from `state`, trying
+    /// each rule registered under the `(StateKind, EventKind)` key in
+    /// insertion order and taking the first whose guard matches.
+    fn lookup(&self, state: &ReplState, event: &Event) -> Option<ReplState> {
+        let key = (StateKind::from(state), EventKind::from(event));
+        self.transitions
+            .get(&key)?
+            .iter()
+            .find(|rule| rule.matches(event))
+            .map(|rule| (rule.target)(event))
+    }
+
+    /// Like [`Self::new`], but every transition taken is recorded into a
+    /// [`CoverageCollector`] so [`CoverageCollector::report`] can later
+    /// show which edges of the formally-modeled graph this session
+    /// actually exercised.
+    pub fn with_coverage() -> Self {
+        Self {
+            coverage: Some(CoverageCollector::new()),
+            ..Self::new()
+        }
+    }
+
+    /// The session's coverage collector, if this machine was built with
+    /// [`Self::with_coverage`].
+    pub fn coverage(&self) -> Option<&CoverageCollector> {
+        self.coverage.as_ref()
+    }
+
     pub fn transition(&mut self, event: Event) -> Result<ReplState, String> {
-        let key = (self.current_state.clone(), event.clone());
-        
-        if let Some(next_state) = self.transitions.get(&key).cloned() {
+        if let Some(next_state) = self.lookup(&self.current_state, &event) {
+            if let Some(coverage) = &mut self.coverage {
+                coverage.record(self.current_state.clone(), &event, next_state.clone());
+            }
             self.history.push((self.current_state.clone(), event));
             self.current_state = next_state.clone();
             Ok(next_state)
         } else {
-            Err(format!("Invalid transition from {:?} with event {:?}", 
+            Err(format!("Invalid transition from {:?} with event {:?}",
                        self.current_state, event))
         }
     }
-    
+
     pub fn current_state(&self) -> &ReplState {
         &self.current_state
     }
-    
+
     pub fn history(&self) -> &[(ReplState, Event)] {
         &self.history
     }
@@ -195,6 +471,40 @@ pub enum TemporalProperty {
     Until(ReplState, ReplState),
 }
 
+/// A concrete execution witnessing a property violation: the sequence of
+/// `(state, event)` steps taken from `Init`. For a safety violation
+/// (`Always`/`Never`) this is the path straight to the offending state;
+/// for a liveness violation (`LeadsTo`/`Until`) it's `prefix(Init..A) +
+/// cycle`, a lasso showing a path that enters the region of interest and
+/// then loops forever without ever reaching the required state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub path: Vec<(ReplState, Event)>,
+}
+
+impl std::fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            return write!(f, "(no witnessing path)");
+        }
+        for (state, event) in &self.path {
+            writeln!(f, "{:?} --{:?}-->", state, event)?;
+        }
+        Ok(())
+    }
+}
+
+/// A falsifying random walk found by [`ModelChecker::simulate`]. The seed
+/// plus the walk index pin down exactly which walk it was, so re-running
+/// `simulate` with the same seed deterministically reproduces it even
+/// without keeping `trace` around.
+#[derive(Debug, Clone)]
+pub struct SimulationViolation {
+    pub seed: u64,
+    pub walk_index: usize,
+    pub counterexample: Counterexample,
+}
+
 /// Model checker for verifying properties
 pub struct ModelChecker {
     state_machine: ReplStateMachine,
@@ -206,146 +516,304 @@ impl ModelChecker {
             state_machine: ReplStateMachine::new(),
         }
     }
-    
-    /// Check if a property holds for all possible execution paths
-    pub fn check_property(&self, property: &TemporalProperty, max_depth: usize) -> bool {
+
+    /// Check whether a property holds, returning the concrete execution
+    /// that violates it when it doesn't.
+    pub fn check_property(
+        &self,
+        property: &TemporalProperty,
+        max_depth: usize,
+    ) -> Result<(), Counterexample> {
         match property {
-            TemporalProperty::Eventually(target) => {
-                self.check_eventually(target, max_depth)
-            }
-            TemporalProperty::Always(states) => {
-                self.check_always(states, max_depth)
-            }
-            TemporalProperty::LeadsTo(from, to) => {
-                self.check_leads_to(from, to, max_depth)
-            }
-            TemporalProperty::Never(state) => {
-                !self.check_eventually(state, max_depth)
-            }
+            TemporalProperty::Eventually(target) => self.check_eventually(target, max_depth),
+            TemporalProperty::Always(states) => self.check_always(states, max_depth),
+            TemporalProperty::LeadsTo(from, to) => self.check_leads_to(from, to, max_depth),
+            TemporalProperty::Never(state) => self.check_never(state, max_depth),
             TemporalProperty::Until(state_a, state_b) => {
                 self.check_until(state_a, state_b, max_depth)
             }
         }
     }
-    
-    fn check_eventually(&self, target: &ReplState, max_depth: usize) -> bool {
-        let mut visited = HashSet::new();
+
+    /// BFS from `Init`, recording one witnessing path per first-reached
+    /// state. A flat visited set is sound here because plain reachability
+    /// ("does *some* path reach this state") doesn't need to distinguish
+    /// "on the current path" from "fully explored" the way cycle
+    /// detection does.
+    fn reachable_with_paths(&self, max_depth: usize) -> HashMap<ReplState, Vec<(ReplState, Event)>> {
+        let mut paths = HashMap::new();
+        paths.insert(ReplState::Init, Vec::new());
         let mut queue = VecDeque::new();
-        
-        queue.push_back((ReplState::Init, 0));
-        visited.insert(ReplState::Init);
-        
-        while let Some((state, depth)) = queue.pop_front() {
-            if &state == target {
-                return true;
-            }
-            
-            if depth >= max_depth {
+        queue.push_back(ReplState::Init);
+
+        while let Some(state) = queue.pop_front() {
+            let path = paths[&state].clone();
+            if path.len() >= max_depth {
                 continue;
             }
-            
-            // Explore all possible transitions from this state
             for event in self.get_possible_events(&state) {
                 if let Some(next_state) = self.get_next_state(&state, &event) {
-                    if !visited.contains(&next_state) {
-                        visited.insert(next_state.clone());
-                        queue.push_back((next_state, depth + 1));
+                    if !paths.contains_key(&next_state) {
+                        let mut next_path = path.clone();
+                        next_path.push((state.clone(), event));
+                        paths.insert(next_state.clone(), next_path);
+                        queue.push_back(next_state);
                     }
                 }
             }
         }
-        
-        false
+
+        paths
     }
-    
-    fn check_always(&self, valid_states: &[ReplState], max_depth: usize) -> bool {
-        let mut visited = HashSet::new();
-        let mut queue = VecDeque::new();
-        
-        queue.push_back((ReplState::Init, 0));
-        
-        while let Some((state, depth)) = queue.pop_front() {
-            if !valid_states.contains(&state) {
-                return false;
+
+    /// DFS from `start` over the reachable graph with `deleted` states
+    /// removed, marking each node on-stack while it's being explored and
+    /// done once fully explored. A transition into a node that's still
+    /// on-stack is a genuine back edge (a cycle), unlike a flat visited
+    /// set which would have silently treated it as already covered.
+    /// Returns the cycle, as the sequence of edges from the back-edge
+    /// target around to the edge that closes it.
+    fn find_cycle(
+        &self,
+        start: &ReplState,
+        deleted: &HashSet<ReplState>,
+        max_depth: usize,
+    ) -> Option<Vec<(ReplState, Event)>> {
+        let mut path_nodes = vec![start.clone()];
+        let mut path_events: Vec<Event> = Vec::new();
+        let mut done = HashSet::new();
+        self.find_cycle_dfs(&mut path_nodes, &mut path_events, &mut done, deleted, max_depth)
+    }
+
+    fn find_cycle_dfs(
+        &self,
+        path_nodes: &mut Vec<ReplState>,
+        path_events: &mut Vec<Event>,
+        done: &mut HashSet<ReplState>,
+        deleted: &HashSet<ReplState>,
+        max_depth: usize,
+    ) -> Option<Vec<(ReplState, Event)>> {
+        if path_nodes.len() > max_depth {
+            return None;
+        }
+        let node = path_nodes.last().unwrap().clone();
+
+        for event in self.get_possible_events(&node) {
+            let Some(next) = self.get_next_state(&node, &event) else {
+                continue;
+            };
+            if deleted.contains(&next) {
+                continue;
             }
-            
-            if depth >= max_depth || visited.contains(&state) {
+            if let Some(pos) = path_nodes.iter().position(|s| s == &next) {
+                // Back edge to a node still on the DFS stack: the stack
+                // from that node onward, plus this closing edge, is the
+                // cycle.
+                let mut cycle: Vec<(ReplState, Event)> = path_nodes[pos..]
+                    .iter()
+                    .cloned()
+                    .zip(path_events[pos..].iter().cloned())
+                    .collect();
+                cycle.push((node.clone(), event));
+                return Some(cycle);
+            }
+            if done.contains(&next) {
                 continue;
             }
-            
-            visited.insert(state.clone());
-            
-            for event in self.get_possible_events(&state) {
-                if let Some(next_state) = self.get_next_state(&state, &event) {
-                    queue.push_back((next_state, depth + 1));
-                }
+            path_nodes.push(next);
+            path_events.push(event);
+            if let Some(cycle) = self.find_cycle_dfs(path_nodes, path_events, done, deleted, max_depth) {
+                return Some(cycle);
             }
+            path_events.pop();
+            path_nodes.pop();
         }
-        
-        true
+
+        done.insert(node);
+        None
     }
-    
-    fn check_leads_to(&self, from: &ReplState, to: &ReplState, max_depth: usize) -> bool {
-        // For all paths starting from 'from' state, eventually reach 'to'
-        let mut visited_from = HashSet::new();
-        let mut queue = VecDeque::new();
-        
-        // First, find all instances of 'from' state
-        queue.push_back((ReplState::Init, 0, false));
-        
-        while let Some((state, depth, after_from)) = queue.pop_front() {
-            if depth >= max_depth {
-                continue;
+
+    fn check_eventually(&self, target: &ReplState, max_depth: usize) -> Result<(), Counterexample> {
+        if self.reachable_with_paths(max_depth).contains_key(target) {
+            Ok(())
+        } else {
+            // There's no finite trace to show for a state that's never
+            // reached; the violation is the absence of one.
+            Err(Counterexample { path: Vec::new() })
+        }
+    }
+
+    fn check_never(&self, target: &ReplState, max_depth: usize) -> Result<(), Counterexample> {
+        let reachable = self.reachable_with_paths(max_depth);
+        match reachable.get(target) {
+            Some(path) => Err(Counterexample { path: path.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    fn check_always(&self, valid_states: &[ReplState], max_depth: usize) -> Result<(), Counterexample> {
+        for (state, path) in self.reachable_with_paths(max_depth) {
+            if !valid_states.contains(&state) {
+                return Err(Counterexample { path });
             }
-            
-            let now_after_from = after_from || &state == from;
-            
-            if now_after_from && &state == to {
-                continue; // This path satisfied the property
+        }
+        Ok(())
+    }
+
+    /// `from` leads to `to`: every path that enters `from` eventually
+    /// reaches `to`. Violated exactly when, after deleting every `to`
+    /// state from the graph, there's a cycle reachable from `from` — a
+    /// lasso the execution can loop around forever instead of reaching
+    /// `to`.
+    fn check_leads_to(&self, from: &ReplState, to: &ReplState, max_depth: usize) -> Result<(), Counterexample> {
+        let reachable = self.reachable_with_paths(max_depth);
+        let Some(prefix) = reachable.get(from) else {
+            // `from` is never reached at all, so the property is vacuously true.
+            return Ok(());
+        };
+
+        let deleted: HashSet<ReplState> = [to.clone()].into_iter().collect();
+        if let Some(cycle) = self.find_cycle(from, &deleted, max_depth) {
+            let mut path = prefix.clone();
+            path.extend(cycle);
+            return Err(Counterexample { path });
+        }
+        Ok(())
+    }
+
+    /// `state_a` holds until `state_b`: every reachable state before
+    /// `state_b` must be `state_a`, and no execution can stay in
+    /// `state_a` forever without ever reaching `state_b`.
+    fn check_until(&self, state_a: &ReplState, state_b: &ReplState, max_depth: usize) -> Result<(), Counterexample> {
+        let reachable = self.reachable_with_paths(max_depth);
+
+        // Safety half: every state besides `state_b` must be `state_a`.
+        for (state, path) in &reachable {
+            if state != state_b && state != state_a {
+                return Err(Counterexample { path: path.clone() });
             }
-            
-            if now_after_from && depth == max_depth - 1 {
-                return false; // Reached max depth without finding 'to'
+        }
+
+        // Liveness half: once in `state_a`, there must be no way to stay
+        // there forever instead of reaching `state_b` — i.e. no cycle
+        // reachable from `state_a` once `state_b` is deleted from the graph.
+        if let Some(prefix) = reachable.get(state_a) {
+            let deleted: HashSet<ReplState> = [state_b.clone()].into_iter().collect();
+            if let Some(cycle) = self.find_cycle(state_a, &deleted, max_depth) {
+                let mut path = prefix.clone();
+                path.extend(cycle);
+                return Err(Counterexample { path });
             }
-            
-            for event in self.get_possible_events(&state) {
-                if let Some(next_state) = self.get_next_state(&state, &event) {
-                    queue.push_back((next_state, depth + 1, now_after_from));
+        }
+
+        Ok(())
+    }
+
+    /// Complements exhaustive checking with bounded random walks, the way
+    /// Deno seeds and shuffles its test list for reproducibility: a
+    /// `seed` plus the walk index it failed on and the exact trace are
+    /// enough to replay any falsifying run deterministically, without
+    /// having to re-run the whole batch. Useful where `max_depth` BFS/DFS
+    /// over the full graph would blow up but most violations are shallow
+    /// enough that a handful of random walks find one cheaply.
+    pub fn simulate(
+        &self,
+        property: &TemporalProperty,
+        seed: u64,
+        walks: usize,
+        max_len: usize,
+    ) -> Result<(), SimulationViolation> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+
+        for walk_index in 0..walks {
+            let mut machine = ReplStateMachine::new();
+            let mut trace: Vec<(ReplState, Event)> = Vec::new();
+
+            loop {
+                if let Some(counterexample) =
+                    Self::check_trace_so_far(property, &trace, machine.current_state())
+                {
+                    return Err(SimulationViolation { seed, walk_index, counterexample });
+                }
+
+                if trace.len() >= max_len {
+                    break;
+                }
+
+                let candidates = self.get_possible_events(machine.current_state());
+                let Some(event) = candidates.choose(&mut rng).cloned() else {
+                    break;
+                };
+
+                let from = machine.current_state().clone();
+                match machine.transition(event.clone()) {
+                    Ok(_) => trace.push((from, event)),
+                    Err(_) => {
+                        // The chosen event wasn't enabled from here: a dead end.
+                        if let Some(counterexample) = Self::check_stuck(property, &trace, &from) {
+                            return Err(SimulationViolation { seed, walk_index, counterexample });
+                        }
+                        break;
+                    }
                 }
             }
         }
-        
-        true
+
+        Ok(())
     }
-    
-    fn check_until(&self, state_a: &ReplState, state_b: &ReplState, max_depth: usize) -> bool {
-        // State A must hold until state B is reached
-        let mut queue = VecDeque::new();
-        queue.push_back((ReplState::Init, 0));
-        
-        while let Some((state, depth)) = queue.pop_front() {
-            if &state == state_b {
-                continue; // Property satisfied on this path
+
+    /// Checks whether `property` is already confirmed violated after
+    /// reaching `current`, without waiting for the walk to end. Safety
+    /// properties (`Always`/`Never`/the safety half of `Until`) can be
+    /// confirmed this way; liveness properties (`Eventually`/`LeadsTo`/
+    /// the liveness half of `Until`) need the walk to actually get stuck
+    /// before a violation can be confirmed — see `check_stuck`.
+    fn check_trace_so_far(
+        property: &TemporalProperty,
+        trace: &[(ReplState, Event)],
+        current: &ReplState,
+    ) -> Option<Counterexample> {
+        match property {
+            TemporalProperty::Never(target) => {
+                (current == target).then(|| Counterexample { path: trace.to_vec() })
             }
-            
-            if &state != state_a {
-                return false; // Violated: not in state A before reaching B
+            TemporalProperty::Always(valid) => {
+                (!valid.contains(current)).then(|| Counterexample { path: trace.to_vec() })
             }
-            
-            if depth >= max_depth {
-                return false; // Didn't reach B within depth limit
+            TemporalProperty::Until(state_a, state_b) => {
+                (current != state_b && current != state_a)
+                    .then(|| Counterexample { path: trace.to_vec() })
             }
-            
-            for event in self.get_possible_events(&state) {
-                if let Some(next_state) = self.get_next_state(&state, &event) {
-                    queue.push_back((next_state, depth + 1));
-                }
+            TemporalProperty::Eventually(_) | TemporalProperty::LeadsTo(_, _) => None,
+        }
+    }
+
+    /// Checks a liveness property once the walk has run out of enabled
+    /// transitions at `stuck_at`: getting permanently stuck without ever
+    /// reaching the required state is exactly the finite analogue of the
+    /// lasso `check_leads_to`/`check_until` look for over the full graph.
+    fn check_stuck(
+        property: &TemporalProperty,
+        trace: &[(ReplState, Event)],
+        stuck_at: &ReplState,
+    ) -> Option<Counterexample> {
+        match property {
+            TemporalProperty::Eventually(target) => {
+                let reached = stuck_at == target || trace.iter().any(|(s, _)| s == target);
+                (!reached).then(|| Counterexample { path: trace.to_vec() })
+            }
+            TemporalProperty::LeadsTo(from, to) => {
+                let entered_from = stuck_at == from || trace.iter().any(|(s, _)| s == from);
+                let reached_to = stuck_at == to || trace.iter().any(|(s, _)| s == to);
+                (entered_from && !reached_to).then(|| Counterexample { path: trace.to_vec() })
+            }
+            TemporalProperty::Until(_, state_b) => {
+                (stuck_at != state_b).then(|| Counterexample { path: trace.to_vec() })
             }
+            TemporalProperty::Always(_) | TemporalProperty::Never(_) => None,
         }
-        
-        true
     }
-    
+
     fn get_possible_events(&self, state: &ReplState) -> Vec<Event> {
         match state {
             ReplState::Init => vec![Event::Start],
@@ -380,7 +848,39 @@ impl ModelChecker {
     }
     
     fn get_next_state(&self, state: &ReplState, event: &Event) -> Option<ReplState> {
-        self.state_machine.transitions.get(&(state.clone(), event.clone())).cloned()
+        self.state_machine.lookup(state, event)
+    }
+
+    /// BFS from `Init`, collecting every edge reachable within
+    /// `max_depth` rather than only the first-reached path per state.
+    /// Used by [`CoverageCollector::report`] as the source of truth for
+    /// "every edge a session could have exercised", since the
+    /// generalized transition table no longer enumerates concrete edges
+    /// by itself.
+    fn all_edges(&self, max_depth: usize) -> HashSet<(ReplState, EventKind, ReplState)> {
+        let mut edges = HashSet::new();
+        let mut depths = HashMap::new();
+        depths.insert(ReplState::Init, 0usize);
+        let mut queue = VecDeque::new();
+        queue.push_back(ReplState::Init);
+
+        while let Some(state) = queue.pop_front() {
+            let depth = depths[&state];
+            if depth >= max_depth {
+                continue;
+            }
+            for event in self.get_possible_events(&state) {
+                if let Some(next_state) = self.get_next_state(&state, &event) {
+                    edges.insert((state.clone(), EventKind::from(&event), next_state.clone()));
+                    if !depths.contains_key(&next_state) {
+                        depths.insert(next_state.clone(), depth + 1);
+                        queue.push_back(next_state);
+                    }
+                }
+            }
+        }
+
+        edges
     }
 }
 
@@ -416,7 +916,7 @@ impl ReplProperties {
             (
                 "Function calls complete".to_string(),
                 TemporalProperty::LeadsTo(
-                    ReplState::CallingFunction("".to_string()),
+                    ReplState::CallingFunction("func".to_string()),
                     ReplState::GeneratingResponse
                 ),
             ),
@@ -449,17 +949,18 @@ mod tests {
         assert!(checker.check_property(
             &TemporalProperty::Eventually(ReplState::DisplayingOutput),
             10
-        ));
-        
+        ).is_ok());
+
         // Check that Init is never reached again after start
         let mut sm = ReplStateMachine::new();
         sm.transition(Event::Start).unwrap();
-        
-        // This should fail as we never return to Init
+
+        // Init is only the starting state, never a transition target, so
+        // this should hold.
         assert!(checker.check_property(
             &TemporalProperty::Never(ReplState::Init),
             10
-        ));
+        ).is_ok());
     }
     
     #[test]
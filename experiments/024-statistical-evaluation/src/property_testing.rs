@@ -5,7 +5,40 @@
 use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
 use quickcheck_macros::quickcheck;
 use proptest::prelude::*;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+/// Seed driving every `rand` call in this module: `GEMINI_TEST_SEED` if set,
+/// otherwise a time-based seed. Either way it's printed once so a failing
+/// property can be replayed with `GEMINI_TEST_SEED=<seed> cargo test`.
+fn test_seed() -> u64 {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    *SEED.get_or_init(|| {
+        let seed = std::env::var("GEMINI_TEST_SEED")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .expect("system clock before UNIX_EPOCH")
+                    .as_nanos() as u64
+            });
+        eprintln!("property_testing: GEMINI_TEST_SEED={seed}");
+        seed
+    })
+}
+
+/// Runs `f` against the module's single seeded RNG, so successive calls
+/// advance the same deterministic stream instead of each restarting from
+/// `test_seed()`.
+fn with_test_rng<R>(f: impl FnOnce(&mut SmallRng) -> R) -> R {
+    static RNG: OnceLock<Mutex<SmallRng>> = OnceLock::new();
+    let rng = RNG.get_or_init(|| Mutex::new(SmallRng::seed_from_u64(test_seed())));
+    f(&mut rng.lock().expect("test rng mutex poisoned"))
+}
 
 /// Custom types for property testing
 
@@ -202,33 +235,41 @@ fn prop_order_independence(ops: Vec<String>) -> TestResult {
         return TestResult::discard();
     }
     
-    let selected_ops: Vec<_> = ops.iter()
-        .filter_map(|_| {
-            let idx = rand::random::<usize>() % operations.len();
-            Some(operations[idx])
-        })
-        .collect();
-    
+    let selected_ops: Vec<_> = with_test_rng(|rng| {
+        ops.iter()
+            .map(|_| operations[rng.gen_range(0..operations.len())])
+            .collect()
+    });
+
     if selected_ops.len() < 2 {
         return TestResult::discard();
     }
-    
+
     // Get functions for original order
     let functions1: Vec<_> = selected_ops.iter()
         .filter_map(|op| analyze_for_function_call(op))
         .map(|(f, _)| f)
         .collect();
-    
-    // Get functions for reversed order
-    let functions2: Vec<_> = selected_ops.iter().rev()
+
+    // Get functions for a shuffled order
+    let shuffled_ops = with_test_rng(|rng| {
+        let mut shuffled = selected_ops.clone();
+        shuffled.shuffle(rng);
+        shuffled
+    });
+    let functions2: Vec<_> = shuffled_ops.iter()
         .filter_map(|op| analyze_for_function_call(op))
         .map(|(f, _)| f)
         .collect();
-    
+
     // Sets should be equal (order doesn't matter for independent ops)
     let set1: HashSet<_> = functions1.into_iter().collect();
     let set2: HashSet<_> = functions2.into_iter().collect();
-    
+
+    if set1 != set2 {
+        eprintln!("prop_order_independence failed; replay with GEMINI_TEST_SEED={}", test_seed());
+    }
+
     TestResult::from_bool(set1 == set2)
 }
 
@@ -237,27 +278,42 @@ fn prop_order_independence(ops: Vec<String>) -> TestResult {
 proptest! {
     #[test]
     fn prop_no_injection(s in ".*") {
-        // Function calls should be safe from injection attacks
+        // sanitize_path is the real enforcement point now: whatever it lets
+        // through must already be free of injection payloads, not merely
+        // whatever `extract_file_path` happens to forward into it.
+        if let Ok(path) = sanitize_path(&s) {
+            let rendered = path.to_string_lossy();
+            prop_assert!(!rendered.contains("../"));
+            prop_assert!(!rendered.contains('~'));
+            prop_assert!(!rendered.contains('$'));
+            prop_assert!(!rendered.contains('|'));
+            prop_assert!(!rendered.contains("&&"));
+        }
+
         let prompt = format!("Read the file {}", s);
-        
         if let Some((_, args)) = analyze_for_function_call(&prompt) {
-            // Args should be properly escaped/sanitized
             prop_assert!(!args.contains("../"));
-            prop_assert!(!args.contains("~"));
-            prop_assert!(!args.contains("$"));
-            prop_assert!(!args.contains("|"));
+            prop_assert!(!args.contains('~'));
+            prop_assert!(!args.contains('$'));
+            prop_assert!(!args.contains('|'));
             prop_assert!(!args.contains("&&"));
         }
     }
-    
+
     #[test]
     fn prop_file_path_normalization(
         segments in prop::collection::vec("[a-zA-Z0-9_-]+", 1..5),
         ext in prop::sample::select(vec!["rs", "py", "js", "md", "txt"])
     ) {
         let path = format!("{}.{}", segments.join("/"), ext);
+
+        let sanitized = sanitize_path(&path).expect("alphanumeric path segments are always safe");
+        let rendered = sanitized.to_string_lossy();
+        prop_assert!(!rendered.contains("//"));
+        prop_assert!(!rendered.contains("./"));
+        prop_assert!(!rendered.starts_with('/'));
+
         let prompt = format!("Read {}", path);
-        
         if let Some(("read_file", args)) = analyze_for_function_call(&prompt) {
             // Path should be normalized
             prop_assert!(!args.contains("//"));
@@ -267,6 +323,66 @@ proptest! {
     }
 }
 
+/// Workspace-relative path validation error raised by [`sanitize_path`].
+#[derive(Debug, PartialEq)]
+pub enum PathError {
+    /// The path tried to escape the workspace root via `..` or an absolute
+    /// path component.
+    Traversal(String),
+    /// The path contained a shell metacharacter.
+    UnsafeCharacter(char),
+    /// Nothing was left once the path was normalized (empty or `.`-only).
+    Empty,
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::Traversal(raw) => write!(f, "path escapes workspace root: {raw}"),
+            PathError::UnsafeCharacter(c) => write!(f, "unsafe character in path: {c:?}"),
+            PathError::Empty => write!(f, "empty path"),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Characters that have no business appearing in a file path argument:
+/// shell metacharacters that could turn a path into a command injection if
+/// it's ever interpolated into a shell string.
+const UNSAFE_PATH_CHARS: &[char] = &['$', '|', '&', '`', ';', '~'];
+
+/// Validates and lexically normalizes `raw` into a path relative to the
+/// (implicit) workspace root: rejects parent-directory traversal (`..`),
+/// absolute-path escapes, and shell metacharacters, and collapses
+/// redundant `//`/`./` segments. Shared by `extract_file_path` and
+/// `extract_pattern` so `prop_no_injection`/`prop_file_path_normalization`
+/// exercise real enforcement rather than a stub extractor.
+pub fn sanitize_path(raw: &str) -> Result<std::path::PathBuf, PathError> {
+    if let Some(c) = raw.chars().find(|c| UNSAFE_PATH_CHARS.contains(c)) {
+        return Err(PathError::UnsafeCharacter(c));
+    }
+
+    let mut normalized = std::path::PathBuf::new();
+    for component in std::path::Path::new(raw).components() {
+        match component {
+            std::path::Component::Normal(part) => normalized.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir
+            | std::path::Component::RootDir
+            | std::path::Component::Prefix(_) => {
+                return Err(PathError::Traversal(raw.to_string()));
+            }
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        return Err(PathError::Empty);
+    }
+
+    Ok(normalized)
+}
+
 /// Mock implementations for testing
 /// In real implementation, these would call the actual REPL logic
 
@@ -314,25 +430,34 @@ fn analyze_for_function_call(prompt: &str) -> Option<(String, String)> {
 }
 
 fn extract_file_path(prompt: &str) -> Option<String> {
-    // Simple extraction - real implementation would be more sophisticated
+    // Simple extraction - real implementation would be more sophisticated.
+    // Whatever it finds is routed through `sanitize_path` before going
+    // anywhere near a tool call, so a prompt like "read ../../etc/passwd"
+    // yields no candidate rather than a traversal.
     let words: Vec<&str> = prompt.split_whitespace().collect();
-    
+
     for word in words {
         if word.contains('.') && !word.ends_with('.') {
-            return Some(word.to_string());
+            if let Ok(path) = sanitize_path(word) {
+                return Some(path.to_string_lossy().into_owned());
+            }
         }
     }
     None
 }
 
 fn extract_pattern(prompt: &str) -> String {
-    if prompt.contains("*.py") {
-        "*.py".to_string()
+    let candidate = if prompt.contains("*.py") {
+        "*.py"
     } else if prompt.contains("*.rs") {
-        "*.rs".to_string()
+        "*.rs"
     } else {
-        "*".to_string()
-    }
+        "*"
+    };
+
+    sanitize_path(candidate)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "*".to_string())
 }
 
 fn extract_search_term(prompt: &str) -> Option<String> {
@@ -354,7 +479,7 @@ fn extract_search_term(prompt: &str) -> Option<String> {
 
 fn get_confidence_score(_context: &str) -> f64 {
     // Mock confidence score - real implementation would use model
-    0.8 + (rand::random::<f64>() * 0.2)
+    0.8 + (with_test_rng(|rng| rng.gen::<f64>()) * 0.2)
 }
 
 #[cfg(test)]
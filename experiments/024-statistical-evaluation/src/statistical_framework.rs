@@ -10,6 +10,7 @@
 use anyhow::Result;
 use statrs::distribution::{Normal, StudentsT, Binomial, ContinuousCDF};
 use statrs::statistics::Statistics;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use ndarray::prelude::*;
@@ -32,7 +33,12 @@ pub struct StatisticalResult {
     pub bootstrap_ci: Option<(f64, f64)>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `Archive`/`RkyvSerialize`/`RkyvDeserialize` let `archive::archive_trials`
+/// bundle a config alongside its raw trial outcomes in a single rkyv file;
+/// the `serde` derives are unrelated and still back `StatisticalResult`'s
+/// human-readable JSON output.
+#[derive(Debug, Clone, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
 pub struct ExperimentConfig {
     pub min_sample_size: usize,
     pub desired_power: f64,
@@ -55,6 +61,32 @@ impl Default for ExperimentConfig {
     }
 }
 
+// Standard Nelder-Mead coefficients and convergence knobs for
+// `StatisticalAnalyzer::optimize_config`.
+const NM_REFLECTION: f64 = 1.0;
+const NM_EXPANSION: f64 = 2.0;
+const NM_CONTRACTION: f64 = 0.5;
+const NM_SHRINK: f64 = 0.5;
+const NM_MAX_ITERATIONS: usize = 200;
+const NM_TOLERANCE: f64 = 1e-8;
+
+/// Inclusive `(min, max)` box constraints for `StatisticalAnalyzer::optimize_config`'s
+/// simplex search, one pair per search dimension: sample size, then alpha.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigBounds {
+    pub sample_size: (f64, f64),
+    pub alpha: (f64, f64),
+}
+
+impl ConfigBounds {
+    fn clamp(self, point: [f64; 2]) -> [f64; 2] {
+        [
+            point[0].clamp(self.sample_size.0, self.sample_size.1),
+            point[1].clamp(self.alpha.0, self.alpha.1),
+        ]
+    }
+}
+
 pub struct StatisticalAnalyzer {
     config: ExperimentConfig,
 }
@@ -104,7 +136,14 @@ impl StatisticalAnalyzer {
         (center - margin, center + margin)
     }
 
-    /// Perform two-proportion z-test
+    /// Perform two-proportion z-test.
+    ///
+    /// `total1 == 0` or `total2 == 0` leave a proportion undefined, and a
+    /// pooled proportion of exactly 0 or 1 (every trial across both groups
+    /// had the same outcome) makes the standard error exactly zero; both
+    /// are reported as "no evidence of a difference" (`z = 0.0`, `p = 1.0`,
+    /// not significant) rather than propagating NaN. `successesN` greater
+    /// than `totalN` is clamped down to `totalN`.
     pub fn two_proportion_test(
         &self,
         successes1: usize,
@@ -112,33 +151,45 @@ impl StatisticalAnalyzer {
         successes2: usize,
         total2: usize,
     ) -> (f64, f64, bool) {
+        if total1 == 0 || total2 == 0 {
+            return (0.0, 1.0, false);
+        }
+
+        let successes1 = successes1.min(total1);
+        let successes2 = successes2.min(total2);
         let p1 = successes1 as f64 / total1 as f64;
         let p2 = successes2 as f64 / total2 as f64;
         let n1 = total1 as f64;
         let n2 = total2 as f64;
-        
+
         // Pooled proportion
         let p_pool = (successes1 + successes2) as f64 / (total1 + total2) as f64;
-        
+
         // Standard error
         let se = (p_pool * (1.0 - p_pool) * (1.0 / n1 + 1.0 / n2)).sqrt();
-        
+        if se == 0.0 {
+            return (0.0, 1.0, false);
+        }
+
         // Z-score
         let z = (p1 - p2) / se;
-        
+
         // P-value (two-tailed)
         let normal = Normal::new(0.0, 1.0).unwrap();
         let p_value = 2.0 * (1.0 - normal.cdf(z.abs()));
-        
+
         let is_significant = p_value < self.config.alpha;
-        
+
         (z, p_value, is_significant)
     }
 
-    /// Calculate Cohen's d effect size for proportions
+    /// Calculate Cohen's d effect size for proportions. `p1`/`p2` are
+    /// clamped into `[0, 1]` first: `asin(sqrt(p))` is only defined there,
+    /// and a rate computed elsewhere as e.g. `1.0000000001` from rounding
+    /// would otherwise push it out of domain.
     pub fn cohens_d_proportion(&self, p1: f64, p2: f64) -> f64 {
-        let h1 = 2.0 * p1.sqrt().asin();
-        let h2 = 2.0 * p2.sqrt().asin();
+        let h1 = 2.0 * p1.clamp(0.0, 1.0).sqrt().asin();
+        let h2 = 2.0 * p2.clamp(0.0, 1.0).sqrt().asin();
         h1 - h2
     }
 
@@ -171,31 +222,60 @@ impl StatisticalAnalyzer {
         (bootstrap_means[lower_idx], bootstrap_means[upper_idx.min(iterations - 1)])
     }
 
-    /// Comprehensive analysis of experiment results
+    /// Comprehensive analysis of experiment results.
+    ///
+    /// `total == 0` (no trials) returns an all-zero, not-significant
+    /// sentinel instead of dividing by zero; `successes > total`
+    /// (malformed input) is clamped down to `successes == total`. A
+    /// standard error of exactly zero (every trial the same outcome) also
+    /// can't drive a z-test, so `z_score`/`p_value` fall back to
+    /// `0.0`/`1.0` there too rather than producing NaN.
     pub fn analyze_results(&self, successes: usize, total: usize) -> StatisticalResult {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let critical_value = normal.inverse_cdf(1.0 - self.config.alpha / 2.0);
+
+        if total == 0 {
+            return StatisticalResult {
+                sample_size: 0,
+                success_rate: 0.0,
+                confidence_interval_95: (0.0, 0.0),
+                confidence_interval_99: (0.0, 0.0),
+                p_value: 1.0,
+                power: 0.0,
+                effect_size: 0.0,
+                standard_error: 0.0,
+                z_score: 0.0,
+                critical_value,
+                is_significant: false,
+                bootstrap_ci: None,
+            };
+        }
+
+        let successes = successes.min(total);
         let success_rate = successes as f64 / total as f64;
-        
+
         // Confidence intervals
         let ci_95 = self.confidence_interval_proportion(successes, total, 0.95);
         let ci_99 = self.confidence_interval_proportion(successes, total, 0.99);
-        
+
         // Standard error
         let se = (success_rate * (1.0 - success_rate) / total as f64).sqrt();
-        
-        // Z-test against baseline
-        let z_score = (success_rate - self.config.baseline_success_rate) / se;
-        let normal = Normal::new(0.0, 1.0).unwrap();
-        let p_value = 2.0 * (1.0 - normal.cdf(z_score.abs()));
-        
-        // Critical value
-        let critical_value = normal.inverse_cdf(1.0 - self.config.alpha / 2.0);
-        
+
+        // Z-test against baseline; undefined when there's no variance to
+        // test against (every trial had the same outcome).
+        let (z_score, p_value) = if se > 0.0 {
+            let z = (success_rate - self.config.baseline_success_rate) / se;
+            (z, 2.0 * (1.0 - normal.cdf(z.abs())))
+        } else {
+            (0.0, 1.0)
+        };
+
         // Effect size
         let effect_size = self.cohens_d_proportion(success_rate, self.config.baseline_success_rate);
-        
+
         // Power
         let power = self.power_analysis(total, effect_size);
-        
+
         StatisticalResult {
             sample_size: total,
             success_rate,
@@ -211,6 +291,98 @@ impl StatisticalAnalyzer {
             bootstrap_ci: None,
         }
     }
+
+    /// Finds an `ExperimentConfig` minimizing `objective` (e.g. expected API
+    /// calls) via a Nelder-Mead simplex search over `(min_sample_size,
+    /// alpha)`, starting from `self.config`'s own values and carrying its
+    /// other fields through unchanged. Reflects with coefficient α=1,
+    /// expands with γ=2, contracts with ρ=0.5, and shrinks with σ=0.5; every
+    /// vertex is clamped into `bounds` after each move, keeping sample sizes
+    /// positive and alpha within the caller's range. Stops when the
+    /// simplex's objective spread drops below a tolerance, or after
+    /// `NM_MAX_ITERATIONS` iterations.
+    pub fn optimize_config(&self, objective: impl Fn(&ExperimentConfig) -> f64, bounds: ConfigBounds) -> ExperimentConfig {
+        let to_config = |point: [f64; 2]| -> ExperimentConfig {
+            ExperimentConfig {
+                min_sample_size: point[0].round().max(1.0) as usize,
+                alpha: point[1],
+                ..self.config.clone()
+            }
+        };
+        let eval = |point: [f64; 2]| objective(&to_config(point));
+
+        let start = [self.config.min_sample_size as f64, self.config.alpha];
+        let step = [
+            (bounds.sample_size.1 - bounds.sample_size.0).max(1.0) * 0.1,
+            (bounds.alpha.1 - bounds.alpha.0).max(1e-3) * 0.1,
+        ];
+        let mut simplex: Vec<[f64; 2]> = vec![
+            bounds.clamp(start),
+            bounds.clamp([start[0] + step[0], start[1]]),
+            bounds.clamp([start[0], start[1] + step[1]]),
+        ];
+
+        for _ in 0..NM_MAX_ITERATIONS {
+            simplex.sort_by(|&a, &b| eval(a).partial_cmp(&eval(b)).unwrap());
+
+            let values: Vec<f64> = simplex.iter().map(|&p| eval(p)).collect();
+            let spread = values.iter().copied().fold(f64::MIN, f64::max) - values.iter().copied().fold(f64::MAX, f64::min);
+            if spread.abs() < NM_TOLERANCE {
+                break;
+            }
+
+            let best = simplex[0];
+            let worst = *simplex.last().unwrap();
+            let second_worst = simplex[simplex.len() - 2];
+            let centroid = {
+                let n = simplex.len() - 1;
+                let sum = simplex[..n].iter().fold([0.0, 0.0], |acc, p| [acc[0] + p[0], acc[1] + p[1]]);
+                [sum[0] / n as f64, sum[1] / n as f64]
+            };
+
+            let reflected = bounds.clamp([
+                centroid[0] + NM_REFLECTION * (centroid[0] - worst[0]),
+                centroid[1] + NM_REFLECTION * (centroid[1] - worst[1]),
+            ]);
+            let reflected_value = eval(reflected);
+
+            if reflected_value < eval(best) {
+                let expanded = bounds.clamp([
+                    centroid[0] + NM_EXPANSION * (reflected[0] - centroid[0]),
+                    centroid[1] + NM_EXPANSION * (reflected[1] - centroid[1]),
+                ]);
+                *simplex.last_mut().unwrap() = if eval(expanded) < reflected_value { expanded } else { reflected };
+            } else if reflected_value < eval(second_worst) {
+                *simplex.last_mut().unwrap() = reflected;
+            } else {
+                let contracted = bounds.clamp(if reflected_value < eval(worst) {
+                    [
+                        centroid[0] + NM_CONTRACTION * (reflected[0] - centroid[0]),
+                        centroid[1] + NM_CONTRACTION * (reflected[1] - centroid[1]),
+                    ]
+                } else {
+                    [
+                        centroid[0] + NM_CONTRACTION * (worst[0] - centroid[0]),
+                        centroid[1] + NM_CONTRACTION * (worst[1] - centroid[1]),
+                    ]
+                });
+
+                if eval(contracted) < eval(worst).min(reflected_value) {
+                    *simplex.last_mut().unwrap() = contracted;
+                } else {
+                    for vertex in simplex.iter_mut().skip(1) {
+                        *vertex = bounds.clamp([
+                            best[0] + NM_SHRINK * (vertex[0] - best[0]),
+                            best[1] + NM_SHRINK * (vertex[1] - best[1]),
+                        ]);
+                    }
+                }
+            }
+        }
+
+        simplex.sort_by(|&a, &b| eval(a).partial_cmp(&eval(b)).unwrap());
+        to_config(simplex[0])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -224,8 +396,24 @@ pub struct ComparisonResult {
     pub is_significant: bool,
 }
 
+/// Haldane-Anscombe continuity correction: adds 0.5 to a cell's success
+/// count and 1 to its total before taking the rate, so a 0% or 100% group
+/// (an exactly-zero contingency-table cell) still yields a finite odds
+/// ratio instead of dividing by zero.
+fn corrected_rate(successes: usize, total: usize) -> f64 {
+    (successes as f64 + 0.5) / (total as f64 + 1.0)
+}
+
 impl StatisticalAnalyzer {
-    /// Compare two groups (e.g., different models or approaches)
+    /// Compare two groups (e.g., different models or approaches).
+    ///
+    /// The odds ratio always uses [`corrected_rate`] (a continuity
+    /// correction), since a group with a 0% or 100% success rate would
+    /// otherwise divide by zero. The chi-squared test applies the same 0.5
+    /// correction to all four contingency-table cells, but only when at
+    /// least one of them is exactly zero (an empty cell, or an empty
+    /// group), to keep its expected counts away from zero without
+    /// perturbing the well-behaved case.
     pub fn compare_groups(
         &self,
         group1_successes: usize,
@@ -233,42 +421,57 @@ impl StatisticalAnalyzer {
         group2_successes: usize,
         group2_total: usize,
     ) -> ComparisonResult {
+        let group1_successes = group1_successes.min(group1_total);
+        let group2_successes = group2_successes.min(group2_total);
+
         let group1_stats = self.analyze_results(group1_successes, group1_total);
         let group2_stats = self.analyze_results(group2_successes, group2_total);
-        
+
         let p1 = group1_stats.success_rate;
         let p2 = group2_stats.success_rate;
-        
+
         // Relative improvement
         let relative_improvement = if p1 > 0.0 {
             (p2 - p1) / p1 * 100.0
         } else {
             f64::INFINITY
         };
-        
-        // Odds ratio
-        let odds1 = p1 / (1.0 - p1);
-        let odds2 = p2 / (1.0 - p2);
+
+        // Odds ratio, via continuity-corrected rates so a 0%/100% group
+        // doesn't produce an infinite or NaN odds ratio.
+        let corrected_p1 = corrected_rate(group1_successes, group1_total);
+        let corrected_p2 = corrected_rate(group2_successes, group2_total);
+        let odds1 = corrected_p1 / (1.0 - corrected_p1);
+        let odds2 = corrected_p2 / (1.0 - corrected_p2);
         let odds_ratio = odds2 / odds1;
-        
-        // Chi-squared test
-        let expected1_success = group1_total as f64 * (group1_successes + group2_successes) as f64 
-            / (group1_total + group2_total) as f64;
-        let expected1_fail = group1_total as f64 - expected1_success;
-        let expected2_success = group2_total as f64 * (group1_successes + group2_successes) as f64 
-            / (group1_total + group2_total) as f64;
-        let expected2_fail = group2_total as f64 - expected2_success;
-        
-        let chi_squared = 
-            (group1_successes as f64 - expected1_success).powi(2) / expected1_success +
-            ((group1_total - group1_successes) as f64 - expected1_fail).powi(2) / expected1_fail +
-            (group2_successes as f64 - expected2_success).powi(2) / expected2_success +
-            ((group2_total - group2_successes) as f64 - expected2_fail).powi(2) / expected2_fail;
-        
+
+        // Chi-squared test. Correct all four cells together when any one
+        // of them is exactly zero (including an empty group), so none of
+        // the expected counts below can be zero.
+        let (s1, f1) = (group1_successes as f64, (group1_total - group1_successes) as f64);
+        let (s2, f2) = (group2_successes as f64, (group2_total - group2_successes) as f64);
+        let (s1, f1, s2, f2) = if s1 == 0.0 || f1 == 0.0 || s2 == 0.0 || f2 == 0.0 {
+            (s1 + 0.5, f1 + 0.5, s2 + 0.5, f2 + 0.5)
+        } else {
+            (s1, f1, s2, f2)
+        };
+        let (total1, total2) = (s1 + f1, s2 + f2);
+        let grand_total = total1 + total2;
+
+        let expected1_success = total1 * (s1 + s2) / grand_total;
+        let expected1_fail = total1 * (f1 + f2) / grand_total;
+        let expected2_success = total2 * (s1 + s2) / grand_total;
+        let expected2_fail = total2 * (f1 + f2) / grand_total;
+
+        let chi_squared = (s1 - expected1_success).powi(2) / expected1_success
+            + (f1 - expected1_fail).powi(2) / expected1_fail
+            + (s2 - expected2_success).powi(2) / expected2_success
+            + (f2 - expected2_fail).powi(2) / expected2_fail;
+
         // Chi-squared p-value (df = 1)
         let chi_dist = statrs::distribution::ChiSquared::new(1.0).unwrap();
         let chi_squared_p_value = 1.0 - chi_dist.cdf(chi_squared);
-        
+
         ComparisonResult {
             group1_stats,
             group2_stats,
@@ -329,4 +532,82 @@ mod tests {
         assert!(!is_sig2);
         assert!(z2.abs() < 1.96);
     }
+
+    #[test]
+    fn test_optimize_config_finds_the_unconstrained_minimum() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+        let bounds = ConfigBounds { sample_size: (10.0, 1000.0), alpha: (0.001, 0.2) };
+
+        // Cost is minimized at sample_size=300, alpha=0.05, nowhere near a bound.
+        let objective = |config: &ExperimentConfig| {
+            (config.min_sample_size as f64 - 300.0).powi(2) + 1_000_000.0 * (config.alpha - 0.05).powi(2)
+        };
+
+        let optimized = analyzer.optimize_config(objective, bounds);
+        assert!((optimized.min_sample_size as f64 - 300.0).abs() < 5.0);
+        assert!((optimized.alpha - 0.05).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_analyze_results_handles_zero_and_overflowing_trials() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+
+        let empty = analyzer.analyze_results(0, 0);
+        assert_eq!(empty.sample_size, 0);
+        assert!(!empty.is_significant);
+        assert!(empty.success_rate.is_finite() && empty.z_score.is_finite() && empty.p_value.is_finite());
+
+        // successes > total is malformed input; should clamp, not panic or NaN.
+        let overflowing = analyzer.analyze_results(150, 100);
+        assert_eq!(overflowing.success_rate, 1.0);
+        assert!(overflowing.z_score.is_finite() && overflowing.standard_error.is_finite());
+    }
+
+    #[test]
+    fn test_two_proportion_test_handles_zero_totals_and_se() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+
+        let (z, p, sig) = analyzer.two_proportion_test(0, 0, 5, 10);
+        assert_eq!((z, p, sig), (0.0, 1.0, false));
+
+        // Both groups all-success: pooled SE is exactly zero.
+        let (z2, p2, sig2) = analyzer.two_proportion_test(10, 10, 20, 20);
+        assert_eq!((z2, p2, sig2), (0.0, 1.0, false));
+    }
+
+    #[test]
+    fn test_cohens_d_proportion_tolerates_out_of_range_rates() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+        let d = analyzer.cohens_d_proportion(1.0000001, -0.0000001);
+        assert!(d.is_finite());
+    }
+
+    #[test]
+    fn test_compare_groups_handles_all_or_nothing_groups() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+
+        let result = analyzer.compare_groups(10, 10, 0, 10);
+        assert!(result.odds_ratio.is_finite());
+        assert!(result.chi_squared.is_finite());
+        assert!(result.chi_squared_p_value.is_finite());
+
+        let both_empty = analyzer.compare_groups(0, 0, 0, 0);
+        assert!(both_empty.odds_ratio.is_finite());
+        assert!(both_empty.chi_squared.is_finite());
+    }
+
+    #[test]
+    fn test_optimize_config_clamps_to_bounds() {
+        let analyzer = StatisticalAnalyzer::new(ExperimentConfig::default());
+        let bounds = ConfigBounds { sample_size: (50.0, 100.0), alpha: (0.01, 0.1) };
+
+        // Cost keeps decreasing past the bounds, so the optimum should sit
+        // at the corner of the feasible region.
+        let objective = |config: &ExperimentConfig| -(config.min_sample_size as f64) - config.alpha * 10_000.0;
+
+        let optimized = analyzer.optimize_config(objective, bounds);
+        assert!(optimized.min_sample_size <= 100);
+        assert!(optimized.alpha <= 0.1 + 1e-9);
+        assert!(optimized.alpha >= 0.01 - 1e-9);
+    }
 }
\ No newline at end of file
@@ -6,8 +6,10 @@
 //! 3. Multi-tool scenarios (should trigger multiple functions in sequence)
 //! 4. Edge cases and adversarial inputs
 
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestScenario {
@@ -36,11 +38,151 @@ pub enum ExpectedBehavior {
         args_pattern: HashMap<String, String>,
     },
     MultipleFunctions {
-        sequence: Vec<(String, HashMap<String, String>)>,
+        sequence: Vec<ToolCallStep>,
         allow_reordering: bool,
     },
 }
 
+impl ExpectedBehavior {
+    /// Every tool name this behavior expects a call to, in no particular
+    /// order. Empty for [`ExpectedBehavior::NoFunctionCall`]. Used to
+    /// validate that an externally-loaded scenario only references known
+    /// tools.
+    fn tool_names(&self) -> Vec<&str> {
+        match self {
+            ExpectedBehavior::NoFunctionCall => Vec::new(),
+            ExpectedBehavior::SingleFunction { name, .. } => vec![name.as_str()],
+            ExpectedBehavior::MultipleFunctions { sequence, .. } => {
+                sequence.iter().map(|step| step.name.as_str()).collect()
+            }
+        }
+    }
+}
+
+/// One step of a [`ExpectedBehavior::MultipleFunctions`] chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallStep {
+    pub name: String,
+    pub args_pattern: HashMap<String, String>,
+    /// Which earlier steps in the same `sequence` this step's arguments are
+    /// derived from, e.g. a `read_file` step whose `file_path` comes from a
+    /// prior `search_code` match rather than appearing verbatim in the
+    /// prompt. Lets the dependency-DAG validator tell "ran out of order"
+    /// apart from "skipped a required prerequisite call".
+    #[serde(default)]
+    pub derived_from: Vec<DataDependency>,
+}
+
+/// A single step-to-step data dependency within a [`ToolCallStep`] sequence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDependency {
+    /// Index into the sequence of the step this value is derived from.
+    pub step: usize,
+    /// The argument key (in the dependent step) whose value flows from it.
+    pub arg: String,
+}
+
+/// Outcome of [`validate_multi_tool_trace`]: either the trace satisfies the
+/// sequence's ordering/dependency constraints, or the first violation found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceValidation {
+    Valid,
+    /// The trace didn't include as many calls as the sequence expects.
+    WrongLength { expected: usize, actual: usize },
+    /// A positional (non-reordered) trace called the wrong tool at `position`.
+    NameMismatch { position: usize, expected: String, actual: String },
+    /// `found_step`'s dependency on `expected_step` wasn't honored: the
+    /// depended-upon call happened, but after `found_step`, not before it.
+    WrongOrder { expected_step: usize, found_step: usize },
+    /// `step` depends on `depends_on`, but no call matching `depends_on`'s
+    /// tool name appeared anywhere in the trace.
+    MissingPrerequisite { step: usize, depends_on: usize },
+}
+
+/// Checks `actual_calls` against `sequence`'s ordering constraints.
+///
+/// When `allow_reordering` is `false`, `actual_calls` must match `sequence`
+/// positionally. When `true`, `sequence` is treated as a partial order:
+/// calls may happen in any order as long as every [`DataDependency`] is
+/// honored (the step it depends on ran first). Matching actual calls back to
+/// steps is done by tool name, so this assumes each step in a given
+/// `sequence` calls a distinct tool.
+#[must_use]
+pub fn validate_multi_tool_trace(sequence: &[ToolCallStep], allow_reordering: bool, actual_calls: &[ActualCall]) -> TraceValidation {
+    if actual_calls.len() != sequence.len() {
+        return TraceValidation::WrongLength { expected: sequence.len(), actual: actual_calls.len() };
+    }
+
+    if !allow_reordering {
+        for (position, (step, actual)) in sequence.iter().zip(actual_calls).enumerate() {
+            if step.name != actual.name {
+                return TraceValidation::NameMismatch { position, expected: step.name.clone(), actual: actual.name.clone() };
+            }
+        }
+        return TraceValidation::Valid;
+    }
+
+    let mut position_of: HashMap<&str, usize> = HashMap::new();
+    for (position, actual) in actual_calls.iter().enumerate() {
+        position_of.entry(actual.name.as_str()).or_insert(position);
+    }
+
+    // Check dependency edges first, ahead of the plain presence check below,
+    // so a missing prerequisite is reported as such rather than as a bare
+    // "this step's name never appeared" on whichever step happens first.
+    for (step_index, step) in sequence.iter().enumerate() {
+        for dependency in &step.derived_from {
+            let prerequisite_name = sequence[dependency.step].name.as_str();
+            match position_of.get(prerequisite_name) {
+                None => return TraceValidation::MissingPrerequisite { step: step_index, depends_on: dependency.step },
+                Some(&prerequisite_position) => match position_of.get(step.name.as_str()) {
+                    Some(&own_position) if prerequisite_position < own_position => {}
+                    Some(_) => return TraceValidation::WrongOrder { expected_step: dependency.step, found_step: step_index },
+                    None => return TraceValidation::NameMismatch { position: step_index, expected: step.name.clone(), actual: String::new() },
+                },
+            }
+        }
+    }
+
+    for (step_index, step) in sequence.iter().enumerate() {
+        if !position_of.contains_key(step.name.as_str()) {
+            return TraceValidation::NameMismatch { position: step_index, expected: step.name.clone(), actual: String::new() };
+        }
+    }
+
+    TraceValidation::Valid
+}
+
+/// The tools the built-in scenarios (and therefore any externally-authored
+/// ones) are allowed to reference. Kept in sync with `src/functions.rs`'s
+/// tool registry.
+const KNOWN_TOOL_NAMES: &[&str] = &["read_file", "write_file", "list_files", "search_code"];
+
+/// An externally-authored scenario, as it appears in a YAML/TOML/JSON
+/// document read by [`ScenarioGenerator::from_reader`]. Unlike
+/// [`TestScenario`], `category` is optional here so a document can set it
+/// once at the top level and let every entry inherit it.
+#[derive(Debug, Clone, Deserialize)]
+struct RawScenario {
+    id: String,
+    #[serde(default)]
+    category: Option<TestCategory>,
+    prompt: String,
+    expected_behavior: ExpectedBehavior,
+    rationale: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Top-level shape of a scenario file: a default `category` for any entry
+/// that omits one, plus the list of entries itself.
+#[derive(Debug, Clone, Deserialize)]
+struct ScenarioDocument {
+    #[serde(default)]
+    category: Option<TestCategory>,
+    scenarios: Vec<RawScenario>,
+}
+
 pub struct ScenarioGenerator;
 
 impl ScenarioGenerator {
@@ -437,17 +579,190 @@ impl ScenarioGenerator {
         ]
     }
 
+    /// Generate multi-tool scenarios: prompts that require a chain of calls,
+    /// where a later step's arguments are derived from an earlier step's
+    /// result rather than appearing verbatim in the prompt.
+    pub fn multi_tool_scenarios() -> Vec<TestScenario> {
+        vec![
+            TestScenario {
+                id: "multi_tool_001".to_string(),
+                category: TestCategory::MultiTool,
+                prompt: "Find every file that imports serde and show me the first one".to_string(),
+                expected_behavior: ExpectedBehavior::MultipleFunctions {
+                    sequence: vec![
+                        ToolCallStep {
+                            name: "search_code".to_string(),
+                            args_pattern: HashMap::from([("pattern".to_string(), "use serde".to_string())]),
+                            derived_from: vec![],
+                        },
+                        ToolCallStep {
+                            name: "read_file".to_string(),
+                            args_pattern: HashMap::new(),
+                            derived_from: vec![DataDependency { step: 0, arg: "file_path".to_string() }],
+                        },
+                    ],
+                    allow_reordering: false,
+                },
+                rationale: "The read's file_path can only be known once search_code returns a match".to_string(),
+                tags: vec!["search".to_string(), "read".to_string(), "chained".to_string()],
+            },
+            TestScenario {
+                id: "multi_tool_002".to_string(),
+                category: TestCategory::MultiTool,
+                prompt: "List the Rust files in src and read the first one".to_string(),
+                expected_behavior: ExpectedBehavior::MultipleFunctions {
+                    sequence: vec![
+                        ToolCallStep {
+                            name: "list_files".to_string(),
+                            args_pattern: HashMap::from([("pattern".to_string(), "src/*.rs".to_string())]),
+                            derived_from: vec![],
+                        },
+                        ToolCallStep {
+                            name: "read_file".to_string(),
+                            args_pattern: HashMap::new(),
+                            derived_from: vec![DataDependency { step: 0, arg: "file_path".to_string() }],
+                        },
+                    ],
+                    allow_reordering: false,
+                },
+                rationale: "The read target comes from whatever list_files happens to return first".to_string(),
+                tags: vec!["list".to_string(), "read".to_string(), "chained".to_string()],
+            },
+            TestScenario {
+                id: "multi_tool_003".to_string(),
+                category: TestCategory::MultiTool,
+                prompt: "Read README.md and also list the Python files".to_string(),
+                expected_behavior: ExpectedBehavior::MultipleFunctions {
+                    sequence: vec![
+                        ToolCallStep {
+                            name: "read_file".to_string(),
+                            args_pattern: HashMap::from([("file_path".to_string(), "README.md".to_string())]),
+                            derived_from: vec![],
+                        },
+                        ToolCallStep {
+                            name: "list_files".to_string(),
+                            args_pattern: HashMap::from([("pattern".to_string(), "*.py".to_string())]),
+                            derived_from: vec![],
+                        },
+                    ],
+                    allow_reordering: true,
+                },
+                rationale: "Neither call depends on the other, so either order is acceptable".to_string(),
+                tags: vec!["read".to_string(), "list".to_string(), "independent".to_string()],
+            },
+        ]
+    }
+
     /// Generate all test scenarios
     pub fn all_scenarios() -> Vec<TestScenario> {
         let mut scenarios = Vec::new();
         scenarios.extend(Self::no_tool_scenarios());
         scenarios.extend(Self::single_tool_scenarios());
+        scenarios.extend(Self::multi_tool_scenarios());
         scenarios.extend(Self::edge_cases());
-        
+
         // Add more categories as implemented
         scenarios
     }
 
+    /// [`Self::all_scenarios`]'s built-ins plus every scenario loaded from
+    /// `dir` (see [`Self::from_dir`]), so a deployment can grow the corpus
+    /// by dropping files in a directory instead of recompiling. Validated
+    /// together, so an externally-authored scenario can't collide with a
+    /// built-in `id`.
+    pub fn all_scenarios_with_external(dir: &Path) -> Result<Vec<TestScenario>> {
+        let mut scenarios = Self::all_scenarios();
+        scenarios.extend(Self::from_dir(dir)?);
+        Self::validate(&scenarios)?;
+        Ok(scenarios)
+    }
+
+    /// Loads every `.json`, `.toml`, `.yaml`, and `.yml` file directly
+    /// inside `dir` (in directory order) via [`Self::from_reader`] and
+    /// concatenates the results.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` can't be read, or if any scenario file in
+    /// it fails to parse or validate.
+    pub fn from_dir(dir: &Path) -> Result<Vec<TestScenario>> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("reading scenario directory {}", dir.display()))?
+            .collect::<std::io::Result<Vec<_>>>()
+            .with_context(|| format!("reading scenario directory {}", dir.display()))?;
+        entries.sort_by_key(std::fs::DirEntry::path);
+
+        let mut scenarios = Vec::new();
+        for entry in entries {
+            let path = entry.path();
+            let Some(format) = path.extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            if !matches!(format, "json" | "toml" | "yaml" | "yml") {
+                continue;
+            }
+
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            let loaded = Self::from_reader(&contents, format)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            scenarios.extend(loaded);
+        }
+        Ok(scenarios)
+    }
+
+    /// Parses `contents` as a [`ScenarioDocument`] in the format named by
+    /// `format` (`"json"`, `"toml"`, `"yaml"`, or `"yml"`), resolves each
+    /// entry's `category` against the document's default (erroring if
+    /// neither sets one), and validates the result (see [`Self::validate`]).
+    pub fn from_reader(contents: &str, format: &str) -> Result<Vec<TestScenario>> {
+        let document: ScenarioDocument = match format {
+            "json" => serde_json::from_str(contents)?,
+            "toml" => toml::from_str(contents)?,
+            "yaml" | "yml" => serde_yaml::from_str(contents)?,
+            other => bail!("unknown scenario format '{other}' (expected json, toml, yaml, or yml)"),
+        };
+
+        let scenarios = document
+            .scenarios
+            .into_iter()
+            .map(|raw| {
+                let category = raw.category.or_else(|| document.category.clone()).ok_or_else(|| {
+                    anyhow::anyhow!("scenario '{}' has no category and the document sets no default", raw.id)
+                })?;
+                Ok(TestScenario {
+                    id: raw.id,
+                    category,
+                    prompt: raw.prompt,
+                    expected_behavior: raw.expected_behavior,
+                    rationale: raw.rationale,
+                    tags: raw.tags,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::validate(&scenarios)?;
+        Ok(scenarios)
+    }
+
+    /// Checks that every scenario's `id` is unique within `scenarios` and
+    /// that every tool name its `expected_behavior` references is one of
+    /// [`KNOWN_TOOL_NAMES`].
+    fn validate(scenarios: &[TestScenario]) -> Result<()> {
+        let mut seen_ids = HashSet::new();
+        for scenario in scenarios {
+            if !seen_ids.insert(scenario.id.as_str()) {
+                bail!("duplicate scenario id '{}'", scenario.id);
+            }
+            for name in scenario.expected_behavior.tool_names() {
+                if !KNOWN_TOOL_NAMES.contains(&name) {
+                    bail!("scenario '{}' references unknown tool '{name}'", scenario.id);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Get scenarios by category
     pub fn by_category(category: TestCategory) -> Vec<TestScenario> {
         Self::all_scenarios()
@@ -465,6 +780,220 @@ impl ScenarioGenerator {
     }
 }
 
+/// A function call a model actually emitted, to be scored against a
+/// [`TestScenario`]'s `expected_behavior`.
+#[derive(Debug, Clone)]
+pub struct ActualCall {
+    pub name: String,
+    pub args: HashMap<String, String>,
+}
+
+/// Per-argument comparison detail produced by [`Evaluator::evaluate`],
+/// explaining which `args_pattern` key drove the score up or down.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgDiff {
+    /// The argument was present and close enough to count as correct.
+    Matched { key: String, similarity: f64 },
+    /// The argument was present but too far from the expected value.
+    Mismatched { key: String, expected: String, actual: String, similarity: f64 },
+    /// An expected argument was never supplied.
+    Missing { key: String, expected: String },
+    /// The model supplied an argument the scenario didn't expect.
+    Extra { key: String, actual: String },
+}
+
+/// A graded (rather than pass/fail) evaluation of an [`ActualCall`] (or a
+/// sequence of them) against a scenario's `expected_behavior`.
+#[derive(Debug, Clone)]
+pub struct EvaluationScore {
+    /// `0.0`-`1.0`, where `1.0` is a perfect match.
+    pub score: f64,
+    /// Whether every function name involved matched exactly.
+    pub name_matched: bool,
+    pub arg_diffs: Vec<ArgDiff>,
+}
+
+/// Scores an [`ActualCall`] trace against a [`TestScenario`], using
+/// Levenshtein edit distance so near-miss argument values (`"./README.md"`
+/// vs `"README.md"`) earn partial credit instead of failing outright.
+pub struct Evaluator {
+    /// Divisor applied to `max(len_a, len_b)` to compute the edit-distance
+    /// budget an argument value must clear to count as "matched" — the
+    /// same heuristic rustc's `find_best_match_for_name` uses. Lower values
+    /// are stricter; the default of `3` matches rustc's.
+    edit_budget_divisor: usize,
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self { edit_budget_divisor: 3 }
+    }
+}
+
+impl Evaluator {
+    #[must_use]
+    pub fn new(edit_budget_divisor: usize) -> Self {
+        Self { edit_budget_divisor }
+    }
+
+    /// Score `actual_calls` against `scenario`. `actual_calls` should be
+    /// empty for a scenario expecting `NoFunctionCall`.
+    pub fn evaluate(&self, scenario: &TestScenario, actual_calls: &[ActualCall]) -> EvaluationScore {
+        match &scenario.expected_behavior {
+            ExpectedBehavior::NoFunctionCall => {
+                let matched = actual_calls.is_empty();
+                EvaluationScore { score: if matched { 1.0 } else { 0.0 }, name_matched: matched, arg_diffs: Vec::new() }
+            }
+            ExpectedBehavior::SingleFunction { name, args_pattern } => match actual_calls.first() {
+                Some(actual) => self.score_single(name, args_pattern, actual),
+                None => EvaluationScore { score: 0.0, name_matched: false, arg_diffs: Vec::new() },
+            },
+            ExpectedBehavior::MultipleFunctions { sequence, .. } => {
+                if sequence.is_empty() {
+                    return EvaluationScore { score: 1.0, name_matched: true, arg_diffs: Vec::new() };
+                }
+
+                let mut arg_diffs = Vec::new();
+                let mut name_matched = true;
+                let mut total = 0.0;
+                for (i, expected_step) in sequence.iter().enumerate() {
+                    let step = match actual_calls.get(i) {
+                        Some(actual) => self.score_single(&expected_step.name, &expected_step.args_pattern, actual),
+                        None => EvaluationScore { score: 0.0, name_matched: false, arg_diffs: Vec::new() },
+                    };
+                    total += step.score;
+                    name_matched &= step.name_matched;
+                    arg_diffs.extend(step.arg_diffs);
+                }
+
+                EvaluationScore { score: total / sequence.len() as f64, name_matched, arg_diffs }
+            }
+        }
+    }
+
+    /// Score a single `actual` call against an expected `name`/`args_pattern`
+    /// pair. The tool name must match exactly; missing or extra arguments
+    /// subtract proportionally by inflating the scoring denominator.
+    fn score_single(&self, name: &str, args_pattern: &HashMap<String, String>, actual: &ActualCall) -> EvaluationScore {
+        if actual.name != name {
+            return EvaluationScore { score: 0.0, name_matched: false, arg_diffs: Vec::new() };
+        }
+
+        let mut arg_diffs = Vec::new();
+        let mut total_similarity = 0.0;
+
+        for (key, expected_value) in args_pattern {
+            match actual.args.get(key) {
+                Some(actual_value) => {
+                    let similarity = string_similarity(expected_value, actual_value);
+                    arg_diffs.push(if self.is_match(expected_value, actual_value) {
+                        ArgDiff::Matched { key: key.clone(), similarity }
+                    } else {
+                        ArgDiff::Mismatched {
+                            key: key.clone(),
+                            expected: expected_value.clone(),
+                            actual: actual_value.clone(),
+                            similarity,
+                        }
+                    });
+                    total_similarity += similarity;
+                }
+                None => arg_diffs.push(ArgDiff::Missing { key: key.clone(), expected: expected_value.clone() }),
+            }
+        }
+
+        let extra_keys: Vec<&String> = actual.args.keys().filter(|k| !args_pattern.contains_key(*k)).collect();
+        for key in &extra_keys {
+            arg_diffs.push(ArgDiff::Extra { key: (*key).clone(), actual: actual.args[*key].clone() });
+        }
+
+        let denominator = (args_pattern.len() + extra_keys.len()).max(1) as f64;
+        EvaluationScore { score: total_similarity / denominator, name_matched: true, arg_diffs }
+    }
+
+    /// Whether `expected` and `actual` are close enough to count as a
+    /// match: their case-insensitive edit distance is within
+    /// `max(len_a, len_b) / edit_budget_divisor`.
+    fn is_match(&self, expected: &str, actual: &str) -> bool {
+        let (expected, actual) = (expected.to_lowercase(), actual.to_lowercase());
+        let max_len = expected.chars().count().max(actual.chars().count());
+        let budget = max_len / self.edit_budget_divisor.max(1);
+        levenshtein_distance(&expected, &actual) <= budget
+    }
+}
+
+/// Classic Levenshtein edit distance via the standard DP table (rows =
+/// chars of `a`, cols = chars of `b`, cell = min of delete/insert/substitute).
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (rows, cols) = (a.len() + 1, b.len() + 1);
+    let mut table = vec![vec![0usize; cols]; rows];
+
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..cols {
+        table[0][j] = j;
+    }
+
+    for i in 1..rows {
+        for j in 1..cols {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            table[i][j] = (table[i - 1][j] + 1).min(table[i][j - 1] + 1).min(table[i - 1][j - 1] + cost);
+        }
+    }
+
+    table[rows - 1][cols - 1]
+}
+
+/// Normalized similarity in `[0, 1]` between two strings, compared
+/// case-insensitively: `1 - distance / max(len_a, len_b)`. Two empty
+/// strings are treated as identical.
+fn string_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein_distance(&a, &b) as f64 / max_len as f64
+}
+
+/// Suggests the closest of `candidates` to a hallucinated tool `name`,
+/// mirroring rustc's `find_best_match_for_name`: candidates are compared
+/// case-insensitively by Levenshtein distance, the closest one wins
+/// provided its distance is within `max(len(name), len(candidate)) / 3`,
+/// and ties are broken in favor of whichever shares the longer prefix with
+/// `name`. Returns `None` if no candidate clears the budget.
+#[must_use]
+pub fn suggest_tool_name(name: &str, candidates: &[&str]) -> Option<String> {
+    let lower_name = name.to_lowercase();
+    candidates
+        .iter()
+        .filter_map(|&candidate| {
+            let distance = levenshtein_distance(&lower_name, &candidate.to_lowercase());
+            let budget = name.chars().count().max(candidate.chars().count()) / 3;
+            (distance <= budget).then_some((candidate, distance))
+        })
+        .min_by(|&(a, a_distance), &(b, b_distance)| {
+            a_distance.cmp(&b_distance).then_with(|| {
+                shared_prefix_len(&lower_name, &b.to_lowercase()).cmp(&shared_prefix_len(&lower_name, &a.to_lowercase()))
+            })
+        })
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+/// Like [`suggest_tool_name`], but compares against [`KNOWN_TOOL_NAMES`]
+/// instead of a caller-supplied candidate list.
+#[must_use]
+pub fn suggest_known_tool_name(name: &str) -> Option<String> {
+    suggest_tool_name(name, KNOWN_TOOL_NAMES)
+}
+
+fn shared_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,12 +1002,16 @@ mod tests {
     fn test_scenario_generation() {
         let no_tool = ScenarioGenerator::no_tool_scenarios();
         assert!(no_tool.len() >= 20);
-        
+
         let single_tool = ScenarioGenerator::single_tool_scenarios();
         assert!(single_tool.len() >= 10);
-        
+
         let edge_cases = ScenarioGenerator::edge_cases();
         assert!(edge_cases.len() >= 5);
+
+        let multi_tool = ScenarioGenerator::multi_tool_scenarios();
+        assert!(multi_tool.len() >= 3);
+        assert!(multi_tool.iter().all(|s| s.category == TestCategory::MultiTool));
     }
 
     #[test]
@@ -492,4 +1025,203 @@ mod tests {
         let search_scenarios = ScenarioGenerator::by_tag("search");
         assert!(search_scenarios.iter().all(|s| s.tags.contains(&"search".to_string())));
     }
+
+    fn call(name: &str, args: &[(&str, &str)]) -> ActualCall {
+        ActualCall {
+            name: name.to_string(),
+            args: args.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    fn single_function_scenario(name: &str, args: &[(&str, &str)]) -> TestScenario {
+        TestScenario {
+            id: "t".to_string(),
+            category: TestCategory::SingleTool,
+            prompt: "prompt".to_string(),
+            expected_behavior: ExpectedBehavior::SingleFunction {
+                name: name.to_string(),
+                args_pattern: args.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            },
+            rationale: "r".to_string(),
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn evaluator_gives_a_perfect_score_for_an_exact_match() {
+        let scenario = single_function_scenario("read_file", &[("file_path", "README.md")]);
+        let score = Evaluator::default().evaluate(&scenario, &[call("read_file", &[("file_path", "README.md")])]);
+        assert_eq!(score.score, 1.0);
+        assert!(score.name_matched);
+    }
+
+    #[test]
+    fn evaluator_gives_partial_credit_for_a_near_miss_argument() {
+        let scenario = single_function_scenario("read_file", &[("file_path", "README.md")]);
+        let score = Evaluator::default().evaluate(&scenario, &[call("read_file", &[("file_path", "./README.md")])]);
+        assert!(score.score > 0.5 && score.score < 1.0);
+        assert!(matches!(score.arg_diffs.as_slice(), [ArgDiff::Matched { .. }]));
+    }
+
+    #[test]
+    fn evaluator_scores_zero_for_a_mismatched_tool_name() {
+        let scenario = single_function_scenario("read_file", &[("file_path", "README.md")]);
+        let score = Evaluator::default().evaluate(&scenario, &[call("write_file", &[("file_path", "README.md")])]);
+        assert_eq!(score.score, 0.0);
+        assert!(!score.name_matched);
+    }
+
+    #[test]
+    fn evaluator_penalizes_missing_and_extra_arguments() {
+        let scenario = single_function_scenario("write_file", &[("file_path", "test.txt"), ("content", "hi")]);
+        let score = Evaluator::default().evaluate(
+            &scenario,
+            &[call("write_file", &[("file_path", "test.txt"), ("mode", "append")])],
+        );
+        assert!(score.score < 1.0);
+        assert!(score.arg_diffs.iter().any(|d| matches!(d, ArgDiff::Missing { key, .. } if key == "content")));
+        assert!(score.arg_diffs.iter().any(|d| matches!(d, ArgDiff::Extra { key, .. } if key == "mode")));
+    }
+
+    #[test]
+    fn evaluator_scores_no_function_call_scenarios() {
+        let scenario = TestScenario {
+            id: "t".to_string(),
+            category: TestCategory::NoTool,
+            prompt: "prompt".to_string(),
+            expected_behavior: ExpectedBehavior::NoFunctionCall,
+            rationale: "r".to_string(),
+            tags: vec![],
+        };
+        assert_eq!(Evaluator::default().evaluate(&scenario, &[]).score, 1.0);
+        assert_eq!(Evaluator::default().evaluate(&scenario, &[call("read_file", &[])]).score, 0.0);
+    }
+
+    #[test]
+    fn levenshtein_distance_matches_known_values() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("README.md", "README.md"), 0);
+    }
+
+    #[test]
+    fn suggest_known_tool_name_finds_a_camelcase_typo() {
+        assert_eq!(suggest_known_tool_name("readFile").as_deref(), Some("read_file"));
+        assert_eq!(suggest_known_tool_name("listFiles").as_deref(), Some("list_files"));
+    }
+
+    #[test]
+    fn suggest_known_tool_name_returns_none_past_the_edit_budget() {
+        assert_eq!(suggest_known_tool_name("frobnicate_widgets"), None);
+    }
+
+    #[test]
+    fn suggest_tool_name_breaks_ties_with_the_shared_prefix() {
+        // Both candidates are distance 1 from "abcd", but "abcx" shares a
+        // longer prefix with it than "xbcd" does.
+        assert_eq!(suggest_tool_name("abcd", &["xbcd", "abcx"]).as_deref(), Some("abcx"));
+    }
+
+    fn step(name: &str, derived_from: Vec<DataDependency>) -> ToolCallStep {
+        ToolCallStep { name: name.to_string(), args_pattern: HashMap::new(), derived_from }
+    }
+
+    #[test]
+    fn validate_multi_tool_trace_accepts_a_positional_match() {
+        let sequence = vec![step("search_code", vec![]), step("read_file", vec![DataDependency { step: 0, arg: "file_path".to_string() }])];
+        let actual = vec![call("search_code", &[]), call("read_file", &[])];
+        assert_eq!(validate_multi_tool_trace(&sequence, false, &actual), TraceValidation::Valid);
+    }
+
+    #[test]
+    fn validate_multi_tool_trace_rejects_the_wrong_position() {
+        let sequence = vec![step("search_code", vec![]), step("read_file", vec![])];
+        let actual = vec![call("read_file", &[]), call("search_code", &[])];
+        assert!(matches!(validate_multi_tool_trace(&sequence, false, &actual), TraceValidation::NameMismatch { .. }));
+    }
+
+    #[test]
+    fn validate_multi_tool_trace_accepts_any_order_satisfying_dependencies() {
+        let sequence = vec![step("search_code", vec![]), step("read_file", vec![DataDependency { step: 0, arg: "file_path".to_string() }])];
+        let actual = vec![call("search_code", &[]), call("read_file", &[])];
+        assert_eq!(validate_multi_tool_trace(&sequence, true, &actual), TraceValidation::Valid);
+    }
+
+    #[test]
+    fn validate_multi_tool_trace_flags_a_dependency_run_out_of_order() {
+        let sequence = vec![step("search_code", vec![]), step("read_file", vec![DataDependency { step: 0, arg: "file_path".to_string() }])];
+        let actual = vec![call("read_file", &[]), call("search_code", &[])];
+        assert!(matches!(
+            validate_multi_tool_trace(&sequence, true, &actual),
+            TraceValidation::WrongOrder { expected_step: 0, found_step: 1 }
+        ));
+    }
+
+    #[test]
+    fn validate_multi_tool_trace_flags_a_missing_prerequisite() {
+        let sequence = vec![step("search_code", vec![]), step("read_file", vec![DataDependency { step: 0, arg: "file_path".to_string() }])];
+        let actual = vec![call("read_file", &[]), call("list_files", &[])];
+        assert!(matches!(
+            validate_multi_tool_trace(&sequence, true, &actual),
+            TraceValidation::MissingPrerequisite { step: 1, depends_on: 0 }
+        ));
+    }
+
+    #[test]
+    fn built_in_scenarios_round_trip_through_json() {
+        let built_in = ScenarioGenerator::all_scenarios();
+        let document = serde_json::json!({ "scenarios": built_in });
+        let loaded = ScenarioGenerator::from_reader(&document.to_string(), "json").unwrap();
+        assert_eq!(loaded.len(), built_in.len());
+        assert_eq!(loaded.iter().map(|s| &s.id).collect::<Vec<_>>(), built_in.iter().map(|s| &s.id).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn from_reader_fills_in_a_missing_category_from_the_document_default() {
+        let toml = r#"
+            category = "SingleTool"
+
+            [[scenarios]]
+            id = "ext_001"
+            prompt = "Read config.yaml"
+            rationale = "external scenario"
+
+            [scenarios.expected_behavior]
+            type = "SingleFunction"
+            name = "read_file"
+            args_pattern = { file_path = "config.yaml" }
+        "#;
+        let loaded = ScenarioGenerator::from_reader(toml, "toml").unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].category, TestCategory::SingleTool);
+    }
+
+    #[test]
+    fn from_reader_rejects_a_duplicate_id() {
+        let json = serde_json::json!({
+            "scenarios": [
+                { "id": "dup", "category": "NoTool", "prompt": "a", "expected_behavior": { "type": "NoFunctionCall" }, "rationale": "r", "tags": [] },
+                { "id": "dup", "category": "NoTool", "prompt": "b", "expected_behavior": { "type": "NoFunctionCall" }, "rationale": "r", "tags": [] },
+            ]
+        });
+        let err = ScenarioGenerator::from_reader(&json.to_string(), "json").unwrap_err();
+        assert!(err.to_string().contains("duplicate scenario id"));
+    }
+
+    #[test]
+    fn from_reader_rejects_an_unknown_tool_name() {
+        let json = serde_json::json!({
+            "scenarios": [
+                {
+                    "id": "bad_tool",
+                    "category": "SingleTool",
+                    "prompt": "a",
+                    "expected_behavior": { "type": "SingleFunction", "name": "delete_everything", "args_pattern": {} },
+                    "rationale": "r",
+                    "tags": [],
+                },
+            ]
+        });
+        let err = ScenarioGenerator::from_reader(&json.to_string(), "json").unwrap_err();
+        assert!(err.to_string().contains("unknown tool"));
+    }
 }
\ No newline at end of file
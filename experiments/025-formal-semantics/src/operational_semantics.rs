@@ -4,8 +4,23 @@
 //! defining precise transition rules and evaluation contexts.
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
 
+/// One node in a multi-step tool-call plan produced by
+/// [`OperationalSemantics::plan`]. Unlike the single `(name, args)` pair
+/// `analyze_for_function_call` hands the small-step FUNCTION-CALL rule, a
+/// plan is an ordered DAG: calls with no unmet `depends_on` can run
+/// concurrently, while a dependent call waits for its predecessors and has
+/// their output substituted into its `args` (see `execute_plan`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: usize,
+    pub name: String,
+    pub args: Vec<Value>,
+    pub depends_on: Vec<usize>,
+}
+
 /// Syntax of REPL expressions
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
@@ -35,6 +50,11 @@ pub enum Command {
     ShowContext,
     SetModel(String),
     UserQuery(String),
+    /// An unrecognized slash-command, parsed into a name and positional
+    /// arguments by [`command_grammar::parse_args`]. Dispatched through
+    /// `Environment::functions` exactly like `FunctionCall`, so new
+    /// slash-commands need only be registered there, not added to this enum.
+    Invoke(String, Vec<Value>),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -69,8 +89,12 @@ pub struct Environment {
 pub struct FunctionDef {
     pub name: String,
     pub parameters: Vec<String>,
+    /// Declared type of each parameter, positionally matching `parameters`.
+    pub parameter_types: Vec<typing::Type>,
     pub description: String,
     pub implementation: FunctionImpl,
+    /// Type produced by a successful call to this function.
+    pub return_type: typing::Type,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +121,13 @@ pub struct Config {
     pub max_context_length: usize,
     pub timeout_ms: u64,
     pub max_function_calls: usize,
+    /// When set, [`OperationalSemantics::eval`] runs `typing::TypeChecker`
+    /// over the initial expression first and refuses to step an ill-typed
+    /// program.
+    pub type_check: bool,
+    /// Upper bound on the number of small-steps `eval` will take before
+    /// giving up (replaces the old hard-coded `MAX_STEPS`).
+    pub max_steps: usize,
 }
 
 /// Evaluation state
@@ -106,9 +137,25 @@ pub struct EvalState {
     pub environment: Environment,
     pub context: Context,
     pub output: Vec<String>,
+    /// Lines accumulated so far for a multiline input still awaiting its
+    /// continuation (see the INPUT rule below). `None` when no multiline
+    /// input is in progress.
+    pub pending_input: Option<String>,
+    /// The query that's driving the current chain of function calls, so a
+    /// FUNCTION-CALL step can loop back into QUERY with the function's
+    /// result folded into context, instead of answering after one call.
+    pub originating_query: Option<String>,
+    /// How many function calls have been made answering `originating_query`,
+    /// bounded by `Config::max_function_calls`.
+    pub function_calls_made: usize,
+    /// The value piped into the current expression by a preceding
+    /// `Sequence` stage via the SEQ-NOOP rule, if any. Mirrors the `$in`
+    /// binding installed in `environment.variables`.
+    pub piped_input: Option<Value>,
 }
 
 /// Small-step operational semantics
+#[derive(Clone)]
 pub struct OperationalSemantics {
     config: Config,
 }
@@ -123,11 +170,35 @@ impl OperationalSemantics {
     pub fn step(&self, state: EvalState) -> Option<EvalState> {
         match &state.expression {
             Expression::Input(input) => {
+                // Rule: INPUT-CONTINUE
+                // A line ending in a trailing `\` is a continuation: fold it
+                // into `pending_input` and wait for the next line instead of
+                // parsing a command.
+                // ⟨Input(s·"\"), σ, h, buf⟩ → ⟨Noop, σ, h, buf + s⟩
+                if let Some(continued) = input.strip_suffix('\\') {
+                    let mut buffer = state.pending_input.clone().unwrap_or_default();
+                    buffer.push_str(continued);
+                    buffer.push('\n');
+                    return Some(EvalState {
+                        expression: Expression::Noop,
+                        pending_input: Some(buffer),
+                        ..state
+                    });
+                }
+
                 // Rule: INPUT
-                // ⟨Input(s), σ, h⟩ → ⟨Command(parse(s)), σ, h⟩
-                let cmd = self.parse_input(input);
+                // ⟨Input(s), σ, h, buf⟩ → ⟨Command(parse(buf + s)), σ, h, None⟩
+                let full_input = match state.pending_input.clone() {
+                    Some(mut buffer) => {
+                        buffer.push_str(input);
+                        buffer
+                    }
+                    None => input.clone(),
+                };
+                let cmd = self.parse_input(&full_input);
                 Some(EvalState {
                     expression: Expression::Command(cmd),
+                    pending_input: None,
                     ..state
                 })
             }
@@ -189,16 +260,42 @@ impl OperationalSemantics {
                             ..state
                         })
                     }
+
+                    Command::Invoke(name, args) => {
+                        // Rule: INVOKE
+                        // ⟨Invoke(f, args), σ, h⟩ → ⟨FunctionCall(f, args), σ, h⟩
+                        // Forwards into the FUNCTION-CALL rule so a
+                        // registered slash-command gets identical lookup,
+                        // native/user dispatch, and chaining semantics as
+                        // any other function.
+                        Some(EvalState {
+                            expression: Expression::FunctionCall(name.clone(), args.clone()),
+                            ..state
+                        })
+                    }
                 }
             }
 
             Expression::Query(query, context) => {
                 // Rule: QUERY-FUNCTION
-                // If query requires function call:
+                // If query requires function call, and the chain hasn't hit
+                // its cap yet:
                 // ⟨Query(q, h), σ, h⟩ → ⟨FunctionCall(f, args), σ, h⟩
-                if let Some((func, args)) = self.analyze_for_function_call(query) {
+                if state.function_calls_made >= self.config.max_function_calls {
+                    let response = self.mock_model_response(query, context);
+                    return Some(EvalState {
+                        expression: Expression::Response(response),
+                        originating_query: None,
+                        function_calls_made: 0,
+                        ..state
+                    });
+                }
+
+                if let Some((func, args)) = self.analyze_for_function_call(query, &state.environment) {
+                    let originating_query = Some(query.clone());
                     Some(EvalState {
                         expression: Expression::FunctionCall(func, args),
+                        originating_query,
                         ..state
                     })
                 } else {
@@ -207,6 +304,8 @@ impl OperationalSemantics {
                     let response = self.mock_model_response(query, context);
                     Some(EvalState {
                         expression: Expression::Response(response),
+                        originating_query: None,
+                        function_calls_made: 0,
                         ..state
                     })
                 }
@@ -217,10 +316,27 @@ impl OperationalSemantics {
                 if let Some(func_def) = state.environment.functions.get(name) {
                     match &func_def.implementation {
                         FunctionImpl::Native(native_name) => {
-                            // ⟨FunctionCall(f, args), σ, h⟩ → ⟨Response(eval(f, args)), σ, h⟩
+                            // ⟨FunctionCall(f, args), σ, h⟩ → ⟨Query(q, h + FnMsg(r)), σ, h + FnMsg(r)⟩
+                            // The result is folded into context and control
+                            // returns to QUERY, so the model can chain
+                            // another function call or produce a final
+                            // Response once it has what it needs.
                             let result = self.eval_native_function(native_name, args);
+                            let mut new_context = state.context.clone();
+                            new_context.messages.push(Message {
+                                role: "function".to_string(),
+                                content: format!("{name}: {result}"),
+                            });
+
+                            let query = state
+                                .originating_query
+                                .clone()
+                                .unwrap_or_else(|| result.clone());
+
                             Some(EvalState {
-                                expression: Expression::Response(result),
+                                expression: Expression::Query(query, new_context.clone()),
+                                context: new_context,
+                                function_calls_made: state.function_calls_made + 1,
                                 ..state
                             })
                         }
@@ -270,9 +386,19 @@ impl OperationalSemantics {
                 // ⟨e1; e2, σ, h⟩ → ⟨e1'; e2, σ', h'⟩
                 if let Expression::Noop = **e1 {
                     // Rule: SEQ-NOOP
-                    // ⟨Noop; e2, σ, h⟩ → ⟨e2, σ, h⟩
+                    // e1's last produced output (if any) becomes the
+                    // pipeline value: bound to `$in` in the environment and
+                    // carried on `piped_input` so e2 can consume it.
+                    // ⟨Noop; e2, σ, h⟩ → ⟨e2, σ[$in ↦ v], h⟩
+                    let piped = state.output.last().cloned().map(Value::String);
+                    let mut new_env = state.environment.clone();
+                    if let Some(v) = piped.clone() {
+                        new_env.variables.insert("$in".to_string(), v);
+                    }
                     Some(EvalState {
                         expression: (**e2).clone(),
+                        environment: new_env,
+                        piped_input: piped,
                         ..state
                     })
                 } else {
@@ -284,9 +410,7 @@ impl OperationalSemantics {
                                 Box::new(stepped.expression),
                                 e2.clone()
                             ),
-                            environment: stepped.environment,
-                            context: stepped.context,
-                            output: stepped.output,
+                            ..stepped
                         })
                     } else {
                         None
@@ -303,11 +427,27 @@ impl OperationalSemantics {
 
     /// Big-step evaluation (evaluates to completion)
     pub fn eval(&self, initial_state: EvalState) -> Result<EvalState, String> {
+        if self.config.type_check {
+            typing::TypeChecker::type_of(&initial_state.expression, &initial_state.environment)?;
+        }
+
+        let deadline = Duration::from_millis(self.config.timeout_ms);
+        let started = Instant::now();
+
         let mut state = initial_state;
         let mut steps = 0;
-        const MAX_STEPS: usize = 1000;
 
-        while steps < MAX_STEPS {
+        while steps < self.config.max_steps {
+            if started.elapsed() >= deadline {
+                // Surface the timeout as a typed `ErrorKind::Timeout` via
+                // the ERROR rule, rather than a bare `Err` string, so
+                // callers get the same signal as any other evaluation error.
+                state.expression = Expression::Error(ErrorKind::Timeout);
+                return Ok(self
+                    .step(state)
+                    .expect("ERROR rule always produces a terminal state"));
+            }
+
             match self.step(state.clone()) {
                 Some(new_state) => state = new_state,
                 None => return Ok(state),
@@ -318,37 +458,80 @@ impl OperationalSemantics {
         Err("Evaluation did not terminate within step limit".to_string())
     }
 
+    /// Parses `source` into a right-nested `Sequence` of `Expression::Input`
+    /// lines (blank lines are skipped), without evaluating it. The AST half
+    /// of a `repl parse` dry-run mode; pair with [`Self::run_program`] to
+    /// also execute it.
+    pub fn parse_program(&self, source: &str) -> Expression {
+        source
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .rev()
+            .fold(Expression::Noop, |acc, line| match acc {
+                Expression::Noop => Expression::Input(line.to_string()),
+                rest => Expression::Sequence(Box::new(Expression::Input(line.to_string())), Box::new(rest)),
+            })
+    }
+
+    /// Batch entry point modeled on `repl run session.txt`: parses `source`
+    /// into a program via [`Self::parse_program`], big-steps it to
+    /// completion in `env`, and returns the accumulated output lines.
+    pub fn run_program(&self, source: &str, env: Environment) -> Result<Vec<String>, String> {
+        let state = EvalState {
+            expression: self.parse_program(source),
+            environment: env,
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        self.eval(state).map(|final_state| final_state.output)
+    }
+
     // Helper methods
 
     fn parse_input(&self, input: &str) -> Command {
         let trimmed = input.trim();
-        if trimmed.starts_with('/') {
-            match trimmed {
-                "/help" => Command::Help,
-                "/exit" | "/quit" => Command::Exit,
-                "/clear" => Command::Clear,
-                "/context" => Command::ShowContext,
-                _ if trimmed.starts_with("/model ") => {
-                    let model = trimmed.trim_start_matches("/model ").to_string();
-                    Command::SetModel(model)
-                }
-                _ => Command::UserQuery(input.to_string()),
-            }
-        } else {
-            Command::UserQuery(input.to_string())
+        let Some(rest) = trimmed.strip_prefix('/') else {
+            return Command::UserQuery(input.to_string());
+        };
+
+        let (name, args_str) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        match name {
+            "help" => Command::Help,
+            "exit" | "quit" => Command::Exit,
+            "clear" => Command::Clear,
+            "context" => Command::ShowContext,
+            "model" => Command::SetModel(args_str.trim().to_string()),
+            _ => Command::Invoke(name.to_string(), command_grammar::parse_args(args_str)),
         }
     }
 
-    fn analyze_for_function_call(&self, query: &str) -> Option<(String, Vec<Value>)> {
+    fn analyze_for_function_call(&self, query: &str, env: &Environment) -> Option<(String, Vec<Value>)> {
         // Simplified analysis - in practice would use NLP/AI
         let lower = query.to_lowercase();
-        
+
+        if lower.contains("summarize") {
+            // Pipeline stage: summarize whatever flowed in via `$in` rather
+            // than re-deriving its argument from the query text.
+            let input = env
+                .variables
+                .get("$in")
+                .cloned()
+                .unwrap_or_else(|| Value::String(query.to_string()));
+            return Some(("summarize".to_string(), vec![input]));
+        }
+
         if lower.contains("read") && lower.contains("file") {
             if let Some(path) = self.extract_file_path(query) {
                 return Some(("read_file".to_string(), vec![Value::String(path)]));
             }
         }
-        
+
         if lower.contains("write") || lower.contains("create") {
             if let Some((path, content)) = self.extract_write_params(query) {
                 return Some(("write_file".to_string(), vec![
@@ -357,10 +540,107 @@ impl OperationalSemantics {
                 ]));
             }
         }
-        
+
         None
     }
 
+    /// Splits `query` into an ordered [`ToolCall`] DAG instead of the single
+    /// `(name, args)` pair `analyze_for_function_call` returns, so a prompt
+    /// like "read Cargo.toml and then summarize it" becomes two calls with
+    /// the second depending on the first. Each segment is analyzed with
+    /// `analyze_for_function_call`; a segment that refers back to a prior
+    /// one (`it`, `that`, `the result`) depends on the immediately
+    /// preceding call and carries a `"$<id>"` placeholder argument that
+    /// `execute_plan` substitutes with that call's output.
+    pub fn plan(&self, query: &str, env: &Environment) -> Vec<ToolCall> {
+        let mut calls = Vec::new();
+        for segment in split_steps(query) {
+            let id = calls.len();
+            let depends_on = if id > 0 && references_prior_output(&segment) {
+                vec![id - 1]
+            } else {
+                Vec::new()
+            };
+
+            let analyzed = if depends_on.is_empty() {
+                self.analyze_for_function_call(&segment, env)
+            } else {
+                None
+            };
+
+            let (name, args) = match analyzed {
+                Some(call) => call,
+                None if !depends_on.is_empty() => {
+                    // No extractable argument of its own: it must be fed by
+                    // its dependency's output instead.
+                    let verb = segment_verb(&segment).unwrap_or_else(|| "summarize".to_string());
+                    (verb, vec![Value::String(format!("${}", depends_on[0]))])
+                }
+                None => continue,
+            };
+
+            calls.push(ToolCall { id, name, args, depends_on });
+        }
+        calls
+    }
+
+    /// Runs a [`ToolCall`] plan to completion: calls whose `depends_on` are
+    /// all satisfied execute concurrently on a `threadpool::ThreadPool`
+    /// sized to `num_cpus::get()`, with their dependencies' outputs
+    /// substituted for any `"$<id>"` placeholder argument first. Iterates
+    /// round by round until every call has run, guarding against cycles in
+    /// `depends_on` and against a plan that never drains within
+    /// `Config::max_steps` rounds.
+    pub fn execute_plan(&self, plan: &[ToolCall]) -> Result<HashMap<usize, String>, String> {
+        if has_cycle(plan) {
+            return Err("cycle detected in tool-call plan".to_string());
+        }
+
+        let pool = threadpool::ThreadPool::new(num_cpus::get().max(1));
+        let mut results: HashMap<usize, String> = HashMap::new();
+        let mut remaining: Vec<&ToolCall> = plan.iter().collect();
+        let mut rounds = 0;
+
+        while !remaining.is_empty() {
+            rounds += 1;
+            if rounds > self.config.max_steps {
+                return Err("tool-call plan did not terminate within step limit".to_string());
+            }
+
+            let (ready, blocked): (Vec<&ToolCall>, Vec<&ToolCall>) = remaining
+                .into_iter()
+                .partition(|call| call.depends_on.iter().all(|dep| results.contains_key(dep)));
+
+            if ready.is_empty() {
+                return Err(
+                    "tool-call plan is stuck: remaining calls depend on calls that never completed"
+                        .to_string(),
+                );
+            }
+
+            let (tx, rx) = std::sync::mpsc::channel();
+            for call in &ready {
+                let tx = tx.clone();
+                let semantics = self.clone();
+                let name = call.name.clone();
+                let args = substitute_dependencies(&call.args, &results);
+                let id = call.id;
+                pool.execute(move || {
+                    let output = semantics.eval_native_function(&name, &args);
+                    let _ = tx.send((id, output));
+                });
+            }
+            drop(tx);
+            for (id, output) in rx {
+                results.insert(id, output);
+            }
+
+            remaining = blocked;
+        }
+
+        Ok(results)
+    }
+
     fn extract_file_path(&self, query: &str) -> Option<String> {
         // Mock implementation
         if query.contains("README.md") {
@@ -401,6 +681,20 @@ impl OperationalSemantics {
                     "Error: Invalid arguments".to_string()
                 }
             }
+            "summarize" => {
+                if let Some(Value::String(text)) = args.first() {
+                    format!("Summary of \"{}\": [summary]", text)
+                } else {
+                    "Error: Invalid arguments".to_string()
+                }
+            }
+            "search_code" => {
+                if let Some(Value::String(query)) = args.first() {
+                    format!("Search results for \"{}\": [matches]", query)
+                } else {
+                    "Error: Invalid arguments".to_string()
+                }
+            }
             _ => format!("Unknown function: {}", name),
         }
     }
@@ -415,7 +709,178 @@ impl OperationalSemantics {
     }
 }
 
+/// Splits a planning query into steps on "and then" / ";" separators,
+/// trimming whitespace and dropping empty segments.
+fn split_steps(query: &str) -> Vec<String> {
+    let lower = query.to_lowercase();
+    let mut steps = Vec::new();
+    let mut start = 0;
+    let mut search_from = 0;
+    while let Some(offset) = lower[search_from..].find(" and then ") {
+        let sep_start = search_from + offset;
+        steps.push(query[start..sep_start].trim().to_string());
+        start = sep_start + " and then ".len();
+        search_from = start;
+    }
+    steps.push(query[start..].trim().to_string());
+
+    steps
+        .into_iter()
+        .flat_map(|step| step.split(';').map(|s| s.trim().to_string()).collect::<Vec<_>>())
+        .filter(|step| !step.is_empty())
+        .collect()
+}
+
+/// Whether `segment` refers back to a prior step's output rather than
+/// naming its own argument, e.g. "summarize it" or "then search the result".
+fn references_prior_output(segment: &str) -> bool {
+    let lower = segment.to_lowercase();
+    let words: Vec<&str> = lower
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .collect();
+    words.contains(&"it") || words.contains(&"that") || lower.contains("the result") || lower.contains("its output")
+}
+
+/// Best-effort guess at which native function a dependent segment (one with
+/// no extractable argument of its own) is invoking.
+fn segment_verb(segment: &str) -> Option<String> {
+    let lower = segment.to_lowercase();
+    if lower.contains("summarize") {
+        Some("summarize".to_string())
+    } else if lower.contains("search") {
+        Some("search_code".to_string())
+    } else if lower.contains("write") || lower.contains("create") {
+        Some("write_file".to_string())
+    } else if lower.contains("read") {
+        Some("read_file".to_string())
+    } else {
+        None
+    }
+}
+
+/// Depth-first cycle check over `depends_on` edges.
+fn has_cycle(plan: &[ToolCall]) -> bool {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit(id: usize, plan: &[ToolCall], marks: &mut HashMap<usize, Mark>) -> bool {
+        match marks.get(&id) {
+            Some(Mark::Done) => return false,
+            Some(Mark::Visiting) => return true,
+            None => {}
+        }
+        marks.insert(id, Mark::Visiting);
+        if let Some(call) = plan.iter().find(|c| c.id == id) {
+            for &dep in &call.depends_on {
+                if visit(dep, plan, marks) {
+                    return true;
+                }
+            }
+        }
+        marks.insert(id, Mark::Done);
+        false
+    }
+
+    let mut marks = HashMap::new();
+    plan.iter().any(|call| visit(call.id, plan, &mut marks))
+}
+
+/// Replaces any `Value::String("$<id>")` placeholder argument with the
+/// corresponding dependency's output, leaving every other argument as-is.
+fn substitute_dependencies(args: &[Value], results: &HashMap<usize, String>) -> Vec<Value> {
+    args.iter()
+        .map(|arg| match arg {
+            Value::String(s) => match s.strip_prefix('$').and_then(|rest| rest.parse::<usize>().ok()) {
+                Some(id) => results
+                    .get(&id)
+                    .map(|output| Value::String(output.clone()))
+                    .unwrap_or_else(|| arg.clone()),
+                None => arg.clone(),
+            },
+            other => other.clone(),
+        })
+        .collect()
+}
+
 /// Formal typing rules
+/// Small parser-combinator-style tokenizer for slash-command arguments.
+///
+/// Each parser takes the remaining input and returns `Some((value,
+/// remaining_input))` on success, `None` on failure, the same shape as a
+/// `nom`-style combinator, without pulling in the dependency.
+mod command_grammar {
+    use super::Value;
+
+    type ParseResult<'a, T> = Option<(T, &'a str)>;
+
+    fn quoted_string(input: &str) -> ParseResult<'_, Value> {
+        let rest = input.strip_prefix('"')?;
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((Value::String(value), &rest[i + 1..])),
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                _ => value.push(c),
+            }
+        }
+        None // unterminated quoted string
+    }
+
+    fn bare_word(input: &str) -> ParseResult<'_, Value> {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        if end == 0 {
+            return None;
+        }
+        let (word, rest) = input.split_at(end);
+        let value = match word {
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            _ => word
+                .parse::<f64>()
+                .map(Value::Number)
+                .unwrap_or_else(|_| Value::String(word.to_string())),
+        };
+        Some((value, rest))
+    }
+
+    fn argument(input: &str) -> ParseResult<'_, Value> {
+        quoted_string(input).or_else(|| bare_word(input))
+    }
+
+    /// Tokenizes a command's argument string into `Value`s, honoring
+    /// double-quoted strings (with `\"` escapes), bare words, integers,
+    /// floats, and `true`/`false` booleans. Malformed trailing input (e.g.
+    /// an unterminated quote) is dropped rather than erroring, matching
+    /// `parse_input`'s best-effort parsing elsewhere.
+    pub fn parse_args(input: &str) -> Vec<Value> {
+        let mut rest = input;
+        let mut args = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            match argument(rest) {
+                Some((value, remaining)) => {
+                    args.push(value);
+                    rest = remaining;
+                }
+                None => break,
+            }
+        }
+        args
+    }
+}
+
 pub mod typing {
     use super::*;
 
@@ -433,13 +898,85 @@ pub mod typing {
     pub struct TypeChecker;
 
     impl TypeChecker {
+        /// Infers (and checks) the type of `expr` under `env`, bidirectionally:
+        /// leaf forms synthesize a type, while `FunctionCall` checks each
+        /// argument's synthesized type against the callee's declared
+        /// parameter types.
         pub fn type_of(expr: &Expression, env: &Environment) -> Result<Type, String> {
             match expr {
                 Expression::Input(_) => Ok(Type::String),
+                Expression::Query(_, _) => Ok(Type::String),
                 Expression::Response(_) => Ok(Type::String),
                 Expression::Error(_) => Ok(Type::Any),
                 Expression::Noop => Ok(Type::Any),
-                _ => Ok(Type::Any), // Simplified
+                Expression::Command(_) => Ok(Type::Any),
+
+                Expression::FunctionCall(name, args) => {
+                    let func_def = env
+                        .functions
+                        .get(name)
+                        .ok_or_else(|| format!("{name}: undefined function"))?;
+
+                    if args.len() != func_def.parameters.len() {
+                        return Err(format!(
+                            "{name}: expected {} argument(s), got {}",
+                            func_def.parameters.len(),
+                            args.len()
+                        ));
+                    }
+
+                    for (i, (arg, expected)) in
+                        args.iter().zip(&func_def.parameter_types).enumerate()
+                    {
+                        let actual = Self::type_of_value(arg);
+                        if !Self::compatible(expected, &actual) {
+                            return Err(format!(
+                                "{name}: argument {i} expected {:?}, got {:?}",
+                                expected, actual
+                            ));
+                        }
+                    }
+
+                    Ok(func_def.return_type.clone())
+                }
+
+                Expression::Sequence(e1, e2) => {
+                    Self::type_of(e1, env)?;
+                    Self::type_of(e2, env)
+                }
+            }
+        }
+
+        /// Synthesizes the [`Type`] of a concrete runtime [`Value`].
+        fn type_of_value(value: &Value) -> Type {
+            match value {
+                Value::String(_) => Type::String,
+                Value::Number(_) => Type::Number,
+                Value::Boolean(_) => Type::Boolean,
+                Value::List(items) => {
+                    Type::List(Box::new(items.first().map(Self::type_of_value).unwrap_or(Type::Any)))
+                }
+                Value::Object(fields) => Type::Object(
+                    fields
+                        .iter()
+                        .map(|(k, v)| (k.clone(), Self::type_of_value(v)))
+                        .collect(),
+                ),
+            }
+        }
+
+        /// Whether a value of type `actual` may be used where `expected` is
+        /// declared. `Any` is compatible with everything in both positions.
+        fn compatible(expected: &Type, actual: &Type) -> bool {
+            match (expected, actual) {
+                (Type::Any, _) | (_, Type::Any) => true,
+                (Type::List(e), Type::List(a)) => Self::compatible(e, a),
+                (Type::Function(ea, er), Type::Function(aa, ar)) => {
+                    ea.len() == aa.len()
+                        && ea.iter().zip(aa).all(|(e, a)| Self::compatible(e, a))
+                        && Self::compatible(er, ar)
+                }
+                _ => expected == actual,
             }
         }
     }
@@ -455,8 +992,10 @@ mod tests {
         functions.insert("read_file".to_string(), FunctionDef {
             name: "read_file".to_string(),
             parameters: vec!["path".to_string()],
+            parameter_types: vec![typing::Type::String],
             description: "Read a file".to_string(),
             implementation: FunctionImpl::Native("read_file".to_string()),
+            return_type: typing::Type::String,
         });
         
         Environment {
@@ -473,6 +1012,8 @@ mod tests {
             max_context_length: 100,
             timeout_ms: 5000,
             max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
         };
         let semantics = OperationalSemantics::new(config);
         
@@ -481,18 +1022,62 @@ mod tests {
             environment: default_environment(),
             context: Context { messages: vec![] },
             output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
         };
         
         let next = semantics.step(state).unwrap();
         assert!(matches!(next.expression, Expression::Command(Command::Help)));
     }
 
+    #[test]
+    fn test_multiline_input_continuation() {
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::Input("first line\\".to_string()),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        let continued = semantics.step(state).unwrap();
+        assert!(matches!(continued.expression, Expression::Noop));
+        assert_eq!(continued.pending_input.as_deref(), Some("first line\n"));
+
+        let state2 = EvalState {
+            expression: Expression::Input("second line".to_string()),
+            ..continued
+        };
+        let done = semantics.step(state2).unwrap();
+        assert!(matches!(
+            done.expression,
+            Expression::Command(Command::UserQuery(ref q)) if q == "first line\nsecond line"
+        ));
+        assert!(done.pending_input.is_none());
+    }
+
     #[test]
     fn test_help_command() {
         let config = Config {
             max_context_length: 100,
             timeout_ms: 5000,
             max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
         };
         let semantics = OperationalSemantics::new(config);
         
@@ -501,6 +1086,10 @@ mod tests {
             environment: default_environment(),
             context: Context { messages: vec![] },
             output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
         };
         
         let next = semantics.step(state).unwrap();
@@ -514,6 +1103,8 @@ mod tests {
             max_context_length: 100,
             timeout_ms: 5000,
             max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
         };
         let semantics = OperationalSemantics::new(config);
         
@@ -525,6 +1116,10 @@ mod tests {
             environment: default_environment(),
             context: Context { messages: vec![] },
             output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
         };
         
         let next = semantics.step(state).unwrap();
@@ -533,4 +1128,386 @@ mod tests {
             Expression::FunctionCall(name, _) if name == "read_file"
         ));
     }
+
+    #[test]
+    fn test_agentic_loop_chains_function_calls_then_caps() {
+        // With max_function_calls capped at 1, the FUNCTION-CALL rule should
+        // loop back into QUERY exactly once before QUERY falls through to a
+        // final Response instead of calling the function again.
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 1,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::Query(
+                "Read the README.md file".to_string(),
+                Context { messages: vec![] },
+            ),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        // QUERY -> FUNCTION-CALL, remembering the originating query.
+        let calling = semantics.step(state).unwrap();
+        assert!(matches!(calling.expression, Expression::FunctionCall(ref name, _) if name == "read_file"));
+        assert_eq!(calling.originating_query.as_deref(), Some("Read the README.md file"));
+
+        // FUNCTION-CALL -> QUERY, with the result folded into context and
+        // the call count incremented.
+        let requerying = semantics.step(calling).unwrap();
+        assert!(matches!(requerying.expression, Expression::Query(..)));
+        assert_eq!(requerying.function_calls_made, 1);
+        assert_eq!(requerying.context.messages.len(), 1);
+
+        // QUERY now sees function_calls_made >= max_function_calls, so it
+        // must stop chaining and produce a final Response instead of
+        // calling read_file again.
+        let done = semantics.step(requerying).unwrap();
+        assert!(matches!(done.expression, Expression::Response(_)));
+        assert_eq!(done.function_calls_made, 0);
+        assert!(done.originating_query.is_none());
+    }
+
+    #[test]
+    fn test_sequence_pipes_output_into_next_stage() {
+        // "read README.md | summarize it": stage one's Response becomes
+        // stage two's `$in`, so analyze_for_function_call sees the piped
+        // text rather than re-deriving it from the query string.
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::Sequence(
+                Box::new(Expression::Response("Contents of README.md: [file data]".to_string())),
+                Box::new(Expression::Query("summarize it".to_string(), Context { messages: vec![] })),
+            ),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        // SEQ-LEFT: e1 (Response) steps to Noop, its text lands in `output`.
+        let after_response = semantics.step(state).unwrap();
+        assert!(matches!(
+            after_response.expression,
+            Expression::Sequence(ref e1, _) if **e1 == Expression::Noop
+        ));
+        assert_eq!(
+            after_response.output.last().map(String::as_str),
+            Some("Contents of README.md: [file data]")
+        );
+
+        // SEQ-NOOP: the piped value is bound to `$in` and e2 takes over.
+        let piped = semantics.step(after_response).unwrap();
+        assert!(matches!(piped.expression, Expression::Query(ref q, _) if q == "summarize it"));
+        assert_eq!(
+            piped.piped_input,
+            Some(Value::String("Contents of README.md: [file data]".to_string()))
+        );
+        assert_eq!(
+            piped.environment.variables.get("$in"),
+            Some(&Value::String("Contents of README.md: [file data]".to_string()))
+        );
+
+        // QUERY-FUNCTION: "summarize" consumes `$in`, not the query text.
+        let summarizing = semantics.step(piped).unwrap();
+        assert!(matches!(
+            summarizing.expression,
+            Expression::FunctionCall(ref name, ref args)
+                if name == "summarize"
+                    && args == &vec![Value::String("Contents of README.md: [file data]".to_string())]
+        ));
+    }
+
+    #[test]
+    fn test_type_checker_accepts_well_typed_call() {
+        let expr = Expression::FunctionCall(
+            "read_file".to_string(),
+            vec![Value::String("README.md".to_string())],
+        );
+        let ty = typing::TypeChecker::type_of(&expr, &default_environment()).unwrap();
+        assert_eq!(ty, typing::Type::String);
+    }
+
+    #[test]
+    fn test_type_checker_rejects_wrong_argument_type() {
+        let expr = Expression::FunctionCall(
+            "read_file".to_string(),
+            vec![Value::Number(42.0)],
+        );
+        let err = typing::TypeChecker::type_of(&expr, &default_environment()).unwrap_err();
+        assert_eq!(err, "read_file: argument 0 expected String, got Number");
+    }
+
+    #[test]
+    fn test_type_checker_rejects_wrong_argument_count() {
+        let expr = Expression::FunctionCall("read_file".to_string(), vec![]);
+        let err = typing::TypeChecker::type_of(&expr, &default_environment()).unwrap_err();
+        assert_eq!(err, "read_file: expected 1 argument(s), got 0");
+    }
+
+    #[test]
+    fn test_type_checker_sequence_checks_both_stages() {
+        let good = Expression::Sequence(
+            Box::new(Expression::Response("hi".to_string())),
+            Box::new(Expression::FunctionCall(
+                "read_file".to_string(),
+                vec![Value::String("README.md".to_string())],
+            )),
+        );
+        assert_eq!(
+            typing::TypeChecker::type_of(&good, &default_environment()).unwrap(),
+            typing::Type::String
+        );
+
+        let bad = Expression::Sequence(
+            Box::new(Expression::FunctionCall("read_file".to_string(), vec![])),
+            Box::new(Expression::Response("unreachable".to_string())),
+        );
+        assert!(typing::TypeChecker::type_of(&bad, &default_environment()).is_err());
+    }
+
+    #[test]
+    fn test_eval_rejects_ill_typed_program_when_type_check_enabled() {
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: true,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::FunctionCall("read_file".to_string(), vec![]),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        let err = semantics.eval(state).unwrap_err();
+        assert_eq!(err, "read_file: expected 1 argument(s), got 0");
+    }
+
+    #[test]
+    fn test_command_grammar_parses_quoted_and_bare_arguments() {
+        let args = command_grammar::parse_args(r#""my file.txt" temperature 0.7 true"#);
+        assert_eq!(
+            args,
+            vec![
+                Value::String("my file.txt".to_string()),
+                Value::String("temperature".to_string()),
+                Value::Number(0.7),
+                Value::Boolean(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_grammar_honors_escaped_quotes() {
+        let args = command_grammar::parse_args(r#""say \"hi\"""#);
+        assert_eq!(args, vec![Value::String(r#"say "hi""#.to_string())]);
+    }
+
+    #[test]
+    fn test_parse_input_builds_invoke_for_unknown_slash_command() {
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let cmd = semantics.parse_input(r#"/read "my file.txt""#);
+        assert_eq!(
+            cmd,
+            Command::Invoke("read".to_string(), vec![Value::String("my file.txt".to_string())])
+        );
+
+        let cmd = semantics.parse_input("/set temperature 0.7");
+        assert_eq!(
+            cmd,
+            Command::Invoke(
+                "set".to_string(),
+                vec![Value::String("temperature".to_string()), Value::Number(0.7)]
+            )
+        );
+    }
+
+    #[test]
+    fn test_invoke_dispatches_through_function_call_rule() {
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::Command(Command::Invoke(
+                "read_file".to_string(),
+                vec![Value::String("README.md".to_string())],
+            )),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        let next = semantics.step(state).unwrap();
+        assert!(matches!(
+            next.expression,
+            Expression::FunctionCall(ref name, _) if name == "read_file"
+        ));
+    }
+
+    #[test]
+    fn test_eval_surfaces_expired_deadline_as_typed_timeout_error() {
+        // A zero-millisecond deadline expires before the first step, so
+        // eval should surface ErrorKind::Timeout via the ERROR rule rather
+        // than stepping normally or returning a bare Err string.
+        let config = Config {
+            max_context_length: 100,
+            timeout_ms: 0,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        };
+        let semantics = OperationalSemantics::new(config);
+
+        let state = EvalState {
+            expression: Expression::Command(Command::Help),
+            environment: default_environment(),
+            context: Context { messages: vec![] },
+            output: vec![],
+            pending_input: None,
+            originating_query: None,
+            function_calls_made: 0,
+            piped_input: None,
+        };
+
+        let result = semantics.eval(state).unwrap();
+        assert!(matches!(result.expression, Expression::Noop));
+        assert!(result.output.iter().any(|o| o.contains("Timeout")));
+    }
+
+    fn test_config() -> Config {
+        Config {
+            max_context_length: 100,
+            timeout_ms: 5000,
+            max_function_calls: 10,
+            type_check: false,
+            max_steps: 1000,
+        }
+    }
+
+    #[test]
+    fn test_parse_program_builds_right_nested_sequence() {
+        let semantics = OperationalSemantics::new(test_config());
+        let program = semantics.parse_program("/help\n\n/context\nhi there");
+
+        assert_eq!(
+            program,
+            Expression::Sequence(
+                Box::new(Expression::Input("/help".to_string())),
+                Box::new(Expression::Sequence(
+                    Box::new(Expression::Input("/context".to_string())),
+                    Box::new(Expression::Input("hi there".to_string())),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_program_empty_source_is_noop() {
+        let semantics = OperationalSemantics::new(test_config());
+        assert_eq!(semantics.parse_program("\n\n"), Expression::Noop);
+    }
+
+    #[test]
+    fn test_run_program_replays_a_recorded_session() {
+        let semantics = OperationalSemantics::new(test_config());
+        let output = semantics
+            .run_program("/help\n/context", default_environment())
+            .unwrap();
+
+        // /help's output, followed by /context's `Debug`-formatted context.
+        assert_eq!(output.len(), 2);
+        assert!(output[0].contains("/help"));
+        assert!(output[1].contains("messages"));
+    }
+
+    #[test]
+    fn test_plan_builds_independent_calls_with_no_dependencies() {
+        let semantics = OperationalSemantics::new(test_config());
+        let plan = semantics.plan("read the file Cargo.toml", &default_environment());
+
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].name, "read_file");
+        assert!(plan[0].depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_plan_chains_a_dependent_step_on_its_predecessor() {
+        let semantics = OperationalSemantics::new(test_config());
+        let plan = semantics.plan("read the file Cargo.toml and then summarize it", &default_environment());
+
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].name, "read_file");
+        assert!(plan[0].depends_on.is_empty());
+        assert_eq!(plan[1].name, "summarize");
+        assert_eq!(plan[1].depends_on, vec![0]);
+        assert_eq!(plan[1].args, vec![Value::String("$0".to_string())]);
+    }
+
+    #[test]
+    fn test_execute_plan_runs_dependents_after_their_dependency() {
+        let semantics = OperationalSemantics::new(test_config());
+        let plan = semantics.plan("read the file Cargo.toml and then summarize it", &default_environment());
+
+        let results = semantics.execute_plan(&plan).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[&0].contains("Contents of Cargo.toml"));
+        assert!(results[&1].contains(&results[&0]));
+    }
+
+    #[test]
+    fn test_execute_plan_rejects_a_cycle() {
+        let plan = vec![
+            ToolCall { id: 0, name: "summarize".to_string(), args: vec![Value::String("$1".to_string())], depends_on: vec![1] },
+            ToolCall { id: 1, name: "summarize".to_string(), args: vec![Value::String("$0".to_string())], depends_on: vec![0] },
+        ];
+        let semantics = OperationalSemantics::new(test_config());
+        let err = semantics.execute_plan(&plan).unwrap_err();
+        assert!(err.contains("cycle"));
+    }
 }
\ No newline at end of file
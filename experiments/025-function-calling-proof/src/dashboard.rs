@@ -0,0 +1,53 @@
+//! Best-effort push of a completed run's `Statistics` and `EnvInfo` to a
+//! remote tracking server, so success-rate trends across models and crate
+//! versions can be watched centrally instead of only living in local
+//! `results/<run_id>/` artifacts. Modeled on Meilisearch's `xtask bench`
+//! upload-to-tracking-server step.
+
+use crate::env_info::EnvInfo;
+use crate::Statistics;
+use serde::Serialize;
+use tracing::warn;
+
+#[derive(Debug, Serialize)]
+struct DashboardPayload<'a> {
+    run_id: &'a str,
+    seed: u64,
+    statistics: &'a Statistics,
+    env_info: Option<&'a EnvInfo>,
+}
+
+/// POSTs the run's stats to `url`, authenticating with `token` (if set) as a
+/// bearer token. Failures are logged and swallowed: a flaky or unreachable
+/// dashboard must never fail the run itself.
+pub async fn report_run(url: &str, token: Option<&str>, run_id: &str, stats: &Statistics) {
+    let payload = DashboardPayload {
+        run_id,
+        seed: stats.seed,
+        statistics: stats,
+        env_info: stats.env_info.as_ref(),
+    };
+
+    let client = reqwest::Client::new();
+    let mut request = client.post(url).json(&payload);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    match request.send().await {
+        Ok(response) if response.status().is_success() => {
+            tracing::info!("Reported run {} to {}", run_id, url);
+        }
+        Ok(response) => {
+            warn!(
+                "Dashboard at {} rejected run {}: HTTP {}",
+                url,
+                run_id,
+                response.status()
+            );
+        }
+        Err(e) => {
+            warn!("Failed to report run {} to {}: {}", run_id, url, e);
+        }
+    }
+}
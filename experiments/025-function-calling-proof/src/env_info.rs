@@ -0,0 +1,72 @@
+//! Captures the environment a run executed in — git commit, crate version,
+//! OS/CPU, hostname, model, and seed — so two `report.md` files can be
+//! diffed and the difference attributed to an actual change rather than
+//! "which commit was this run against?" Modeled on Meilisearch's `env_info`
+//! bench-stamping module.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvInfo {
+    pub git_commit: String,
+    pub git_describe: String,
+    pub crate_version: String,
+    pub os: String,
+    pub arch: String,
+    pub hostname: String,
+    pub model: String,
+    pub seed: u64,
+}
+
+impl EnvInfo {
+    pub fn collect(model: &str, seed: u64) -> Self {
+        Self {
+            git_commit: git_output(&["rev-parse", "--short", "HEAD"]),
+            git_describe: git_output(&["describe", "--always", "--dirty"]),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            hostname: hostname(),
+            model: model.to_string(),
+            seed,
+        }
+    }
+
+    /// Renders the Markdown table this goes at the top of `report.md`.
+    pub fn to_markdown_table(&self) -> String {
+        let mut out = String::new();
+        out.push_str("| Field | Value |\n");
+        out.push_str("|-------|-------|\n");
+        out.push_str(&format!("| Git commit | `{}` |\n", self.git_commit));
+        out.push_str(&format!("| Git describe | `{}` |\n", self.git_describe));
+        out.push_str(&format!("| Crate version | {} |\n", self.crate_version));
+        out.push_str(&format!("| OS / Arch | {} / {} |\n", self.os, self.arch));
+        out.push_str(&format!("| Hostname | {} |\n", self.hostname));
+        out.push_str(&format!("| Model | {} |\n", self.model));
+        out.push_str(&format!("| Seed | {} |\n", self.seed));
+        out
+    }
+}
+
+fn git_output(args: &[&str]) -> String {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn hostname() -> String {
+    if let Ok(name) = std::env::var("HOSTNAME") {
+        return name;
+    }
+    Command::new("hostname")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -1,13 +1,22 @@
 //! Comprehensive test to prove function calling works
 //! This will run 500 test cases and collect statistics
 
+mod dashboard;
+mod env_info;
+mod reporters;
+mod results;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use env_info::EnvInfo;
 use futures::stream::{self, StreamExt};
 use gemini_repl::api::{Content, GeminiClient, Part};
 use gemini_repl::functions::get_available_tools;
+use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use reporters::reporter_from_name;
+use results::{format_run_list, DirectoryManager, RunSummary};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
@@ -39,6 +48,7 @@ struct TestResult {
 
 #[derive(Debug, Default, Serialize)]
 struct Statistics {
+    seed: u64,
     total_tests: u32,
     successful_function_calls: u32,
     failed_function_calls: u32,
@@ -47,6 +57,7 @@ struct Statistics {
     rate_limit_hits: u32,
     by_category: HashMap<String, CategoryStats>,
     by_function: HashMap<String, u32>,
+    env_info: Option<EnvInfo>,
 }
 
 #[derive(Debug, Default, Serialize)]
@@ -56,167 +67,109 @@ struct CategoryStats {
     failure: u32,
 }
 
-fn generate_test_cases() -> Vec<TestCase> {
-    let mut test_cases = Vec::new();
-    let mut id = 0;
-
-    // Direct read file prompts (should have high success rate)
-    let read_targets = vec![
-        "Makefile", "README.md", "Cargo.toml", "src/main.rs", 
-        "src/api.rs", "src/lib.rs", ".gitignore", "LICENSE"
-    ];
-    
-    for target in &read_targets {
-        // Direct commands
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("read {}", target),
-            category: "direct_read".to_string(),
-            expected_function: "read_file".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("show me {}", target),
-            category: "show_file".to_string(),
-            expected_function: "read_file".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("display the contents of {}", target),
-            category: "display_file".to_string(),
-            expected_function: "read_file".to_string(),
-        });
-        id += 1;
-        
-        // More natural language
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("what's in {}?", target),
-            category: "whats_in".to_string(),
-            expected_function: "read_file".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("can you read {} for me", target),
-            category: "can_you_read".to_string(),
-            expected_function: "read_file".to_string(),
-        });
-        id += 1;
+/// Resolves the shuffle seed for a run, in priority order: the `--seed`
+/// CLI flag, the `GEMINI_PROOF_SEED` env var, or (if neither is set) a
+/// freshly-drawn random seed. Whichever seed is used is always returned so
+/// the caller can log and record it, since a drawn seed is only reproducible
+/// if someone writes it down.
+fn resolve_seed(cli_seed: Option<u64>) -> u64 {
+    if let Some(seed) = cli_seed {
+        return seed;
     }
-
-    // List files prompts
-    let directories = vec!["src", "tests", "experiments", ".", "target"];
-    for dir in &directories {
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("list files in {}", dir),
-            category: "list_direct".to_string(),
-            expected_function: "list_files".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("show all files in the {} directory", dir),
-            category: "show_all_files".to_string(),
-            expected_function: "list_files".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("what files are in {}?", dir),
-            category: "what_files".to_string(),
-            expected_function: "list_files".to_string(),
-        });
-        id += 1;
+    if let Ok(raw) = env::var("GEMINI_PROOF_SEED") {
+        if let Ok(seed) = raw.parse() {
+            return seed;
+        }
     }
+    rand::thread_rng().gen()
+}
 
-    // Search prompts
-    let search_terms = vec!["TODO", "function", "async", "error", "test", "impl"];
-    for term in &search_terms {
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("search for {}", term),
-            category: "search_direct".to_string(),
-            expected_function: "search_code".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("find all occurrences of {}", term),
-            category: "find_occurrences".to_string(),
-            expected_function: "search_code".to_string(),
-        });
-        id += 1;
-        
-        test_cases.push(TestCase {
-            id: id,
-            prompt: format!("look for {} in the code", term),
-            category: "look_for".to_string(),
-            expected_function: "search_code".to_string(),
-        });
-        id += 1;
-    }
+fn default_weight() -> u32 {
+    1
+}
+
+/// One prompt entry in a workload file. `weight` controls how many copies of
+/// this entry go into the pool that gets shuffled and truncated to the
+/// requested total, so a workload can bias toward certain categories without
+/// duplicating near-identical entries by hand.
+#[derive(Debug, Clone, Deserialize)]
+struct WorkloadEntry {
+    prompt: String,
+    category: String,
+    expected_function: String,
+    #[serde(default = "default_weight")]
+    weight: u32,
+}
+
+/// A schema'd JSON workload file: a named corpus of prompts plus the model
+/// it was designed against. Several of these can be loaded and merged into
+/// one run.
+#[derive(Debug, Clone, Deserialize)]
+struct Workload {
+    name: String,
+    #[serde(default)]
+    model: Option<String>,
+    cases: Vec<WorkloadEntry>,
+}
 
-    // Write file prompts (be careful with these)
-    test_cases.push(TestCase {
-        id: id,
-        prompt: "create a file called test_output.txt with content 'Hello World'".to_string(),
-        category: "create_file".to_string(),
-        expected_function: "write_file".to_string(),
-    });
-    id += 1;
-
-    // Mix in some variations
-    let variations = vec![
-        ("read the configuration from Cargo.toml", "read_file"),
-        ("show me all Rust files", "list_files"),
-        ("find TODO comments", "search_code"),
-        ("display Makefile contents", "read_file"),
-        ("what's inside the src folder", "list_files"),
-        ("check what's in main.rs", "read_file"),
-        ("scan for println statements", "search_code"),
-        ("view the README file", "read_file"),
-    ];
-
-    for (prompt, expected) in variations {
-        test_cases.push(TestCase {
-            id: id,
-            prompt: prompt.to_string(),
-            category: "variation".to_string(),
-            expected_function: expected.to_string(),
-        });
-        id += 1;
+/// Loads and merges every workload file in `paths`.
+fn load_workloads(paths: &[String]) -> Result<Vec<Workload>> {
+    paths
+        .iter()
+        .map(|path| {
+            let raw = fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("failed to read workload {}: {}", path, e))?;
+            serde_json::from_str(&raw)
+                .map_err(|e| anyhow::anyhow!("failed to parse workload {}: {}", path, e))
+        })
+        .collect()
+}
+
+/// Expands every workload's entries by their `weight`, shuffles the pool
+/// with a seeded RNG so runs are replayable, and truncates/repeats it to
+/// exactly `total` cases, renumbered from 0.
+fn generate_test_cases(workloads: &[Workload], seed: u64, total: usize) -> Vec<TestCase> {
+    let mut pool = Vec::new();
+    for workload in workloads {
+        for entry in &workload.cases {
+            for _ in 0..entry.weight.max(1) {
+                pool.push(TestCase {
+                    id: 0,
+                    prompt: entry.prompt.clone(),
+                    category: entry.category.clone(),
+                    expected_function: entry.expected_function.clone(),
+                });
+            }
+        }
     }
 
-    // Shuffle and take 500 (or repeat if needed)
-    let mut rng = rand::thread_rng();
+    let mut rng = SmallRng::seed_from_u64(seed);
     let mut final_cases = Vec::new();
-    
-    while final_cases.len() < 500 {
-        let mut batch = test_cases.clone();
+
+    while final_cases.len() < total {
+        let mut batch = pool.clone();
         batch.shuffle(&mut rng);
         final_cases.extend(batch);
     }
-    
-    final_cases.truncate(500);
-    
-    // Re-number them
+
+    final_cases.truncate(total);
+
     for (i, case) in final_cases.iter_mut().enumerate() {
         case.id = i;
     }
-    
+
     final_cases
 }
 
+/// A rate-limit hit isn't a real failure of the harness or the model, so
+/// `--fail-fast` shouldn't abort on it.
+fn is_rate_limit_error(result: &TestResult) -> bool {
+    result
+        .error
+        .as_deref()
+        .map_or(false, |e| e.contains("429") || e.contains("RESOURCE_EXHAUSTED"))
+}
+
 async fn run_single_test(
     client: &GeminiClient,
     test_case: TestCase,
@@ -294,40 +247,181 @@ async fn main() -> Result<()> {
                 .add_directive("function_calling_proof=info".parse()?)
         )
         .init();
-    
+
+    // `list` / `show <run_id>` / `delete <run_id>` / `delete --oldest <n>`
+    // manage past runs without executing the suite.
+    let manager = DirectoryManager::new(results::default_root());
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("list") => {
+            print!("{}", format_run_list(&manager.list_runs()?));
+            return Ok(());
+        }
+        Some("show") => {
+            let run_id = args.get(2).expect("usage: show <run_id>");
+            print!("{}", manager.show_run(run_id)?);
+            return Ok(());
+        }
+        Some("delete") => {
+            if args.get(2).map(String::as_str) == Some("--oldest") {
+                let n: usize = args.get(3).expect("usage: delete --oldest <n>").parse()?;
+                let deleted = manager.delete_oldest(n)?;
+                println!("Deleted {} run(s): {}", deleted.len(), deleted.join(", "));
+            } else {
+                let run_id = args.get(2).expect("usage: delete <run_id>");
+                manager.delete_run(run_id)?;
+                println!("Deleted run {}", run_id);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
     info!("Starting function calling validation experiment");
-    
+
+    // Parse --seed <u64> if present; otherwise fall back to GEMINI_PROOF_SEED
+    // or a freshly-drawn random seed.
+    let cli_seed = args
+        .windows(2)
+        .find(|pair| pair[0] == "--seed")
+        .and_then(|pair| pair[1].parse().ok());
+    let seed = resolve_seed(cli_seed);
+    info!("Using shuffle seed {} (pass --seed {} to replay this run)", seed, seed);
+
+    // Parse --workload <path> (repeatable; defaults to the shipped corpus)
+    // and --total/--count <n> (defaults to 500).
+    let workload_paths: Vec<String> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--workload")
+        .map(|pair| pair[1].clone())
+        .collect();
+    let workload_paths = if workload_paths.is_empty() {
+        vec!["experiments/025-function-calling-proof/workloads/function_calling.json".to_string()]
+    } else {
+        workload_paths
+    };
+    let total: usize = args
+        .windows(2)
+        .find(|pair| pair[0] == "--total" || pair[0] == "--count")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or(500);
+
+    // Parse the remaining run-shaping flags: prompt/category filters,
+    // fail-fast, and pipeline tuning knobs.
+    let filter: Option<String> = args
+        .windows(2)
+        .find(|pair| pair[0] == "--filter")
+        .map(|pair| pair[1].clone());
+    let category: Option<String> = args
+        .windows(2)
+        .find(|pair| pair[0] == "--category")
+        .map(|pair| pair[1].clone());
+    let fail_fast = args.iter().any(|a| a == "--fail-fast");
+    let concurrency: usize = args
+        .windows(2)
+        .find(|pair| pair[0] == "--concurrency")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or(3);
+    let request_delay_ms: u64 = args
+        .windows(2)
+        .find(|pair| pair[0] == "--request-delay-ms")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or(200);
+    let batch_delay_ms: u64 = args
+        .windows(2)
+        .find(|pair| pair[0] == "--batch-delay-ms")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or(2000);
+
+    // Parse --reporter <pretty|json|junit> (defaults to pretty) and the
+    // --report-path its output gets written to.
+    let reporter_name = args
+        .windows(2)
+        .find(|pair| pair[0] == "--reporter")
+        .map(|pair| pair[1].clone())
+        .unwrap_or_else(|| "pretty".to_string());
+    let reporter = reporter_from_name(&reporter_name)?;
+    let report_path = args
+        .windows(2)
+        .find(|pair| pair[0] == "--report-path")
+        .map(|pair| pair[1].clone());
+
+    // Parse --report-url <endpoint> (optional); the bearer token, if any,
+    // comes from GEMINI_PROOF_REPORT_TOKEN rather than the command line.
+    let report_url = args
+        .windows(2)
+        .find(|pair| pair[0] == "--report-url")
+        .map(|pair| pair[1].clone());
+    let report_token = env::var("GEMINI_PROOF_REPORT_TOKEN").ok();
+
+    let workloads = load_workloads(&workload_paths)?;
+    info!(
+        "Loaded {} workload(s): {}",
+        workloads.len(),
+        workloads.iter().map(|w| w.name.as_str()).collect::<Vec<_>>().join(", ")
+    );
+
     // Get API key
     let api_key = env::var("GOOGLE_AI_API_KEY")
         .or_else(|_| env::var("GEMINI_API_KEY"))
         .expect("Set GOOGLE_AI_API_KEY or GEMINI_API_KEY");
-    
+
     // Create client
-    let client = GeminiClient::new(api_key, "gemini-2.0-flash-exp".to_string())?;
-    
-    // Generate test cases
-    let test_cases = generate_test_cases();
-    info!("Generated {} test cases", test_cases.len());
-    
-    // Create output directory
-    let output_dir = "experiments/025-function-calling-proof/results";
-    fs::create_dir_all(output_dir)?;
+    let model = workloads
+        .first()
+        .and_then(|w| w.model.clone())
+        .unwrap_or_else(|| "gemini-2.0-flash-exp".to_string());
+    let client = GeminiClient::new(api_key, model.clone())?;
+
+    // Generate test cases, then narrow to --filter/--category if given
+    let test_cases = generate_test_cases(&workloads, seed, total);
+    let test_cases: Vec<TestCase> = test_cases
+        .into_iter()
+        .filter(|tc| filter.as_deref().map_or(true, |f| tc.prompt.contains(f)))
+        .filter(|tc| category.as_deref().map_or(true, |c| tc.category == c))
+        .collect();
+    info!(
+        "Generated {} test cases{}",
+        test_cases.len(),
+        match (&filter, &category) {
+            (Some(f), Some(c)) => format!(" (filter={:?}, category={:?})", f, c),
+            (Some(f), None) => format!(" (filter={:?})", f),
+            (None, Some(c)) => format!(" (category={:?})", c),
+            (None, None) => String::new(),
+        }
+    );
+
+    // Allocate this run's directory: results/<run_id>/
+    let run_started_at = Utc::now();
+    let run_id = manager.allocate_run(run_started_at)?;
+    let output_dir = manager.run_dir(&run_id);
+    let output_dir = output_dir.to_str().expect("run dir path is valid UTF-8");
+    info!("Run ID: {}", run_id);
     
     // Rate limiting setup
     let rate_limit_counter = Arc::new(AtomicU32::new(0));
     let mut results = Vec::new();
-    let mut stats = Statistics::default();
+    let mut stats = Statistics {
+        seed,
+        env_info: Some(EnvInfo::collect(&model, seed)),
+        ..Statistics::default()
+    };
     
     // Process in batches with rate limiting
     let batch_size = 10;
-    let delay_between_batches = Duration::from_secs(2);
-    let delay_between_requests = Duration::from_millis(200);
-    
-    info!("Starting test execution with {} ms between requests", delay_between_requests.as_millis());
-    
+    let delay_between_batches = Duration::from_millis(batch_delay_ms);
+    let delay_between_requests = Duration::from_millis(request_delay_ms);
+
+    info!(
+        "Starting test execution with {} ms between requests, {} concurrent",
+        delay_between_requests.as_millis(),
+        concurrency
+    );
+
+    let mut aborted_early = false;
     for (batch_idx, chunk) in test_cases.chunks(batch_size).enumerate() {
         info!("Processing batch {}/{}", batch_idx + 1, (test_cases.len() + batch_size - 1) / batch_size);
-        
+
         // Process batch concurrently with limited parallelism
         let batch_results: Vec<TestResult> = stream::iter(chunk)
             .map(|test_case| {
@@ -339,10 +433,10 @@ async fn main() -> Result<()> {
                     run_single_test(client, test_case.clone(), counter).await
                 }
             })
-            .buffer_unordered(3) // Max 3 concurrent requests
+            .buffer_unordered(concurrency)
             .collect()
             .await;
-        
+
         // Update statistics
         for result in &batch_results {
             stats.total_tests += 1;
@@ -375,17 +469,29 @@ async fn main() -> Result<()> {
             } else {
                 cat_stats.failure += 1;
             }
+
+            if fail_fast && !result.success && !is_rate_limit_error(result) {
+                warn!(
+                    "--fail-fast: aborting after test {} (\"{}\") failed",
+                    result.test_case.id, result.test_case.prompt
+                );
+                aborted_early = true;
+            }
         }
-        
+
         results.extend(batch_results);
-        
+
         // Save intermediate results
         if batch_idx % 5 == 0 {
             let intermediate_file = format!("{}/intermediate_{}.json", output_dir, batch_idx);
             fs::write(&intermediate_file, serde_json::to_string_pretty(&results)?)?;
             info!("Saved intermediate results to {}", intermediate_file);
         }
-        
+
+        if aborted_early {
+            break;
+        }
+
         // Rate limit check
         if stats.rate_limit_hits > 5 {
             warn!("Multiple rate limits hit, increasing delay");
@@ -394,22 +500,54 @@ async fn main() -> Result<()> {
             sleep(delay_between_batches).await;
         }
     }
-    
-    // Save final results
-    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-    let results_file = format!("{}/results_{}.json", output_dir, timestamp);
-    let stats_file = format!("{}/statistics_{}.json", output_dir, timestamp);
-    let report_file = format!("{}/report_{}.md", output_dir, timestamp);
-    
+
+    // Save final results under the run directory; the run ID already
+    // disambiguates runs, so these filenames stay fixed.
+    let results_file = format!("{}/results.json", output_dir);
+    let stats_file = format!("{}/statistics.json", output_dir);
+    let report_file = format!("{}/report.md", output_dir);
+
     fs::write(&results_file, serde_json::to_string_pretty(&results)?)?;
     fs::write(&stats_file, serde_json::to_string_pretty(&stats)?)?;
-    
+
     // Generate report
     let report = generate_report(&stats, &results);
     fs::write(&report_file, report)?;
-    
+
+    let success_rate = if stats.total_tests > 0 {
+        (stats.successful_function_calls as f64 / stats.total_tests as f64) * 100.0
+    } else {
+        0.0
+    };
+    manager.record_run(RunSummary {
+        run_id: run_id.clone(),
+        timestamp: run_started_at,
+        model: model.clone(),
+        seed,
+        total_tests: stats.total_tests,
+        success_rate,
+    })?;
+
+    // Best-effort push to a tracking dashboard; never fails the run.
+    if let Some(url) = &report_url {
+        dashboard::report_run(url, report_token.as_deref(), &run_id, &stats).await;
+    }
+
+    // Emit the selected --reporter's document, if it produces one.
+    if let Some(doc) = reporter.report(&stats, &results) {
+        match &report_path {
+            Some(path) => {
+                fs::write(path, doc)?;
+                info!("Wrote {} report to {}", reporter_name, path);
+            }
+            None => println!("{}", doc),
+        }
+    }
+
     // Print summary
     println!("\n=== FUNCTION CALLING VALIDATION RESULTS ===");
+    println!("Run ID: {} (show with `show {}`)", run_id, run_id);
+    println!("Seed: {} (pass --seed {} to replay this run)", stats.seed, stats.seed);
     println!("Total tests: {}", stats.total_tests);
     println!("Successful function calls: {} ({:.1}%)", 
         stats.successful_function_calls,
@@ -426,12 +564,16 @@ async fn main() -> Result<()> {
 
 fn generate_report(stats: &Statistics, results: &[TestResult]) -> String {
     let mut report = String::new();
-    
+
     report.push_str(&format!("# Function Calling Validation Report\n\n"));
-    report.push_str(&format!("Generated: {}\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
-    report.push_str(&format!("Model: gemini-2.0-flash-exp\n"));
-    report.push_str(&format!("Library Version: 0.1.1\n\n"));
-    
+    report.push_str(&format!("Generated: {}\n\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
+
+    if let Some(env_info) = &stats.env_info {
+        report.push_str("## Environment\n\n");
+        report.push_str(&env_info.to_markdown_table());
+        report.push('\n');
+    }
+
     report.push_str("## Executive Summary\n\n");
     report.push_str(&format!("**Total Tests Run**: {}\n", stats.total_tests));
     report.push_str(&format!("**Function Call Success Rate**: {:.1}%\n", 
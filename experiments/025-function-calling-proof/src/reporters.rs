@@ -0,0 +1,121 @@
+//! CI-facing output formats for a completed run, selected via `--reporter`.
+//! `Pretty` is the existing console summary and Markdown report; the others
+//! render a document the caller writes wherever `--report-path` points.
+//! Mirrors `023-function-calling`'s `Reporter` trait and reporter set.
+
+use crate::{Statistics, TestResult};
+use anyhow::{bail, Result};
+
+pub trait Reporter {
+    /// Returns the document to write to `--report-path`, or `None` if this
+    /// reporter only has side effects (e.g. `pretty`, which prints directly).
+    fn report(&self, stats: &Statistics, results: &[TestResult]) -> Option<String>;
+}
+
+/// The default: no extra document, since the Markdown report and console
+/// summary are already written by `main`.
+pub struct PrettyReporter;
+
+impl Reporter for PrettyReporter {
+    fn report(&self, _stats: &Statistics, _results: &[TestResult]) -> Option<String> {
+        None
+    }
+}
+
+/// Serializes every `TestResult` plus the final `Statistics` as
+/// pretty-printed JSON, for scripts to parse pass/fail gating out of.
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn report(&self, stats: &Statistics, results: &[TestResult]) -> Option<String> {
+        let doc = serde_json::json!({
+            "statistics": stats,
+            "results": results,
+        });
+        serde_json::to_string_pretty(&doc).ok()
+    }
+}
+
+/// Emits a minimal JUnit XML document: one `<testsuite>` per category, one
+/// `<testcase>` per `TestResult`, with `<failure>` children built from
+/// `TestResult.error` or the expected/actual `function_name` mismatch, for
+/// CI dashboards that ingest JUnit.
+pub struct JUnitXmlReporter;
+
+impl Reporter for JUnitXmlReporter {
+    fn report(&self, _stats: &Statistics, results: &[TestResult]) -> Option<String> {
+        Some(render_junit_xml(results))
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn failure_message(result: &TestResult) -> Option<String> {
+    if let Some(error) = &result.error {
+        return Some(error.clone());
+    }
+    if !result.success {
+        return Some(format!(
+            "expected function {:?}, got {:?}",
+            result.test_case.expected_function, result.function_name
+        ));
+    }
+    None
+}
+
+fn render_junit_xml(results: &[TestResult]) -> String {
+    let mut by_category: std::collections::BTreeMap<&str, Vec<&TestResult>> =
+        std::collections::BTreeMap::new();
+    for result in results {
+        by_category
+            .entry(result.test_case.category.as_str())
+            .or_default()
+            .push(result);
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<testsuites>\n");
+    for (category, cases) in &by_category {
+        let failures = cases.iter().filter(|r| !r.success).count();
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            xml_escape(category),
+            cases.len(),
+            failures,
+        ));
+        for result in cases {
+            out.push_str(&format!(
+                "    <testcase classname=\"function-calling-proof\" name=\"{}\">\n",
+                xml_escape(&result.test_case.prompt),
+            ));
+            if let Some(message) = failure_message(result) {
+                out.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    xml_escape(&message),
+                    xml_escape(&result.response),
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Parses `--reporter`'s value into the matching `Reporter` impl.
+pub fn reporter_from_name(name: &str) -> Result<Box<dyn Reporter>> {
+    match name {
+        "pretty" => Ok(Box::new(PrettyReporter)),
+        "json" => Ok(Box::new(JsonReporter)),
+        "junit" => Ok(Box::new(JUnitXmlReporter)),
+        other => bail!("unknown reporter '{}': expected pretty, json, or junit", other),
+    }
+}
@@ -0,0 +1,156 @@
+//! Persisted run directory: assigns each execution a stable run ID, stores
+//! its artifacts under `results/<run_id>/`, and maintains an `index.json`
+//! so past runs can be listed, reprinted, or deleted without grepping
+//! through a flat folder of `results_<timestamp>.json` files. Modeled on
+//! Fuchsia ffx test's `DirectoryManager`.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Stable identifier for one run, e.g. `20260729_143000`. Sorts
+/// chronologically as a plain string, so the index needs no separate
+/// timestamp field for ordering.
+pub type RunId = String;
+
+/// One row of `index.json`: enough to list past runs without opening their
+/// `statistics.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub run_id: RunId,
+    pub timestamp: DateTime<Utc>,
+    pub model: String,
+    pub seed: u64,
+    pub total_tests: u32,
+    pub success_rate: f64,
+}
+
+/// Owns the `results/` directory: where each run's artifacts live and the
+/// index that tracks them.
+pub struct DirectoryManager {
+    root: PathBuf,
+}
+
+impl DirectoryManager {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    pub fn run_dir(&self, run_id: &str) -> PathBuf {
+        self.root.join(run_id)
+    }
+
+    /// Allocates a fresh run directory named after the current timestamp,
+    /// disambiguating with a numeric suffix if two runs land in the same
+    /// second.
+    pub fn allocate_run(&self, now: DateTime<Utc>) -> Result<RunId> {
+        fs::create_dir_all(&self.root)?;
+        let base = now.format("%Y%m%d_%H%M%S").to_string();
+        let mut run_id = base.clone();
+        let mut suffix = 1;
+        while self.run_dir(&run_id).exists() {
+            run_id = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        fs::create_dir_all(self.run_dir(&run_id))?;
+        Ok(run_id)
+    }
+
+    fn load_index(&self) -> Result<Vec<RunSummary>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read index {}", path.display()))?;
+        Ok(serde_json::from_str(&raw)
+            .with_context(|| format!("failed to parse index {}", path.display()))?)
+    }
+
+    fn save_index(&self, summaries: &[RunSummary]) -> Result<()> {
+        fs::write(self.index_path(), serde_json::to_string_pretty(summaries)?)?;
+        Ok(())
+    }
+
+    /// Appends (or replaces, if re-run under the same ID) a run's summary
+    /// in the index.
+    pub fn record_run(&self, summary: RunSummary) -> Result<()> {
+        let mut summaries = self.load_index()?;
+        summaries.retain(|s| s.run_id != summary.run_id);
+        summaries.push(summary);
+        self.save_index(&summaries)
+    }
+
+    /// Lists past runs, most recent first.
+    pub fn list_runs(&self) -> Result<Vec<RunSummary>> {
+        let mut summaries = self.load_index()?;
+        summaries.sort_by(|a, b| b.run_id.cmp(&a.run_id));
+        Ok(summaries)
+    }
+
+    /// Reprints the saved Markdown report for `run_id`.
+    pub fn show_run(&self, run_id: &str) -> Result<String> {
+        let report_path = self.run_dir(run_id).join("report.md");
+        fs::read_to_string(&report_path)
+            .with_context(|| format!("no report for run {} ({})", run_id, report_path.display()))
+    }
+
+    /// Deletes one run's directory and its index entry.
+    pub fn delete_run(&self, run_id: &str) -> Result<()> {
+        let dir = self.run_dir(run_id);
+        if !dir.exists() {
+            bail!("no such run: {}", run_id);
+        }
+        fs::remove_dir_all(&dir)?;
+        let mut summaries = self.load_index()?;
+        summaries.retain(|s| s.run_id != run_id);
+        self.save_index(&summaries)
+    }
+
+    /// Deletes the `n` oldest runs to reclaim space.
+    pub fn delete_oldest(&self, n: usize) -> Result<Vec<RunId>> {
+        let mut summaries = self.load_index()?;
+        summaries.sort_by(|a, b| a.run_id.cmp(&b.run_id));
+        let doomed: Vec<RunId> = summaries.iter().take(n).map(|s| s.run_id.clone()).collect();
+        for run_id in &doomed {
+            let dir = self.run_dir(run_id);
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+        }
+        summaries.retain(|s| !doomed.contains(&s.run_id));
+        self.save_index(&summaries)?;
+        Ok(doomed)
+    }
+}
+
+/// Formats the `list` subcommand's table.
+pub fn format_run_list(summaries: &[RunSummary]) -> String {
+    if summaries.is_empty() {
+        return "No past runs.".to_string();
+    }
+    let mut out = String::new();
+    out.push_str("RUN ID             DATE                 MODEL                     TESTS  SUCCESS\n");
+    for s in summaries {
+        out.push_str(&format!(
+            "{:<18} {:<20} {:<25} {:<6} {:.1}%\n",
+            s.run_id,
+            s.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            s.model,
+            s.total_tests,
+            s.success_rate,
+        ));
+    }
+    out
+}
+
+/// Root directory used by the harness unless overridden.
+pub fn default_root() -> &'static Path {
+    Path::new("experiments/025-function-calling-proof/results")
+}
@@ -2,7 +2,9 @@
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::audit_logger::{AuditLogger, AuditEvent, Principal as AuditPrincipal, SecurityLevel};
@@ -16,22 +18,22 @@ pub enum Permission {
     UseApiKey,
     RotateApiKey,
     RevokeApiKey,
-    
+
     // Data Access
     ReadData,
     WriteData,
     DeleteData,
-    
+
     // Audit
     ViewAuditLogs,
     ExportAuditLogs,
     VerifyAuditIntegrity,
-    
+
     // Administration
     ManageUsers,
     ManageRoles,
     ManagePermissions,
-    
+
     // Security
     SecurityIncidentResponse,
     EmergencyAccess,
@@ -45,24 +47,306 @@ pub enum Role {
     Developer,
     Auditor,
     ReadOnly,
-    
+
     // Custom role
     Custom(String),
 }
 
-#[derive(Debug, Clone)]
-pub struct AccessControl {
+impl Role {
+    /// Stable discriminant for a built-in role; `Custom` always carries
+    /// the same discriminant and is distinguished by its name instead.
+    fn discriminant(&self) -> i64 {
+        match self {
+            Role::Admin => 0,
+            Role::SecurityOfficer => 1,
+            Role::Developer => 2,
+            Role::Auditor => 3,
+            Role::ReadOnly => 4,
+            Role::Custom(_) => 5,
+        }
+    }
+
+    /// Round-trip a role to the `(discriminant, custom_name)` pair a
+    /// [`RoleStore`] persists, mirroring Pslink's `Role::to_i64` /
+    /// `Role::convert` pattern so `Role::Custom(name)` survives a process
+    /// restart alongside the built-in variants.
+    pub fn to_i64(&self) -> (i64, Option<String>) {
+        match self {
+            Role::Custom(name) => (self.discriminant(), Some(name.clone())),
+            _ => (self.discriminant(), None),
+        }
+    }
+
+    /// Inverse of [`Self::to_i64`].
+    pub fn convert(discriminant: i64, custom_name: Option<String>) -> Result<Self> {
+        match (discriminant, custom_name) {
+            (0, _) => Ok(Role::Admin),
+            (1, _) => Ok(Role::SecurityOfficer),
+            (2, _) => Ok(Role::Developer),
+            (3, _) => Ok(Role::Auditor),
+            (4, _) => Ok(Role::ReadOnly),
+            (5, Some(name)) => Ok(Role::Custom(name)),
+            (5, None) => Err(anyhow!("custom role discriminant requires a name")),
+            (other, _) => Err(anyhow!("unknown role discriminant: {other}")),
+        }
+    }
+}
+
+/// The action half of a [`PermRule`]. Reuses the coarse `Permission` set so
+/// resource-scoped rules compose with the existing role permissions instead
+/// of introducing a parallel vocabulary.
+pub type Action = Permission;
+
+/// A resource-scoped permission rule, e.g. `apikey.self.*` or
+/// `data.project-x.read`, mirroring fabaccess's `lab.some.*` role rules.
+/// `resource` is matched segment-by-segment against a concrete resource
+/// string: `*` matches exactly one segment, and a trailing `*` also
+/// swallows all remaining segments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PermRule {
+    pub action: Action,
+    pub resource: String,
+}
+
+impl PermRule {
+    pub fn new(action: Action, resource: impl Into<String>) -> Self {
+        Self { action, resource: resource.into() }
+    }
+
+    /// Whether this rule's resource pattern matches a concrete `resource`.
+    fn matches_resource(&self, resource: &str) -> bool {
+        let pattern_segments: Vec<&str> = self.resource.split('.').collect();
+        let resource_segments: Vec<&str> = resource.split('.').collect();
+
+        for (i, pattern_seg) in pattern_segments.iter().enumerate() {
+            if *pattern_seg == "*" && i == pattern_segments.len() - 1 {
+                return true; // trailing wildcard swallows the rest
+            }
+            match resource_segments.get(i) {
+                Some(seg) if *pattern_seg == "*" || pattern_seg == seg => continue,
+                _ => return false,
+            }
+        }
+
+        pattern_segments.len() == resource_segments.len()
+    }
+}
+
+/// Access mode for an [`AccessRule`], the way xline's
+/// `role_grant_permission` takes a `PermissionType` bound to a key/range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AccessMode {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl AccessMode {
+    /// Whether a rule granting `self` satisfies a request for `requested`.
+    fn satisfies(&self, requested: AccessMode) -> bool {
+        *self == AccessMode::ReadWrite || *self == requested
+    }
+}
+
+/// A range-based access rule, e.g. read-only on `audit/` or read-write on
+/// `data/tmp/`: `resource_prefix` matches any concrete resource that starts
+/// with it, mirroring xline's key/range-scoped permission grants (finer
+/// grained than [`PermRule`]'s single-granularity boolean).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AccessRule {
+    pub resource_prefix: String,
+    pub mode: AccessMode,
+}
+
+impl AccessRule {
+    pub fn new(resource_prefix: impl Into<String>, mode: AccessMode) -> Self {
+        Self { resource_prefix: resource_prefix.into(), mode }
+    }
+}
+
+/// Authorization decision engine behind [`AccessControl`], modeled on the
+/// actor/object/action triple Casbin-based enforcers expose. Letting
+/// [`AccessControl::new`] take any `Arc<dyn PolicyEnforcer>` means an
+/// organization can plug in a Casbin `Enforcer` wrapper backed by its own
+/// model/policy files instead of the built-in [`DefaultEnforcer`].
+#[async_trait::async_trait]
+pub trait PolicyEnforcer: Send + Sync {
+    /// Decide whether `actor` may perform `action` on `object`.
+    async fn enforce(&self, actor: &str, object: &str, action: &str) -> Result<bool>;
+
+    /// All coarse permissions `actor` currently holds, regardless of
+    /// object. Backs [`AccessControl::get_effective_permissions`].
+    async fn effective_permissions(&self, actor: &str) -> Result<HashSet<Permission>>;
+
+    /// Downcast hook so [`AccessControl`]'s role-administration methods
+    /// (`grant_role` and friends) can reach the concrete [`DefaultEnforcer`]
+    /// when that's what backs them; a custom enforcer manages its own
+    /// policy/roles and simply doesn't support this.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Snapshot of the durable state a [`RoleStore`] loads and persists:
+/// every role's coarse permissions and every user's role assignments.
+#[derive(Debug, Clone, Default)]
+pub struct RoleStoreSnapshot {
+    pub role_permissions: HashMap<Role, HashSet<Permission>>,
+    pub user_roles: HashMap<String, HashSet<Role>>,
+}
+
+/// Durable backing store for roles and user-role assignments, so
+/// [`DefaultEnforcer`]'s state survives a process restart instead of
+/// living only in its in-memory `RwLock<HashMap>`s. Mirrors the
+/// `Arc<dyn AuditStorage>`/`Arc<dyn PolicyEnforcer>` pluggable-backend
+/// pattern used elsewhere in this crate.
+#[async_trait::async_trait]
+pub trait RoleStore: Send + Sync {
+    /// Load every persisted role's permissions and every user's role
+    /// assignments, for [`DefaultEnforcer::load`] to rebuild state from.
+    async fn load_all(&self) -> Result<RoleStoreSnapshot>;
+
+    /// Persist the full `user_id -> roles` assignment table.
+    async fn persist_user_roles(&self, user_roles: &HashMap<String, HashSet<Role>>) -> Result<()>;
+
+    /// Persist the full `role -> permissions` table.
+    async fn persist_role_permissions(&self, role_permissions: &HashMap<Role, HashSet<Permission>>) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct PersistedSnapshot {
+    role_permissions: Vec<PersistedRolePermissions>,
+    user_roles: Vec<PersistedUserRoles>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedRolePermissions {
+    discriminant: i64,
+    custom_name: Option<String>,
+    permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedUserRoles {
+    user_id: String,
+    roles: Vec<(i64, Option<String>)>,
+}
+
+impl TryFrom<PersistedSnapshot> for RoleStoreSnapshot {
+    type Error = anyhow::Error;
+
+    fn try_from(persisted: PersistedSnapshot) -> Result<Self> {
+        let mut role_permissions = HashMap::new();
+        for entry in persisted.role_permissions {
+            let role = Role::convert(entry.discriminant, entry.custom_name)?;
+            role_permissions.insert(role, entry.permissions.into_iter().collect());
+        }
+
+        let mut user_roles = HashMap::new();
+        for entry in persisted.user_roles {
+            let mut roles = HashSet::new();
+            for (discriminant, custom_name) in entry.roles {
+                roles.insert(Role::convert(discriminant, custom_name)?);
+            }
+            user_roles.insert(entry.user_id, roles);
+        }
+
+        Ok(Self { role_permissions, user_roles })
+    }
+}
+
+/// Default [`RoleStore`] backing `AccessControl` with a JSON file on disk.
+pub struct FileRoleStore {
+    path: PathBuf,
+}
+
+impl FileRoleStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    async fn read_persisted(&self) -> Result<PersistedSnapshot> {
+        if !self.path.exists() {
+            return Ok(PersistedSnapshot::default());
+        }
+        let raw = tokio::fs::read_to_string(&self.path).await?;
+        Ok(serde_json::from_str(&raw)?)
+    }
+
+    async fn write_persisted(&self, snapshot: &PersistedSnapshot) -> Result<()> {
+        let raw = serde_json::to_string_pretty(snapshot)?;
+        tokio::fs::write(&self.path, raw).await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl RoleStore for FileRoleStore {
+    async fn load_all(&self) -> Result<RoleStoreSnapshot> {
+        self.read_persisted().await?.try_into()
+    }
+
+    async fn persist_user_roles(&self, user_roles: &HashMap<String, HashSet<Role>>) -> Result<()> {
+        let mut snapshot = self.read_persisted().await?;
+        snapshot.user_roles = user_roles
+            .iter()
+            .map(|(user_id, roles)| PersistedUserRoles {
+                user_id: user_id.clone(),
+                roles: roles.iter().map(Role::to_i64).collect(),
+            })
+            .collect();
+        self.write_persisted(&snapshot).await
+    }
+
+    async fn persist_role_permissions(&self, role_permissions: &HashMap<Role, HashSet<Permission>>) -> Result<()> {
+        let mut snapshot = self.read_persisted().await?;
+        snapshot.role_permissions = role_permissions
+            .iter()
+            .map(|(role, perms)| {
+                let (discriminant, custom_name) = role.to_i64();
+                PersistedRolePermissions {
+                    discriminant,
+                    custom_name,
+                    permissions: perms.iter().cloned().collect(),
+                }
+            })
+            .collect();
+        self.write_persisted(&snapshot).await
+    }
+}
+
+/// Built-in in-memory RBAC engine: the role/permission logic this module
+/// has always used, now shipped as one [`PolicyEnforcer`] implementation
+/// among potentially several.
+#[derive(Clone)]
+pub struct DefaultEnforcer {
     role_permissions: Arc<RwLock<HashMap<Role, HashSet<Permission>>>>,
+    /// Parent roles a role inherits permissions from, mirroring fabaccess's
+    /// `parents = [...]` role config. Walked transitively by
+    /// [`DefaultEnforcer::effective_permissions_for_role`].
+    role_parents: Arc<RwLock<HashMap<Role, HashSet<Role>>>>,
+    /// Resource-scoped rules held alongside each role's coarse permissions,
+    /// consulted by [`DefaultEnforcer::enforce`].
+    role_perm_rules: Arc<RwLock<HashMap<Role, HashSet<PermRule>>>>,
+    /// Range-based [`AccessRule`]s held alongside each role's coarse
+    /// permissions, consulted by [`DefaultEnforcer::has_access`].
+    role_access_rules: Arc<RwLock<HashMap<Role, HashSet<AccessRule>>>>,
     user_roles: Arc<RwLock<HashMap<String, HashSet<Role>>>>,
-    audit_logger: Arc<AuditLogger>,
+    /// Backing store written through on every role/permission mutation, if
+    /// one was supplied via [`DefaultEnforcer::load`].
+    store: Option<Arc<dyn RoleStore>>,
 }
 
-impl AccessControl {
-    pub fn new(audit_logger: Arc<AuditLogger>) -> Self {
+impl Default for DefaultEnforcer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultEnforcer {
+    pub fn new() -> Self {
         let mut role_permissions = HashMap::new();
-        
+
         // Define default role permissions (principle of least privilege)
-        
+
         // Admin: Full access
         role_permissions.insert(
             Role::Admin,
@@ -85,7 +369,7 @@ impl AccessControl {
                 Permission::EmergencyAccess,
             ].into_iter().collect()
         );
-        
+
         // Security Officer: Security-focused permissions
         role_permissions.insert(
             Role::SecurityOfficer,
@@ -100,7 +384,7 @@ impl AccessControl {
                 Permission::EmergencyAccess,
             ].into_iter().collect()
         );
-        
+
         // Developer: Standard development permissions
         role_permissions.insert(
             Role::Developer,
@@ -113,7 +397,7 @@ impl AccessControl {
                 Permission::WriteData,
             ].into_iter().collect()
         );
-        
+
         // Auditor: Read-only audit access
         role_permissions.insert(
             Role::Auditor,
@@ -123,7 +407,7 @@ impl AccessControl {
                 Permission::VerifyAuditIntegrity,
             ].into_iter().collect()
         );
-        
+
         // ReadOnly: Minimal permissions
         role_permissions.insert(
             Role::ReadOnly,
@@ -131,94 +415,585 @@ impl AccessControl {
                 Permission::ReadData,
             ].into_iter().collect()
         );
-        
+
         Self {
             role_permissions: Arc::new(RwLock::new(role_permissions)),
+            role_parents: Arc::new(RwLock::new(HashMap::new())),
+            role_perm_rules: Arc::new(RwLock::new(HashMap::new())),
+            role_access_rules: Arc::new(RwLock::new(HashMap::new())),
             user_roles: Arc::new(RwLock::new(HashMap::new())),
-            audit_logger,
+            store: None,
+        }
+    }
+
+    /// Rebuild state from `store` at startup: seeds the usual built-in
+    /// role permissions, then overlays whatever was persisted (custom
+    /// roles and user assignments), and writes through to `store` on every
+    /// subsequent `grant_role`/`revoke_role`/`create_custom_role` call.
+    pub async fn load(store: Arc<dyn RoleStore>) -> Result<Self> {
+        let snapshot = store.load_all().await?;
+        let mut enforcer = Self::new();
+
+        {
+            let mut role_permissions = enforcer.role_permissions.write().await;
+            for (role, perms) in snapshot.role_permissions {
+                role_permissions.insert(role, perms);
+            }
+        }
+        {
+            let mut user_roles = enforcer.user_roles.write().await;
+            *user_roles = snapshot.user_roles;
+        }
+
+        enforcer.store = Some(store);
+        Ok(enforcer)
+    }
+
+    /// Write through the current `user_roles` table to `store`, if one is
+    /// configured.
+    async fn persist_user_roles(&self) -> Result<()> {
+        if let Some(store) = &self.store {
+            let user_roles = self.user_roles.read().await.clone();
+            store.persist_user_roles(&user_roles).await?;
+        }
+        Ok(())
+    }
+
+    /// Write through the current `role_permissions` table to `store`, if
+    /// one is configured.
+    async fn persist_role_permissions(&self) -> Result<()> {
+        if let Some(store) = &self.store {
+            let role_permissions = self.role_permissions.read().await.clone();
+            store.persist_role_permissions(&role_permissions).await?;
+        }
+        Ok(())
+    }
+
+    /// Add a resource-scoped rule to `role`, alongside its coarse
+    /// permissions.
+    pub async fn add_perm_rule(&self, role: Role, rule: PermRule) -> Result<()> {
+        self.role_perm_rules
+            .write()
+            .await
+            .entry(role)
+            .or_insert_with(HashSet::new)
+            .insert(rule);
+        Ok(())
+    }
+
+    /// Replace `role`'s full parent set, rejecting the change if any parent
+    /// edge would introduce a cycle in the inheritance graph.
+    pub async fn set_role_parents(&self, role: Role, parents: HashSet<Role>) -> Result<()> {
+        let graph = self.role_parents.read().await.clone();
+        for parent in &parents {
+            if Self::reaches(&graph, parent, &role, &mut HashSet::new()) {
+                return Err(anyhow!(
+                    "cannot set {:?} as a parent of {:?}: would introduce a cycle",
+                    parent, role
+                ));
+            }
+        }
+
+        self.role_parents.write().await.insert(role, parents);
+        Ok(())
+    }
+
+    /// Add a single parent role to `role`'s inheritance set, rejecting the
+    /// edge if `parent` already (transitively) inherits from `role`.
+    pub async fn add_parent(&self, role: Role, parent: Role) -> Result<()> {
+        let graph = self.role_parents.read().await.clone();
+        if Self::reaches(&graph, &parent, &role, &mut HashSet::new()) {
+            return Err(anyhow!(
+                "cannot set {:?} as a parent of {:?}: would introduce a cycle",
+                parent, role
+            ));
+        }
+
+        self.role_parents
+            .write()
+            .await
+            .entry(role)
+            .or_insert_with(HashSet::new)
+            .insert(parent);
+        Ok(())
+    }
+
+    /// Whether `from` can reach `target` by walking the parent graph
+    /// (i.e. `target` is already an ancestor of `from`). Used to reject
+    /// edges that would introduce a cycle.
+    fn reaches(graph: &HashMap<Role, HashSet<Role>>, from: &Role, target: &Role, visited: &mut HashSet<Role>) -> bool {
+        if from == target {
+            return true;
+        }
+        if !visited.insert(from.clone()) {
+            return false; // already explored this branch; cycle, not a hit
+        }
+        if let Some(parents) = graph.get(from) {
+            for parent in parents {
+                if Self::reaches(graph, parent, target, visited) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Accumulate the direct permissions of `role` and every role it
+    /// transitively inherits from, via the `role_parents` graph. A
+    /// `HashSet<Role>` of visited roles bounds the walk so a cycle (left in
+    /// place defensively, since `add_parent`/`set_role_parents` already
+    /// reject introducing one) is a non-fatal stop rather than an infinite
+    /// loop.
+    async fn effective_permissions_for_role(&self, role: &Role) -> HashSet<Permission> {
+        let role_permissions = self.role_permissions.read().await;
+        let role_parents = self.role_parents.read().await;
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![role.clone()];
+        let mut permissions = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+
+            if let Some(perms) = role_permissions.get(&current) {
+                permissions.extend(perms.iter().cloned());
+            }
+
+            if let Some(parents) = role_parents.get(&current) {
+                stack.extend(parents.iter().cloned());
+            }
+        }
+
+        permissions
+    }
+
+    /// Mirrors [`Self::effective_permissions_for_role`], but for
+    /// resource-scoped [`PermRule`]s instead of coarse permissions.
+    async fn effective_perm_rules_for_role(&self, role: &Role) -> HashSet<PermRule> {
+        let role_perm_rules = self.role_perm_rules.read().await;
+        let role_parents = self.role_parents.read().await;
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![role.clone()];
+        let mut rules = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+
+            if let Some(role_rules) = role_perm_rules.get(&current) {
+                rules.extend(role_rules.iter().cloned());
+            }
+
+            if let Some(parents) = role_parents.get(&current) {
+                stack.extend(parents.iter().cloned());
+            }
+        }
+
+        rules
+    }
+
+    /// Mirrors [`Self::effective_permissions_for_role`], but for range-based
+    /// [`AccessRule`]s instead of coarse permissions.
+    async fn effective_access_rules_for_role(&self, role: &Role) -> HashSet<AccessRule> {
+        let role_access_rules = self.role_access_rules.read().await;
+        let role_parents = self.role_parents.read().await;
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![role.clone()];
+        let mut rules = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+
+            if let Some(role_rules) = role_access_rules.get(&current) {
+                rules.extend(role_rules.iter().cloned());
+            }
+
+            if let Some(parents) = role_parents.get(&current) {
+                stack.extend(parents.iter().cloned());
+            }
+        }
+
+        rules
+    }
+
+    /// Attach an `(resource_prefix, AccessMode)` rule to `role`.
+    pub async fn grant_access_rule(&self, role: Role, rule: AccessRule) -> Result<()> {
+        self.role_access_rules
+            .write()
+            .await
+            .entry(role)
+            .or_insert_with(HashSet::new)
+            .insert(rule);
+        Ok(())
+    }
+
+    /// Whether `actor` may access `resource` in `mode`: granted when a role
+    /// it holds has an [`AccessRule`] whose `resource_prefix` is a prefix
+    /// of `resource` and whose mode is `ReadWrite` or equals `mode`.
+    pub async fn has_access(&self, actor: &str, resource: &str, mode: AccessMode) -> Result<bool> {
+        match Self::parse_actor(actor) {
+            ParsedActor::System => Ok(true),
+
+            ParsedActor::User(user_id) => {
+                let roles = self.user_roles.read().await.get(user_id).cloned().unwrap_or_default();
+
+                for role in &roles {
+                    if self
+                        .effective_access_rules_for_role(role)
+                        .await
+                        .iter()
+                        .any(|rule| resource.starts_with(&rule.resource_prefix) && rule.mode.satisfies(mode))
+                    {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+
+            _ => Ok(false),
+        }
+    }
+
+    /// Grant a role to a user, writing through to `store` if configured.
+    pub async fn grant_role(&self, user_id: &str, role: Role) -> Result<()> {
+        {
+            let mut user_roles = self.user_roles.write().await;
+            user_roles.entry(user_id.to_string())
+                .or_insert_with(HashSet::new)
+                .insert(role);
+        }
+        self.persist_user_roles().await
+    }
+
+    /// Revoke a role from a user, writing through to `store` if configured.
+    pub async fn revoke_role(&self, user_id: &str, role: &Role) -> Result<()> {
+        {
+            let mut user_roles = self.user_roles.write().await;
+            if let Some(roles) = user_roles.get_mut(user_id) {
+                roles.remove(role);
+            }
+        }
+        self.persist_user_roles().await
+    }
+
+    /// Create a custom role with specific permissions and, optionally, a
+    /// set of parent roles to inherit permissions from (rejected if any
+    /// parent edge would introduce a cycle). Writes through to `store` if
+    /// configured.
+    pub async fn create_custom_role(
+        &self,
+        role_name: String,
+        permissions: HashSet<Permission>,
+        parents: Option<HashSet<Role>>,
+    ) -> Result<()> {
+        // Validate role name
+        if role_name.is_empty() || role_name.len() > 50 {
+            return Err(anyhow!("Invalid role name"));
+        }
+
+        let role = Role::Custom(role_name);
+
+        if let Some(parents) = parents {
+            self.set_role_parents(role.clone(), parents).await?;
+        }
+
+        {
+            let mut role_permissions = self.role_permissions.write().await;
+            role_permissions.insert(role, permissions);
+        }
+
+        self.persist_role_permissions().await
+    }
+}
+
+#[async_trait::async_trait]
+impl PolicyEnforcer for DefaultEnforcer {
+    async fn enforce(&self, actor: &str, object: &str, action: &str) -> Result<bool> {
+        match Self::parse_actor(actor) {
+            ParsedActor::System => Ok(true), // System has all permissions
+
+            ParsedActor::User(user_id) => {
+                let roles = self.user_roles.read().await.get(user_id).cloned().unwrap_or_default();
+
+                for role in &roles {
+                    if self.effective_permissions_for_role(role).await.iter().any(|p| action_name(p) == action) {
+                        return Ok(true);
+                    }
+                    if self
+                        .effective_perm_rules_for_role(role)
+                        .await
+                        .iter()
+                        .any(|rule| action_name(&rule.action) == action && rule.matches_resource(object))
+                    {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+
+            ParsedActor::Service(service_id) => {
+                // Services have limited, predefined permissions
+                match (service_id, action) {
+                    ("monitoring", a) if a == action_name(&Permission::ViewAuditLogs) => Ok(true),
+                    ("backup", a) if a == action_name(&Permission::ExportAuditLogs) => Ok(true),
+                    _ => Ok(false),
+                }
+            }
+
+            ParsedActor::Anonymous => Ok(false), // Anonymous has no permissions
         }
     }
-    
-    /// Check if a principal has a specific permission
+
+    async fn effective_permissions(&self, actor: &str) -> Result<HashSet<Permission>> {
+        match Self::parse_actor(actor) {
+            ParsedActor::System => {
+                // System has all permissions
+                Ok(vec![
+                    Permission::CreateApiKey,
+                    Permission::ReadApiKey,
+                    Permission::UseApiKey,
+                    Permission::RotateApiKey,
+                    Permission::RevokeApiKey,
+                    Permission::ReadData,
+                    Permission::WriteData,
+                    Permission::DeleteData,
+                    Permission::ViewAuditLogs,
+                    Permission::ExportAuditLogs,
+                    Permission::VerifyAuditIntegrity,
+                    Permission::ManageUsers,
+                    Permission::ManageRoles,
+                    Permission::ManagePermissions,
+                    Permission::SecurityIncidentResponse,
+                    Permission::EmergencyAccess,
+                ].into_iter().collect())
+            }
+
+            ParsedActor::User(user_id) => {
+                let roles = self.user_roles.read().await.get(user_id).cloned().unwrap_or_default();
+
+                let mut permissions = HashSet::new();
+                for role in &roles {
+                    permissions.extend(self.effective_permissions_for_role(role).await);
+                }
+
+                Ok(permissions)
+            }
+
+            ParsedActor::Service(service_id) => {
+                let permissions = match service_id {
+                    "monitoring" => vec![Permission::ViewAuditLogs],
+                    "backup" => vec![Permission::ExportAuditLogs],
+                    _ => vec![],
+                };
+                Ok(permissions.into_iter().collect())
+            }
+
+            ParsedActor::Anonymous => Ok(HashSet::new()),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A [`Principal`] rendered into the `actor` string a [`PolicyEnforcer`]
+/// sees, and the parsed form `DefaultEnforcer` matches back against.
+enum ParsedActor<'a> {
+    User(&'a str),
+    Service(&'a str),
+    System,
+    Anonymous,
+}
+
+impl DefaultEnforcer {
+    fn parse_actor(actor: &str) -> ParsedActor<'_> {
+        if let Some(id) = actor.strip_prefix("user:") {
+            ParsedActor::User(id)
+        } else if let Some(id) = actor.strip_prefix("service:") {
+            ParsedActor::Service(id)
+        } else if actor == "system" {
+            ParsedActor::System
+        } else {
+            ParsedActor::Anonymous
+        }
+    }
+}
+
+/// Render a [`Permission`] the way a [`PolicyEnforcer`] sees it on the wire:
+/// a plain action name, matching the Debug-derived names already used in
+/// this module's audit resource strings (e.g. `permission:ReadData@*`).
+fn action_name(permission: &Permission) -> String {
+    format!("{:?}", permission)
+}
+
+#[derive(Clone)]
+pub struct AccessControl {
+    enforcer: Arc<dyn PolicyEnforcer>,
+    audit_logger: Arc<AuditLogger>,
+}
+
+impl AccessControl {
+    pub fn new(enforcer: Arc<dyn PolicyEnforcer>, audit_logger: Arc<AuditLogger>) -> Self {
+        Self { enforcer, audit_logger }
+    }
+
+    /// Rebuild `AccessControl` state from `store` at startup, so
+    /// `user_roles` and custom `role_permissions` survive a process
+    /// restart instead of resetting to the built-in defaults every time.
+    pub async fn load(store: Arc<dyn RoleStore>, audit_logger: Arc<AuditLogger>) -> Result<Self> {
+        let enforcer = DefaultEnforcer::load(store).await?;
+        Ok(Self::new(Arc::new(enforcer), audit_logger))
+    }
+
+    /// Reach the concrete [`DefaultEnforcer`] backing this `AccessControl`,
+    /// for the role-administration methods below. Fails with a clear error
+    /// when a custom [`PolicyEnforcer`] is plugged in instead, since those
+    /// manage roles through their own tooling (e.g. Casbin policy files).
+    fn default_enforcer(&self) -> Result<&DefaultEnforcer> {
+        self.enforcer
+            .as_any()
+            .downcast_ref::<DefaultEnforcer>()
+            .ok_or_else(|| anyhow!("role administration requires the built-in DefaultEnforcer"))
+    }
+
+    /// Add a resource-scoped rule to `role`, alongside its coarse
+    /// permissions.
+    pub async fn add_perm_rule(
+        &self,
+        granter: &Principal,
+        role: Role,
+        rule: PermRule,
+    ) -> Result<()> {
+        self.check_permission(granter, &Permission::ManageRoles).await?;
+        self.default_enforcer()?.add_perm_rule(role, rule).await
+    }
+
+    /// Attach a range-based `(resource_prefix, AccessMode)` rule to `role`,
+    /// alongside its coarse permissions and [`PermRule`]s.
+    pub async fn grant_access_rule(
+        &self,
+        granter: &Principal,
+        role: Role,
+        rule: AccessRule,
+    ) -> Result<()> {
+        self.check_permission(granter, &Permission::ManageRoles).await?;
+        self.default_enforcer()?.grant_access_rule(role, rule).await
+    }
+
+    /// Check if a principal may access `resource` in `mode`, the
+    /// fine-grained, range-based counterpart to [`Self::check_permission_on`]
+    /// (e.g. read-only on `audit/` but read-write on `data/tmp/`).
+    pub async fn check_access(
+        &self,
+        principal: &Principal,
+        resource: &str,
+        mode: AccessMode,
+    ) -> Result<()> {
+        let granted = self
+            .default_enforcer()?
+            .has_access(&principal_actor(principal), resource, mode)
+            .await?;
+
+        let outcome = if granted { "granted" } else { "denied" };
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: self.principal_to_audit(principal),
+            action: "access_check".to_string(),
+            resource: format!("access:{:?}@{}", mode, resource),
+            outcome: outcome.to_string(),
+            security_level: SecurityLevel::Low,
+            details: serde_json::json!({
+                "resource": resource,
+                "mode": mode,
+                "granted": granted,
+            }),
+        }).await?;
+
+        if granted {
+            Ok(())
+        } else {
+            Err(anyhow!("Access denied: {:?} on {}", mode, resource))
+        }
+    }
+
+    /// Replace `role`'s full parent set, rejecting the change if any parent
+    /// edge would introduce a cycle in the inheritance graph.
+    pub async fn set_role_parents(&self, role: Role, parents: HashSet<Role>) -> Result<()> {
+        self.default_enforcer()?.set_role_parents(role, parents).await
+    }
+
+    /// Add a single parent role to `role`'s inheritance set, rejecting the
+    /// edge if `parent` already (transitively) inherits from `role`.
+    pub async fn add_parent(&self, role: Role, parent: Role) -> Result<()> {
+        self.default_enforcer()?.add_parent(role, parent).await
+    }
+
+    /// Check if a principal has a specific permission, regardless of
+    /// resource. Equivalent to `check_permission_on(principal, permission,
+    /// "*")`.
     pub async fn check_permission(
         &self,
         principal: &Principal,
         permission: &Permission,
     ) -> Result<()> {
-        let has_permission = self.has_permission(principal, permission).await?;
-        
+        self.check_permission_on(principal, permission, "*").await
+    }
+
+    /// Check if a principal may perform `action` on a concrete `resource`,
+    /// delegating the decision to `self.enforcer` and auditing the outcome
+    /// regardless of which engine made the call.
+    pub async fn check_permission_on(
+        &self,
+        principal: &Principal,
+        action: &Action,
+        resource: &str,
+    ) -> Result<()> {
+        let actor = principal_actor(principal);
+        let granted = self.enforcer.enforce(&actor, resource, &action_name(action)).await?;
+
         // Audit the permission check
-        let outcome = if has_permission { "granted" } else { "denied" };
-        let security_level = match permission {
-            Permission::EmergencyAccess | 
+        let outcome = if granted { "granted" } else { "denied" };
+        let security_level = match action {
+            Permission::EmergencyAccess |
             Permission::SecurityIncidentResponse |
             Permission::RevokeApiKey => SecurityLevel::High,
-            
+
             Permission::ManageUsers |
             Permission::ManageRoles |
             Permission::ManagePermissions => SecurityLevel::Medium,
-            
+
             _ => SecurityLevel::Low,
         };
-        
+
         self.audit_logger.log(AuditEvent {
             timestamp: Utc::now(),
             principal: self.principal_to_audit(principal),
             action: "permission_check".to_string(),
-            resource: format!("permission:{:?}", permission),
+            resource: format!("permission:{:?}@{}", action, resource),
             outcome: outcome.to_string(),
             security_level,
             details: serde_json::json!({
-                "permission": permission,
-                "granted": has_permission,
+                "permission": action,
+                "resource": resource,
+                "granted": granted,
             }),
         }).await?;
-        
-        if has_permission {
+
+        if granted {
             Ok(())
         } else {
-            Err(anyhow!("Permission denied: {:?}", permission))
-        }
-    }
-    
-    /// Check if principal has permission (internal, no audit)
-    async fn has_permission(
-        &self,
-        principal: &Principal,
-        permission: &Permission,
-    ) -> Result<bool> {
-        match principal {
-            Principal::System => Ok(true), // System has all permissions
-            
-            Principal::User(user_id) => {
-                let user_roles = self.user_roles.read().await;
-                let role_permissions = self.role_permissions.read().await;
-                
-                if let Some(roles) = user_roles.get(user_id) {
-                    for role in roles {
-                        if let Some(perms) = role_permissions.get(role) {
-                            if perms.contains(permission) {
-                                return Ok(true);
-                            }
-                        }
-                    }
-                }
-                Ok(false)
-            }
-            
-            Principal::Service(service_id) => {
-                // Services have limited, predefined permissions
-                match (service_id.as_str(), permission) {
-                    ("monitoring", Permission::ViewAuditLogs) => Ok(true),
-                    ("backup", Permission::ExportAuditLogs) => Ok(true),
-                    _ => Ok(false),
-                }
-            }
-            
-            Principal::Anonymous => Ok(false), // Anonymous has no permissions
+            Err(anyhow!("Permission denied: {:?} on {}", action, resource))
         }
     }
-    
+
     /// Grant a role to a user
     pub async fn grant_role(
         &self,
@@ -228,15 +1003,9 @@ impl AccessControl {
     ) -> Result<()> {
         // Check if granter has permission to manage roles
         self.check_permission(granter, &Permission::ManageRoles).await?;
-        
-        // Add role to user
-        {
-            let mut user_roles = self.user_roles.write().await;
-            user_roles.entry(user_id.to_string())
-                .or_insert_with(HashSet::new)
-                .insert(role.clone());
-        }
-        
+
+        self.default_enforcer()?.grant_role(user_id, role.clone()).await?;
+
         // Audit log
         self.audit_logger.log(AuditEvent {
             timestamp: Utc::now(),
@@ -250,10 +1019,10 @@ impl AccessControl {
                 "user": user_id,
             }),
         }).await?;
-        
+
         Ok(())
     }
-    
+
     /// Revoke a role from a user
     pub async fn revoke_role(
         &self,
@@ -263,15 +1032,9 @@ impl AccessControl {
     ) -> Result<()> {
         // Check if revoker has permission to manage roles
         self.check_permission(revoker, &Permission::ManageRoles).await?;
-        
-        // Remove role from user
-        {
-            let mut user_roles = self.user_roles.write().await;
-            if let Some(roles) = user_roles.get_mut(user_id) {
-                roles.remove(role);
-            }
-        }
-        
+
+        self.default_enforcer()?.revoke_role(user_id, role).await?;
+
         // Audit log
         self.audit_logger.log(AuditEvent {
             timestamp: Utc::now(),
@@ -285,31 +1048,27 @@ impl AccessControl {
                 "user": user_id,
             }),
         }).await?;
-        
+
         Ok(())
     }
-    
-    /// Create a custom role with specific permissions
+
+    /// Create a custom role with specific permissions and, optionally, a
+    /// set of parent roles to inherit permissions from (rejected if any
+    /// parent edge would introduce a cycle).
     pub async fn create_custom_role(
         &self,
         creator: &Principal,
         role_name: String,
         permissions: HashSet<Permission>,
+        parents: Option<HashSet<Role>>,
     ) -> Result<()> {
         // Check if creator has permission to manage roles
         self.check_permission(creator, &Permission::ManageRoles).await?;
-        
-        // Validate role name
-        if role_name.is_empty() || role_name.len() > 50 {
-            return Err(anyhow!("Invalid role name"));
-        }
-        
-        // Add custom role
-        {
-            let mut role_permissions = self.role_permissions.write().await;
-            role_permissions.insert(Role::Custom(role_name.clone()), permissions.clone());
-        }
-        
+
+        self.default_enforcer()?
+            .create_custom_role(role_name.clone(), permissions.clone(), parents)
+            .await?;
+
         // Audit log
         self.audit_logger.log(AuditEvent {
             timestamp: Utc::now(),
@@ -323,68 +1082,18 @@ impl AccessControl {
                 "permissions": permissions,
             }),
         }).await?;
-        
+
         Ok(())
     }
-    
+
     /// Get effective permissions for a principal
     pub async fn get_effective_permissions(
         &self,
         principal: &Principal,
     ) -> Result<HashSet<Permission>> {
-        match principal {
-            Principal::System => {
-                // System has all permissions
-                Ok(vec![
-                    Permission::CreateApiKey,
-                    Permission::ReadApiKey,
-                    Permission::UseApiKey,
-                    Permission::RotateApiKey,
-                    Permission::RevokeApiKey,
-                    Permission::ReadData,
-                    Permission::WriteData,
-                    Permission::DeleteData,
-                    Permission::ViewAuditLogs,
-                    Permission::ExportAuditLogs,
-                    Permission::VerifyAuditIntegrity,
-                    Permission::ManageUsers,
-                    Permission::ManageRoles,
-                    Permission::ManagePermissions,
-                    Permission::SecurityIncidentResponse,
-                    Permission::EmergencyAccess,
-                ].into_iter().collect())
-            }
-            
-            Principal::User(user_id) => {
-                let mut permissions = HashSet::new();
-                let user_roles = self.user_roles.read().await;
-                let role_permissions = self.role_permissions.read().await;
-                
-                if let Some(roles) = user_roles.get(user_id) {
-                    for role in roles {
-                        if let Some(perms) = role_permissions.get(role) {
-                            permissions.extend(perms.iter().cloned());
-                        }
-                    }
-                }
-                
-                Ok(permissions)
-            }
-            
-            Principal::Service(service_id) => {
-                // Return predefined service permissions
-                let permissions = match service_id.as_str() {
-                    "monitoring" => vec![Permission::ViewAuditLogs],
-                    "backup" => vec![Permission::ExportAuditLogs],
-                    _ => vec![],
-                };
-                Ok(permissions.into_iter().collect())
-            }
-            
-            Principal::Anonymous => Ok(HashSet::new()),
-        }
+        self.enforcer.effective_permissions(&principal_actor(principal)).await
     }
-    
+
     fn principal_to_audit(&self, principal: &Principal) -> AuditPrincipal {
         match principal {
             Principal::User(id) => AuditPrincipal::User(id.clone()),
@@ -395,7 +1104,7 @@ impl AccessControl {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Principal {
     User(String),
     Service(String),
@@ -403,6 +1112,16 @@ pub enum Principal {
     Anonymous,
 }
 
+/// Render a [`Principal`] into the `actor` string a [`PolicyEnforcer`] sees.
+fn principal_actor(principal: &Principal) -> String {
+    match principal {
+        Principal::User(id) => format!("user:{}", id),
+        Principal::Service(id) => format!("service:{}", id),
+        Principal::System => "system".to_string(),
+        Principal::Anonymous => "anonymous".to_string(),
+    }
+}
+
 // Mock implementations for testing
 
 pub struct MockAccessControl;
@@ -411,7 +1130,7 @@ impl MockAccessControl {
     pub fn new() -> Self {
         Self
     }
-    
+
     pub async fn check_permission(
         &self,
         _principal: &Principal,
@@ -425,53 +1144,259 @@ impl MockAccessControl {
 mod tests {
     use super::*;
     use crate::audit_logger::MockAuditLogger;
-    
+
+    fn new_access_control() -> AccessControl {
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        AccessControl::new(Arc::new(DefaultEnforcer::new()), audit_logger)
+    }
+
     #[tokio::test]
     async fn test_role_based_permissions() {
-        let audit_logger = Arc::new(MockAuditLogger::new());
-        let ac = AccessControl::new(audit_logger);
-        
+        let ac = new_access_control();
+
         // Grant developer role to user
         let admin = Principal::System;
         let user_id = "test_user";
-        
+
         ac.grant_role(&admin, user_id, Role::Developer).await.unwrap();
-        
+
         // Check developer permissions
         let user = Principal::User(user_id.to_string());
         assert!(ac.check_permission(&user, &Permission::ReadData).await.is_ok());
         assert!(ac.check_permission(&user, &Permission::WriteData).await.is_ok());
-        
+
         // Check permission user doesn't have
         assert!(ac.check_permission(&user, &Permission::ManageUsers).await.is_err());
     }
-    
+
     #[tokio::test]
     async fn test_custom_roles() {
-        let audit_logger = Arc::new(MockAuditLogger::new());
-        let ac = AccessControl::new(audit_logger);
-        
+        let ac = new_access_control();
+
         // Create custom role
         let admin = Principal::System;
         let custom_perms = vec![
             Permission::ReadData,
             Permission::ViewAuditLogs,
         ].into_iter().collect();
-        
+
         ac.create_custom_role(
             &admin,
             "CustomReader".to_string(),
             custom_perms,
+            None,
         ).await.unwrap();
-        
+
         // Grant custom role
         ac.grant_role(&admin, "test_user", Role::Custom("CustomReader".to_string()))
             .await.unwrap();
-        
+
         // Verify permissions
         let user = Principal::User("test_user".to_string());
         assert!(ac.check_permission(&user, &Permission::ReadData).await.is_ok());
         assert!(ac.check_permission(&user, &Permission::ViewAuditLogs).await.is_ok());
         assert!(ac.check_permission(&user, &Permission::WriteData).await.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_role_inherits_parent_permissions() {
+        let ac = new_access_control();
+        let admin = Principal::System;
+
+        // SeniorDev extends Developer: no permissions of its own, but
+        // should gain everything Developer has via inheritance.
+        ac.create_custom_role(&admin, "SeniorDev".to_string(), HashSet::new(), None)
+            .await
+            .unwrap();
+        ac.add_parent(Role::Custom("SeniorDev".to_string()), Role::Developer)
+            .await
+            .unwrap();
+
+        ac.grant_role(&admin, "senior", Role::Custom("SeniorDev".to_string()))
+            .await
+            .unwrap();
+
+        let senior = Principal::User("senior".to_string());
+        let effective = ac.get_effective_permissions(&senior).await.unwrap();
+        assert!(effective.contains(&Permission::ReadData));
+        assert!(effective.contains(&Permission::WriteData));
+        assert!(ac.check_permission(&senior, &Permission::WriteData).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_role_parent_cycle_is_rejected() {
+        let ac = new_access_control();
+
+        ac.add_parent(Role::Developer, Role::Auditor).await.unwrap();
+
+        // Auditor -> Developer would close the loop Developer -> Auditor -> Developer.
+        assert!(ac.add_parent(Role::Auditor, Role::Developer).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_custom_role_with_parents_rejects_self_cycle() {
+        let ac = new_access_control();
+        let admin = Principal::System;
+
+        let role = Role::Custom("SelfReferential".to_string());
+        let mut parents = HashSet::new();
+        parents.insert(role.clone());
+
+        let result = ac
+            .create_custom_role(&admin, "SelfReferential".to_string(), HashSet::new(), Some(parents))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_perm_rule_wildcard_matching() {
+        let rule = PermRule::new(Permission::WriteData, "data.project-x.*");
+        assert!(rule.matches_resource("data.project-x.read"));
+        assert!(rule.matches_resource("data.project-x.write"));
+        assert!(!rule.matches_resource("data.project-y.read"));
+
+        let single_segment = PermRule::new(Permission::UseApiKey, "apikey.self.*");
+        assert!(single_segment.matches_resource("apikey.self.rotate"));
+        assert!(!single_segment.matches_resource("apikey.other.rotate"));
+
+        let exact = PermRule::new(Permission::ReadData, "data.project-x.read");
+        assert!(exact.matches_resource("data.project-x.read"));
+        assert!(!exact.matches_resource("data.project-x.read.extra"));
+    }
+
+    #[tokio::test]
+    async fn test_check_permission_on_grants_via_matching_wildcard_rule() {
+        let ac = new_access_control();
+        let admin = Principal::System;
+
+        ac.create_custom_role(&admin, "ProjectXWriter".to_string(), HashSet::new(), None)
+            .await
+            .unwrap();
+        ac.add_perm_rule(
+            &admin,
+            Role::Custom("ProjectXWriter".to_string()),
+            PermRule::new(Permission::WriteData, "data.project-x.*"),
+        )
+        .await
+        .unwrap();
+        ac.grant_role(&admin, "dev", Role::Custom("ProjectXWriter".to_string()))
+            .await
+            .unwrap();
+
+        let dev = Principal::User("dev".to_string());
+        assert!(ac
+            .check_permission_on(&dev, &Permission::WriteData, "data.project-x.write")
+            .await
+            .is_ok());
+        assert!(ac
+            .check_permission_on(&dev, &Permission::WriteData, "data.project-y.write")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_permission_still_works_resource_agnostic() {
+        let ac = new_access_control();
+        let admin = Principal::System;
+
+        ac.grant_role(&admin, "dev", Role::Developer).await.unwrap();
+        let dev = Principal::User("dev".to_string());
+        assert!(ac.check_permission(&dev, &Permission::WriteData).await.is_ok());
+    }
+
+    /// A trivial enforcer that grants everything, demonstrating that
+    /// `AccessControl` works against any `PolicyEnforcer` (e.g. a Casbin
+    /// wrapper), not just `DefaultEnforcer`.
+    struct AllowAllEnforcer;
+
+    #[async_trait::async_trait]
+    impl PolicyEnforcer for AllowAllEnforcer {
+        async fn enforce(&self, _actor: &str, _object: &str, _action: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn effective_permissions(&self, _actor: &str) -> Result<HashSet<Permission>> {
+            Ok(vec![Permission::ReadData, Permission::WriteData].into_iter().collect())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_control_works_with_a_custom_enforcer() {
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let ac = AccessControl::new(Arc::new(AllowAllEnforcer), audit_logger);
+
+        let anyone = Principal::Anonymous;
+        assert!(ac.check_permission(&anyone, &Permission::ManageUsers).await.is_ok());
+        assert!(ac.get_effective_permissions(&anyone).await.unwrap().contains(&Permission::ReadData));
+
+        // Role administration is out of scope for a custom enforcer.
+        assert!(ac.grant_role(&anyone, "someone", Role::Developer).await.is_err());
+    }
+
+    #[test]
+    fn test_role_i64_round_trips() {
+        for role in [Role::Admin, Role::SecurityOfficer, Role::Developer, Role::Auditor, Role::ReadOnly] {
+            let (discriminant, name) = role.to_i64();
+            assert_eq!(Role::convert(discriminant, name).unwrap(), role);
+        }
+
+        let custom = Role::Custom("Reviewer".to_string());
+        let (discriminant, name) = custom.to_i64();
+        assert_eq!(Role::convert(discriminant, name).unwrap(), custom);
+
+        assert!(Role::convert(5, None).is_err());
+        assert!(Role::convert(99, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_state_survives_reload_from_file_store() {
+        let path = std::env::temp_dir().join(format!("access-control-store-test-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store: Arc<dyn RoleStore> = Arc::new(FileRoleStore::new(path.clone()));
+
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let ac = AccessControl::load(store.clone(), audit_logger.clone()).await.unwrap();
+        let admin = Principal::System;
+
+        ac.create_custom_role(&admin, "Reviewer".to_string(), vec![Permission::ReadData].into_iter().collect(), None)
+            .await
+            .unwrap();
+        ac.grant_role(&admin, "alice", Role::Custom("Reviewer".to_string())).await.unwrap();
+
+        // Reload as if the process had restarted.
+        let reloaded = AccessControl::load(store, audit_logger).await.unwrap();
+        let alice = Principal::User("alice".to_string());
+        assert!(reloaded.check_permission(&alice, &Permission::ReadData).await.is_ok());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_check_access_grants_by_mode_and_prefix() {
+        let ac = new_access_control();
+        let admin = Principal::System;
+
+        ac.create_custom_role(&admin, "ProjectXAuditor".to_string(), HashSet::new(), None)
+            .await
+            .unwrap();
+        ac.grant_access_rule(&admin, Role::Custom("ProjectXAuditor".to_string()), AccessRule::new("audit/", AccessMode::Read))
+            .await
+            .unwrap();
+        ac.grant_access_rule(&admin, Role::Custom("ProjectXAuditor".to_string()), AccessRule::new("data/tmp/", AccessMode::ReadWrite))
+            .await
+            .unwrap();
+        ac.grant_role(&admin, "auditor", Role::Custom("ProjectXAuditor".to_string()))
+            .await
+            .unwrap();
+
+        let auditor = Principal::User("auditor".to_string());
+        assert!(ac.check_access(&auditor, "audit/2026-01.log", AccessMode::Read).await.is_ok());
+        assert!(ac.check_access(&auditor, "audit/2026-01.log", AccessMode::Write).await.is_err());
+        assert!(ac.check_access(&auditor, "data/tmp/scratch.json", AccessMode::Write).await.is_ok());
+        assert!(ac.check_access(&auditor, "data/prod/scratch.json", AccessMode::Read).await.is_err());
+    }
+}
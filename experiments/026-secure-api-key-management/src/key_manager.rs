@@ -5,6 +5,7 @@ use secrecy::{ExposeSecret, Secret, SecretString, Zeroize};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 use sha2::{Sha256, Digest};
@@ -21,17 +22,80 @@ use crate::secure_storage::SecureStorage;
 pub struct ApiKeyMetadata {
     pub id: Uuid,
     pub name: String,
+    /// The principal that created this key, and the only one who may
+    /// delegate access to it via `ApiKeyManager::grant_key`.
+    pub owner: Principal,
     pub created_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
     pub last_used: Option<DateTime<Utc>>,
     pub last_rotated: Option<DateTime<Utc>>,
     pub permissions: Vec<Permission>,
+    /// Namespaced, wildcard-capable grants layered on top of `permissions`
+    /// (e.g. `documents.add`, `documents.*`). Empty means this key isn't
+    /// scope-restricted — callers that never pass `requested_action` to
+    /// `validate_key` are unaffected either way.
+    #[serde(default)]
+    pub scopes: Vec<ScopedAction>,
     pub rate_limit: RateLimit,
     pub key_hash: String, // SHA-256 hash for verification
     pub status: KeyStatus,
     pub rotation_policy: RotationPolicy,
     pub usage_count: u64,
     pub failed_attempts: u32,
+    /// Set when this key was created via `generate_split_key` instead of
+    /// `generate_key` — the plaintext was never persisted as a whole, only
+    /// reconstructable from `threshold` of its `total_shares` shares.
+    pub split_key: Option<SplitKeyInfo>,
+    /// Set when this key's secret is HKDF-derived from the manager's
+    /// versioned master secret rather than pure randomness; names the
+    /// epoch it was last (re-)derived under. `rotate_master_secret` bumps
+    /// this for every derived key in one pass. Explicitly random keys
+    /// (from `generate_key`/`generate_split_key`) leave this `None` and are
+    /// never touched by a master-secret rotation.
+    #[serde(default)]
+    pub epoch: Option<u32>,
+    /// The derived key's hash under the previous epoch, kept for one
+    /// rotation as a grace window so in-flight clients presenting the old
+    /// secret aren't locked out mid-rollover. Cleared by the next rotation.
+    #[serde(default)]
+    pub previous_key_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitKeyInfo {
+    pub threshold: u8,
+    pub total_shares: u8,
+}
+
+/// A hierarchical, dot-segmented permission scope — e.g. `documents.add`,
+/// `documents.*`, or the top-level `*` — for deployments that need
+/// namespaced, wildcard-scoped API key grants instead of (or alongside) the
+/// flat `Permission` enum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScopedAction(String);
+
+impl ScopedAction {
+    pub fn new(action: impl Into<String>) -> Self {
+        Self(action.into())
+    }
+
+    /// Whether this granted scope permits `requested`, matching dot
+    /// segments left-to-right without allocating: a `*` segment in the
+    /// grant matches any remaining requested segments, including none.
+    pub fn grants(&self, requested: &str) -> bool {
+        let mut granted = self.0.split('.');
+        let mut requested = requested.split('.');
+
+        loop {
+            match (granted.next(), requested.next()) {
+                (Some("*"), _) => return true,
+                (Some(g), Some(r)) if g == r => continue,
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -60,6 +124,169 @@ pub struct RotationPolicy {
     pub notify_days_before: u32,
 }
 
+/// One share of a Shamir-split API key: the evaluation point and the
+/// per-byte polynomial values, neither of which leaks anything about the
+/// secret alone. Any `threshold` distinct shares reconstruct the key via
+/// `ApiKeyManager::reconstruct_key`.
+#[derive(Clone)]
+pub struct KeyShare {
+    pub key_id: Uuid,
+    pub x: u8,
+    pub threshold: u8,
+    pub total_shares: u8,
+    values: Zeroizing<Vec<u8>>,
+}
+
+/// Reconstruct the secret byte-by-byte via Lagrange interpolation at x = 0,
+/// using each share's evaluation point and byte values.
+fn reconstruct_secret(shares: &[&KeyShare]) -> Result<Vec<u8>> {
+    let len = shares[0].values.len();
+    if shares.iter().any(|s| s.values.len() != len) {
+        return Err(anyhow!("shares disagree on secret length"));
+    }
+
+    let mut secret = Zeroizing::new(vec![0u8; len]);
+    for byte_idx in 0..len {
+        let points: Vec<(u8, u8)> = shares.iter().map(|s| (s.x, s.values[byte_idx])).collect();
+        secret[byte_idx] = gf256::lagrange_interpolate_at_zero(&points);
+    }
+
+    Ok(secret.to_vec())
+}
+
+/// GF(256) arithmetic with the AES reduction polynomial (0x11B, i.e. `x^8 =
+/// 0x1B` once the carry-out bit is reduced) — used only by Shamir secret
+/// sharing above.
+mod gf256 {
+    fn add(a: u8, b: u8) -> u8 {
+        a ^ b
+    }
+
+    fn mul(a: u8, b: u8) -> u8 {
+        let mut result = 0u8;
+        let mut a = a;
+        let mut b = b;
+        for _ in 0..8 {
+            if b & 1 == 1 {
+                result ^= a;
+            }
+            let carry = a & 0x80;
+            a <<= 1;
+            if carry != 0 {
+                a ^= 0x1B;
+            }
+            b >>= 1;
+        }
+        result
+    }
+
+    /// Multiplicative inverse via `a^254`: the field's multiplicative group
+    /// has order 255, so `a^255 == 1` for every nonzero `a`.
+    fn inv(a: u8) -> u8 {
+        assert!(a != 0, "zero has no multiplicative inverse in GF(256)");
+        let mut result = 1u8;
+        let mut base = a;
+        let mut exp = 254u8;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = mul(result, base);
+            }
+            base = mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn div(a: u8, b: u8) -> u8 {
+        mul(a, inv(b))
+    }
+
+    /// Evaluate the polynomial with `coeffs[0]` as the constant term at `x`,
+    /// via Horner's method.
+    pub fn eval_poly(coeffs: &[u8], x: u8) -> u8 {
+        let mut result = 0u8;
+        for &c in coeffs.iter().rev() {
+            result = add(mul(result, x), c);
+        }
+        result
+    }
+
+    /// Lagrange-interpolate `points` (distinct x-coordinates) at x = 0.
+    pub fn lagrange_interpolate_at_zero(points: &[(u8, u8)]) -> u8 {
+        let mut secret = 0u8;
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            let mut numerator = 1u8;
+            let mut denominator = 1u8;
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i != j {
+                    numerator = mul(numerator, xj);
+                    denominator = mul(denominator, add(xi, xj));
+                }
+            }
+            secret = add(secret, mul(yi, div(numerator, denominator)));
+        }
+        secret
+    }
+}
+
+/// Minimal HKDF-SHA256 (RFC 5869) built on the crate's existing `Sha256`
+/// dependency, used only to derive per-key secrets from `ApiKeyManager`'s
+/// master secret — see `generate_derived_key` and `rotate_master_secret`.
+mod hkdf {
+    use sha2::{Sha256, Digest};
+    use zeroize::Zeroizing;
+
+    const BLOCK_SIZE: usize = 64;
+    const HASH_LEN: usize = 32;
+
+    fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; HASH_LEN] {
+        let mut block_key = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            block_key[..HASH_LEN].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut ipad = [0x36u8; BLOCK_SIZE];
+        let mut opad = [0x5cu8; BLOCK_SIZE];
+        for i in 0..BLOCK_SIZE {
+            ipad[i] ^= block_key[i];
+            opad[i] ^= block_key[i];
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(ipad);
+        inner.update(data);
+        let inner_hash = inner.finalize();
+
+        let mut outer = Sha256::new();
+        outer.update(opad);
+        outer.update(inner_hash);
+        outer.finalize().into()
+    }
+
+    /// Extract-and-expand `len` bytes of output key material from `ikm`
+    /// (input key material) salted and labeled per RFC 5869 sections 2.2-2.3.
+    pub fn derive(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Zeroizing<Vec<u8>> {
+        let prk = hmac_sha256(salt, ikm);
+
+        let mut okm = Zeroizing::new(Vec::with_capacity(len));
+        let mut previous: Vec<u8> = Vec::new();
+        let mut counter: u8 = 1;
+        while okm.len() < len {
+            let mut data = Vec::with_capacity(previous.len() + info.len() + 1);
+            data.extend_from_slice(&previous);
+            data.extend_from_slice(info);
+            data.push(counter);
+            previous = hmac_sha256(&prk, &data).to_vec();
+            okm.extend_from_slice(&previous);
+            counter += 1;
+        }
+        okm.truncate(len);
+        okm
+    }
+}
+
 impl Default for RotationPolicy {
     fn default() -> Self {
         Self {
@@ -71,12 +298,101 @@ impl Default for RotationPolicy {
     }
 }
 
+/// Token-bucket state for one key's rate limit, refilled continuously from
+/// `last_refill` so `validate_key` can charge a token without a background
+/// task. Each of the minute/hour/day windows gets its own bucket; the
+/// minute bucket's capacity is widened by `burst_size` above its steady
+/// per-minute rate, per `RateLimit`.
+#[derive(Debug, Clone, Copy)]
+struct Buckets {
+    last_refill: Instant,
+    minute_tokens: f64,
+    hour_tokens: f64,
+    day_tokens: f64,
+}
+
+impl Buckets {
+    fn new(rate_limit: &RateLimit) -> Self {
+        Self {
+            last_refill: Instant::now(),
+            minute_tokens: Self::minute_capacity(rate_limit),
+            hour_tokens: rate_limit.requests_per_hour as f64,
+            day_tokens: rate_limit.requests_per_day as f64,
+        }
+    }
+
+    fn minute_capacity(rate_limit: &RateLimit) -> f64 {
+        rate_limit.requests_per_minute as f64 + rate_limit.burst_size as f64
+    }
+
+    /// Refill every window by elapsed time, then take one token from each
+    /// if all three have quota; denies (without partially charging) if any
+    /// window is exhausted.
+    fn try_consume(&mut self, rate_limit: &RateLimit) -> bool {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.last_refill = Instant::now();
+
+        let minute_capacity = Self::minute_capacity(rate_limit);
+        let hour_capacity = rate_limit.requests_per_hour as f64;
+        let day_capacity = rate_limit.requests_per_day as f64;
+
+        self.minute_tokens = (self.minute_tokens + elapsed * minute_capacity / 60.0).min(minute_capacity);
+        self.hour_tokens = (self.hour_tokens + elapsed * hour_capacity / 3600.0).min(hour_capacity);
+        self.day_tokens = (self.day_tokens + elapsed * day_capacity / 86400.0).min(day_capacity);
+
+        if self.minute_tokens >= 1.0 && self.hour_tokens >= 1.0 && self.day_tokens >= 1.0 {
+            self.minute_tokens -= 1.0;
+            self.hour_tokens -= 1.0;
+            self.day_tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A key's remaining rate-limit budget at the time it was last checked, for
+/// callers to surface `X-RateLimit-Remaining`-style response headers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RemainingQuota {
+    pub minute: u32,
+    pub hour: u32,
+    pub day: u32,
+}
+
 /// Secure API Key Manager
+/// A time-boxed delegation of (a subset of) a key's permissions to another
+/// `Principal`, without ever sharing the key's secret. Grants live only in
+/// `ApiKeyManager::grants` — never in `SecureStorage` — so they evaporate on
+/// process restart, mirroring a per-boot database.
+#[derive(Debug, Clone)]
+pub struct Grant {
+    pub id: Uuid,
+    pub key_id: Uuid,
+    pub owner: Principal,
+    pub grantee: Principal,
+    pub permissions: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// The manager's current master secret and its epoch, used to derive keys
+/// minted by `generate_derived_key`. `rotate_master_secret` replaces
+/// `current` and increments `epoch` atomically under one write lock.
+struct MasterSecret {
+    epoch: u32,
+    current: Zeroizing<Vec<u8>>,
+}
+
 pub struct ApiKeyManager {
     storage: Arc<dyn SecureStorage>,
     access_control: Arc<AccessControl>,
     audit_logger: Arc<AuditLogger>,
     keys: Arc<RwLock<HashMap<Uuid, ApiKeyMetadata>>>,
+    rate_limiters: Arc<RwLock<HashMap<Uuid, Buckets>>>,
+    /// In-memory-only grant table; deliberately never written to `storage`.
+    grants: Arc<RwLock<HashMap<Uuid, Grant>>>,
+    master_secret: Arc<RwLock<MasterSecret>>,
     rng: SystemRandom,
 }
 
@@ -86,21 +402,51 @@ impl ApiKeyManager {
         access_control: Arc<AccessControl>,
         audit_logger: Arc<AuditLogger>,
     ) -> Self {
+        let rng = SystemRandom::new();
+        let mut initial_secret = Zeroizing::new(vec![0u8; 32]);
+        rng.fill(&mut initial_secret[..]).expect("failed to seed master secret");
+
         Self {
             storage,
             access_control,
             audit_logger,
             keys: Arc::new(RwLock::new(HashMap::new())),
-            rng: SystemRandom::new(),
+            rate_limiters: Arc::new(RwLock::new(HashMap::new())),
+            grants: Arc::new(RwLock::new(HashMap::new())),
+            master_secret: Arc::new(RwLock::new(MasterSecret { epoch: 0, current: initial_secret })),
+            rng,
         }
     }
 
+    /// Rebuild the in-memory `keys` cache from `storage` at startup, fixing
+    /// the gap where a fresh `ApiKeyManager::new` has no knowledge of keys
+    /// persisted by a prior process. Rate limiters and grants are not
+    /// persisted and so always start cold, same as before.
+    pub async fn load(
+        storage: Arc<dyn SecureStorage>,
+        access_control: Arc<AccessControl>,
+        audit_logger: Arc<AuditLogger>,
+    ) -> Result<Self> {
+        let manager = Self::new(storage, access_control, audit_logger);
+
+        let loaded = manager.storage.load_all().await?;
+        {
+            let mut keys = manager.keys.write().unwrap();
+            for metadata in loaded {
+                keys.insert(metadata.id, metadata);
+            }
+        }
+
+        Ok(manager)
+    }
+
     /// Generate a new API key with specified permissions
     pub async fn generate_key(
         &self,
         principal: &Principal,
         name: String,
         permissions: Vec<Permission>,
+        scopes: Vec<ScopedAction>,
         rotation_policy: Option<RotationPolicy>,
     ) -> Result<(Uuid, SecretString)> {
         // Check if principal has permission to create keys
@@ -112,19 +458,21 @@ impl ApiKeyManager {
         // Generate cryptographically secure random key
         let key = self.generate_secure_key()?;
         let key_id = Uuid::new_v4();
-        
+
         // Hash the key for storage
         let key_hash = self.hash_key(key.expose_secret())?;
-        
+
         // Create metadata
         let metadata = ApiKeyMetadata {
             id: key_id,
             name: name.clone(),
+            owner: principal.clone(),
             created_at: Utc::now(),
             expires_at: None,
             last_used: None,
             last_rotated: None,
             permissions,
+            scopes,
             rate_limit: RateLimit {
                 requests_per_minute: 60,
                 requests_per_hour: 1000,
@@ -136,6 +484,9 @@ impl ApiKeyManager {
             rotation_policy: rotation_policy.unwrap_or_default(),
             usage_count: 0,
             failed_attempts: 0,
+            split_key: None,
+            epoch: None,
+            previous_key_hash: None,
         };
 
         // Store encrypted key
@@ -164,19 +515,347 @@ impl ApiKeyManager {
         Ok((key_id, key))
     }
 
-    /// Validate an API key
+    /// Generate a key split into `total_shares` Shamir shares, any
+    /// `threshold` of which reconstruct it via [`reconstruct_key`]. Unlike
+    /// `generate_key`, the plaintext is never handed to `SecureStorage` as a
+    /// whole — only its hash and share metadata are kept, so no single
+    /// operator or HSM holds enough to recover the key on its own.
+    pub async fn generate_split_key(
+        &self,
+        principal: &Principal,
+        name: String,
+        permissions: Vec<Permission>,
+        scopes: Vec<ScopedAction>,
+        threshold: u8,
+        total_shares: u8,
+        rotation_policy: Option<RotationPolicy>,
+    ) -> Result<(Uuid, Vec<KeyShare>)> {
+        // Check if principal has permission to create keys
+        self.access_control.check_permission(
+            principal,
+            &Permission::CreateApiKey,
+        ).await?;
+
+        if threshold < 2 || total_shares < threshold {
+            return Err(anyhow!(
+                "threshold must be at least 2 and no greater than total_shares"
+            ));
+        }
+
+        // Generate cryptographically secure random key
+        let key = self.generate_secure_key()?;
+        let key_id = Uuid::new_v4();
+
+        // Hash the key for storage
+        let key_hash = self.hash_key(key.expose_secret())?;
+
+        let shares = self.split_key(key_id, key.expose_secret().as_bytes(), threshold, total_shares)?;
+
+        // Create metadata
+        let metadata = ApiKeyMetadata {
+            id: key_id,
+            name: name.clone(),
+            owner: principal.clone(),
+            created_at: Utc::now(),
+            expires_at: None,
+            last_used: None,
+            last_rotated: None,
+            permissions,
+            scopes,
+            rate_limit: RateLimit {
+                requests_per_minute: 60,
+                requests_per_hour: 1000,
+                requests_per_day: 10000,
+                burst_size: 10,
+            },
+            key_hash,
+            status: KeyStatus::Active,
+            rotation_policy: rotation_policy.unwrap_or_default(),
+            usage_count: 0,
+            failed_attempts: 0,
+            split_key: Some(SplitKeyInfo { threshold, total_shares }),
+            epoch: None,
+            previous_key_hash: None,
+        };
+
+        // Update in-memory cache only; no SecureStorage backend ever sees the whole key
+        {
+            let mut keys = self.keys.write().unwrap();
+            keys.insert(key_id, metadata.clone());
+        }
+
+        // Audit log
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: principal.clone(),
+            action: "create_split_api_key".to_string(),
+            resource: format!("key:{}", key_id),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::High,
+            details: serde_json::json!({
+                "key_name": name,
+                "permissions": metadata.permissions,
+                "threshold": threshold,
+                "total_shares": total_shares,
+            }),
+        }).await?;
+
+        Ok((key_id, shares))
+    }
+
+    /// Reconstruct a split key's plaintext from `threshold` or more distinct
+    /// shares via Lagrange interpolation at x = 0 over GF(256).
+    pub fn reconstruct_key(shares: &[KeyShare]) -> Result<SecretString> {
+        let first = shares.first().ok_or_else(|| anyhow!("no shares supplied"))?;
+        let threshold = first.threshold as usize;
+
+        let mut distinct = HashMap::new();
+        for share in shares {
+            distinct.entry(share.x).or_insert(share);
+        }
+
+        if distinct.len() < threshold {
+            return Err(anyhow!(
+                "need at least {} distinct shares to reconstruct, got {}",
+                threshold,
+                distinct.len()
+            ));
+        }
+
+        let chosen: Vec<&KeyShare> = distinct.values().take(threshold).copied().collect();
+        let secret_bytes = reconstruct_secret(&chosen)?;
+        let secret = String::from_utf8(secret_bytes)
+            .map_err(|_| anyhow!("reconstructed key is not valid UTF-8"))?;
+
+        Ok(SecretString::new(secret))
+    }
+
+    /// Split `secret` into `total_shares` Shamir shares requiring
+    /// `threshold` to reconstruct: one random degree-`(threshold - 1)`
+    /// polynomial per byte, constant term the secret byte, evaluated at
+    /// x = 1..=`total_shares`.
+    fn split_key(
+        &self,
+        key_id: Uuid,
+        secret: &[u8],
+        threshold: u8,
+        total_shares: u8,
+    ) -> Result<Vec<KeyShare>> {
+        let mut coeffs_per_byte: Vec<Zeroizing<Vec<u8>>> = Vec::with_capacity(secret.len());
+
+        for &secret_byte in secret {
+            let mut coeffs = Zeroizing::new(vec![0u8; threshold as usize]);
+            coeffs[0] = secret_byte;
+            if threshold > 1 {
+                let mut randomness = Zeroizing::new(vec![0u8; (threshold - 1) as usize]);
+                self.rng.fill(&mut randomness)
+                    .map_err(|_| anyhow!("failed to generate share randomness"))?;
+                coeffs[1..].copy_from_slice(&randomness);
+            }
+            coeffs_per_byte.push(coeffs);
+        }
+
+        let mut shares = Vec::with_capacity(total_shares as usize);
+        for x in 1..=total_shares {
+            let mut values = Zeroizing::new(vec![0u8; secret.len()]);
+            for (byte_idx, coeffs) in coeffs_per_byte.iter().enumerate() {
+                values[byte_idx] = gf256::eval_poly(coeffs, x);
+            }
+            shares.push(KeyShare { key_id, x, threshold, total_shares, values });
+        }
+
+        Ok(shares)
+    }
+
+    /// Generate a key whose secret is `HKDF-SHA256(master_secret[epoch],
+    /// salt=key_id, info=name)` rather than raw randomness. A suspected
+    /// master-secret compromise can then rotate every derived key's hash
+    /// atomically via `rotate_master_secret`, instead of re-minting and
+    /// re-distributing each one individually; explicitly random keys from
+    /// `generate_key`/`generate_split_key` are unaffected either way.
+    pub async fn generate_derived_key(
+        &self,
+        principal: &Principal,
+        name: String,
+        permissions: Vec<Permission>,
+        scopes: Vec<ScopedAction>,
+        rotation_policy: Option<RotationPolicy>,
+    ) -> Result<(Uuid, SecretString)> {
+        self.access_control.check_permission(
+            principal,
+            &Permission::CreateApiKey,
+        ).await?;
+
+        let key_id = Uuid::new_v4();
+        let (epoch, key) = {
+            let master = self.master_secret.read().unwrap();
+            (master.epoch, self.derive_key_secret(&master.current, key_id, &name))
+        };
+        let key_hash = self.hash_key(key.expose_secret())?;
+
+        let metadata = ApiKeyMetadata {
+            id: key_id,
+            name: name.clone(),
+            owner: principal.clone(),
+            created_at: Utc::now(),
+            expires_at: None,
+            last_used: None,
+            last_rotated: None,
+            permissions,
+            scopes,
+            rate_limit: RateLimit {
+                requests_per_minute: 60,
+                requests_per_hour: 1000,
+                requests_per_day: 10000,
+                burst_size: 10,
+            },
+            key_hash,
+            status: KeyStatus::Active,
+            rotation_policy: rotation_policy.unwrap_or_default(),
+            usage_count: 0,
+            failed_attempts: 0,
+            split_key: None,
+            epoch: Some(epoch),
+            previous_key_hash: None,
+        };
+
+        self.storage.store_key(key_id, &key, &metadata).await?;
+
+        {
+            let mut keys = self.keys.write().unwrap();
+            keys.insert(key_id, metadata.clone());
+        }
+
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: principal.clone(),
+            action: "create_derived_api_key".to_string(),
+            resource: format!("key:{}", key_id),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::High,
+            details: serde_json::json!({
+                "key_name": name,
+                "permissions": metadata.permissions,
+                "epoch": epoch,
+            }),
+        }).await?;
+
+        Ok((key_id, key))
+    }
+
+    /// Rotate the master secret: bumps the epoch, generates fresh entropy,
+    /// and re-derives every derived key's hash under the new epoch in one
+    /// pass, so a suspected master compromise invalidates all derived
+    /// secrets atomically. Each re-derived key keeps its previous hash as a
+    /// one-rotation grace window (see `validate_key`); explicitly random
+    /// keys (`epoch: None`) are left untouched.
+    pub async fn rotate_master_secret(&self, principal: &Principal) -> Result<()> {
+        self.access_control.check_permission(
+            principal,
+            &Permission::RotateApiKey,
+        ).await?;
+
+        let mut new_secret = Zeroizing::new(vec![0u8; 32]);
+        self.rng.fill(&mut new_secret[..])
+            .map_err(|_| anyhow!("failed to generate new master secret"))?;
+
+        let (new_epoch, new_secret) = {
+            let mut master = self.master_secret.write().unwrap();
+            master.epoch += 1;
+            master.current = new_secret;
+            (master.epoch, master.current.clone())
+        };
+
+        let mut rotated = 0u32;
+        {
+            let mut keys = self.keys.write().unwrap();
+            for metadata in keys.values_mut() {
+                if metadata.epoch.is_none() {
+                    continue;
+                }
+                let derived = self.derive_key_secret(&new_secret, metadata.id, &metadata.name);
+                let new_hash = self.hash_key(derived.expose_secret())?;
+                metadata.previous_key_hash = Some(metadata.key_hash.clone());
+                metadata.key_hash = new_hash;
+                metadata.epoch = Some(new_epoch);
+                rotated += 1;
+            }
+        }
+
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: principal.clone(),
+            action: "rotate_master_secret".to_string(),
+            resource: "master_secret".to_string(),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::Critical,
+            details: serde_json::json!({
+                "new_epoch": new_epoch,
+                "keys_rotated": rotated,
+            }),
+        }).await?;
+
+        Ok(())
+    }
+
+    /// Validate an API key. When `requested_action` is set and the key
+    /// carries scopes, the action must be granted by at least one scope
+    /// (prefix-wildcard match via [`ScopedAction::grants`]); keys with no
+    /// scopes are unaffected, so this is opt-in alongside the flat
+    /// `Permission` model.
     pub async fn validate_key(
         &self,
         key: &SecretString,
+        requested_action: Option<&str>,
     ) -> Result<(Uuid, Vec<Permission>)> {
         let key_hash = self.hash_key(key.expose_secret())?;
-        
-        // Find key by hash (constant time comparison)
+
+        // Find key by hash (constant time comparison), falling back to a
+        // derived key's previous-epoch hash during its one-rotation grace
+        // window so in-flight clients aren't locked out mid-rollover.
         let keys = self.keys.read().unwrap();
+        let mut used_previous_epoch = false;
         let metadata = keys.values()
-            .find(|m| constant_time_eq(m.key_hash.as_bytes(), key_hash.as_bytes()))
+            .find(|m| {
+                if constant_time_eq(m.key_hash.as_bytes(), key_hash.as_bytes()) {
+                    return true;
+                }
+                if let Some(previous) = &m.previous_key_hash {
+                    if constant_time_eq(previous.as_bytes(), key_hash.as_bytes()) {
+                        used_previous_epoch = true;
+                        return true;
+                    }
+                }
+                false
+            })
             .ok_or_else(|| anyhow!("Invalid API key"))?;
 
+        if used_previous_epoch {
+            self.audit_logger.log(AuditEvent {
+                timestamp: Utc::now(),
+                principal: Principal::System,
+                action: "derived_key_grace_window_used".to_string(),
+                resource: format!("key:{}", metadata.id),
+                outcome: "success".to_string(),
+                security_level: SecurityLevel::Critical,
+                details: serde_json::json!({ "epoch": metadata.epoch }),
+            }).await?;
+        }
+
+        // Enforce the key's declared rate limit before anything else consumes quota
+        if !self.check_rate_limit(metadata.id, &metadata.rate_limit) {
+            self.audit_logger.log(AuditEvent {
+                timestamp: Utc::now(),
+                principal: Principal::System,
+                action: "rate_limit_exceeded".to_string(),
+                resource: format!("key:{}", metadata.id),
+                outcome: "blocked".to_string(),
+                security_level: SecurityLevel::Medium,
+                details: serde_json::json!({}),
+            }).await?;
+            return Err(anyhow!("API key has exceeded its rate limit"));
+        }
+
         // Check key status
         match &metadata.status {
             KeyStatus::Active => {},
@@ -211,6 +890,13 @@ impl ApiKeyManager {
             self.mark_for_rotation(metadata.id).await?;
         }
 
+        // Check scope, if the caller asked for a specific action
+        if let Some(action) = requested_action {
+            if !metadata.scopes.is_empty() && !metadata.scopes.iter().any(|scope| scope.grants(action)) {
+                return Err(anyhow!("API key scope does not grant action '{}'", action));
+            }
+        }
+
         // Update usage statistics
         self.update_usage(metadata.id).await?;
 
@@ -299,6 +985,12 @@ impl ApiKeyManager {
         // Remove from secure storage
         self.storage.delete_key(key_id).await?;
 
+        // A revoked key takes all of its delegated grants down with it
+        {
+            let mut grants = self.grants.write().unwrap();
+            grants.retain(|_, grant| grant.key_id != key_id);
+        }
+
         // Audit log with HIGH severity
         self.audit_logger.log(AuditEvent {
             timestamp: Utc::now(),
@@ -315,6 +1007,140 @@ impl ApiKeyManager {
         Ok(())
     }
 
+    /// Delegate scoped, time-boxed use of `key_id` to `grantee` without
+    /// sharing its secret. Only `key_id`'s own owner may grant access to
+    /// it, and the granted permissions must be a subset of the key's own.
+    /// Returns an opaque grant handle for `validate_grant`/`revoke_grant`.
+    pub async fn grant_key(
+        &self,
+        owner: &Principal,
+        key_id: Uuid,
+        grantee: Principal,
+        permissions_subset: Vec<Permission>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Uuid> {
+        let metadata = {
+            let keys = self.keys.read().unwrap();
+            keys.get(&key_id).cloned().ok_or_else(|| anyhow!("Key not found"))?
+        };
+
+        if metadata.owner != *owner {
+            return Err(anyhow!("only the key's owner may grant access to it"));
+        }
+
+        if !permissions_subset.iter().all(|p| metadata.permissions.contains(p)) {
+            return Err(anyhow!("a grant cannot exceed the permissions of the key it delegates"));
+        }
+
+        let grant_id = Uuid::new_v4();
+        let grant = Grant {
+            id: grant_id,
+            key_id,
+            owner: owner.clone(),
+            grantee,
+            permissions: permissions_subset,
+            created_at: Utc::now(),
+            expires_at,
+        };
+
+        {
+            let mut grants = self.grants.write().unwrap();
+            grants.insert(grant_id, grant.clone());
+        }
+
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: owner.clone(),
+            action: "grant_key_access".to_string(),
+            resource: format!("key:{}", key_id),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::Medium,
+            details: serde_json::json!({
+                "grant_id": grant_id,
+                "permissions": grant.permissions,
+                "expires_at": expires_at,
+            }),
+        }).await?;
+
+        Ok(grant_id)
+    }
+
+    /// Resolve a grant handle to the underlying key's id and permissions
+    /// (narrowed to the grant's subset), applying the same rate limiting as
+    /// `validate_key` plus the grant's own expiry. A grant whose parent key
+    /// is gone or inactive, or that has itself expired, is removed and
+    /// rejected.
+    pub async fn validate_grant(&self, grant_id: Uuid) -> Result<(Uuid, Vec<Permission>)> {
+        let grant = {
+            let grants = self.grants.read().unwrap();
+            grants.get(&grant_id).cloned()
+        };
+        let grant = grant.ok_or_else(|| anyhow!("Grant not found or has been revoked"))?;
+
+        if Utc::now() > grant.expires_at {
+            let mut grants = self.grants.write().unwrap();
+            grants.remove(&grant_id);
+            return Err(anyhow!("Grant has expired"));
+        }
+
+        let metadata = {
+            let keys = self.keys.read().unwrap();
+            keys.get(&grant.key_id).cloned()
+        };
+        let metadata = match metadata {
+            Some(m) if m.status == KeyStatus::Active => m,
+            _ => {
+                let mut grants = self.grants.write().unwrap();
+                grants.remove(&grant_id);
+                return Err(anyhow!("Underlying key is no longer active"));
+            }
+        };
+
+        if !self.check_rate_limit(metadata.id, &metadata.rate_limit) {
+            return Err(anyhow!("API key has exceeded its rate limit"));
+        }
+
+        self.update_usage(metadata.id).await?;
+
+        let effective_permissions: Vec<Permission> = grant.permissions.iter()
+            .filter(|p| metadata.permissions.contains(p))
+            .cloned()
+            .collect();
+
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: grant.grantee.clone(),
+            action: "use_key_grant".to_string(),
+            resource: format!("key:{}", grant.key_id),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::Low,
+            details: serde_json::json!({ "grant_id": grant_id }),
+        }).await?;
+
+        Ok((metadata.id, effective_permissions))
+    }
+
+    /// Revoke a grant before its `expires_at`.
+    pub async fn revoke_grant(&self, revoker: &Principal, grant_id: Uuid) -> Result<()> {
+        let grant = {
+            let mut grants = self.grants.write().unwrap();
+            grants.remove(&grant_id)
+        };
+        let grant = grant.ok_or_else(|| anyhow!("Grant not found"))?;
+
+        self.audit_logger.log(AuditEvent {
+            timestamp: Utc::now(),
+            principal: revoker.clone(),
+            action: "revoke_key_grant".to_string(),
+            resource: format!("key:{}", grant.key_id),
+            outcome: "success".to_string(),
+            security_level: SecurityLevel::Medium,
+            details: serde_json::json!({ "grant_id": grant_id }),
+        }).await?;
+
+        Ok(())
+    }
+
     /// Mark a key as compromised (immediate revocation + security alert)
     pub async fn mark_compromised(
         &self,
@@ -363,8 +1189,28 @@ impl ApiKeyManager {
         Ok(())
     }
 
+    /// Remaining rate-limit budget for `key_id`, or `None` if it hasn't been
+    /// validated (and thus rate-limited) yet.
+    pub fn remaining_quota(&self, key_id: Uuid) -> Option<RemainingQuota> {
+        let limiters = self.rate_limiters.read().unwrap();
+        limiters.get(&key_id).map(|buckets| RemainingQuota {
+            minute: buckets.minute_tokens.floor().max(0.0) as u32,
+            hour: buckets.hour_tokens.floor().max(0.0) as u32,
+            day: buckets.day_tokens.floor().max(0.0) as u32,
+        })
+    }
+
     // Private helper methods
 
+    /// Refill and charge `key_id`'s token buckets against `rate_limit`,
+    /// creating them on first use. Returns `false` once any window (minute,
+    /// hour, or day) is exhausted.
+    fn check_rate_limit(&self, key_id: Uuid, rate_limit: &RateLimit) -> bool {
+        let mut limiters = self.rate_limiters.write().unwrap();
+        let buckets = limiters.entry(key_id).or_insert_with(|| Buckets::new(rate_limit));
+        buckets.try_consume(rate_limit)
+    }
+
     fn generate_secure_key(&self) -> Result<SecretString> {
         let mut key_bytes = Zeroizing::new([0u8; 32]);
         self.rng.fill(&mut *key_bytes)
@@ -375,6 +1221,15 @@ impl ApiKeyManager {
         Ok(SecretString::new(key_string))
     }
 
+    /// Derive a key's secret as `HKDF-SHA256(master_secret, salt=key_id,
+    /// info=name)`, encoded the same base64-URL-safe way as a random key so
+    /// derived and random secrets are indistinguishable in shape.
+    fn derive_key_secret(&self, master_secret: &[u8], key_id: Uuid, name: &str) -> SecretString {
+        let derived = hkdf::derive(master_secret, key_id.as_bytes(), name.as_bytes(), 32);
+        let key_string = base64::encode_config(&*derived, base64::URL_SAFE_NO_PAD);
+        SecretString::new(key_string)
+    }
+
     fn hash_key(&self, key: &str) -> Result<String> {
         let mut hasher = Sha256::new();
         hasher.update(key.as_bytes());
@@ -456,13 +1311,47 @@ mod tests {
             &principal,
             "Test Key".to_string(),
             vec![Permission::ReadData],
+            vec![],
             None,
         ).await.unwrap();
-        
+
         assert!(!secret.expose_secret().is_empty());
         assert_ne!(key_id, Uuid::nil());
     }
 
+    fn test_rate_limit() -> RateLimit {
+        RateLimit {
+            requests_per_minute: 2,
+            requests_per_hour: 1000,
+            requests_per_day: 10000,
+            burst_size: 0,
+        }
+    }
+
+    #[test]
+    fn test_buckets_deny_once_minute_quota_is_exhausted() {
+        let rate_limit = test_rate_limit();
+        let mut buckets = Buckets::new(&rate_limit);
+
+        assert!(buckets.try_consume(&rate_limit));
+        assert!(buckets.try_consume(&rate_limit));
+        assert!(!buckets.try_consume(&rate_limit));
+    }
+
+    #[test]
+    fn test_buckets_refill_over_time() {
+        let rate_limit = test_rate_limit();
+        let mut buckets = Buckets::new(&rate_limit);
+
+        assert!(buckets.try_consume(&rate_limit));
+        assert!(buckets.try_consume(&rate_limit));
+        assert!(!buckets.try_consume(&rate_limit));
+
+        // Simulate 30 seconds of elapsed time (half the minute window).
+        buckets.last_refill -= std::time::Duration::from_secs(30);
+        assert!(buckets.try_consume(&rate_limit));
+    }
+
     #[tokio::test]
     async fn test_key_validation() {
         let storage = Arc::new(MockSecureStorage::new());
@@ -476,12 +1365,322 @@ mod tests {
             &principal,
             "Test Key".to_string(),
             vec![Permission::ReadData],
+            vec![],
             None,
         ).await.unwrap();
-        
+
         // Validate the key
-        let (validated_id, permissions) = manager.validate_key(&secret).await.unwrap();
+        let (validated_id, permissions) = manager.validate_key(&secret, None).await.unwrap();
         assert_eq!(validated_id, key_id);
         assert_eq!(permissions, vec![Permission::ReadData]);
     }
+
+    fn sample_share(key_id: Uuid, x: u8, threshold: u8, total_shares: u8, values: Vec<u8>) -> KeyShare {
+        KeyShare { key_id, x, threshold, total_shares, values: Zeroizing::new(values) }
+    }
+
+    #[test]
+    fn test_gf256_mul_and_div_are_inverses() {
+        for a in 1..=255u8 {
+            for b in 1..=255u8 {
+                assert_eq!(gf256::div(gf256::mul(a, b), b), a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_and_reconstruct_key_round_trips() {
+        let manager_storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(manager_storage, access_control, audit_logger);
+
+        let secret = b"super-secret-api-key-material!!";
+        let key_id = Uuid::new_v4();
+        let shares = manager.split_key(key_id, secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        let chosen: Vec<&KeyShare> = shares.iter().skip(1).take(3).collect();
+        let reconstructed = reconstruct_secret(&chosen).unwrap();
+        assert_eq!(reconstructed, secret);
+    }
+
+    #[test]
+    fn test_reconstruct_key_requires_threshold_distinct_shares() {
+        let key_id = Uuid::new_v4();
+        let shares = vec![
+            sample_share(key_id, 1, 3, 5, vec![42]),
+            sample_share(key_id, 2, 3, 5, vec![7]),
+        ];
+
+        let result = ApiKeyManager::reconstruct_key(&shares);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scoped_action_wildcard_matching() {
+        assert!(ScopedAction::new("documents.*").grants("documents.add"));
+        assert!(ScopedAction::new("documents.*").grants("documents.delete.permanent"));
+        assert!(ScopedAction::new("*").grants("anything.at.all"));
+        assert!(ScopedAction::new("documents.add").grants("documents.add"));
+        assert!(!ScopedAction::new("documents.add").grants("documents.delete"));
+        assert!(!ScopedAction::new("documents.add").grants("documents"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_key_enforces_scope() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let principal = Principal::User("test_user".to_string());
+        let (_key_id, secret) = manager.generate_key(
+            &principal,
+            "Scoped Key".to_string(),
+            vec![Permission::ReadData],
+            vec![ScopedAction::new("documents.*")],
+            None,
+        ).await.unwrap();
+
+        assert!(manager.validate_key(&secret, Some("documents.add")).await.is_ok());
+        assert!(manager.validate_key(&secret, Some("billing.charge")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grant_key_resolves_to_intersected_permissions() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let owner = Principal::User("owner".to_string());
+        let (key_id, _secret) = manager.generate_key(
+            &owner,
+            "Owned Key".to_string(),
+            vec![Permission::ReadData, Permission::WriteData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        let grantee = Principal::User("delegate".to_string());
+        let grant_id = manager.grant_key(
+            &owner,
+            key_id,
+            grantee,
+            vec![Permission::ReadData],
+            Utc::now() + Duration::hours(1),
+        ).await.unwrap();
+
+        let (validated_id, permissions) = manager.validate_grant(grant_id).await.unwrap();
+        assert_eq!(validated_id, key_id);
+        assert_eq!(permissions, vec![Permission::ReadData]);
+    }
+
+    #[tokio::test]
+    async fn test_grant_key_rejects_non_owner_and_permission_escalation() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let owner = Principal::User("owner".to_string());
+        let (key_id, _secret) = manager.generate_key(
+            &owner,
+            "Owned Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        let not_owner = Principal::User("someone_else".to_string());
+        let grantee = Principal::User("delegate".to_string());
+
+        let result = manager.grant_key(
+            &not_owner,
+            key_id,
+            grantee.clone(),
+            vec![Permission::ReadData],
+            Utc::now() + Duration::hours(1),
+        ).await;
+        assert!(result.is_err());
+
+        let result = manager.grant_key(
+            &owner,
+            key_id,
+            grantee,
+            vec![Permission::WriteData],
+            Utc::now() + Duration::hours(1),
+        ).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_grant_is_rejected() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let owner = Principal::User("owner".to_string());
+        let (key_id, _secret) = manager.generate_key(
+            &owner,
+            "Owned Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        let grantee = Principal::User("delegate".to_string());
+        let grant_id = manager.grant_key(
+            &owner,
+            key_id,
+            grantee,
+            vec![Permission::ReadData],
+            Utc::now() - Duration::seconds(1),
+        ).await.unwrap();
+
+        assert!(manager.validate_grant(grant_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revoking_the_key_invalidates_its_grants() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let owner = Principal::User("owner".to_string());
+        let (key_id, _secret) = manager.generate_key(
+            &owner,
+            "Owned Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        let grantee = Principal::User("delegate".to_string());
+        let grant_id = manager.grant_key(
+            &owner,
+            key_id,
+            grantee,
+            vec![Permission::ReadData],
+            Utc::now() + Duration::hours(1),
+        ).await.unwrap();
+
+        manager.revoke_key(&owner, key_id, "no longer needed").await.unwrap();
+
+        assert!(manager.validate_grant(grant_id).await.is_err());
+    }
+
+    #[test]
+    fn test_hkdf_is_deterministic_and_salt_sensitive() {
+        let ikm = b"master-secret-material";
+        let a = hkdf::derive(ikm, b"salt-a", b"info", 32);
+        let b = hkdf::derive(ikm, b"salt-a", b"info", 32);
+        let c = hkdf::derive(ikm, b"salt-b", b"info", 32);
+
+        assert_eq!(&*a, &*b);
+        assert_ne!(&*a, &*c);
+        assert_eq!(a.len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_generate_derived_key_records_current_epoch() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let principal = Principal::User("test_user".to_string());
+        let (key_id, secret) = manager.generate_derived_key(
+            &principal,
+            "Derived Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        let (validated_id, _) = manager.validate_key(&secret, None).await.unwrap();
+        assert_eq!(validated_id, key_id);
+
+        let keys = manager.keys.read().unwrap();
+        assert_eq!(keys.get(&key_id).unwrap().epoch, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_master_secret_invalidates_old_secret_after_grace_window() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let principal = Principal::User("test_user".to_string());
+        let (_key_id, old_secret) = manager.generate_derived_key(
+            &principal,
+            "Derived Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        manager.rotate_master_secret(&principal).await.unwrap();
+
+        // Old secret still validates once, inside the one-rotation grace window...
+        assert!(manager.validate_key(&old_secret, None).await.is_ok());
+
+        // ...but a second rotation drops it for good.
+        manager.rotate_master_secret(&principal).await.unwrap();
+        assert!(manager.validate_key(&old_secret, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_master_secret_leaves_random_keys_untouched() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+        let manager = ApiKeyManager::new(storage, access_control, audit_logger);
+
+        let principal = Principal::User("test_user".to_string());
+        let (key_id, secret) = manager.generate_key(
+            &principal,
+            "Random Key".to_string(),
+            vec![Permission::ReadData],
+            vec![],
+            None,
+        ).await.unwrap();
+
+        manager.rotate_master_secret(&principal).await.unwrap();
+
+        let (validated_id, _) = manager.validate_key(&secret, None).await.unwrap();
+        assert_eq!(validated_id, key_id);
+    }
+
+    #[tokio::test]
+    async fn test_load_repopulates_cache_from_storage() {
+        let storage = Arc::new(MockSecureStorage::new());
+        let access_control = Arc::new(MockAccessControl::new());
+        let audit_logger = Arc::new(MockAuditLogger::new());
+
+        let principal = Principal::User("test_user".to_string());
+        let key_id = {
+            let manager = ApiKeyManager::new(storage.clone(), access_control.clone(), audit_logger.clone());
+            let (key_id, _secret) = manager.generate_key(
+                &principal,
+                "Persisted Key".to_string(),
+                vec![Permission::ReadData],
+                vec![],
+                None,
+            ).await.unwrap();
+            key_id
+        };
+
+        // A fresh manager over the same storage starts with an empty cache...
+        let cold = ApiKeyManager::new(storage.clone(), access_control.clone(), audit_logger.clone());
+        assert!(cold.keys.read().unwrap().is_empty());
+
+        // ...but `load` rebuilds it from what was persisted.
+        let warm = ApiKeyManager::load(storage, access_control, audit_logger).await.unwrap();
+        assert!(warm.keys.read().unwrap().contains_key(&key_id));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,205 @@
+//! Encrypted-at-rest backing storage for `ApiKeyManager`'s keys.
+
+use anyhow::{Result, anyhow};
+use secrecy::{ExposeSecret, SecretString};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+use ring::aead::{self, LessSafeKey, UnboundKey, Nonce, Aad, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::key_manager::ApiKeyMetadata;
+
+/// Pluggable backing store for API key secrets and their metadata, mirroring
+/// the `Arc<dyn AuditStorage>`/`Arc<dyn RoleStore>` pattern used elsewhere in
+/// this crate. Never handed the plaintext key back out once stored; callers
+/// that need to re-verify a key compare against `ApiKeyMetadata::key_hash`
+/// instead.
+#[async_trait::async_trait]
+pub trait SecureStorage: Send + Sync {
+    /// Persist `key`'s encrypted secret and metadata, overwriting any
+    /// existing entry for the same `key_id` (used by `rotate_key`).
+    async fn store_key(&self, key_id: Uuid, key: &SecretString, metadata: &ApiKeyMetadata) -> Result<()>;
+
+    /// Remove a key's secret and metadata entirely; called by
+    /// `revoke_key`/`mark_compromised` so no residue survives.
+    async fn delete_key(&self, key_id: Uuid) -> Result<()>;
+
+    /// Load every persisted key's metadata, for `ApiKeyManager::load` to
+    /// repopulate its in-memory cache from at startup. Never returns
+    /// plaintext secrets.
+    async fn load_all(&self) -> Result<Vec<ApiKeyMetadata>>;
+}
+
+/// In-memory `SecureStorage` for tests; never touches disk.
+pub struct MockSecureStorage {
+    entries: RwLock<HashMap<Uuid, ApiKeyMetadata>>,
+}
+
+impl MockSecureStorage {
+    pub fn new() -> Self {
+        Self { entries: RwLock::new(HashMap::new()) }
+    }
+}
+
+impl Default for MockSecureStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl SecureStorage for MockSecureStorage {
+    async fn store_key(&self, key_id: Uuid, _key: &SecretString, metadata: &ApiKeyMetadata) -> Result<()> {
+        self.entries.write().unwrap().insert(key_id, metadata.clone());
+        Ok(())
+    }
+
+    async fn delete_key(&self, key_id: Uuid) -> Result<()> {
+        self.entries.write().unwrap().remove(&key_id);
+        Ok(())
+    }
+
+    async fn load_all(&self) -> Result<Vec<ApiKeyMetadata>> {
+        Ok(self.entries.read().unwrap().values().cloned().collect())
+    }
+}
+
+/// Production `SecureStorage` backed by SQLite, split across a `key_entry`
+/// table (queryable metadata columns, plus the full metadata as JSON for
+/// exact round-tripping) and a `key_blob` table holding only the
+/// AEAD-encrypted secret, so metadata queries never touch ciphertext.
+/// Secrets are sealed with an envelope key supplied at construction, under a
+/// fresh random nonce per write.
+pub struct SqliteSecureStorage {
+    pool: sqlx::SqlitePool,
+    envelope_key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl SqliteSecureStorage {
+    pub async fn new(database_url: &str, envelope_key: [u8; 32]) -> Result<Self> {
+        let pool = sqlx::SqlitePool::connect(database_url).await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS key_entry (
+                uuid TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                rotation_policy TEXT NOT NULL,
+                usage_count INTEGER NOT NULL,
+                metadata TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS key_blob (
+                entry_id TEXT PRIMARY KEY REFERENCES key_entry(uuid),
+                nonce BLOB NOT NULL,
+                ciphertext BLOB NOT NULL
+            );
+            "#
+        )
+        .execute(&pool)
+        .await?;
+
+        let unbound = UnboundKey::new(&aead::AES_256_GCM, &envelope_key)
+            .map_err(|_| anyhow!("invalid envelope key length"))?;
+
+        Ok(Self {
+            pool,
+            envelope_key: LessSafeKey::new(unbound),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Seal `plaintext` under a fresh random nonce, returning `(nonce,
+    /// ciphertext_with_tag)`.
+    fn seal(&self, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce_bytes).map_err(|_| anyhow!("failed to generate nonce"))?;
+        let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+
+        let mut in_out = plaintext.to_vec();
+        self.envelope_key
+            .seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| anyhow!("failed to seal key secret"))?;
+
+        Ok((nonce_bytes.to_vec(), in_out))
+    }
+}
+
+#[async_trait::async_trait]
+impl SecureStorage for SqliteSecureStorage {
+    async fn store_key(&self, key_id: Uuid, key: &SecretString, metadata: &ApiKeyMetadata) -> Result<()> {
+        let (nonce, ciphertext) = self.seal(key.expose_secret().as_bytes())?;
+        let metadata_json = serde_json::to_string(metadata)?;
+        let rotation_policy_json = serde_json::to_string(&metadata.rotation_policy)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO key_entry (uuid, name, status, created_at, rotation_policy, usage_count, metadata)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(uuid) DO UPDATE SET
+                name = excluded.name,
+                status = excluded.status,
+                rotation_policy = excluded.rotation_policy,
+                usage_count = excluded.usage_count,
+                metadata = excluded.metadata
+            "#
+        )
+        .bind(key_id.to_string())
+        .bind(&metadata.name)
+        .bind(format!("{:?}", metadata.status))
+        .bind(metadata.created_at.to_rfc3339())
+        .bind(rotation_policy_json)
+        .bind(metadata.usage_count as i64)
+        .bind(metadata_json)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO key_blob (entry_id, nonce, ciphertext) VALUES (?, ?, ?)
+            ON CONFLICT(entry_id) DO UPDATE SET nonce = excluded.nonce, ciphertext = excluded.ciphertext
+            "#
+        )
+        .bind(key_id.to_string())
+        .bind(nonce)
+        .bind(ciphertext)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete_key(&self, key_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM key_blob WHERE entry_id = ?")
+            .bind(key_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM key_entry WHERE uuid = ?")
+            .bind(key_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn load_all(&self) -> Result<Vec<ApiKeyMetadata>> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT metadata FROM key_entry")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|(metadata_json,)| serde_json::from_str(&metadata_json).map_err(|e| anyhow!(e)))
+            .collect()
+    }
+}
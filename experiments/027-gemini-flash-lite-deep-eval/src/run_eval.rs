@@ -32,6 +32,16 @@ struct Args {
     /// Starting batch number (1-40)
     #[arg(long, default_value = "1")]
     start_batch: usize,
+
+    /// After evaluating, diff against the most recent prior results found in
+    /// this directory (per-category deltas, pass/fail flips, timing changes)
+    #[arg(long)]
+    baseline: Option<String>,
+
+    /// Instead of running an evaluation, aggregate every batch already in
+    /// `results/` into one top-level report and exit
+    #[arg(long)]
+    summary: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +51,10 @@ struct Question {
     expected_tool_calls: Vec<String>,
     category: String,
     context: String,
+    /// When true, `expected_tool_calls` must appear in `actual_tools` in the
+    /// same relative order, not just be present somewhere.
+    #[serde(default)]
+    ordered: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,18 +65,24 @@ struct Batch {
     questions: Vec<Question>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct EvalResult {
     question_id: String,
     question: String,
+    category: String,
     expected_tools: Vec<String>,
     actual_tools: Vec<String>,
+    false_positive_tools: Vec<String>,
+    precision: f64,
+    recall: f64,
+    f1: f64,
+    order_correct: Option<bool>,
     success: bool,
     response_time_ms: u64,
     error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct BatchResult {
     batch_id: String,
     model: String,
@@ -70,11 +90,40 @@ struct BatchResult {
     successful: usize,
     failed: usize,
     success_rate: f64,
+    mean_f1: f64,
+    total_false_positives: usize,
     results: Vec<EvalResult>,
     started_at: String,
     completed_at: String,
 }
 
+/// Precision/recall/F1 of `actual` against `expected`, treating both as
+/// sets (duplicates and order don't matter here; see `order_correct` for
+/// ordering). An empty `expected` scores 1.0 across the board when `actual`
+/// is also empty (the question expected no tool calls and got none), else
+/// 0.0 (every actual call is a false positive).
+fn score_tool_sets(expected: &[String], actual: &[String]) -> (f64, f64, f64, Vec<String>) {
+    let false_positives: Vec<String> = actual.iter().filter(|tool| !expected.contains(tool)).cloned().collect();
+
+    if expected.is_empty() {
+        return if actual.is_empty() { (1.0, 1.0, 1.0, false_positives) } else { (0.0, 1.0, 0.0, false_positives) };
+    }
+
+    let true_positives = expected.iter().filter(|tool| actual.contains(tool)).count();
+    let precision = if actual.is_empty() { 0.0 } else { true_positives as f64 / actual.len() as f64 };
+    let recall = true_positives as f64 / expected.len() as f64;
+    let f1 = if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) };
+
+    (precision, recall, f1, false_positives)
+}
+
+/// Whether every tool in `expected` appears in `actual` in the same
+/// relative order (extra, unexpected calls interleaved are fine).
+fn tool_order_correct(expected: &[String], actual: &[String]) -> bool {
+    let mut positions = actual.iter();
+    expected.iter().all(|tool| positions.any(|candidate| candidate == tool))
+}
+
 async fn evaluate_question(question: &Question, model: &str, client: &GeminiClient) -> EvalResult {
     let start = std::time::Instant::now();
     
@@ -111,22 +160,26 @@ async fn evaluate_question(question: &Question, model: &str, client: &GeminiClie
                 }
             }
 
-            // Check if actual tools match expected tools
-            let success = if question.expected_tool_calls.is_empty() {
-                // Non-tool question - success if no function calls
-                actual_tools.is_empty()
-            } else {
-                // Tool question - check if all expected tools were called
-                question.expected_tool_calls.iter().all(|expected| {
-                    actual_tools.contains(expected)
-                })
-            };
+            let (precision, recall, f1, false_positive_tools) = score_tool_sets(&question.expected_tool_calls, &actual_tools);
+            let order_correct = question.ordered.then(|| tool_order_correct(&question.expected_tool_calls, &actual_tools));
+
+            // All expected tools present, no order violation, and no
+            // spurious calls is still the bar for a clean pass; `precision`/
+            // `recall`/`f1` carry the partial-credit signal for everything
+            // short of that.
+            let success = recall == 1.0 && false_positive_tools.is_empty() && order_correct != Some(false);
 
             EvalResult {
                 question_id: question.id.clone(),
                 question: question.question.clone(),
+                category: question.category.clone(),
                 expected_tools: question.expected_tool_calls.clone(),
                 actual_tools,
+                false_positive_tools,
+                precision,
+                recall,
+                f1,
+                order_correct,
                 success,
                 response_time_ms: start.elapsed().as_millis() as u64,
                 error: None,
@@ -136,8 +189,14 @@ async fn evaluate_question(question: &Question, model: &str, client: &GeminiClie
             EvalResult {
                 question_id: question.id.clone(),
                 question: question.question.clone(),
+                category: question.category.clone(),
                 expected_tools: question.expected_tool_calls.clone(),
                 actual_tools: vec![],
+                false_positive_tools: vec![],
+                precision: 0.0,
+                recall: 0.0,
+                f1: 0.0,
+                order_correct: None,
                 success: false,
                 response_time_ms: start.elapsed().as_millis() as u64,
                 error: Some(e.to_string()),
@@ -185,7 +244,9 @@ async fn process_batch(batch_path: &Path, model: &str, delay_secs: u64) -> Resul
     let successful = results.iter().filter(|r| r.success).count();
     let failed = results.len() - successful;
     let success_rate = successful as f64 / results.len() as f64;
-    
+    let mean_f1 = results.iter().map(|r| r.f1).sum::<f64>() / results.len() as f64;
+    let total_false_positives = results.iter().map(|r| r.false_positive_tools.len()).sum();
+
     Ok(BatchResult {
         batch_id: batch.batch_id,
         model: model.to_string(),
@@ -193,12 +254,251 @@ async fn process_batch(batch_path: &Path, model: &str, delay_secs: u64) -> Resul
         successful,
         failed,
         success_rate,
+        mean_f1,
+        total_false_positives,
         results,
         started_at,
         completed_at: Utc::now().to_rfc3339(),
     })
 }
 
+#[derive(Debug, Serialize)]
+struct CategoryDelta {
+    category: String,
+    baseline_rate: f64,
+    current_rate: f64,
+    delta: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct RegressionReport {
+    batch_id: String,
+    baseline_batch_id: String,
+    regressions: Vec<String>,
+    fixes: Vec<String>,
+    category_deltas: Vec<CategoryDelta>,
+    baseline_mean_response_time_ms: f64,
+    current_mean_response_time_ms: f64,
+    baseline_p95_response_time_ms: u64,
+    current_p95_response_time_ms: u64,
+}
+
+fn mean_ms(results: &[EvalResult]) -> f64 {
+    if results.is_empty() {
+        return 0.0;
+    }
+    results.iter().map(|r| r.response_time_ms as f64).sum::<f64>() / results.len() as f64
+}
+
+fn percentile_ms(results: &[EvalResult], p: f64) -> u64 {
+    if results.is_empty() {
+        return 0;
+    }
+    let mut times: Vec<u64> = results.iter().map(|r| r.response_time_ms).collect();
+    times.sort_unstable();
+    let index = ((times.len() - 1) as f64 * p).round() as usize;
+    times[index]
+}
+
+fn category_success_rates(results: &[EvalResult]) -> Vec<(String, f64)> {
+    let mut by_category: std::collections::BTreeMap<&str, (usize, usize)> = std::collections::BTreeMap::new();
+    for result in results {
+        let entry = by_category.entry(&result.category).or_insert((0, 0));
+        entry.0 += 1;
+        if result.success {
+            entry.1 += 1;
+        }
+    }
+    by_category
+        .into_iter()
+        .map(|(category, (total, successful))| (category.to_string(), successful as f64 / total as f64))
+        .collect()
+}
+
+/// Finds the most recently written `results_batch_{batch_num:03}_*.json` in
+/// `dir`, since a baseline directory may hold several runs over time.
+fn find_latest_batch_result(dir: &Path, batch_num: usize) -> Result<Option<BatchResult>> {
+    let prefix = format!("results_batch_{:03}_", batch_num);
+    let mut candidates: Vec<std::path::PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".json"))
+        })
+        .collect();
+
+    // Timestamps are unix seconds of equal width for decades, so the latest
+    // file sorts last lexicographically too.
+    candidates.sort();
+
+    let Some(latest) = candidates.pop() else {
+        return Ok(None);
+    };
+
+    let content = fs::read_to_string(latest)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// Diffs `current` against the most recent prior run for the same batch in
+/// `baseline_dir`, or prints a note and does nothing if there's no baseline
+/// to compare against yet.
+fn report_regressions(baseline_dir: &Path, current: &BatchResult, batch_num: usize) -> Result<()> {
+    let Some(baseline) = find_latest_batch_result(baseline_dir, batch_num)? else {
+        println!("  No baseline found for batch {:03} in {:?}, skipping comparison", batch_num, baseline_dir);
+        return Ok(());
+    };
+
+    let baseline_by_id: std::collections::HashMap<&str, bool> =
+        baseline.results.iter().map(|r| (r.question_id.as_str(), r.success)).collect();
+
+    let mut regressions = Vec::new();
+    let mut fixes = Vec::new();
+    for result in &current.results {
+        if let Some(&was_success) = baseline_by_id.get(result.question_id.as_str()) {
+            if was_success && !result.success {
+                regressions.push(result.question_id.clone());
+            } else if !was_success && result.success {
+                fixes.push(result.question_id.clone());
+            }
+        }
+    }
+
+    let baseline_rates: std::collections::HashMap<String, f64> = category_success_rates(&baseline.results).into_iter().collect();
+    let current_rates = category_success_rates(&current.results);
+    let category_deltas: Vec<CategoryDelta> = current_rates
+        .into_iter()
+        .map(|(category, current_rate)| {
+            let baseline_rate = *baseline_rates.get(&category).unwrap_or(&0.0);
+            CategoryDelta { category, baseline_rate, current_rate, delta: current_rate - baseline_rate }
+        })
+        .collect();
+
+    let report = RegressionReport {
+        batch_id: current.batch_id.clone(),
+        baseline_batch_id: baseline.batch_id.clone(),
+        regressions,
+        fixes,
+        category_deltas,
+        baseline_mean_response_time_ms: mean_ms(&baseline.results),
+        current_mean_response_time_ms: mean_ms(&current.results),
+        baseline_p95_response_time_ms: percentile_ms(&baseline.results, 0.95),
+        current_p95_response_time_ms: percentile_ms(&current.results, 0.95),
+    };
+
+    println!(
+        "  vs baseline {}: {} regression(s), {} fix(es), mean response time {:.0}ms -> {:.0}ms",
+        report.baseline_batch_id,
+        report.regressions.len(),
+        report.fixes.len(),
+        report.baseline_mean_response_time_ms,
+        report.current_mean_response_time_ms,
+    );
+
+    let report_file = Path::new("results").join(format!("regression_batch_{:03}_{}.json", batch_num, Utc::now().timestamp()));
+    fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct CategoryStats {
+    category: String,
+    total: usize,
+    successful: usize,
+    success_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolStats {
+    tool: String,
+    true_positives: usize,
+    false_positives: usize,
+    false_negatives: usize,
+    precision: f64,
+    recall: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct SummaryReport {
+    total_batches: usize,
+    total_questions: usize,
+    overall_success_rate: f64,
+    categories: Vec<CategoryStats>,
+    tools: Vec<ToolStats>,
+}
+
+/// Loads the latest result file for every batch number found in
+/// `results_dir` and rolls them all up into one report.
+fn build_summary(results_dir: &Path) -> Result<SummaryReport> {
+    let mut batches = Vec::new();
+    for batch_num in 1..=40 {
+        if let Some(batch_result) = find_latest_batch_result(results_dir, batch_num)? {
+            batches.push(batch_result);
+        }
+    }
+
+    let all_results: Vec<&EvalResult> = batches.iter().flat_map(|b| b.results.iter()).collect();
+    let total_questions = all_results.len();
+    let successful = all_results.iter().filter(|r| r.success).count();
+    let overall_success_rate = if total_questions == 0 { 0.0 } else { successful as f64 / total_questions as f64 };
+
+    let mut by_category: std::collections::BTreeMap<&str, (usize, usize)> = std::collections::BTreeMap::new();
+    for result in &all_results {
+        let entry = by_category.entry(&result.category).or_insert((0, 0));
+        entry.0 += 1;
+        if result.success {
+            entry.1 += 1;
+        }
+    }
+    let categories = by_category
+        .into_iter()
+        .map(|(category, (total, successful))| CategoryStats {
+            category: category.to_string(),
+            total,
+            successful,
+            success_rate: successful as f64 / total as f64,
+        })
+        .collect();
+
+    let mut by_tool: std::collections::BTreeMap<String, (usize, usize, usize)> = std::collections::BTreeMap::new();
+    for result in &all_results {
+        for tool in &result.expected_tools {
+            let entry = by_tool.entry(tool.clone()).or_insert((0, 0, 0));
+            if result.actual_tools.contains(tool) {
+                entry.0 += 1; // true positive
+            } else {
+                entry.2 += 1; // false negative
+            }
+        }
+        for tool in &result.actual_tools {
+            if !result.expected_tools.contains(tool) {
+                by_tool.entry(tool.clone()).or_insert((0, 0, 0)).1 += 1; // false positive
+            }
+        }
+    }
+    let tools = by_tool
+        .into_iter()
+        .map(|(tool, (tp, fp, fn_))| ToolStats {
+            tool,
+            true_positives: tp,
+            false_positives: fp,
+            false_negatives: fn_,
+            precision: if tp + fp == 0 { 0.0 } else { tp as f64 / (tp + fp) as f64 },
+            recall: if tp + fn_ == 0 { 0.0 } else { tp as f64 / (tp + fn_) as f64 },
+        })
+        .collect();
+
+    Ok(SummaryReport {
+        total_batches: batches.len(),
+        total_questions,
+        overall_success_rate,
+        categories,
+        tools,
+    })
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -210,7 +510,30 @@ async fn main() -> Result<()> {
     let data_dir = Path::new("data");
     let results_dir = Path::new("results");
     fs::create_dir_all(results_dir)?;
-    
+
+    if args.summary {
+        let report = build_summary(results_dir)?;
+        println!(
+            "{} batch(es), {} question(s), overall success rate {:.1}%",
+            report.total_batches,
+            report.total_questions,
+            report.overall_success_rate * 100.0
+        );
+        for category in &report.categories {
+            println!("  {}: {}/{} ({:.1}%)", category.category, category.successful, category.total, category.success_rate * 100.0);
+        }
+        for tool in &report.tools {
+            println!(
+                "  {}: precision {:.1}%, recall {:.1}%",
+                tool.tool,
+                tool.precision * 100.0,
+                tool.recall * 100.0
+            );
+        }
+        fs::write(results_dir.join("summary.json"), serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
+
     if args.full {
         println!("Running full evaluation (1000 questions)...");
         
@@ -235,6 +558,10 @@ async fn main() -> Result<()> {
                     result.total_questions,
                     result.success_rate * 100.0
                 );
+
+                if let Some(baseline_dir) = &args.baseline {
+                    report_regressions(Path::new(baseline_dir), &result, batch_num)?;
+                }
             }
         }
     } else {
@@ -260,6 +587,10 @@ async fn main() -> Result<()> {
                 result.total_questions,
                 result.success_rate * 100.0
             );
+
+            if let Some(baseline_dir) = &args.baseline {
+                report_regressions(Path::new(baseline_dir), &result, args.start_batch)?;
+            }
         } else {
             eprintln!("Batch file not found: {:?}", batch_file);
         }
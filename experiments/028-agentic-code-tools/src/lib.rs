@@ -0,0 +1,6 @@
+//! Agentic code-analysis experiment: a multi-step tool-calling loop over
+//! the Gemini API, backed by a small registry of Rust source-analysis
+//! tools (`analyze_rust_code`, `find_function`, `find_struct`).
+
+pub mod tool_loop;
+pub mod tools;
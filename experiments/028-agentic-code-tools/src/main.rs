@@ -0,0 +1,32 @@
+//! Binary entry point: runs a single multi-step `run_tool_loop` conversation
+//! against the real Gemini endpoint and prints every tool call made along
+//! with the model's final answer. Successor to
+//! `024-function-calling-fix/debug_response.rs`, which only detected a
+//! `functionCall` part instead of executing it.
+
+use agentic_code_tools::tool_loop::{run_tool_loop, DEFAULT_MAX_ITERATIONS};
+use agentic_code_tools::tools::default_tools;
+use anyhow::Result;
+use std::env;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_target(false).init();
+
+    let prompt = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "Analyze src/main.rs and find the function named main".to_string());
+
+    let tools = default_tools();
+    let (answer, invoked) = run_tool_loop(&prompt, &tools, DEFAULT_MAX_ITERATIONS).await?;
+
+    println!("=== Tool calls ===");
+    for (name, args) in &invoked {
+        println!("{}({})", name, args);
+    }
+
+    println!("\n=== Final answer ===");
+    println!("{}", answer.unwrap_or_else(|| "(model made no text reply)".to_string()));
+
+    Ok(())
+}
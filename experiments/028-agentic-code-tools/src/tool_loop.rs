@@ -0,0 +1,128 @@
+//! Iterative function-calling driver for the Gemini API. The debug binary
+//! this experiment replaces (`024-function-calling-fix/debug_response.rs`)
+//! stopped at noticing a `functionCall` part in the response; `run_tool_loop`
+//! actually executes it: send `contents`/`tools`, look up each returned
+//! `functionCall` by name in the tool registry, call `Tool::execute`, append
+//! a `functionResponse` message, and re-POST the accumulated conversation.
+//! Repeats until a response carries no `functionCall`, or `max_iterations`
+//! is hit.
+
+use crate::tools::{declared_function_tools, Tool};
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::env;
+use tracing::{debug, info, warn};
+
+/// Upper bound on how many request/response round-trips `run_tool_loop`
+/// will make for a single conversation, so a model stuck calling functions
+/// forever can't hang the caller.
+pub const DEFAULT_MAX_ITERATIONS: usize = 8;
+
+async fn send_generate_request(contents: &[Value], tools: &[Box<dyn Tool>]) -> Result<Value> {
+    let api_key = env::var("GOOGLE_AI_API_KEY")
+        .or_else(|_| env::var("GEMINI_API_KEY"))
+        .context("GOOGLE_AI_API_KEY or GEMINI_API_KEY must be set")?;
+    let model = env::var("GEMINI_MODEL").unwrap_or_else(|_| "gemini-2.0-flash-lite".to_string());
+
+    let request = serde_json::json!({
+        "contents": contents,
+        "tools": [{ "functionDeclarations": declared_function_tools(tools) }],
+    });
+
+    let url = format!(
+        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+        model, api_key
+    );
+
+    let response = reqwest::Client::new().post(&url).json(&request).send().await?;
+    let status = response.status();
+    let body: Value = response.json().await?;
+
+    if !status.is_success() {
+        bail!("Gemini API error {}: {}", status, body);
+    }
+
+    Ok(body)
+}
+
+/// Pulls every `functionCall` part out of the first candidate's response, in
+/// `(name, args)` form.
+fn extract_function_calls(body: &Value) -> Vec<(String, Value)> {
+    body["candidates"][0]["content"]["parts"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("functionCall"))
+                .filter_map(|call| {
+                    let name = call.get("name")?.as_str()?.to_string();
+                    let args = call.get("args").cloned().unwrap_or_else(|| serde_json::json!({}));
+                    Some((name, args))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Pulls the plain-text answer out of the first candidate's response, if any.
+fn extract_text(body: &Value) -> Option<String> {
+    body["candidates"][0]["content"]["parts"]
+        .as_array()?
+        .iter()
+        .find_map(|part| part.get("text")?.as_str().map(str::to_string))
+}
+
+/// Drives `prompt` through `tools` to completion: feed the prompt, execute
+/// every `functionCall` the model returns against the matching `Tool` by
+/// name, append a `functionResponse` for each, and re-POST the accumulated
+/// `contents`. Stops once a response carries no `functionCall` (or
+/// `max_iterations` is reached) and returns that final text along with every
+/// call made along the way, in order, so a prompt can chain several tools in
+/// one turn.
+pub async fn run_tool_loop(
+    prompt: &str,
+    tools: &[Box<dyn Tool>],
+    max_iterations: usize,
+) -> Result<(Option<String>, Vec<(String, Value)>)> {
+    let mut contents = vec![serde_json::json!({
+        "role": "user",
+        "parts": [{ "text": prompt }]
+    })];
+    let mut invoked = Vec::new();
+
+    for iteration in 0..max_iterations {
+        let body = send_generate_request(&contents, tools).await?;
+        let calls = extract_function_calls(&body);
+
+        if calls.is_empty() {
+            return Ok((extract_text(&body), invoked));
+        }
+
+        debug!("iteration {}: {} function call(s)", iteration, calls.len());
+
+        let mut call_parts = Vec::new();
+        let mut response_parts = Vec::new();
+        for (name, args) in calls {
+            let tool = tools
+                .iter()
+                .find(|t| t.name() == name)
+                .with_context(|| format!("model called unknown tool '{}'", name))?;
+
+            let result = tool.execute(args.clone()).await?;
+            info!("called {} -> {}", name, result);
+
+            call_parts.push(serde_json::json!({ "functionCall": { "name": name.clone(), "args": args.clone() } }));
+            response_parts.push(serde_json::json!({ "functionResponse": { "name": name.clone(), "response": result } }));
+            invoked.push((name, args));
+        }
+
+        // The `model` turn carries the calls it just made, the following
+        // `user` turn carries their results — the shape Gemini expects a
+        // function-calling round trip to take.
+        contents.push(serde_json::json!({ "role": "model", "parts": call_parts }));
+        contents.push(serde_json::json!({ "role": "user", "parts": response_parts }));
+    }
+
+    warn!("run_tool_loop hit max_iterations ({}) without a final text answer", max_iterations);
+    Ok((None, invoked))
+}
@@ -0,0 +1,213 @@
+//! Local tool registry for `tool_loop::run_tool_loop`: a minimal `Tool`
+//! trait plus the three static-analysis tools the loop dispatches
+//! `functionCall`s to.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use syn::{parse_file, Fields, Item, ItemFn, Visibility};
+
+#[async_trait]
+pub trait Tool: Send + Sync {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    fn parameters_schema(&self) -> Value;
+    async fn execute(&self, params: Value) -> Result<Value>;
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+/// Parses a Rust source file and summarizes how many of each top-level item
+/// kind it declares.
+pub struct AnalyzeRustCodeTool;
+
+#[async_trait]
+impl Tool for AnalyzeRustCodeTool {
+    fn name(&self) -> &str {
+        "analyze_rust_code"
+    }
+
+    fn description(&self) -> &str {
+        "Analyze a Rust source file and summarize its top-level items"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": { "type": "string", "description": "Path to the .rs file to analyze" }
+            },
+            "required": ["file_path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            file_path: String,
+        }
+        let params: Params = serde_json::from_value(params)?;
+        let code = fs::read_to_string(&params.file_path)?;
+        let syntax_tree = parse_file(&code)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", params.file_path, e))?;
+
+        let mut functions = 0;
+        let mut structs = 0;
+        let mut enums = 0;
+        let mut traits = 0;
+        let mut impls = 0;
+        for item in &syntax_tree.items {
+            match item {
+                Item::Fn(_) => functions += 1,
+                Item::Struct(_) => structs += 1,
+                Item::Enum(_) => enums += 1,
+                Item::Trait(_) => traits += 1,
+                Item::Impl(_) => impls += 1,
+                _ => {}
+            }
+        }
+
+        Ok(json!({
+            "file_path": params.file_path,
+            "functions": functions,
+            "structs": structs,
+            "enums": enums,
+            "traits": traits,
+            "impls": impls,
+        }))
+    }
+}
+
+fn describe_function(item_fn: &ItemFn) -> Value {
+    json!({
+        "found": true,
+        "name": item_fn.sig.ident.to_string(),
+        "public": is_public(&item_fn.vis),
+        "arg_count": item_fn.sig.inputs.len(),
+        "is_async": item_fn.sig.asyncness.is_some(),
+    })
+}
+
+/// Finds a single top-level function by name in a Rust source file.
+pub struct FindFunctionTool;
+
+#[async_trait]
+impl Tool for FindFunctionTool {
+    fn name(&self) -> &str {
+        "find_function"
+    }
+
+    fn description(&self) -> &str {
+        "Find a top-level function by name in a Rust source file"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": { "type": "string", "description": "Path to the .rs file to search" },
+                "name": { "type": "string", "description": "Function name to find" }
+            },
+            "required": ["file_path", "name"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            file_path: String,
+            name: String,
+        }
+        let params: Params = serde_json::from_value(params)?;
+        let code = fs::read_to_string(&params.file_path)?;
+        let syntax_tree = parse_file(&code)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", params.file_path, e))?;
+
+        let found = syntax_tree.items.iter().find_map(|item| match item {
+            Item::Fn(item_fn) if item_fn.sig.ident == params.name => Some(describe_function(item_fn)),
+            _ => None,
+        });
+
+        Ok(found.unwrap_or_else(|| json!({ "found": false, "name": params.name })))
+    }
+}
+
+/// Finds a single top-level struct by name in a Rust source file.
+pub struct FindStructTool;
+
+#[async_trait]
+impl Tool for FindStructTool {
+    fn name(&self) -> &str {
+        "find_struct"
+    }
+
+    fn description(&self) -> &str {
+        "Find a top-level struct by name in a Rust source file"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": { "type": "string", "description": "Path to the .rs file to search" },
+                "name": { "type": "string", "description": "Struct name to find" }
+            },
+            "required": ["file_path", "name"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            file_path: String,
+            name: String,
+        }
+        let params: Params = serde_json::from_value(params)?;
+        let code = fs::read_to_string(&params.file_path)?;
+        let syntax_tree = parse_file(&code)
+            .map_err(|e| anyhow::anyhow!("failed to parse {}: {}", params.file_path, e))?;
+
+        let found = syntax_tree.items.iter().find_map(|item| match item {
+            Item::Struct(item_struct) if item_struct.ident == params.name => Some(json!({
+                "found": true,
+                "name": item_struct.ident.to_string(),
+                "public": is_public(&item_struct.vis),
+                "field_count": match &item_struct.fields {
+                    Fields::Named(f) => f.named.len(),
+                    Fields::Unnamed(f) => f.unnamed.len(),
+                    Fields::Unit => 0,
+                },
+            })),
+            _ => None,
+        });
+
+        Ok(found.unwrap_or_else(|| json!({ "found": false, "name": params.name })))
+    }
+}
+
+/// This experiment's tools, in the order `declared_function_tools` exposes
+/// them to the model.
+pub fn default_tools() -> Vec<Box<dyn Tool>> {
+    vec![
+        Box::new(AnalyzeRustCodeTool),
+        Box::new(FindFunctionTool),
+        Box::new(FindStructTool),
+    ]
+}
+
+/// Declares `tools` in the `functionDeclarations` shape the Gemini API's
+/// `tools` field expects.
+pub fn declared_function_tools(tools: &[Box<dyn Tool>]) -> Value {
+    json!(tools
+        .iter()
+        .map(|tool| json!({
+            "name": tool.name(),
+            "description": tool.description(),
+            "parameters": tool.parameters_schema(),
+        }))
+        .collect::<Vec<_>>())
+}
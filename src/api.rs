@@ -1,17 +1,191 @@
 //! Gemini API client implementation
 
+use crate::config::GeminiConfig;
+use crate::errors::ApiError;
+use crate::logging::ApiLogger;
+use crate::vertex_auth::AdcTokenProvider;
 use anyhow::{bail, Result};
-use reqwest::Client;
+use futures::StreamExt;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How to retry a Gemini API request that failed with a transient (429 or
+/// 5xx) status. 400-level errors other than 429 are never retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(32),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait before the given attempt (1-indexed). Honors an
+    /// explicit `retry_after` (from `Retry-After` or Gemini's `retryDelay`
+    /// error detail) when present; otherwise computes full-jitter
+    /// exponential backoff from `base_delay`, capped at `max_delay`.
+    fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(delay) = retry_after {
+            return delay.min(self.max_delay);
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16);
+        let cap_ms = self.max_delay.as_millis();
+        let upper_ms = self
+            .base_delay
+            .as_millis()
+            .saturating_mul(1u128 << exponent)
+            .min(cap_ms) as u64;
+
+        Duration::from_millis((upper_ms as f64 * jitter_fraction()) as u64)
+    }
+}
+
+/// A pseudo-random fraction in `[0, 1)`, derived from a fresh UUID's bytes
+/// rather than pulling in a dedicated `rand` dependency just for backoff
+/// jitter.
+fn jitter_fraction() -> f64 {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    let n = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    n as f64 / u32::MAX as f64
+}
+
+/// Extracts a server-suggested retry delay from a failed response: the
+/// `Retry-After` header (seconds) if present, else Gemini's error body
+/// `error.details[].retryDelay` field (e.g. `"13s"`).
+fn retry_delay_from(headers: &reqwest::header::HeaderMap, body: &str) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .or_else(|| retry_delay_from_body(body))
+}
+
+/// Default `Endpoint::Public` host, overridable via [`GeminiClient::with_base_url`]
+/// (or [`crate::config::GeminiConfig`]) so users behind a proxy, self-hosted
+/// relay, or OpenAI-compatible gateway shim can point the client elsewhere.
+pub(crate) const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// How a request authenticates itself, decided once per call alongside the
+/// URL it's sent to.
+enum RequestAuth {
+    /// The API key is already embedded in the URL's `?key=` query string.
+    QueryParam,
+    /// The API key is sent via the `x-goog-api-key` header instead, so it
+    /// doesn't leak into proxy/gateway access logs that record full URLs.
+    Header(String),
+    /// An OAuth bearer token, used by `Endpoint::Vertex`.
+    Bearer(String),
+}
+
+impl RequestAuth {
+    fn apply(self, request_builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            RequestAuth::QueryParam => request_builder,
+            RequestAuth::Header(api_key) => request_builder.header("x-goog-api-key", api_key),
+            RequestAuth::Bearer(token) => request_builder.bearer_auth(token),
+        }
+    }
+}
+
+fn retry_delay_from_body(body: &str) -> Option<Duration> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    let details = value.get("error")?.get("details")?.as_array()?;
+    details.iter().find_map(|detail| {
+        let delay = detail.get("retryDelay")?.as_str()?;
+        delay.strip_suffix('s')?.parse::<f64>().ok().map(Duration::from_secs_f64)
+    })
+}
+
+/// Which deployment of Gemini to talk to, and how to authenticate to it.
+/// Selected via `GEMINI_BACKEND` (`public` or `vertex`) so enterprise users
+/// pinned to Vertex AI can use the same client and REPL.
+pub enum Endpoint {
+    /// `generativelanguage.googleapis.com`, authenticated with an API key
+    /// query parameter.
+    Public { api_key: String },
+    /// `{region}-aiplatform.googleapis.com`, authenticated with an OAuth
+    /// bearer token minted from Application Default Credentials.
+    Vertex {
+        project: String,
+        region: String,
+        credentials: AdcTokenProvider,
+    },
+}
 
 /// Gemini API client
 pub struct GeminiClient {
     client: Client,
-    api_key: String,
+    endpoint: Endpoint,
     model: String,
+    /// Overrides the default tool-use system instruction. `None` falls back
+    /// to the built-in prompt (or no system instruction at all, for plain
+    /// chat requests without tools).
+    system_instruction: Option<String>,
+    /// Safety filter strictness applied across every harm category.
+    /// `None` leaves the API's own default in place.
+    safety_threshold: Option<HarmBlockThreshold>,
+    /// Sampling parameters (temperature, top_p, top_k, max_output_tokens).
+    /// `None` leaves the API's own defaults in place.
+    generation_config: Option<GenerationConfig>,
+    /// Governs retries on transient (429/5xx) failures.
+    retry_policy: RetryPolicy,
+    /// Host `Endpoint::Public` requests are sent to; overridable for
+    /// proxies, self-hosted relays, or gateway shims. Does not affect
+    /// `Endpoint::Vertex`, which always targets its own regional host.
     base_url: String,
+    /// If `true`, the `Endpoint::Public` API key is sent via the
+    /// `x-goog-api-key` header instead of the `?key=` query parameter, so
+    /// it doesn't end up in a proxy's access logs.
+    api_key_in_header: bool,
+    /// When set, every `send_turn` request/response is captured through
+    /// this logger, producing the `reqs.jsonl`/`resps.jsonl` transcripts
+    /// [`crate::replay::ReplayClient`] reads back for offline replay.
+    logger: Option<Arc<ApiLogger>>,
+}
+
+/// How a prompt should be framed for the model.
+#[derive(Debug, Clone)]
+pub enum PromptMode {
+    /// Ordinary multi-turn chat: send `conversation` as-is.
+    Chat,
+    /// Fill-in-the-middle: ask the model to produce the text that belongs
+    /// between `prefix` and `suffix`, framed as a single user turn using
+    /// the `<|fim_prefix|>`/`<|fim_suffix|>`/`<|fim_middle|>` convention.
+    FillInMiddle { prefix: String, suffix: String },
+}
+
+impl PromptMode {
+    /// Render this prompt mode into the single user `Content` to send.
+    fn into_content(self) -> Content {
+        match self {
+            PromptMode::Chat => unreachable!("Chat mode does not produce a single Content"),
+            PromptMode::FillInMiddle { prefix, suffix } => Content {
+                role: "user".to_string(),
+                parts: vec![Part {
+                    text: Some(format!(
+                        "<|fim_prefix|>{prefix}<|fim_suffix|>{suffix}<|fim_middle|>"
+                    )),
+                    function_call: None,
+                    function_response: None,
+                }],
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +218,55 @@ struct GenerateContentRequest {
     contents: Vec<Content>,
     tools: Option<Vec<Value>>,
     system_instruction: Option<SystemInstruction>,
+    safety_settings: Option<Vec<SafetySetting>>,
+    generation_config: Option<GenerationConfig>,
+}
+
+/// How strictly Gemini's safety filter should block content, applied
+/// uniformly across every [`HARM_CATEGORIES`] entry by
+/// [`HarmBlockThreshold::settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HarmBlockThreshold {
+    BlockNone,
+    BlockOnlyHigh,
+    BlockMediumAndAbove,
+    BlockLowAndAbove,
+}
+
+/// Harm categories Gemini's safety filter evaluates.
+const HARM_CATEGORIES: &[&str] = &[
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+impl HarmBlockThreshold {
+    /// Expand this single threshold into one `SafetySetting` per harm
+    /// category, which is the shape the API actually expects.
+    fn settings(self) -> Vec<SafetySetting> {
+        HARM_CATEGORIES
+            .iter()
+            .map(|category| SafetySetting { category: (*category).to_string(), threshold: self })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SafetySetting {
+    category: String,
+    threshold: HarmBlockThreshold,
+}
+
+/// Sampling parameters for a generation request. Fields left `None` fall
+/// back to the API's own defaults.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct GenerationConfig {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u32>,
+    pub max_output_tokens: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,76 +281,322 @@ struct SystemPart {
 
 #[derive(Debug, Deserialize)]
 struct GenerateContentResponse {
+    #[serde(default)]
     candidates: Vec<Candidate>,
+    /// Present instead of `candidates` when the API fails mid-stream (a
+    /// blocking request instead reports this as a non-2xx HTTP status, so
+    /// `send_turn` never needs to look for it).
+    error: Option<StreamApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamApiError {
+    code: i32,
+    message: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct Candidate {
     content: Content,
+    #[serde(rename = "finishReason")]
+    finish_reason: Option<String>,
 }
 
 impl GeminiClient {
-    /// Create a new Gemini client
+    /// Create a new client targeting the public Gemini API with an API key.
     pub fn new(api_key: String, model: String) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()?;
-        
+
         Ok(Self {
             client,
-            api_key,
+            endpoint: Endpoint::Public { api_key },
             model,
-            base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
+            system_instruction: None,
+            safety_threshold: None,
+            generation_config: None,
+            retry_policy: RetryPolicy::default(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key_in_header: false,
+            logger: None,
         })
     }
-    
+
+    /// Create a new client targeting Vertex AI, authenticating with
+    /// Application Default Credentials instead of an API key.
+    pub fn new_vertex(project: String, region: String, model: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            client,
+            endpoint: Endpoint::Vertex {
+                project,
+                region,
+                credentials: AdcTokenProvider::from_env()?,
+            },
+            model,
+            system_instruction: None,
+            safety_threshold: None,
+            generation_config: None,
+            retry_policy: RetryPolicy::default(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key_in_header: false,
+            logger: None,
+        })
+    }
+
+    /// Like [`Self::new_vertex`], but reads Application Default Credentials
+    /// from `adc_file` instead of `GOOGLE_APPLICATION_CREDENTIALS`/the
+    /// well-known gcloud path.
+    pub fn new_vertex_with_adc_file(project: String, region: String, model: String, adc_file: PathBuf) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            client,
+            endpoint: Endpoint::Vertex {
+                project,
+                region,
+                credentials: AdcTokenProvider::from_adc_file(adc_file)?,
+            },
+            model,
+            system_instruction: None,
+            safety_threshold: None,
+            generation_config: None,
+            retry_policy: RetryPolicy::default(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key_in_header: false,
+            logger: None,
+        })
+    }
+
+    /// Create a client targeting the public Gemini API from a fully
+    /// resolved [`GeminiConfig`] (see [`GeminiConfig::load`] for how one is
+    /// built from defaults, `gemini.toml`, environment variables, and CLI
+    /// overrides).
+    pub fn from_config(config: GeminiConfig) -> Result<Self> {
+        let client = Client::builder().timeout(config.timeout).build()?;
+
+        Ok(Self {
+            client,
+            endpoint: Endpoint::Public { api_key: config.api_key },
+            model: config.model,
+            system_instruction: config.system_instruction,
+            safety_threshold: None,
+            generation_config: None,
+            retry_policy: config.retry_policy,
+            base_url: config.base_url,
+            api_key_in_header: false,
+            logger: None,
+        })
+    }
+
+    /// Override the system instruction sent alongside tool-enabled requests.
+    pub fn with_system_instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.system_instruction = Some(instruction.into());
+        self
+    }
+
+    /// The system instruction currently configured, if any was set via
+    /// [`Self::with_system_instruction`] or [`Self::set_system_instruction`].
+    pub fn system_instruction(&self) -> Option<&str> {
+        self.system_instruction.as_deref()
+    }
+
+    /// Replace the system instruction at runtime, e.g. from a REPL `/system`
+    /// command. `None` reverts to the client's built-in default.
+    pub fn set_system_instruction(&mut self, instruction: Option<String>) {
+        self.system_instruction = instruction;
+    }
+
+    /// Apply `threshold` across every harm category in every request this
+    /// client sends.
+    pub fn with_safety_threshold(mut self, threshold: HarmBlockThreshold) -> Self {
+        self.safety_threshold = Some(threshold);
+        self
+    }
+
+    /// Send `config`'s sampling parameters with every request this client
+    /// sends.
+    pub fn with_generation_config(mut self, config: GenerationConfig) -> Self {
+        self.generation_config = Some(config);
+        self
+    }
+
+    /// Override the default retry policy for transient (429/5xx) failures.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Override the host `Endpoint::Public` requests are sent to, e.g. to
+    /// route through a corporate proxy, a self-hosted relay, or an
+    /// OpenAI-compatible gateway shim. Has no effect on `Endpoint::Vertex`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Send the `Endpoint::Public` API key via the `x-goog-api-key` header
+    /// instead of the `?key=` query parameter, so it doesn't end up logged
+    /// in a proxy's access logs.
+    pub fn with_api_key_header(mut self, enabled: bool) -> Self {
+        self.api_key_in_header = enabled;
+        self
+    }
+
+    /// Capture every `send_turn` request/response through `logger`,
+    /// producing a transcript [`crate::replay::ReplayClient`] can later
+    /// replay offline.
+    pub fn with_logger(mut self, logger: ApiLogger) -> Self {
+        self.logger = Some(Arc::new(logger));
+        self
+    }
+
+    /// Sends `request_builder`, retrying on 429/5xx with full-jitter
+    /// exponential backoff (or the server's requested delay, if any) up to
+    /// `retry_policy.max_attempts`. Any other 4xx fails immediately as
+    /// [`ApiError::BadRequest`].
+    async fn send_with_retry(&self, request_builder: RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0u32;
+        loop {
+            attempt += 1;
+            let builder = request_builder
+                .try_clone()
+                .ok_or_else(|| anyhow::anyhow!("request body does not support retries"))?;
+            let response = builder.send().await.map_err(|e| ApiError::network(e.to_string()))?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let headers = response.headers().clone();
+            let body = response.text().await.unwrap_or_default();
+
+            if status.as_u16() == 429 || status.is_server_error() {
+                if attempt >= self.retry_policy.max_attempts {
+                    return Err(if status.as_u16() == 429 {
+                        ApiError::RateLimited { retry_after: retry_delay_from(&headers, &body) }.into()
+                    } else {
+                        ApiError::ServerError(status.as_u16()).into()
+                    });
+                }
+                let delay = self.retry_policy.delay_for(attempt, retry_delay_from(&headers, &body));
+                tracing::warn!(attempt, delay_ms = delay.as_millis() as u64, %status, "retrying Gemini API request");
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            return Err(ApiError::BadRequest(body).into());
+        }
+    }
+
     /// Send a message without tools
     pub async fn send_message(&self, conversation: &[Content]) -> Result<String> {
         self.send_message_with_tools(conversation, None).await
     }
-    
+
+    /// Send a fill-in-the-middle completion request: `prefix` and `suffix`
+    /// frame the gap the model should fill, sent as a single user turn.
+    pub async fn send_fim(&self, prefix: impl Into<String>, suffix: impl Into<String>) -> Result<String> {
+        let content = PromptMode::FillInMiddle {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+        }
+        .into_content();
+        self.send_message(&[content]).await
+    }
+
     /// Send a message with tool definitions
     pub async fn send_message_with_tools(&self, conversation: &[Content], tools: Option<Vec<Value>>) -> Result<String> {
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            self.base_url, self.model, self.api_key
-        );
-        
-        // Add system instruction for function calling
+        let content = self.send_turn(conversation, tools).await?;
+        Ok(content
+            .parts
+            .first()
+            .and_then(|p| p.text.clone())
+            .unwrap_or_else(|| "No text in response".to_string()))
+    }
+
+    /// Send a turn and return the full model `Content` (all parts, including
+    /// any `FunctionCall`s), rather than just the first text part. Used by
+    /// the agentic function-calling loop in [`crate::functions`].
+    pub async fn send_turn(&self, conversation: &[Content], tools: Option<Vec<Value>>) -> Result<Content> {
+        let (url, auth) = match &self.endpoint {
+            Endpoint::Public { api_key } => {
+                if self.api_key_in_header {
+                    (
+                        format!("{}/models/{}:generateContent", self.base_url, self.model),
+                        RequestAuth::Header(api_key.clone()),
+                    )
+                } else {
+                    (
+                        format!("{}/models/{}:generateContent?key={}", self.base_url, self.model, api_key),
+                        RequestAuth::QueryParam,
+                    )
+                }
+            }
+            Endpoint::Vertex { project, region, credentials } => (
+                format!(
+                    "https://{region}-aiplatform.googleapis.com/v1/projects/{project}/locations/{region}/publishers/google/models/{}:generateContent",
+                    self.model
+                ),
+                RequestAuth::Bearer(credentials.access_token().await?),
+            ),
+        };
+
+        // Add a system instruction for function calling, preferring any
+        // instruction the caller configured via `with_system_instruction`.
         let system_instruction = if tools.is_some() {
+            let text = self.system_instruction.clone().unwrap_or_else(|| {
+                "You are a helpful AI assistant with access to tools. When the user asks you to perform actions that require tools, use the available function calls to complete the request. Always provide clear explanations of what you're doing and what the results mean.".to_string()
+            });
             Some(SystemInstruction {
-                parts: vec![SystemPart {
-                    text: "You are a helpful AI assistant with access to tools. When the user asks you to perform actions that require tools, use the available function calls to complete the request. Always provide clear explanations of what you're doing and what the results mean.".to_string(),
-                }],
+                parts: vec![SystemPart { text }],
             })
         } else {
             None
         };
-        
+
         let request = GenerateContentRequest {
             contents: conversation.to_vec(),
             tools,
             system_instruction,
+            safety_settings: self.safety_threshold.map(HarmBlockThreshold::settings),
+            generation_config: self.generation_config,
         };
-        
+
         tracing::debug!("Sending request to Gemini API: {}", serde_json::to_string_pretty(&request)?);
-        
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            bail!("API request failed with status {}: {}", status, error_text);
+
+        let log_target = self.logger.as_ref().and_then(|logger| {
+            let parsed = reqwest::Url::parse(&url).ok()?;
+            let path = match parsed.query() {
+                Some(query) => format!("{}?{}", parsed.path(), query),
+                None => parsed.path().to_string(),
+            };
+            Some((logger, parsed.host_str()?.to_string(), path))
+        });
+        if let Some((logger, host, path)) = &log_target {
+            let body = serde_json::to_value(&request)?;
+            let _ = logger.log_request(host, path, "POST", &[], &body);
         }
-        
+
+        let start = Instant::now();
+        let request_builder = auth.apply(self.client.post(&url).json(&request));
+        let response = self.send_with_retry(request_builder).await?;
+        let status = response.status().as_u16();
+
         let response_text = response.text().await?;
         tracing::debug!("Received response from Gemini API: {}", response_text);
-        
+
+        if let Some((logger, host, path)) = &log_target {
+            let body = serde_json::from_str(&response_text).unwrap_or(Value::Null);
+            let _ = logger.log_response(host, path, status, &body, start.elapsed().as_millis() as u64);
+        }
+
         let response: GenerateContentResponse = serde_json::from_str(&response_text)?;
         
         if response.candidates.is_empty() {
@@ -135,25 +604,251 @@ impl GeminiClient {
         }
         
         let candidate = &response.candidates[0];
+        if let Some(reason) = &candidate.finish_reason {
+            if reason != "STOP" {
+                return Err(ApiError::invalid_response(format!(
+                    "candidate blocked or truncated: finishReason={reason}"
+                ))
+                .into());
+            }
+        }
         if candidate.content.parts.is_empty() {
             bail!("No parts in candidate content");
         }
-        
-        let part = &candidate.content.parts[0];
-        part.text.clone().unwrap_or_else(|| "No text in response".to_string())
-            .pipe(Ok)
+
+        Ok(candidate.content.clone())
+    }
+
+    /// Like [`Self::send_turn`], but hits `:streamGenerateContent?alt=sse`
+    /// and calls `on_chunk` with each text fragment as it arrives, so the
+    /// REPL can render tokens live instead of waiting for the full reply.
+    /// Returns the same full `Content` `send_turn` would, with any
+    /// function-call parts preserved in order alongside the streamed text.
+    pub async fn send_turn_stream(
+        &self,
+        conversation: &[Content],
+        tools: Option<Vec<Value>>,
+        mut on_chunk: impl FnMut(&str),
+    ) -> Result<Content> {
+        let (url, auth) = match &self.endpoint {
+            Endpoint::Public { api_key } => {
+                if self.api_key_in_header {
+                    (
+                        format!("{}/models/{}:streamGenerateContent?alt=sse", self.base_url, self.model),
+                        RequestAuth::Header(api_key.clone()),
+                    )
+                } else {
+                    (
+                        format!(
+                            "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+                            self.base_url, self.model, api_key
+                        ),
+                        RequestAuth::QueryParam,
+                    )
+                }
+            }
+            Endpoint::Vertex { project, region, credentials } => (
+                format!(
+                    "https://{region}-aiplatform.googleapis.com/v1/projects/{project}/locations/{region}/publishers/google/models/{}:streamGenerateContent?alt=sse",
+                    self.model
+                ),
+                RequestAuth::Bearer(credentials.access_token().await?),
+            ),
+        };
+
+        let system_instruction = if tools.is_some() {
+            let text = self.system_instruction.clone().unwrap_or_else(|| {
+                "You are a helpful AI assistant with access to tools. When the user asks you to perform actions that require tools, use the available function calls to complete the request. Always provide clear explanations of what you're doing and what the results mean.".to_string()
+            });
+            Some(SystemInstruction {
+                parts: vec![SystemPart { text }],
+            })
+        } else {
+            None
+        };
+
+        let request = GenerateContentRequest {
+            contents: conversation.to_vec(),
+            tools,
+            system_instruction,
+            safety_settings: self.safety_threshold.map(HarmBlockThreshold::settings),
+            generation_config: self.generation_config,
+        };
+
+        tracing::debug!("Streaming request to Gemini API: {}", serde_json::to_string_pretty(&request)?);
+
+        let request_builder = auth.apply(self.client.post(&url).json(&request));
+        let response = self.send_with_retry(request_builder).await?;
+
+        let mut byte_stream = response.bytes_stream();
+        let mut pending_bytes: Vec<u8> = Vec::new();
+        let mut buffer = String::new();
+        let mut parts: Vec<Part> = Vec::new();
+        let mut pending_text = String::new();
+
+        while let Some(chunk) = byte_stream.next().await {
+            let chunk = chunk?;
+            pending_bytes.extend_from_slice(&chunk);
+
+            // A single SSE event's bytes can split a multi-byte UTF-8
+            // sequence across two network reads; only decode the longest
+            // valid prefix and leave the rest for the next chunk instead of
+            // lossily mangling a sequence that's merely incomplete so far.
+            let valid_len = match std::str::from_utf8(&pending_bytes) {
+                Ok(s) => s.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            buffer.push_str(std::str::from_utf8(&pending_bytes[..valid_len]).expect("validated above"));
+            pending_bytes.drain(..valid_len);
+
+            for object in drain_sse_objects(&mut buffer) {
+                let parsed: GenerateContentResponse = serde_json::from_str(&object)?;
+                if let Some(error) = parsed.error {
+                    bail!("Gemini API error during stream: {} (code {})", error.message, error.code);
+                }
+                let Some(candidate) = parsed.candidates.into_iter().next() else { continue };
+
+                if let Some(reason) = &candidate.finish_reason {
+                    tracing::debug!(reason, "stream chunk carried a finish reason");
+                    if reason != "STOP" {
+                        return Err(ApiError::invalid_response(format!(
+                            "candidate blocked or truncated: finishReason={reason}"
+                        ))
+                        .into());
+                    }
+                }
+
+                for part in candidate.content.parts {
+                    if let Some(text) = &part.text {
+                        on_chunk(text);
+                        pending_text.push_str(text);
+                    }
+                    if let Some(call) = part.function_call {
+                        if !pending_text.is_empty() {
+                            parts.push(Part {
+                                text: Some(std::mem::take(&mut pending_text)),
+                                function_call: None,
+                                function_response: None,
+                            });
+                        }
+                        parts.push(Part { text: None, function_call: Some(call), function_response: None });
+                    }
+                }
+            }
+        }
+
+        if !pending_text.is_empty() {
+            parts.push(Part { text: Some(pending_text), function_call: None, function_response: None });
+        }
+
+        if parts.is_empty() {
+            bail!("No parts in streamed response");
+        }
+
+        Ok(Content { role: "model".to_string(), parts })
     }
 }
 
-// Helper trait for pipeline operations
-trait Pipe<T> {
-    fn pipe<F, U>(self, f: F) -> U
-    where
-        F: FnOnce(Self) -> U,
-        Self: Sized,
-    {
-        f(self)
+/// Pull complete `data: {...}` JSON payloads out of `buffer`, in the order
+/// they appear, removing each one (plus its SSE framing) once it's
+/// extracted. An object split across network reads is left in the buffer
+/// until a later call completes it.
+fn drain_sse_objects(buffer: &mut String) -> Vec<String> {
+    let mut objects = Vec::new();
+
+    loop {
+        let Some(data_pos) = buffer.find("data:") else { break };
+        let after_prefix = &buffer[data_pos + "data:".len()..];
+        let json_start = data_pos + "data:".len() + (after_prefix.len() - after_prefix.trim_start().len());
+
+        match find_balanced_json_end(&buffer[json_start..]) {
+            Some(len) => {
+                objects.push(buffer[json_start..json_start + len].to_string());
+                buffer.drain(..json_start + len);
+            }
+            None => break, // incomplete object; wait for more bytes
+        }
+    }
+
+    objects
+}
+
+/// Scan `s` for a single balanced `{...}` JSON object starting at its
+/// first `{`, returning the byte length of the match (including both
+/// braces) once depth returns to zero. Braces inside quoted strings (and
+/// escaped quotes within them) don't affect the count, so a JSON object
+/// split mid-string across network reads is correctly left unparsed.
+fn find_balanced_json_end(s: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut started = false;
+
+    for (i, c) in s.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                depth += 1;
+                started = true;
+            }
+            '}' => {
+                depth -= 1;
+                if started && depth == 0 {
+                    return Some(i + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
     }
+
+    None
 }
 
-impl<T> Pipe<T> for T {}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drains_a_single_complete_sse_object() {
+        let mut buffer = "data: {\"candidates\":[]}\n\n".to_string();
+        let objects = drain_sse_objects(&mut buffer);
+        assert_eq!(objects, vec!["{\"candidates\":[]}".to_string()]);
+        assert!(buffer.trim().is_empty());
+    }
+
+    #[test]
+    fn waits_for_an_object_split_across_reads() {
+        let mut buffer = "data: {\"candidates\":[{\"content\"".to_string();
+        assert!(drain_sse_objects(&mut buffer).is_empty());
+
+        buffer.push_str(":{\"parts\":[]}}]}\n\n");
+        let objects = drain_sse_objects(&mut buffer);
+        assert_eq!(objects.len(), 1);
+        assert!(objects[0].contains("\"parts\":[]"));
+    }
+
+    #[test]
+    fn ignores_braces_inside_quoted_strings() {
+        let mut buffer = "data: {\"text\": \"a } brace { inside a string\"}\n\n".to_string();
+        let objects = drain_sse_objects(&mut buffer);
+        assert_eq!(objects.len(), 1);
+    }
+
+    #[test]
+    fn drains_multiple_events_in_order() {
+        let mut buffer = "data: {\"a\":1}\n\ndata: {\"a\":2}\n\n".to_string();
+        let objects = drain_sse_objects(&mut buffer);
+        assert_eq!(objects, vec!["{\"a\":1}".to_string(), "{\"a\":2}".to_string()]);
+    }
+}
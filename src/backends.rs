@@ -0,0 +1,546 @@
+//! Pluggable LLM backend abstraction
+//!
+//! `Content`/`Part` (see [`crate::api`]) are the neutral intermediate
+//! representation for a conversation. Each [`Backend`] translates that IR
+//! into a specific provider's wire format and back, so the REPL's
+//! function-calling path works unchanged regardless of which provider is
+//! configured.
+
+use crate::api::{Content, FunctionCall, Part};
+use anyhow::{bail, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// A tool/function declaration, in our neutral shape.
+///
+/// Backends translate this into whatever shape the provider expects
+/// (`functionDeclarations` for Gemini, `tools`/`functions` for OpenAI, ...).
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+impl ToolSpec {
+    /// Convert a `{"name", "description", "parameters"}` tool definition
+    /// (the shape [`crate::tools::ToolRegistry::get_tool_definitions`]
+    /// returns) into a `ToolSpec`. Returns `None` if `name` is missing.
+    pub fn from_value(v: &Value) -> Option<Self> {
+        Some(Self {
+            name: v.get("name")?.as_str()?.to_string(),
+            description: v.get("description").and_then(Value::as_str).unwrap_or_default().to_string(),
+            parameters: v.get("parameters").cloned().unwrap_or(Value::Null),
+        })
+    }
+}
+
+/// Translates the neutral `Content`/`Part` IR into a provider's wire format
+/// and back.
+pub trait Backend: Send + Sync {
+    /// Build the provider-specific request body for a turn.
+    fn build_request(&self, contents: &[Content], tools: &[ToolSpec]) -> Value;
+
+    /// Parse a provider-specific response body back into `Part`s.
+    fn parse_response(&self, raw: &Value) -> Result<Vec<Part>>;
+
+    /// Endpoint to POST the request body to, given the configured model.
+    fn endpoint(&self, model: &str) -> String;
+}
+
+/// Which backend to talk to, selected via `provider = "..."` in config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Gemini,
+    OpenAi,
+    Anthropic,
+    Ollama,
+}
+
+impl Provider {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "gemini" => Ok(Provider::Gemini),
+            "openai" => Ok(Provider::OpenAi),
+            "anthropic" => Ok(Provider::Anthropic),
+            "ollama" => Ok(Provider::Ollama),
+            other => bail!("unknown provider: {other}"),
+        }
+    }
+
+    /// Build the `Backend` implementation for this provider.
+    pub fn backend(self) -> Box<dyn Backend> {
+        self.backend_with_api_base(None)
+    }
+
+    /// Like [`Self::backend`], but overrides the default endpoint with
+    /// `api_base` when the provider supports one. Currently only
+    /// `Provider::OpenAi` does — that's how this client is pointed at
+    /// LocalAI or another OpenAI-compatible server (e.g.
+    /// `http://localhost:8080/v1`) instead of `api.openai.com`.
+    pub fn backend_with_api_base(self, api_base: Option<String>) -> Box<dyn Backend> {
+        match (self, api_base) {
+            (Provider::OpenAi, Some(api_base)) => Box::new(OpenAiBackend::new(api_base)),
+            (Provider::Gemini, _) => Box::new(GeminiBackend),
+            (Provider::OpenAi, None) => Box::new(OpenAiBackend::default()),
+            (Provider::Anthropic, _) => Box::new(AnthropicBackend),
+            (Provider::Ollama, _) => Box::new(OllamaBackend),
+        }
+    }
+}
+
+/// Native Gemini `generateContent` wire format.
+pub struct GeminiBackend;
+
+impl Backend for GeminiBackend {
+    fn build_request(&self, contents: &[Content], tools: &[ToolSpec]) -> Value {
+        let function_declarations: Vec<Value> = tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.parameters,
+                })
+            })
+            .collect();
+
+        json!({
+            "contents": contents,
+            "tools": if function_declarations.is_empty() {
+                Value::Null
+            } else {
+                json!([{ "functionDeclarations": function_declarations }])
+            },
+        })
+    }
+
+    fn parse_response(&self, raw: &Value) -> Result<Vec<Part>> {
+        let parts = raw
+            .pointer("/candidates/0/content/parts")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("no parts in Gemini response"))?;
+
+        Ok(parts
+            .iter()
+            .filter_map(|p| serde_json::from_value(p.clone()).ok())
+            .collect())
+    }
+
+    fn endpoint(&self, model: &str) -> String {
+        format!("https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent")
+    }
+}
+
+/// OpenAI-compatible `chat/completions` wire format (also used by most
+/// OpenAI-compatible gateways and local servers, e.g. LocalAI).
+pub struct OpenAiBackend {
+    /// Base URL requests are sent under, e.g. `https://api.openai.com/v1`
+    /// or `http://localhost:8080/v1` for a local LocalAI instance.
+    api_base: String,
+}
+
+impl Default for OpenAiBackend {
+    fn default() -> Self {
+        Self { api_base: "https://api.openai.com/v1".to_string() }
+    }
+}
+
+impl OpenAiBackend {
+    /// Point this backend at a non-default `api_base`, for LocalAI or any
+    /// other OpenAI-compatible server.
+    pub fn new(api_base: impl Into<String>) -> Self {
+        Self { api_base: api_base.into() }
+    }
+}
+
+impl Backend for OpenAiBackend {
+    fn build_request(&self, contents: &[Content], tools: &[ToolSpec]) -> Value {
+        let messages: Vec<Value> = contents
+            .iter()
+            .map(|c| {
+                json!({
+                    "role": if c.role == "model" { "assistant" } else { c.role.as_str() },
+                    "content": c.parts.iter().filter_map(|p| p.text.clone()).collect::<Vec<_>>().join("\n"),
+                })
+            })
+            .collect();
+
+        let functions: Vec<Value> = tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.parameters,
+                    }
+                })
+            })
+            .collect();
+
+        json!({
+            "messages": messages,
+            "tools": if functions.is_empty() { Value::Null } else { Value::Array(functions) },
+        })
+    }
+
+    fn parse_response(&self, raw: &Value) -> Result<Vec<Part>> {
+        let message = raw
+            .pointer("/choices/0/message")
+            .ok_or_else(|| anyhow::anyhow!("no message in OpenAI response"))?;
+
+        let mut parts = Vec::new();
+
+        if let Some(text) = message.get("content").and_then(Value::as_str) {
+            parts.push(Part {
+                text: Some(text.to_string()),
+                function_call: None,
+                function_response: None,
+            });
+        }
+
+        if let Some(calls) = message.get("tool_calls").and_then(Value::as_array) {
+            for call in calls {
+                let name = call
+                    .pointer("/function/name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let args_raw = call
+                    .pointer("/function/arguments")
+                    .and_then(Value::as_str)
+                    .unwrap_or("{}");
+                let args = serde_json::from_str(args_raw).unwrap_or(Value::Null);
+                parts.push(Part {
+                    text: None,
+                    function_call: Some(FunctionCall { name, args }),
+                    function_response: None,
+                });
+            }
+        }
+
+        Ok(parts)
+    }
+
+    fn endpoint(&self, _model: &str) -> String {
+        format!("{}/chat/completions", self.api_base.trim_end_matches('/'))
+    }
+}
+
+/// Anthropic `messages` wire format.
+pub struct AnthropicBackend;
+
+impl Backend for AnthropicBackend {
+    fn build_request(&self, contents: &[Content], tools: &[ToolSpec]) -> Value {
+        let messages: Vec<Value> = contents
+            .iter()
+            .map(|c| {
+                json!({
+                    "role": if c.role == "model" { "assistant" } else { "user" },
+                    "content": c.parts.iter().filter_map(|p| p.text.clone()).collect::<Vec<_>>().join("\n"),
+                })
+            })
+            .collect();
+
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "input_schema": t.parameters,
+                })
+            })
+            .collect();
+
+        json!({
+            "messages": messages,
+            "tools": if tools.is_empty() { Value::Null } else { Value::Array(tools) },
+        })
+    }
+
+    fn parse_response(&self, raw: &Value) -> Result<Vec<Part>> {
+        let blocks = raw
+            .get("content")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("no content in Anthropic response"))?;
+
+        let mut parts = Vec::new();
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => {
+                    if let Some(text) = block.get("text").and_then(Value::as_str) {
+                        parts.push(Part {
+                            text: Some(text.to_string()),
+                            function_call: None,
+                            function_response: None,
+                        });
+                    }
+                }
+                Some("tool_use") => {
+                    let name = block.get("name").and_then(Value::as_str).unwrap_or_default();
+                    let args = block.get("input").cloned().unwrap_or(Value::Null);
+                    parts.push(Part {
+                        text: None,
+                        function_call: Some(FunctionCall {
+                            name: name.to_string(),
+                            args,
+                        }),
+                        function_response: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(parts)
+    }
+
+    fn endpoint(&self, _model: &str) -> String {
+        "https://api.anthropic.com/v1/messages".to_string()
+    }
+}
+
+/// Local Ollama `/api/chat` wire format.
+pub struct OllamaBackend;
+
+impl Backend for OllamaBackend {
+    fn build_request(&self, contents: &[Content], tools: &[ToolSpec]) -> Value {
+        let messages: Vec<Value> = contents
+            .iter()
+            .map(|c| {
+                json!({
+                    "role": if c.role == "model" { "assistant" } else { c.role.as_str() },
+                    "content": c.parts.iter().filter_map(|p| p.text.clone()).collect::<Vec<_>>().join("\n"),
+                })
+            })
+            .collect();
+
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.parameters,
+                    }
+                })
+            })
+            .collect();
+
+        json!({
+            "messages": messages,
+            "tools": if tools.is_empty() { Value::Null } else { Value::Array(tools) },
+            "stream": false,
+        })
+    }
+
+    fn parse_response(&self, raw: &Value) -> Result<Vec<Part>> {
+        let message = raw
+            .get("message")
+            .ok_or_else(|| anyhow::anyhow!("no message in Ollama response"))?;
+
+        let mut parts = Vec::new();
+        if let Some(text) = message.get("content").and_then(Value::as_str) {
+            if !text.is_empty() {
+                parts.push(Part {
+                    text: Some(text.to_string()),
+                    function_call: None,
+                    function_response: None,
+                });
+            }
+        }
+        if let Some(calls) = message.get("tool_calls").and_then(Value::as_array) {
+            for call in calls {
+                let name = call
+                    .pointer("/function/name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let args = call.pointer("/function/arguments").cloned().unwrap_or(Value::Null);
+                parts.push(Part {
+                    text: None,
+                    function_call: Some(FunctionCall { name, args }),
+                    function_response: None,
+                });
+            }
+        }
+        Ok(parts)
+    }
+
+    fn endpoint(&self, _model: &str) -> String {
+        "http://localhost:11434/api/chat".to_string()
+    }
+}
+
+/// A client for any provider a [`Backend`] can translate to/from, including
+/// OpenAI-compatible endpoints such as LocalAI. Mirrors
+/// [`crate::api::GeminiClient`]'s `send_message`/`send_message_with_tools`
+/// shape so the REPL's simple chat loop can run against either; the
+/// self-modification agentic tool-calling loop in [`crate::functions`]
+/// remains Gemini-specific.
+pub struct BackendClient {
+    http: Client,
+    backend: Box<dyn Backend>,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl BackendClient {
+    /// Create a client for `provider`'s default endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying HTTP client cannot be built.
+    pub fn new(provider: Provider, model: String, api_key: Option<String>) -> Result<Self> {
+        Self::with_api_base(provider, model, api_key, None)
+    }
+
+    /// Like [`Self::new`], but overrides the provider's default endpoint
+    /// with `api_base` — for `Provider::OpenAi`, this is how the client is
+    /// pointed at LocalAI or another OpenAI-compatible server instead of
+    /// `api.openai.com`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying HTTP client cannot be built.
+    pub fn with_api_base(
+        provider: Provider,
+        model: String,
+        api_key: Option<String>,
+        api_base: Option<String>,
+    ) -> Result<Self> {
+        let http = Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self {
+            http,
+            backend: provider.backend_with_api_base(api_base),
+            model,
+            api_key,
+        })
+    }
+
+    /// Send a message without tools.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response has no parts.
+    pub async fn send_message(&self, conversation: &[Content]) -> Result<String> {
+        self.send_message_with_tools(conversation, &[]).await
+    }
+
+    /// Send a message with tool definitions, returning just the first text
+    /// part of the reply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response has no parts.
+    pub async fn send_message_with_tools(&self, conversation: &[Content], tools: &[ToolSpec]) -> Result<String> {
+        let content = self.send_turn(conversation, tools).await?;
+        Ok(content
+            .parts
+            .iter()
+            .find_map(|p| p.text.clone())
+            .unwrap_or_else(|| "No text in response".to_string()))
+    }
+
+    /// Send a turn and return the full model `Content` (all parts,
+    /// including any `FunctionCall`s).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the response status isn't
+    /// successful, or the response has no parts.
+    pub async fn send_turn(&self, conversation: &[Content], tools: &[ToolSpec]) -> Result<Content> {
+        let body = self.backend.build_request(conversation, tools);
+
+        let mut request = self.http.post(self.backend.endpoint(&self.model)).json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("API request failed with status {status}: {}", response.text().await?);
+        }
+
+        let raw: Value = response.json().await?;
+        let parts = self.backend.parse_response(&raw)?;
+        if parts.is_empty() {
+            bail!("No parts in response");
+        }
+
+        Ok(Content { role: "model".to_string(), parts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_provider_names() {
+        assert!(matches!(Provider::parse("gemini"), Ok(Provider::Gemini)));
+        assert!(matches!(Provider::parse("openai"), Ok(Provider::OpenAi)));
+        assert!(matches!(Provider::parse("anthropic"), Ok(Provider::Anthropic)));
+        assert!(matches!(Provider::parse("ollama"), Ok(Provider::Ollama)));
+        assert!(Provider::parse("bogus").is_err());
+    }
+
+    #[test]
+    fn openai_backend_defaults_to_the_public_api() {
+        assert_eq!(OpenAiBackend::default().endpoint("gpt-4o"), "https://api.openai.com/v1/chat/completions");
+    }
+
+    #[test]
+    fn openai_backend_honors_a_custom_api_base_for_localai() {
+        let backend = OpenAiBackend::new("http://localhost:8080/v1");
+        assert_eq!(backend.endpoint("any-model"), "http://localhost:8080/v1/chat/completions");
+    }
+
+    #[test]
+    fn openai_backend_strips_a_trailing_slash_from_a_custom_api_base() {
+        let backend = OpenAiBackend::new("http://localhost:8080/v1/");
+        assert_eq!(backend.endpoint("any-model"), "http://localhost:8080/v1/chat/completions");
+    }
+
+    #[test]
+    fn backend_with_api_base_only_affects_openai() {
+        let gemini = Provider::Gemini.backend_with_api_base(Some("http://localhost:1234".to_string()));
+        assert_eq!(gemini.endpoint("gemini-2.0-flash"), GeminiBackend.endpoint("gemini-2.0-flash"));
+    }
+
+    #[test]
+    fn tool_spec_from_value_converts_a_tool_definition() {
+        let value = json!({"name": "read_file", "description": "reads a file", "parameters": {"type": "object"}});
+        let spec = ToolSpec::from_value(&value).unwrap();
+        assert_eq!(spec.name, "read_file");
+        assert_eq!(spec.description, "reads a file");
+    }
+
+    #[test]
+    fn tool_spec_from_value_requires_a_name() {
+        assert!(ToolSpec::from_value(&json!({"description": "no name"})).is_none());
+    }
+
+    #[test]
+    fn openai_backend_parses_tool_calls() {
+        let raw = json!({
+            "choices": [{
+                "message": {
+                    "content": null,
+                    "tool_calls": [{
+                        "function": { "name": "read_file", "arguments": "{\"file_path\":\"Makefile\"}" }
+                    }]
+                }
+            }]
+        });
+        let parts = OpenAiBackend.parse_response(&raw).unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].function_call.as_ref().unwrap().name, "read_file");
+    }
+}
@@ -0,0 +1,274 @@
+//! Layered configuration for constructing a [`crate::api::GeminiClient`].
+//!
+//! Values are resolved in increasing priority, each layer only overriding
+//! fields the previous layer actually set: built-in defaults, then
+//! `gemini.toml` (if present), then environment variables, then explicit
+//! CLI overrides. This mirrors how config crates like `figment` merge
+//! layers, without adding that dependency.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::api::{RetryPolicy, DEFAULT_BASE_URL};
+use crate::tools::permissions::{PermissionsPolicy, ToolFlagPolicy};
+use crate::tools::rust_tools::DEFAULT_TOOL_TIMEOUT_MS;
+
+const DEFAULT_MODEL: &str = "gemini-2.0-flash-exp";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// A single, possibly-partial configuration layer. Every field is
+/// optional so a layer (an env var that wasn't set, a `gemini.toml` that
+/// omits a key) can leave a field for a lower-priority layer to fill in.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GeminiConfigLayer {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub base_url: Option<String>,
+    pub system_instruction: Option<String>,
+    pub retry_max_attempts: Option<u32>,
+    pub retry_base_delay_ms: Option<u64>,
+    pub retry_max_delay_secs: Option<u64>,
+    /// Per-invocation default for a tool call's own `timeout_ms` parameter,
+    /// applied when a call doesn't set one.
+    pub tool_timeout_ms: Option<u64>,
+    /// `[permissions]` table: which tools/flags an agent may use. Absent
+    /// entirely means "no additional restriction" (least-surprise default
+    /// for a config file written before this section existed).
+    pub permissions: Option<PermissionsConfigLayer>,
+}
+
+/// The `[permissions]` table in `gemini.toml`. Unlike the scalar fields
+/// above, this isn't deep-merged across layers: a layer that sets
+/// `permissions` at all replaces the whole table from lower-priority
+/// layers, the same way a `gemini.toml` author would expect redeclaring a
+/// TOML table to replace it rather than splice it.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PermissionsConfigLayer {
+    /// Tool names this agent may invoke. `None` allows every registered
+    /// tool (today's behavior); `Some(vec![])` allows none.
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+    /// Workspace paths tools may read or write. Empty means no additional
+    /// restriction beyond each tool's own workspace sandbox.
+    #[serde(default)]
+    pub workspace_paths: Vec<PathBuf>,
+    /// Per-tool CLI flag allow/deny lists, keyed by tool name (e.g.
+    /// `clippy`, `cargo_build`), e.g. forbidding `--fix` or `--release`.
+    #[serde(default)]
+    pub flags: HashMap<String, ToolFlagPolicy>,
+    /// When true, tools report what they would have run instead of
+    /// spawning anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl From<PermissionsConfigLayer> for PermissionsPolicy {
+    fn from(layer: PermissionsConfigLayer) -> Self {
+        PermissionsPolicy {
+            allowed_tools: layer.allowed_tools.map(|tools| tools.into_iter().collect()),
+            workspace_paths: layer.workspace_paths,
+            flags: layer.flags,
+            dry_run: layer.dry_run,
+        }
+    }
+}
+
+impl GeminiConfigLayer {
+    /// Reads `GEMINI_API_KEY`, `GEMINI_MODEL`, `GEMINI_TIMEOUT_SECS`,
+    /// `GEMINI_BASE_URL`, `GEMINI_SYSTEM_INSTRUCTION`,
+    /// `GEMINI_RETRY_MAX_ATTEMPTS`, `GEMINI_RETRY_BASE_DELAY_MS`, and
+    /// `GEMINI_RETRY_MAX_DELAY_SECS`. A variable that's unset or fails to
+    /// parse is left as `None` rather than erroring the whole layer.
+    pub fn from_env() -> Self {
+        Self {
+            api_key: std::env::var("GEMINI_API_KEY").ok(),
+            model: std::env::var("GEMINI_MODEL").ok(),
+            timeout_secs: std::env::var("GEMINI_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()),
+            base_url: std::env::var("GEMINI_BASE_URL").ok(),
+            system_instruction: std::env::var("GEMINI_SYSTEM_INSTRUCTION").ok(),
+            retry_max_attempts: std::env::var("GEMINI_RETRY_MAX_ATTEMPTS").ok().and_then(|v| v.parse().ok()),
+            retry_base_delay_ms: std::env::var("GEMINI_RETRY_BASE_DELAY_MS").ok().and_then(|v| v.parse().ok()),
+            retry_max_delay_secs: std::env::var("GEMINI_RETRY_MAX_DELAY_SECS").ok().and_then(|v| v.parse().ok()),
+            tool_timeout_ms: std::env::var("GEMINI_TOOL_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()),
+            // No env-var equivalent: a table this shaped doesn't map onto
+            // a single environment variable, so it's only ever set from a
+            // `gemini.toml` file or the CLI layer.
+            permissions: None,
+        }
+    }
+
+    /// Reads a TOML layer from `path`. A missing file is treated as an
+    /// empty layer (not an error) since `gemini.toml` is optional; a
+    /// present-but-malformed file still fails loudly.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                toml::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Merges `self` with `override_layer`, preferring `override_layer`'s
+    /// value for every field it sets.
+    fn merge(self, override_layer: Self) -> Self {
+        Self {
+            api_key: override_layer.api_key.or(self.api_key),
+            model: override_layer.model.or(self.model),
+            timeout_secs: override_layer.timeout_secs.or(self.timeout_secs),
+            base_url: override_layer.base_url.or(self.base_url),
+            system_instruction: override_layer.system_instruction.or(self.system_instruction),
+            retry_max_attempts: override_layer.retry_max_attempts.or(self.retry_max_attempts),
+            retry_base_delay_ms: override_layer.retry_base_delay_ms.or(self.retry_base_delay_ms),
+            retry_max_delay_secs: override_layer.retry_max_delay_secs.or(self.retry_max_delay_secs),
+            tool_timeout_ms: override_layer.tool_timeout_ms.or(self.tool_timeout_ms),
+            permissions: override_layer.permissions.or(self.permissions),
+        }
+    }
+}
+
+/// Fully-resolved configuration for [`crate::api::GeminiClient::from_config`].
+/// Unlike [`GeminiConfigLayer`], every field here is required and has
+/// already been defaulted or validated.
+#[derive(Debug, Clone)]
+pub struct GeminiConfig {
+    pub api_key: String,
+    pub model: String,
+    pub timeout: Duration,
+    /// Host for `Endpoint::Public` requests; see [`crate::api::GeminiClient::with_base_url`].
+    pub base_url: String,
+    pub system_instruction: Option<String>,
+    pub retry_policy: RetryPolicy,
+    pub tool_timeout_ms: u64,
+    pub permissions: PermissionsPolicy,
+}
+
+impl GeminiConfig {
+    /// Resolves a [`GeminiConfig`] by merging, from lowest to highest
+    /// priority: built-in defaults, `gemini.toml` in the current
+    /// directory, environment variables, then `cli` (whatever the caller
+    /// parsed from command-line flags).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the missing field if `api_key` isn't set by
+    /// any layer, or if `gemini.toml` exists but fails to parse.
+    pub fn load(cli: GeminiConfigLayer) -> Result<Self> {
+        let file = GeminiConfigLayer::from_file(Path::new("gemini.toml"))?;
+        let resolved = GeminiConfigLayer::default()
+            .merge(file)
+            .merge(GeminiConfigLayer::from_env())
+            .merge(cli);
+
+        Self::from_layer(resolved)
+    }
+
+    /// Like [`Self::load`], but reads the `gemini.toml` layer from
+    /// `config_path` instead of the current directory. Exposed so callers
+    /// (and tests) can point at a specific file.
+    pub fn load_from(config_path: &Path, cli: GeminiConfigLayer) -> Result<Self> {
+        let file = GeminiConfigLayer::from_file(config_path)?;
+        let resolved = GeminiConfigLayer::default()
+            .merge(file)
+            .merge(GeminiConfigLayer::from_env())
+            .merge(cli);
+
+        Self::from_layer(resolved)
+    }
+
+    fn from_layer(layer: GeminiConfigLayer) -> Result<Self> {
+        let api_key = layer
+            .api_key
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("missing configuration field: api-key"))?;
+
+        let default_retry = RetryPolicy::default();
+
+        Ok(Self {
+            api_key,
+            model: layer.model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            timeout: Duration::from_secs(layer.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+            base_url: layer.base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            system_instruction: layer.system_instruction,
+            retry_policy: RetryPolicy {
+                max_attempts: layer.retry_max_attempts.unwrap_or(default_retry.max_attempts),
+                base_delay: layer
+                    .retry_base_delay_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(default_retry.base_delay),
+                max_delay: layer
+                    .retry_max_delay_secs
+                    .map(Duration::from_secs)
+                    .unwrap_or(default_retry.max_delay),
+            },
+            tool_timeout_ms: layer.tool_timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS),
+            permissions: layer.permissions.map(PermissionsPolicy::from).unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_api_key_names_the_field() {
+        let err = GeminiConfig::from_layer(GeminiConfigLayer::default()).unwrap_err();
+        assert!(err.to_string().contains("missing configuration field: api-key"));
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let base = GeminiConfigLayer {
+            api_key: Some("base-key".to_string()),
+            model: Some("base-model".to_string()),
+            ..GeminiConfigLayer::default()
+        };
+        let override_layer = GeminiConfigLayer {
+            model: Some("override-model".to_string()),
+            ..GeminiConfigLayer::default()
+        };
+
+        let merged = base.merge(override_layer);
+        assert_eq!(merged.api_key.as_deref(), Some("base-key"));
+        assert_eq!(merged.model.as_deref(), Some("override-model"));
+    }
+
+    #[test]
+    fn unset_fields_fall_back_to_defaults() {
+        let config = GeminiConfig::from_layer(GeminiConfigLayer {
+            api_key: Some("key".to_string()),
+            ..GeminiConfigLayer::default()
+        })
+        .unwrap();
+
+        assert_eq!(config.model, DEFAULT_MODEL);
+        assert_eq!(config.timeout, Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn missing_config_file_is_an_empty_layer_not_an_error() {
+        let layer = GeminiConfigLayer::from_file(Path::new("/nonexistent/gemini.toml")).unwrap();
+        assert!(layer.api_key.is_none());
+    }
+
+    #[test]
+    fn loads_fields_from_a_toml_file() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-config-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("gemini.toml");
+        std::fs::write(&path, "api_key = \"from-file\"\nmodel = \"from-file-model\"\n").unwrap();
+
+        let layer = GeminiConfigLayer::from_file(&path).unwrap();
+        assert_eq!(layer.api_key.as_deref(), Some("from-file"));
+        assert_eq!(layer.model.as_deref(), Some("from-file-model"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 //! Custom error types for the Gemini REPL
 
 use std::fmt;
+use std::time::Duration;
 
 /// Main error type for the Gemini REPL
 #[derive(Debug)]
@@ -39,8 +40,17 @@ pub enum ToolError {
 pub enum ApiError {
     /// Authentication failed
     Authentication(String),
-    /// Rate limit exceeded
-    RateLimit,
+    /// Rate limited (HTTP 429). Carries how long to wait before retrying,
+    /// taken from the `Retry-After` header or the error body's
+    /// `retryDelay` field when the server sent one.
+    RateLimited { retry_after: Option<Duration> },
+    /// The server reported a transient failure (HTTP 5xx) that retries
+    /// didn't resolve.
+    ServerError(u16),
+    /// The server rejected the request outright (HTTP 4xx other than 429)
+    /// and retrying would fail the same way, so the raw error body is
+    /// surfaced as-is.
+    BadRequest(String),
     /// Network error
     Network(String),
     /// Invalid response
@@ -87,7 +97,12 @@ impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ApiError::Authentication(msg) => write!(f, "Authentication failed: {}", msg),
-            ApiError::RateLimit => write!(f, "Rate limit exceeded"),
+            ApiError::RateLimited { retry_after: Some(d) } => {
+                write!(f, "Rate limit exceeded, retry after {:.1}s", d.as_secs_f64())
+            }
+            ApiError::RateLimited { retry_after: None } => write!(f, "Rate limit exceeded"),
+            ApiError::ServerError(status) => write!(f, "Server error (HTTP {})", status),
+            ApiError::BadRequest(body) => write!(f, "Bad request: {}", body),
             ApiError::Network(msg) => write!(f, "Network error: {}", msg),
             ApiError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
         }
@@ -1,8 +1,192 @@
 //! Function definitions for tool calling
 
-use crate::api::{FunctionDeclaration, FunctionParameters, ParameterProperty, Tool};
+use crate::api::{Content, FunctionDeclaration, FunctionParameters, FunctionResponse, ParameterProperty, Part, Tool};
+use crate::tools::ToolRegistry;
+use anyhow::Result;
+use futures::StreamExt;
 use std::collections::HashMap;
 
+/// Hard cap on round-trips in [`run_agent_loop`], guarding against a model
+/// that keeps calling tools (or keeps calling the same tool) forever.
+pub const DEFAULT_MAX_STEPS: usize = 8;
+
+/// Whether invoking a tool can change state on disk or in the environment.
+///
+/// The agent loop runs [`Effect::Pure`] tools without asking, but pauses
+/// before running an [`Effect::Mutates`] tool so the user gets a chance to
+/// confirm it — unless `/yolo` (auto-approval) is enabled for the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Only reads state; safe to run without confirmation.
+    Pure,
+    /// Writes, deletes, or runs commands; requires confirmation.
+    Mutates,
+}
+
+/// Classify a tool by name. Unknown tools are treated as `Mutates` so a
+/// newly-added tool fails safe (requires confirmation) until explicitly
+/// classified here. By convention a tool named with a `may_` prefix is
+/// always side-effecting, so it's a mistake for one to end up in the
+/// `Pure` list below.
+pub fn effect_of(tool_name: &str) -> Effect {
+    debug_assert!(
+        !tool_name.starts_with("may_"),
+        "tool '{tool_name}' uses the may_ prefix convention for side-effecting tools; it can't be Pure"
+    );
+
+    match tool_name {
+        "read_file" | "list_files" | "search_code" | "find_files" | "detect_language" | "disk_usage" => Effect::Pure,
+        _ => Effect::Mutates,
+    }
+}
+
+/// Best-effort extraction of the path a mutating call's arguments reference,
+/// checked across every path-like key this crate's tools use for one
+/// (`path` for most file/Rust tools, `file_path`/`directory` for a few
+/// others) rather than just `write_file`'s, so invalidation isn't limited
+/// to one tool's argument shape.
+fn mutated_path(args: &serde_json::Value) -> Option<&str> {
+    ["path", "file_path", "directory"]
+        .iter()
+        .find_map(|key| args.get(key).and_then(|v| v.as_str()))
+}
+
+/// Asks the user (or an automated policy) whether to proceed with a
+/// state-mutating tool call. Implementations back this with a readline
+/// prompt in the REPL, or always return `true` when `/yolo` is enabled.
+pub trait ConfirmMutation {
+    fn confirm(&self, tool_name: &str, args: &serde_json::Value) -> bool;
+}
+
+/// Auto-approves every mutation. Used when the session has `/yolo` enabled.
+pub struct AutoApprove;
+
+impl ConfirmMutation for AutoApprove {
+    fn confirm(&self, _tool_name: &str, _args: &serde_json::Value) -> bool {
+        true
+    }
+}
+
+/// Default confirmation policy: prompt on stdin when attached to a
+/// terminal, auto-deny otherwise. A script piping input into the REPL (or
+/// a CI job) has no one to answer a prompt, so it fails safe rather than
+/// blocking forever on a read that will never come.
+pub struct StdinConfirm;
+
+impl ConfirmMutation for StdinConfirm {
+    fn confirm(&self, tool_name: &str, args: &serde_json::Value) -> bool {
+        use std::io::IsTerminal;
+
+        if !std::io::stdin().is_terminal() {
+            tracing::warn!(tool = tool_name, "denied non-interactively: no terminal to prompt");
+            return false;
+        }
+
+        print!("Allow '{tool_name}' with args {args}? [y/N] ");
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(_) => matches!(line.trim().to_lowercase().as_str(), "y" | "yes"),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Memoizes tool call results within a single [`run_agent_loop`] run, keyed
+/// on `(function_call.name, canonicalized args JSON)`. Opt-in: a caller
+/// passes `None` to skip caching entirely, e.g. in tests that expect every
+/// call to hit the registry.
+///
+/// A model sometimes re-requests an identical call (the same tool, the
+/// same arguments) a few steps later in a conversation, e.g. re-reading a
+/// file it already read. Serving that from cache avoids the redundant I/O
+/// and the round-trip token cost of feeding the result back in.
+#[derive(Default)]
+pub struct ToolCache {
+    entries: std::sync::Mutex<HashMap<(String, String), serde_json::Value>>,
+    hits: std::sync::atomic::AtomicU64,
+    misses: std::sync::atomic::AtomicU64,
+}
+
+impl ToolCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the memoized result for `(name, args)`, if any, and count the
+    /// lookup towards `/cache`'s hit/miss stats.
+    pub fn get(&self, name: &str, args: &serde_json::Value) -> Option<serde_json::Value> {
+        let result = self.entries.lock().unwrap().get(&Self::key(name, args)).cloned();
+        let counter = if result.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        result
+    }
+
+    /// Number of `get` calls that found a memoized result.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of `get` calls that found nothing memoized.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Number of results currently memoized.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no results are currently memoized.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every memoized result and reset the hit/miss counters.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+        self.hits.store(0, std::sync::atomic::Ordering::Relaxed);
+        self.misses.store(0, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Memoize `result` for `(name, args)`.
+    pub fn insert(&self, name: &str, args: &serde_json::Value, result: serde_json::Value) {
+        self.entries.lock().unwrap().insert(Self::key(name, args), result);
+    }
+
+    /// Drop every cached entry whose canonicalized args mention `path`, so
+    /// a write through `write_file` can't leave a stale read served back
+    /// from cache. Path matching is a substring check on the canonicalized
+    /// args JSON rather than parsing each tool's own argument shape, so it
+    /// covers `file_path`, `directory`, and any future path-bearing field
+    /// without a tool-by-tool mapping.
+    pub fn invalidate_path(&self, path: &str) {
+        self.entries.lock().unwrap().retain(|(_, args_json), _| !args_json.contains(path));
+    }
+
+    /// Canonicalize `args` into a JSON string with object keys sorted, so
+    /// two calls with the same arguments in a different key order hash to
+    /// the same cache key.
+    fn key(name: &str, args: &serde_json::Value) -> (String, String) {
+        (name.to_string(), serde_json::to_string(&Self::sorted(args)).unwrap_or_default())
+    }
+
+    fn sorted(value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let sorted: std::collections::BTreeMap<_, _> =
+                    map.iter().map(|(k, v)| (k.clone(), Self::sorted(v))).collect();
+                serde_json::to_value(sorted).unwrap_or(serde_json::Value::Null)
+            }
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(Self::sorted).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
 /// Get all available tools for function calling
 pub fn get_available_tools() -> Vec<Tool> {
     vec![
@@ -115,4 +299,125 @@ pub fn get_available_tools() -> Vec<Tool> {
             ],
         },
     ]
+}
+
+/// Run the agentic function-calling loop: send `conversation` to the model,
+/// and for every `FunctionCall` part it returns, execute the matching tool
+/// via `registry` and feed the `FunctionResponse`s back, repeating until the
+/// model returns a text-only turn or `max_steps` round-trips are used up.
+///
+/// `client` is generic over every backend `crate::ChatClient` wraps
+/// (Gemini, OpenAI-compatible, and offline replay all implement
+/// `send_turn`), so the loop drives the same multi-step tool execution
+/// regardless of which provider is configured.
+///
+/// Function calls within a single model turn are executed concurrently, up
+/// to `max_parallel` at a time, but the resulting `FunctionResponse` parts
+/// are still appended in the same order as the `FunctionCall` parts that
+/// produced them, since the API requires the two to line up positionally.
+pub async fn run_agent_loop(
+    client: &crate::ChatClient,
+    conversation: &mut Vec<Content>,
+    registry: &ToolRegistry,
+    max_steps: usize,
+    max_parallel: usize,
+    confirm: &dyn ConfirmMutation,
+    cache: Option<&ToolCache>,
+) -> Result<()> {
+    let tool_defs = registry.get_tool_definitions();
+    let mut previous_calls: Option<Vec<(String, serde_json::Value)>> = None;
+
+    for step in 0..max_steps.max(1) {
+        let turn = client
+            .send_turn(conversation, Some(tool_defs.clone()))
+            .await?;
+
+        let calls: Vec<_> = turn
+            .parts
+            .iter()
+            .filter_map(|p| p.function_call.clone())
+            .collect();
+
+        conversation.push(turn);
+
+        if calls.is_empty() {
+            tracing::debug!("agent loop finished after {} step(s)", step + 1);
+            return Ok(());
+        }
+
+        let this_round: Vec<_> = calls.iter().map(|c| (c.name.clone(), c.args.clone())).collect();
+        if previous_calls.as_ref() == Some(&this_round) {
+            anyhow::bail!("agent loop aborted: model repeated the same function call(s)");
+        }
+        previous_calls = Some(this_round);
+
+        tracing::info!(step = step + 1, calls = calls.len(), "executing tool call(s)");
+
+        // Execute every call in this turn concurrently, up to `max_parallel`
+        // at a time, preserving order. Mutating tools are gated on
+        // `confirm` before they run.
+        let executions = calls.into_iter().map(|call| {
+            let registry = registry;
+            async move {
+                // Only ever cache `Effect::Pure` results, so a cache hit can
+                // never be the thing that lets a mutation skip its
+                // confirmation prompt. A hit still runs `check_tool` (the
+                // same policy/permission gate `execute_tool` enforces) so a
+                // memoized `read_file` can't bypass a workspace-path or
+                // permission restriction just because it's replayed from
+                // memory instead of actually touching disk.
+                let cached = cache
+                    .filter(|_| effect_of(&call.name) == Effect::Pure)
+                    .and_then(|c| c.get(&call.name, &call.args));
+
+                let response = if let Some(cached) = cached {
+                    match registry.check_tool(&call.name, &call.args) {
+                        Ok(()) => {
+                            tracing::info!(tool = %call.name, "tool cache hit, skipping execution");
+                            cached
+                        }
+                        Err(e) => serde_json::json!({ "error": e.to_string() }),
+                    }
+                } else if effect_of(&call.name) == Effect::Mutates
+                    && !confirm.confirm(&call.name, &call.args)
+                {
+                    serde_json::json!({ "error": format!("'{}' was not approved by the user", call.name) })
+                } else {
+                    match registry.execute_tool(&call.name, call.args.clone()).await {
+                        Ok(value) => {
+                            if let Some(cache) = cache {
+                                if effect_of(&call.name) == Effect::Pure {
+                                    cache.insert(&call.name, &call.args, value.clone());
+                                } else if let Some(path) = mutated_path(&call.args) {
+                                    // Any mutating tool can invalidate a
+                                    // stale cached read of the same path,
+                                    // not just `write_file`.
+                                    cache.invalidate_path(path);
+                                }
+                            }
+                            value
+                        }
+                        Err(e) => serde_json::json!({ "error": e.to_string() }),
+                    }
+                };
+                Part {
+                    text: None,
+                    function_call: None,
+                    function_response: Some(FunctionResponse {
+                        name: call.name,
+                        response,
+                    }),
+                }
+            }
+        });
+        let response_parts: Vec<Part> =
+            futures::stream::iter(executions).buffered(max_parallel.max(1)).collect().await;
+
+        conversation.push(Content {
+            role: "function".to_string(),
+            parts: response_parts,
+        });
+    }
+
+    anyhow::bail!("agent loop exceeded max_steps ({max_steps}) without a final answer")
 }
\ No newline at end of file
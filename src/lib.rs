@@ -1,11 +1,20 @@
 //! Gemini REPL library
 
 pub mod api;
+pub mod backends;
+pub mod config;
+pub mod errors;
 pub mod functions;
 pub mod logging;
+pub mod replay;
+pub mod tools;
+pub mod vertex_auth;
+pub mod watch;
 
 // Re-export public API
 pub use api::{Content, GeminiClient, Part};
+pub use backends::{Backend, BackendClient, Provider, ToolSpec};
+pub use replay::{ReplayClient, ReplayMode};
 
 #[must_use]
 pub fn add(a: i32, b: i32) -> i32 {
@@ -1,11 +1,14 @@
 //! Logging infrastructure for debugging and request/response capture
 
-use anyhow::Result;
+use crate::vertex_auth::AdcTokenProvider;
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
@@ -23,14 +26,200 @@ pub struct ApiLogEntry {
     pub duration_ms: Option<u64>,
 }
 
+/// Where captured request/response JSONL lines get written. `ApiLogger`
+/// writes through this instead of hardcoding the filesystem, so a caller
+/// running in a container with no local volume can plug in something like
+/// [`GcsSink`] instead.
+pub trait LogSink: Send + Sync {
+    /// Append one JSONL `line` to the request log for `key` (a
+    /// filesystem/object-storage-safe name derived from the request's
+    /// host and path).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line can't be persisted.
+    fn append_request(&self, key: &str, line: &str) -> Result<()>;
+
+    /// Append one JSONL `line` to the response log for `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line can't be persisted.
+    fn append_response(&self, key: &str, line: &str) -> Result<()>;
+}
+
+/// The original behavior: one `reqs.jsonl`/`resps.jsonl` pair per `key`
+/// under `base_dir`.
+pub struct FsSink {
+    base_dir: PathBuf,
+}
+
+impl FsSink {
+    pub fn new(base_dir: impl AsRef<Path>) -> Self {
+        Self { base_dir: base_dir.as_ref().to_path_buf() }
+    }
+
+    fn append(&self, key: &str, file_name: &str, line: &str) -> Result<()> {
+        let dir = self.base_dir.join(key);
+        fs::create_dir_all(&dir)?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(dir.join(file_name))?;
+        writeln!(file, "{line}")?;
+
+        Ok(())
+    }
+}
+
+impl LogSink for FsSink {
+    fn append_request(&self, key: &str, line: &str) -> Result<()> {
+        self.append(key, "reqs.jsonl", line)
+    }
+
+    fn append_response(&self, key: &str, line: &str) -> Result<()> {
+        self.append(key, "resps.jsonl", line)
+    }
+}
+
+/// Buffers JSONL lines in memory and uploads them to
+/// `gs://{bucket}/{prefix}/{key}/{reqs,resps}.jsonl` via the GCS resumable
+/// upload protocol, authenticating the same way [`AdcTokenProvider`] does
+/// for Vertex AI.
+///
+/// GCS objects can't be appended to in place, so each flush re-uploads the
+/// full accumulated buffer for that object rather than just the new line
+/// — it's still framed as a resumable session (initiate, then a single
+/// `Content-Range` PUT spanning the whole body) so a flush interrupted
+/// mid-upload can resume instead of restarting from byte zero.
+///
+/// Each call drives its network requests on a dedicated single-threaded
+/// Tokio runtime, since [`LogSink`]'s methods are synchronous; don't call
+/// into a `GcsSink` from inside an existing Tokio runtime.
+pub struct GcsSink {
+    bucket: String,
+    prefix: String,
+    http: reqwest::Client,
+    credentials: AdcTokenProvider,
+    buffers: Mutex<HashMap<String, String>>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl GcsSink {
+    /// Creates a new GCS sink, locating Application Default Credentials
+    /// the same way [`AdcTokenProvider::from_env`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if ADC can't be located, or the dedicated Tokio
+    /// runtime can't be built.
+    pub fn new(bucket: impl Into<String>, prefix: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            bucket: bucket.into(),
+            prefix: prefix.into(),
+            http: reqwest::Client::new(),
+            credentials: AdcTokenProvider::from_env()?,
+            buffers: Mutex::new(HashMap::new()),
+            runtime: tokio::runtime::Builder::new_current_thread().enable_all().build()?,
+        })
+    }
+
+    fn append(&self, key: &str, file_name: &str, line: &str) -> Result<()> {
+        let object_name = format!("{}/{key}/{file_name}", self.prefix.trim_matches('/'));
+
+        let body = {
+            let mut buffers = self.buffers.lock().unwrap();
+            let buffer = buffers.entry(object_name.clone()).or_default();
+            buffer.push_str(line);
+            buffer.push('\n');
+            buffer.clone()
+        };
+
+        self.runtime.block_on(self.upload(&object_name, &body))
+    }
+
+    async fn upload(&self, object_name: &str, body: &str) -> Result<()> {
+        let token = self.credentials.access_token().await?;
+
+        let session_url = format!(
+            "https://storage.googleapis.com/upload/storage/v1/b/{}/o?uploadType=resumable&name={}",
+            self.bucket,
+            percent_encode_object_name(object_name)
+        );
+
+        let init = self.http.post(&session_url).bearer_auth(&token).send().await?;
+        if !init.status().is_success() {
+            let status = init.status();
+            bail!("GCS resumable upload session failed with status {status}: {}", init.text().await?);
+        }
+
+        let upload_url = init
+            .headers()
+            .get("Location")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow::anyhow!("GCS did not return a resumable upload session URL"))?
+            .to_string();
+
+        let response = self
+            .http
+            .put(&upload_url)
+            .header("Content-Range", format!("bytes 0-{}/{}", body.len().saturating_sub(1), body.len()))
+            .body(body.to_string())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("GCS upload failed with status {status}: {}", response.text().await?);
+        }
+
+        Ok(())
+    }
+}
+
+impl LogSink for GcsSink {
+    fn append_request(&self, key: &str, line: &str) -> Result<()> {
+        self.append(key, "reqs.jsonl", line)
+    }
+
+    fn append_response(&self, key: &str, line: &str) -> Result<()> {
+        self.append(key, "resps.jsonl", line)
+    }
+}
+
+fn default_redacted_headers() -> Vec<String> {
+    DEFAULT_REDACTED_HEADERS.iter().map(|h| (*h).to_string()).collect()
+}
+
+/// Minimal percent-encoding for a GCS object name used as a URL query
+/// parameter: escapes everything outside the unreserved ASCII set, since
+/// object names routinely contain path-like slashes.
+fn percent_encode_object_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Header names whose values get redacted in captured logs by default.
+/// Compared case-insensitively.
+const DEFAULT_REDACTED_HEADERS: &[&str] = &["authorization", "x-goog-api-key"];
+
+/// The string a redacted header value or query parameter is replaced with.
+const REDACTED_PLACEHOLDER: &str = "***";
+
 /// Logger for API requests/responses
 pub struct ApiLogger {
-    base_dir: PathBuf,
+    sink: Box<dyn LogSink>,
     enabled: bool,
+    redacted_headers: Vec<String>,
 }
 
 impl ApiLogger {
-    /// Creates a new API logger.
+    /// Creates a new API logger that writes to the local filesystem, with
+    /// [`DEFAULT_REDACTED_HEADERS`] redaction enabled.
     ///
     /// # Errors
     ///
@@ -40,14 +229,61 @@ impl ApiLogger {
         if enabled {
             fs::create_dir_all(&base_dir)?;
         }
-        Ok(Self { base_dir, enabled })
+        Ok(Self {
+            sink: Box::new(FsSink::new(base_dir)),
+            enabled,
+            redacted_headers: default_redacted_headers(),
+        })
+    }
+
+    /// Like [`Self::new`], but writes through a caller-supplied [`LogSink`]
+    /// instead of always defaulting to the filesystem.
+    pub fn with_sink(sink: Box<dyn LogSink>, enabled: bool) -> Self {
+        Self { sink, enabled, redacted_headers: default_redacted_headers() }
+    }
+
+    /// Adds `headers` (matched case-insensitively, in addition to
+    /// [`DEFAULT_REDACTED_HEADERS`]) to the set whose values get replaced
+    /// with `"***"` before a request is persisted.
+    #[must_use]
+    pub fn with_redaction(mut self, headers: Vec<String>) -> Self {
+        self.redacted_headers.extend(headers.into_iter().map(|h| h.to_lowercase()));
+        self
+    }
+
+    /// The directory-like key a request/response pair is filed under:
+    /// `{host}/{path}`, each sanitized the same way the old filesystem
+    /// layout was. The query string is dropped so a `?key=...` never ends
+    /// up embedded in a log directory name.
+    fn key_for(host: &str, path: &str) -> String {
+        let route = path.split('?').next().unwrap_or(path);
+        format!("{}/{}", host.replace(':', "_"), route.trim_start_matches('/').replace('/', "_"))
+    }
+
+    /// Replaces the value of any query parameter named `key` (e.g. the
+    /// Gemini API key passed as `?key=...`) with `"***"`.
+    fn redact_path(&self, path: &str) -> String {
+        let Some((route, query)) = path.split_once('?') else {
+            return path.to_string();
+        };
+
+        let redacted_query = query
+            .split('&')
+            .map(|param| match param.split_once('=') {
+                Some((name, _)) if name.eq_ignore_ascii_case("key") => format!("{name}={REDACTED_PLACEHOLDER}"),
+                _ => param.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+
+        format!("{route}?{redacted_query}")
     }
 
     /// Log a request (before sending).
     ///
     /// # Errors
     ///
-    /// Returns an error if the log file cannot be written.
+    /// Returns an error if the log entry cannot be persisted.
     pub fn log_request(
         &self,
         host: &str,
@@ -63,32 +299,27 @@ impl ApiLogger {
         let request_id = uuid::Uuid::new_v4().to_string();
         let timestamp = Utc::now();
 
-        // Create directory structure: logs/{host}/{path}/
-        let log_dir = self
-            .base_dir
-            .join(host.replace(':', "_"))
-            .join(path.trim_start_matches('/').replace('/', "_"));
-        fs::create_dir_all(&log_dir)?;
+        let redacted_headers = headers.iter().map(|(name, value)| {
+            if self.redacted_headers.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+                (name.clone(), REDACTED_PLACEHOLDER.to_string())
+            } else {
+                (name.clone(), value.clone())
+            }
+        });
 
-        // Log to reqs.jsonl
         let entry = ApiLogEntry {
             timestamp,
             host: host.to_string(),
-            path: path.to_string(),
+            path: self.redact_path(path),
             method: method.to_string(),
-            headers: headers.iter().cloned().collect(),
+            headers: redacted_headers.collect(),
             body: body.clone(),
             response_status: None,
             response_body: None,
             duration_ms: None,
         };
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_dir.join("reqs.jsonl"))?;
-
-        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        self.sink.append_request(&Self::key_for(host, path), &serde_json::to_string(&entry)?)?;
 
         Ok(request_id)
     }
@@ -97,7 +328,7 @@ impl ApiLogger {
     ///
     /// # Errors
     ///
-    /// Returns an error if the log file cannot be written.
+    /// Returns an error if the log entry cannot be persisted.
     pub fn log_response(
         &self,
         host: &str,
@@ -110,12 +341,6 @@ impl ApiLogger {
             return Ok(());
         }
 
-        let log_dir = self
-            .base_dir
-            .join(host.replace(':', "_"))
-            .join(path.trim_start_matches('/').replace('/', "_"));
-
-        // Log to resps.jsonl
         let entry = serde_json::json!({
             "timestamp": Utc::now(),
             "status": status,
@@ -123,12 +348,7 @@ impl ApiLogger {
             "duration_ms": duration_ms,
         });
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_dir.join("resps.jsonl"))?;
-
-        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        self.sink.append_response(&Self::key_for(host, path), &serde_json::to_string(&entry)?)?;
 
         Ok(())
     }
@@ -187,3 +407,106 @@ pub fn is_debug_mode() -> bool {
         .map(|v| v.to_lowercase() == "true" || v == "1")
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fs_sink_appends_lines_under_a_key_directory() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-logsink-test-{}", uuid::Uuid::new_v4()));
+        let sink = FsSink::new(&dir);
+
+        sink.append_request("host_v1_generate", "line one").unwrap();
+        sink.append_request("host_v1_generate", "line two").unwrap();
+
+        let contents = fs::read_to_string(dir.join("host_v1_generate").join("reqs.jsonl")).unwrap();
+        assert_eq!(contents, "line one\nline two\n");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn api_logger_with_sink_delegates_through_the_sink() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-logsink-test-{}", uuid::Uuid::new_v4()));
+        let logger = ApiLogger::with_sink(Box::new(FsSink::new(&dir)), true);
+
+        logger
+            .log_request("api.example.com", "/v1/generate", "POST", &[], &serde_json::json!({}))
+            .unwrap();
+        logger
+            .log_response("api.example.com", "/v1/generate", 200, &serde_json::json!({"ok": true}), 12)
+            .unwrap();
+
+        assert!(dir.join("api.example.com_v1_generate").join("reqs.jsonl").exists());
+        assert!(dir.join("api.example.com_v1_generate").join("resps.jsonl").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn percent_encode_object_name_escapes_reserved_characters() {
+        assert_eq!(percent_encode_object_name("a/b_c.txt"), "a%2Fb_c.txt");
+        assert_eq!(percent_encode_object_name("abc-123~"), "abc-123~");
+    }
+
+    #[test]
+    fn log_request_redacts_default_sensitive_headers_and_api_key_query_param() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-logsink-test-{}", uuid::Uuid::new_v4()));
+        let logger = ApiLogger::with_sink(Box::new(FsSink::new(&dir)), true);
+
+        logger
+            .log_request(
+                "generativelanguage.googleapis.com",
+                "/v1beta/models/gemini-2.0-flash:generateContent?key=super-secret",
+                "POST",
+                &[
+                    ("Authorization".to_string(), "Bearer super-secret".to_string()),
+                    ("Content-Type".to_string(), "application/json".to_string()),
+                ],
+                &serde_json::json!({}),
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(
+            dir.join("generativelanguage.googleapis.com_v1beta_models_gemini-2.0-flash_generateContent")
+                .join("reqs.jsonl"),
+        )
+        .unwrap();
+        let entry: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+
+        assert_eq!(entry["headers"]["Authorization"], "***");
+        assert_eq!(entry["headers"]["Content-Type"], "application/json");
+        assert!(entry["path"].as_str().unwrap().contains("key=***"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn with_redaction_adds_custom_header_names_on_top_of_the_defaults() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-logsink-test-{}", uuid::Uuid::new_v4()));
+        let logger = ApiLogger::with_sink(Box::new(FsSink::new(&dir)), true)
+            .with_redaction(vec!["x-custom-secret".to_string()]);
+
+        logger
+            .log_request(
+                "host",
+                "/path",
+                "POST",
+                &[
+                    ("x-custom-secret".to_string(), "shh".to_string()),
+                    ("x-goog-api-key".to_string(), "also-shh".to_string()),
+                ],
+                &serde_json::json!({}),
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(dir.join("host_path").join("reqs.jsonl")).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+
+        assert_eq!(entry["headers"]["x-custom-secret"], "***");
+        assert_eq!(entry["headers"]["x-goog-api-key"], "***");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,22 +1,33 @@
 //! Gemini REPL - A secure, performant REPL for AI conversations with self-modification capabilities
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
+use serde_json::Value;
 use std::env;
+use std::path::{Path, PathBuf};
 
 mod api;
+mod backends;
+mod config;
 mod tools;
+mod functions;
 mod logging;
 mod models;
 mod utils;
 mod self_modification;
 mod errors;
+mod replay;
+mod vertex_auth;
+mod watch;
 
 use api::{Content, GeminiClient, Part};
+use backends::{BackendClient, Provider, ToolSpec};
+use functions::{run_agent_loop, AutoApprove, ConfirmMutation, StdinConfirm, DEFAULT_MAX_STEPS};
 use tools::ToolRegistry;
-use logging::{init_logging, is_debug_mode};
+use logging::{init_logging, is_debug_mode, ApiLogger};
+use replay::{ReplayClient, ReplayMode};
 
 #[derive(Parser, Debug)]
 #[command(name = "gemini-repl")]
@@ -37,6 +48,98 @@ struct Args {
     /// Enable self-modification features
     #[arg(long)]
     enable_self_modification: bool,
+
+    /// Auto-approve state-mutating tool calls instead of asking for confirmation
+    #[arg(long)]
+    yolo: bool,
+
+    /// Which deployment to talk to: `public` (Gemini API key), `vertex`
+    /// (Gemini via Application Default Credentials), or
+    /// `openai-compatible` (any OpenAI `chat/completions`-shaped endpoint,
+    /// including LocalAI)
+    #[arg(long, env = "GEMINI_BACKEND", default_value = "public")]
+    backend: String,
+
+    /// Base URL for `--backend openai-compatible`, e.g.
+    /// `http://localhost:8080/v1` for a local LocalAI instance
+    #[arg(long, env = "OPENAI_API_BASE")]
+    openai_api_base: Option<String>,
+
+    /// API key for `--backend openai-compatible` (many local servers, like
+    /// LocalAI, don't require one)
+    #[arg(long, env = "OPENAI_API_KEY", hide_env_values = true)]
+    openai_api_key: Option<String>,
+
+    /// GCP project ID to use when `--backend vertex` is selected
+    #[arg(long, env = "GOOGLE_CLOUD_PROJECT")]
+    vertex_project: Option<String>,
+
+    /// GCP region to use when `--backend vertex` is selected
+    #[arg(long, env = "GOOGLE_CLOUD_REGION", default_value = "us-central1")]
+    vertex_region: String,
+
+    /// Path to an Application Default Credentials JSON file to use for
+    /// `--backend vertex`, instead of `GOOGLE_APPLICATION_CREDENTIALS`/the
+    /// well-known gcloud path
+    #[arg(long, env = "GOOGLE_APPLICATION_CREDENTIALS")]
+    vertex_adc_file: Option<std::path::PathBuf>,
+
+    /// Directory of `reqs.jsonl`/`resps.jsonl` logs to replay from, for
+    /// `--backend replay` (see `--log-dir` to produce one)
+    #[arg(long)]
+    replay_dir: Option<PathBuf>,
+
+    /// How `--backend replay` behaves on a cache miss: `strict` errors,
+    /// `loose` returns the nearest recorded response
+    #[arg(long, default_value = "strict")]
+    replay_mode: String,
+
+    /// Capture every request/response to `reqs.jsonl`/`resps.jsonl` under
+    /// this directory, for later offline replay via `--backend replay`
+    #[arg(long)]
+    log_dir: Option<PathBuf>,
+
+    /// Maximum number of tool calls to run concurrently when a single model
+    /// turn returns several `function_call` parts. Defaults to the
+    /// available CPU parallelism, since these calls are typically I/O-bound
+    /// (file reads, greps) rather than CPU-bound.
+    #[arg(long, default_value_t = default_max_parallel_tools())]
+    max_parallel_tools: usize,
+
+    /// System instruction to prepend to every tool-enabled request, or
+    /// `@path/to/file` to read it from disk. Only the Gemini backend
+    /// (`--backend public`/`vertex`) supports this; it can also be viewed or
+    /// replaced at runtime with `/system`.
+    #[arg(long)]
+    system_prompt: Option<String>,
+
+    /// Sampling temperature sent as `generationConfig.temperature`
+    #[arg(long)]
+    temperature: Option<f64>,
+
+    /// Nucleus sampling threshold sent as `generationConfig.topP`
+    #[arg(long)]
+    top_p: Option<f64>,
+
+    /// Cap on generated tokens, sent as `generationConfig.maxOutputTokens`
+    #[arg(long)]
+    max_output_tokens: Option<u32>,
+}
+
+/// Resolves `--system-prompt`'s value: `@path` reads the instruction from a
+/// file (so long prompts don't have to live on the command line), anything
+/// else is used verbatim.
+fn resolve_system_prompt(raw: &str) -> Result<String> {
+    match raw.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).with_context(|| format!("reading system prompt from {path}")),
+        None => Ok(raw.to_string()),
+    }
+}
+
+/// Default for `--max-parallel-tools`: the available CPU parallelism, or 4
+/// if it can't be determined.
+fn default_max_parallel_tools() -> usize {
+    std::thread::available_parallelism().map(std::num::NonZeroUsize::get).unwrap_or(4)
 }
 
 #[tokio::main]
@@ -60,20 +163,101 @@ async fn main() -> Result<()> {
         tool_registry.initialize_self_modification_tools()?;
     }
 
+    // Optional `[permissions]` table in `gemini.toml`, if present. Ignored
+    // (rather than erroring the whole REPL) on a missing or malformed file
+    // so this stays additive to the existing CLI-args-based startup path.
+    if let Ok(layer) = config::GeminiConfigLayer::from_file(Path::new("gemini.toml")) {
+        if let Some(permissions) = layer.permissions {
+            tool_registry.set_policy(permissions.into());
+        }
+    }
+
     // Print welcome message
     print_welcome(&args, client.is_some());
 
+    // Captured once, up front, so `/watch` keeps resolving paths here even
+    // if a tool call changes the process's cwd mid-session.
+    let initial_cwd = env::current_dir().context("determining initial working directory")?;
+
+    // Only constructed when self-modification is enabled, since `/self_modify`
+    // and `/rollback` commit straight to the workspace's git history.
+    let self_mod_engine = args
+        .enable_self_modification
+        .then(|| self_modification::SelfModificationEngine::new(initial_cwd.clone()));
+
     // Run the REPL
-    run_repl(client, &args, tool_registry).await?;
+    run_repl(client, &args, tool_registry, initial_cwd, self_mod_engine).await?;
 
     tracing::info!("Gemini REPL shutting down");
     Ok(())
 }
 
-async fn run_repl(client: Option<GeminiClient>, args: &Args, tool_registry: ToolRegistry) -> Result<()> {
+/// A chat client backed by either the dedicated [`GeminiClient`] or a
+/// generic [`BackendClient`] (OpenAI-compatible/LocalAI), so the REPL loop
+/// doesn't need to know which one it's talking to.
+enum ChatClient {
+    Gemini(GeminiClient),
+    Generic(BackendClient),
+    Replay(ReplayClient),
+}
+
+impl ChatClient {
+    /// Send a turn and return the full model `Content`, including any
+    /// `FunctionCall` parts. This is what [`run_agent_loop`] drives, so
+    /// every backend variant gets the same multi-step tool-execution loop
+    /// rather than just the Gemini one.
+    async fn send_turn(&self, conversation: &[Content], tools: Option<Vec<Value>>) -> Result<Content> {
+        match self {
+            ChatClient::Gemini(client) => client.send_turn(conversation, tools).await,
+            ChatClient::Generic(client) => {
+                let specs: Vec<ToolSpec> = tools.unwrap_or_default().iter().filter_map(ToolSpec::from_value).collect();
+                client.send_turn(conversation, &specs).await
+            }
+            ChatClient::Replay(client) => client.send_turn(conversation, tools).await,
+        }
+    }
+
+    /// The active system instruction, if the backend supports one and one is
+    /// set. Only the Gemini backend does today.
+    fn system_instruction(&self) -> Option<&str> {
+        match self {
+            ChatClient::Gemini(client) => client.system_instruction(),
+            ChatClient::Generic(_) | ChatClient::Replay(_) => None,
+        }
+    }
+
+    /// Replace the active system instruction at runtime. Returns `false` if
+    /// this backend doesn't support one, so the caller can tell the user why
+    /// nothing changed.
+    fn set_system_instruction(&mut self, instruction: Option<String>) -> bool {
+        match self {
+            ChatClient::Gemini(client) => {
+                client.set_system_instruction(instruction);
+                true
+            }
+            ChatClient::Generic(_) | ChatClient::Replay(_) => false,
+        }
+    }
+}
+
+async fn run_repl(
+    mut client: Option<ChatClient>,
+    args: &Args,
+    tool_registry: ToolRegistry,
+    initial_cwd: PathBuf,
+    self_mod_engine: Option<self_modification::SelfModificationEngine>,
+) -> Result<()> {
     // Conversation history
     let mut conversation: Vec<Content> = Vec::new();
 
+    // Memoizes tool calls across the whole session so a model re-reading
+    // the same file a few turns later hits cache instead of the registry.
+    let cache = functions::ToolCache::new();
+
+    // The most recent prompt sent to the model, so `/watch` has something
+    // to re-run on each debounced filesystem change.
+    let mut last_query: Option<String> = None;
+
     // Initialize readline
     let mut rl = DefaultEditor::new()?;
 
@@ -91,13 +275,51 @@ async fn run_repl(client: Option<GeminiClient>, args: &Args, tool_registry: Tool
                     continue;
                 }
 
-                if let Some(should_break) = handle_command(trimmed, args, &conversation, &tool_registry) {
+                if let Some(rest) = parse_system_command(trimmed) {
+                    handle_system_command(rest, client.as_mut());
+                    continue;
+                }
+
+                if let Some((path, source_file)) = parse_self_modify_command(trimmed) {
+                    match &self_mod_engine {
+                        Some(engine) => run_self_modify_command(engine, &path, &source_file),
+                        None => println!("Self-modification features are disabled. Use --enable-self-modification to enable."),
+                    }
+                    continue;
+                }
+
+                if let Some(commit) = parse_rollback_command(trimmed) {
+                    match &self_mod_engine {
+                        Some(engine) => run_rollback_command(engine, &commit),
+                        None => println!("Self-modification features are disabled. Use --enable-self-modification to enable."),
+                    }
+                    continue;
+                }
+
+                if let Some(paths) = parse_watch_command(trimmed) {
+                    run_watch_command(
+                        &paths,
+                        &initial_cwd,
+                        &last_query,
+                        client.as_ref(),
+                        &mut conversation,
+                        &tool_registry,
+                        args.yolo,
+                        args.max_parallel_tools,
+                        &cache,
+                    )
+                    .await;
+                    continue;
+                }
+
+                if let Some(should_break) = handle_command(trimmed, args, &conversation, &tool_registry, &cache) {
                     if should_break {
                         break;
                     }
                 } else {
                     // Handle user input
-                    handle_user_input(trimmed, client.as_ref(), &mut conversation, &tool_registry).await;
+                    last_query = Some(trimmed.to_string());
+                    handle_user_input(trimmed, client.as_ref(), &mut conversation, &tool_registry, args.yolo, args.max_parallel_tools, &cache).await;
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -117,20 +339,91 @@ async fn run_repl(client: Option<GeminiClient>, args: &Args, tool_registry: Tool
     Ok(())
 }
 
-fn initialize_client(args: &Args) -> Result<Option<GeminiClient>> {
+/// Applies `--system-prompt`/`--temperature`/`--top-p`/`--max-output-tokens`
+/// to a freshly-built Gemini client, if any were passed.
+fn apply_generation_settings(mut client: GeminiClient, args: &Args) -> Result<GeminiClient> {
+    if let Some(raw) = &args.system_prompt {
+        client = client.with_system_instruction(resolve_system_prompt(raw)?);
+    }
+
+    if args.temperature.is_some() || args.top_p.is_some() || args.max_output_tokens.is_some() {
+        client = client.with_generation_config(api::GenerationConfig {
+            temperature: args.temperature,
+            top_p: args.top_p,
+            top_k: None,
+            max_output_tokens: args.max_output_tokens,
+        });
+    }
+
+    Ok(client)
+}
+
+fn initialize_client(args: &Args) -> Result<Option<ChatClient>> {
     // Check for noop mode
     let noop_mode = env::var("NOOP_MODE")
         .map(|v| v.to_lowercase() == "true" || v == "1")
         .unwrap_or(false);
 
-    // Initialize API client if not in noop mode and API key is available
-    if !noop_mode && args.api_key.is_some() {
-        Ok(Some(GeminiClient::new(
-            args.api_key.clone().unwrap(),
-            args.model.clone(),
-        )?))
-    } else {
-        Ok(None)
+    if noop_mode {
+        return Ok(None);
+    }
+
+    match args.backend.as_str() {
+        "vertex" => {
+            let project = args
+                .vertex_project
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--vertex-project (or GOOGLE_CLOUD_PROJECT) is required for --backend vertex"))?;
+            let mut client = match &args.vertex_adc_file {
+                Some(adc_file) => GeminiClient::new_vertex_with_adc_file(
+                    project,
+                    args.vertex_region.clone(),
+                    args.model.clone(),
+                    adc_file.clone(),
+                )?,
+                None => GeminiClient::new_vertex(project, args.vertex_region.clone(), args.model.clone())?,
+            };
+            if let Some(log_dir) = &args.log_dir {
+                client = client.with_logger(ApiLogger::new(log_dir, true)?);
+            }
+            client = apply_generation_settings(client, args)?;
+            Ok(Some(ChatClient::Gemini(client)))
+        }
+        "public" => {
+            if let Some(api_key) = args.api_key.clone() {
+                let mut client = GeminiClient::new(api_key, args.model.clone())?;
+                if let Some(log_dir) = &args.log_dir {
+                    client = client.with_logger(ApiLogger::new(log_dir, true)?);
+                }
+                client = apply_generation_settings(client, args)?;
+                Ok(Some(ChatClient::Gemini(client)))
+            } else {
+                Ok(None)
+            }
+        }
+        "openai-compatible" => {
+            let client = BackendClient::with_api_base(
+                Provider::OpenAi,
+                args.model.clone(),
+                args.openai_api_key.clone(),
+                args.openai_api_base.clone(),
+            )?;
+            Ok(Some(ChatClient::Generic(client)))
+        }
+        "replay" => {
+            let replay_dir = args
+                .replay_dir
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("--replay-dir is required for --backend replay"))?;
+            let mode = match args.replay_mode.as_str() {
+                "strict" => ReplayMode::Strict,
+                "loose" => ReplayMode::Loose,
+                other => anyhow::bail!("unknown --replay-mode '{other}' (expected 'strict' or 'loose')"),
+            };
+            let client = ReplayClient::new(replay_dir, args.model.clone(), mode)?;
+            Ok(Some(ChatClient::Replay(client)))
+        }
+        other => anyhow::bail!("unknown --backend '{other}' (expected 'public', 'vertex', 'openai-compatible', or 'replay')"),
     }
 }
 
@@ -157,7 +450,13 @@ fn print_welcome(args: &Args, has_client: bool) {
     }
 }
 
-fn handle_command(trimmed: &str, args: &Args, conversation: &[Content], tool_registry: &ToolRegistry) -> Option<bool> {
+fn handle_command(
+    trimmed: &str,
+    args: &Args,
+    conversation: &[Content],
+    tool_registry: &ToolRegistry,
+    cache: &functions::ToolCache,
+) -> Option<bool> {
     match trimmed {
         "/exit" | "/quit" => {
             println!("Goodbye!");
@@ -171,6 +470,10 @@ fn handle_command(trimmed: &str, args: &Args, conversation: &[Content], tool_reg
             println!("Current model: {}", args.model);
             Some(false)
         }
+        "/version" => {
+            print_version(args);
+            Some(false)
+        }
         "/clear" => {
             // Clear screen
             print!("\x1B[2J\x1B[1;1H");
@@ -192,6 +495,21 @@ fn handle_command(trimmed: &str, args: &Args, conversation: &[Content], tool_reg
             }
             Some(false)
         }
+        "/cache" => {
+            println!(
+                "Tool cache: {} entr{} memoized, {} hit(s), {} miss(es)",
+                cache.len(),
+                if cache.len() == 1 { "y" } else { "ies" },
+                cache.hits(),
+                cache.misses(),
+            );
+            Some(false)
+        }
+        "/cache clear" => {
+            cache.clear();
+            println!("Tool cache cleared.");
+            Some(false)
+        }
         input if input.starts_with('/') => {
             println!("Unknown command: {input}. Type /help for available commands.");
             Some(false)
@@ -200,6 +518,23 @@ fn handle_command(trimmed: &str, args: &Args, conversation: &[Content], tool_reg
     }
 }
 
+/// Prints the REPL's version and capability handshake: the client version,
+/// the configured model, and which feature sets are compiled in and
+/// enabled for this session. Used to diagnose mismatches between what a
+/// script assumes the REPL supports and what it actually does.
+fn print_version(args: &Args) {
+    println!("gemini-repl {}", env!("CARGO_PKG_VERSION"));
+    println!("model: {}", args.model);
+    println!("capabilities:");
+    println!("  function_calling: true");
+    println!(
+        "  self_modification: {}",
+        if args.enable_self_modification { "enabled" } else { "disabled" }
+    );
+    println!("  yolo: {}", if args.yolo { "enabled" } else { "disabled" });
+    println!("  watch: true");
+}
+
 fn print_context(conversation: &[Content]) {
     if conversation.is_empty() {
         println!("No conversation history yet");
@@ -232,9 +567,12 @@ fn print_capabilities() {
 
 async fn handle_user_input(
     input: &str,
-    client: Option<&GeminiClient>,
+    client: Option<&ChatClient>,
     conversation: &mut Vec<Content>,
     tool_registry: &ToolRegistry,
+    yolo: bool,
+    max_parallel_tools: usize,
+    cache: &functions::ToolCache,
 ) {
     if let Some(client) = client {
         // Add user message to conversation
@@ -247,28 +585,24 @@ async fn handle_user_input(
             }],
         });
 
-        // Make API call with tools
-        let tools = tool_registry.get_tool_definitions();
-        match client
-            .send_message_with_tools(conversation, Some(tools))
-            .await
-        {
-            Ok(response) => {
-                println!("{response}");
-
-                // Add assistant response to conversation
-                conversation.push(Content {
-                    role: "model".to_string(),
-                    parts: vec![Part {
-                        text: Some(response),
-                        function_call: None,
-                        function_response: None,
-                    }],
-                });
-            }
-            Err(e) => {
-                eprintln!("Error: {e}");
+        // `run_agent_loop` dispatches any `function_call` the model returns
+        // through `tool_registry`, feeds the result back as a
+        // `functionResponse`, and repeats until the model answers with text
+        // alone. It drives every backend the same way, since `ChatClient`
+        // implements `send_turn` for Gemini, the generic
+        // (OpenAI-compatible) backend, and offline replay alike.
+        let confirm: Box<dyn ConfirmMutation> =
+            if yolo { Box::new(AutoApprove) } else { Box::new(StdinConfirm) };
+
+        match run_agent_loop(client, conversation, tool_registry, DEFAULT_MAX_STEPS, max_parallel_tools, confirm.as_ref(), Some(cache)).await {
+            Ok(()) => {
+                if let Some(text) =
+                    conversation.last().and_then(|c| c.parts.iter().find_map(|p| p.text.clone()))
+                {
+                    println!("{text}");
+                }
             }
+            Err(e) => eprintln!("Error: {e}"),
         }
     } else {
         // Noop mode - echo input back
@@ -277,17 +611,186 @@ async fn handle_user_input(
     }
 }
 
+/// Parses a `/system [instruction]` line into the (possibly empty) trimmed
+/// remainder, or `None` if `trimmed` isn't a `/system` invocation.
+fn parse_system_command(trimmed: &str) -> Option<&str> {
+    let rest = trimmed.strip_prefix("/system")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        // e.g. "/systemd" is not "/system"
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// `/system` alone prints the active instruction (or notes none is set);
+/// `/system <text>` replaces it for every request from now on. The
+/// conversation history is left intact, since the instruction is resent
+/// fresh with every tool-enabled request rather than baked into history.
+fn handle_system_command(rest: &str, client: Option<&mut ChatClient>) {
+    let Some(client) = client else {
+        println!("No active backend to set a system instruction on.");
+        return;
+    };
+
+    if rest.is_empty() {
+        match client.system_instruction() {
+            Some(instruction) => println!("Current system instruction: {instruction}"),
+            None => println!("No system instruction set (using the backend's default)."),
+        }
+        return;
+    }
+
+    if client.set_system_instruction(Some(rest.to_string())) {
+        println!("System instruction updated.");
+    } else {
+        println!("This backend doesn't support a runtime-configurable system instruction.");
+    }
+}
+
+/// Parses a `/watch [path...]` line into the (possibly empty) list of raw
+/// path arguments, or `None` if `trimmed` isn't a `/watch` invocation.
+fn parse_watch_command(trimmed: &str) -> Option<Vec<String>> {
+    let rest = trimmed.strip_prefix("/watch")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        // e.g. "/watchdog" is not "/watch"
+        return None;
+    }
+    Some(rest.split_whitespace().map(str::to_string).collect())
+}
+
+/// Watches `paths` (resolved against `initial_cwd`, defaulting to
+/// `initial_cwd` itself when empty) and re-sends `last_query` through the
+/// model on each debounced change, until the user presses Ctrl+C.
+async fn run_watch_command(
+    paths: &[String],
+    initial_cwd: &Path,
+    last_query: &Option<String>,
+    client: Option<&ChatClient>,
+    conversation: &mut Vec<Content>,
+    tool_registry: &ToolRegistry,
+    yolo: bool,
+    max_parallel_tools: usize,
+    cache: &functions::ToolCache,
+) {
+    let Some(query) = last_query.clone() else {
+        println!("Nothing to watch yet: send a message first so /watch has something to re-run.");
+        return;
+    };
+
+    let roots: Vec<PathBuf> = if paths.is_empty() {
+        vec![initial_cwd.to_path_buf()]
+    } else {
+        paths.iter().map(|p| initial_cwd.join(p)).collect()
+    };
+
+    println!(
+        "Watching {} for changes, will re-run: {query}",
+        roots.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+    );
+    println!("(press Ctrl+C to stop watching)");
+
+    let watch_fut = watch::run_watch_loop(roots, || async {
+        handle_user_input(&query, client, &mut *conversation, tool_registry, yolo, max_parallel_tools, cache).await;
+        Ok(true)
+    });
+
+    tokio::select! {
+        result = watch_fut => {
+            if let Err(e) = result {
+                eprintln!("Watch error: {e}");
+            }
+        }
+        _ = tokio::signal::ctrl_c() => {
+            println!("\nStopped watching.");
+        }
+    }
+}
+
+/// Parses `/self_modify <path> <source_file>` into the (workspace-relative)
+/// target path and the file holding its proposed new contents, or `None` if
+/// `trimmed` isn't that command. `source_file` is typically a file the model
+/// already wrote via `write_file`/`edit_file` earlier in the
+/// function-calling loop, so this command can run it through the
+/// checkpoint/test/commit-or-rollback pipeline without re-typing the
+/// contents into the REPL line.
+fn parse_self_modify_command(trimmed: &str) -> Option<(String, String)> {
+    let rest = trimmed.strip_prefix("/self_modify")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        // e.g. "/self_modify_all" is not "/self_modify"
+        return None;
+    }
+    let mut parts = rest.split_whitespace();
+    let path = parts.next()?.to_string();
+    let source_file = parts.next()?.to_string();
+    Some((path, source_file))
+}
+
+/// Parses `/rollback <commit>` into the commit to reset the workspace to, or
+/// `None` if `trimmed` isn't that command.
+fn parse_rollback_command(trimmed: &str) -> Option<String> {
+    let rest = trimmed.strip_prefix("/rollback")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        // e.g. "/rollbacker" is not "/rollback"
+        return None;
+    }
+    let commit = rest.split_whitespace().next()?;
+    Some(commit.to_string())
+}
+
+/// Reads `source_file` and runs its contents through `engine`'s
+/// checkpoint/validate/test/commit-or-rollback pipeline against `path`,
+/// reporting the outcome the same way the rest of the REPL reports tool
+/// results: a `println!` either way, never a panic.
+fn run_self_modify_command(engine: &self_modification::SelfModificationEngine, path: &str, source_file: &str) {
+    let new_contents = match std::fs::read_to_string(source_file) {
+        Ok(contents) => contents,
+        Err(e) => {
+            println!("Could not read {source_file}: {e}");
+            return;
+        }
+    };
+
+    match engine.apply_change(Path::new(path), &new_contents) {
+        Ok(report) => match report.outcome {
+            self_modification::ChangeOutcome::Applied { restore_commit } => {
+                println!("Applied change to {}. Restore point: {restore_commit}", report.path.display());
+            }
+            self_modification::ChangeOutcome::RolledBack => {
+                println!("Change to {} failed tests and was rolled back:", report.path.display());
+                println!("{}", report.test_output);
+            }
+        },
+        Err(e) => println!("Self-modification failed: {e}"),
+    }
+}
+
+/// Resets the workspace back to `commit` via `engine`, reporting success or
+/// failure the same way `run_self_modify_command` does.
+fn run_rollback_command(engine: &self_modification::SelfModificationEngine, commit: &str) {
+    match engine.rollback_to(commit) {
+        Ok(()) => println!("Rolled back to {commit}."),
+        Err(e) => println!("Rollback failed: {e}"),
+    }
+}
+
 fn print_help(self_modification_enabled: bool) {
     println!("Available commands:");
     println!("  /help       - Show this help message");
     println!("  /exit       - Exit the REPL (/quit also works)");
     println!("  /model      - Show current model");
+    println!("  /version    - Show version and capability handshake");
     println!("  /clear      - Clear the screen");
     println!("  /context    - Show conversation history");
     println!("  /tools      - List available tools");
-    
+    println!("  /watch [path...] - Re-run the last message whenever the given paths change (default: cwd)");
+    println!("  /system [text] - Show or replace the active system instruction (Gemini backend only)");
+    println!("  /cache      - Show tool-call cache hit/miss stats");
+    println!("  /cache clear - Clear the tool-call cache");
+
     if self_modification_enabled {
         println!("  /capabilities - Show self-modification capabilities");
+        println!("  /self_modify <path> <source_file> - Apply <source_file>'s contents to <path>, committing on success and rolling back on test failure");
+        println!("  /rollback <commit> - Reset the workspace back to <commit>");
     }
     
     println!();
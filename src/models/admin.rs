@@ -0,0 +1,122 @@
+//! Admin RPC Surface
+//!
+//! A request/response pair for driving [`ModelRegistry`] remotely (e.g.
+//! over a control-plane connection between nodes), so a caller doesn't
+//! need direct access to the registry's methods. Every command maps to an
+//! existing registry method; errors are carried in [`RegistryResponse::Error`]
+//! rather than returned as a `Result`, since a remote caller needs a
+//! response either way.
+
+use std::collections::HashMap;
+
+use super::{ModelConfig, RegisteredModel};
+use super::registry::{ModelRegistry, RegistryStats};
+
+/// A request to be applied to a [`ModelRegistry`] via [`handle_command`].
+#[derive(Debug, Clone)]
+pub enum RegistryCommand {
+    Register(RegisteredModel),
+    Unregister { model_id: String },
+    UpdateConfig { model_id: String, config: ModelConfig },
+    UpdateMetadata { model_id: String, metadata: HashMap<String, String> },
+    Search { query: String },
+    Stats,
+    ListByProvider { provider_id: String },
+    Repair,
+}
+
+/// The result of applying a [`RegistryCommand`].
+#[derive(Debug, Clone)]
+pub enum RegistryResponse {
+    Ok,
+    Model(RegisteredModel),
+    Models(Vec<RegisteredModel>),
+    Stats(RegistryStats),
+    Error(String),
+}
+
+/// Apply `cmd` to `registry`, mapping it to the corresponding
+/// [`ModelRegistry`] method and translating any `Err` into
+/// [`RegistryResponse::Error`] instead of propagating it, so a remote
+/// caller always gets a response.
+pub async fn handle_command(registry: &mut ModelRegistry, cmd: RegistryCommand) -> RegistryResponse {
+    match cmd {
+        RegistryCommand::Register(model) => match registry.register_model(model) {
+            Ok(()) => RegistryResponse::Ok,
+            Err(e) => RegistryResponse::Error(e.to_string()),
+        },
+        RegistryCommand::Unregister { model_id } => match registry.unregister_model(&model_id) {
+            Ok(model) => RegistryResponse::Model(model),
+            Err(e) => RegistryResponse::Error(e.to_string()),
+        },
+        RegistryCommand::UpdateConfig { model_id, config } => {
+            match registry.update_model_config(&model_id, config) {
+                Ok(()) => RegistryResponse::Ok,
+                Err(e) => RegistryResponse::Error(e.to_string()),
+            }
+        }
+        RegistryCommand::UpdateMetadata { model_id, metadata } => {
+            match registry.update_model_metadata(&model_id, metadata) {
+                Ok(()) => RegistryResponse::Ok,
+                Err(e) => RegistryResponse::Error(e.to_string()),
+            }
+        }
+        RegistryCommand::Search { query } => RegistryResponse::Models(registry.search_models(&query)),
+        RegistryCommand::Stats => RegistryResponse::Stats(registry.get_stats()),
+        RegistryCommand::ListByProvider { provider_id } => {
+            RegistryResponse::Models(registry.list_models_by_provider(&provider_id))
+        }
+        RegistryCommand::Repair => match registry.repair() {
+            Ok(report) => {
+                if report.is_clean() {
+                    RegistryResponse::Ok
+                } else {
+                    RegistryResponse::Error(format!("repaired inconsistencies: {:?}", report))
+                }
+            }
+            Err(e) => RegistryResponse::Error(e.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::capabilities::{CapabilityType, ModelCapabilities};
+
+    fn test_model(id: &str, provider_id: &str) -> RegisteredModel {
+        RegisteredModel {
+            id: id.to_string(),
+            name: format!("Test Model {}", id),
+            provider_id: provider_id.to_string(),
+            capabilities: ModelCapabilities::new(vec![CapabilityType::TextGeneration]),
+            default_config: ModelConfig::default(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn register_then_stats_reflects_the_new_model() {
+        let mut registry = ModelRegistry::new();
+
+        let response = handle_command(&mut registry, RegistryCommand::Register(test_model("gpt-4", "openai"))).await;
+        assert!(matches!(response, RegistryResponse::Ok));
+
+        let response = handle_command(&mut registry, RegistryCommand::Stats).await;
+        match response {
+            RegistryResponse::Stats(stats) => assert_eq!(stats.total_models, 1),
+            other => panic!("expected Stats, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unregister_unknown_model_returns_error_response_instead_of_panicking() {
+        let mut registry = ModelRegistry::new();
+
+        let response = handle_command(&mut registry, RegistryCommand::Unregister { model_id: "missing".to_string() }).await;
+        assert!(matches!(response, RegistryResponse::Error(_)));
+    }
+}
@@ -0,0 +1,195 @@
+//! Synchronous mirror of [`super::provider`], gated behind the `blocking`
+//! feature for callers that can't (or don't want to) run inside a Tokio
+//! runtime. Follows the "maybe-async" pattern: this module is a
+//! near-line-for-line copy of the async trait and its `BaseProvider`,
+//! swapping `async fn`/`.await` for plain `fn` and `reqwest::blocking`.
+//!
+//! Streaming has no natural blocking equivalent, so [`ModelProvider`]
+//! here has no `generate_stream` — a caller that needs streaming output
+//! should build with the default (async) feature instead.
+//!
+//! `ProviderConfig`/`RetryConfig`/`RequestConfig` and the rest of the
+//! connection configuration are shared with [`super::provider`] rather
+//! than duplicated, so the two stacks stay in lockstep. Only the
+//! transport and retry loop actually differ. A binary should still pick
+//! one provider implementation and run with it: `reqwest::blocking::Client`
+//! panics if invoked from inside a Tokio runtime, so mixing `BaseProvider`
+//! and `blocking::BaseProvider` in the same async context doesn't work.
+
+use super::provider::{ProviderCapabilities, ProviderConfig, ProviderInfo, RateLimits, RequestConfig, UsageStats};
+use super::{ModelConfig, ModelError, ModelResult};
+
+/// Synchronous counterpart to [`super::provider::ModelProvider`].
+pub trait ModelProvider: Send + Sync {
+    /// Get provider information and supported models
+    fn get_info(&self) -> ProviderInfo;
+
+    /// Generate text using this provider. `request_config`, if given,
+    /// overrides the provider's default timeout/retry behavior for this
+    /// call only.
+    fn generate(&self, prompt: &str, config: &ModelConfig, request_config: Option<&RequestConfig>) -> ModelResult<String>;
+
+    /// Validate provider configuration
+    fn validate_config(&self, config: &ProviderConfig) -> ModelResult<()>;
+
+    /// Perform health check
+    fn health_check(&self) -> ModelResult<()>;
+
+    /// Get usage statistics (optional)
+    fn get_usage_stats(&self) -> Option<UsageStats> {
+        None
+    }
+
+    /// Get rate limit information (optional)
+    fn get_rate_limits(&self) -> Option<RateLimits> {
+        None
+    }
+}
+
+/// Synchronous counterpart to [`super::provider::BaseProvider`]. Shares
+/// `ProviderConfig`/`RetryConfig`/`RequestConfig` with the async stack so
+/// a provider's configuration doesn't change shape depending on which
+/// feature is enabled — only the transport and retry loop are blocking.
+/// Doesn't carry rate limiting, OAuth2, or request logging: those involve
+/// either async-only primitives (`tokio::sync::Mutex`) or are rare enough
+/// in blocking use cases that they're left for a caller to layer on top
+/// if it ever needs them.
+pub struct BaseProvider {
+    pub info: ProviderInfo,
+    pub config: ProviderConfig,
+    pub client: reqwest::blocking::Client,
+    pub usage_stats: std::sync::Mutex<UsageStats>,
+}
+
+impl BaseProvider {
+    pub fn new(info: ProviderInfo, config: ProviderConfig) -> ModelResult<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_millis(config.connection.timeout_ms))
+            .build()
+            .map_err(|e| ModelError::ProviderError(format!("Failed to create HTTP client: {}", e)))?;
+
+        let usage_stats = UsageStats {
+            total_requests: 0,
+            successful_requests: 0,
+            failed_requests: 0,
+            avg_response_time_ms: 0.0,
+            total_tokens: None,
+            total_cost: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            last_updated: chrono::Utc::now(),
+        };
+
+        Ok(Self { info, config, client, usage_stats: std::sync::Mutex::new(usage_stats) })
+    }
+
+    fn update_stats(&self, success: bool, response_time_ms: u64) {
+        let mut stats = self.usage_stats.lock().expect("usage_stats mutex poisoned");
+        stats.total_requests += 1;
+        if success {
+            stats.successful_requests += 1;
+        } else {
+            stats.failed_requests += 1;
+        }
+        let n = stats.total_requests as f64;
+        stats.avg_response_time_ms += (response_time_ms as f64 - stats.avg_response_time_ms) / n;
+        stats.last_updated = chrono::Utc::now();
+    }
+
+    /// Perform an HTTP request with the same retry/backoff shape as
+    /// [`super::provider::BaseProvider::make_request`] — server errors
+    /// and 429s are retried with full-jitter backoff — except every wait
+    /// blocks the calling thread instead of yielding to an executor.
+    pub fn make_request(&self, request: reqwest::blocking::Request, request_config: Option<&RequestConfig>) -> ModelResult<reqwest::blocking::Response> {
+        let mut attempts = 0;
+        let default_retry_config = self.config.connection.retry.clone();
+        let retry_config = request_config.and_then(|rc| rc.retry.as_ref()).unwrap_or(&default_retry_config);
+        let max_attempts = if request_config.map(|rc| rc.disable_retry).unwrap_or(false) { 0 } else { retry_config.max_attempts };
+        let started_at = std::time::Instant::now();
+
+        let result = loop {
+            // `try_clone` fails on a streaming/non-repeatable body; surface
+            // that before any sleep rather than retrying something that
+            // can never succeed.
+            let request_clone = match request.try_clone() {
+                Some(r) => r,
+                None => break Err(ModelError::ProviderError("Failed to clone request".to_string())),
+            };
+
+            match self.client.execute(request_clone) {
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = status.is_server_error() || status.as_u16() == 429;
+
+                    if status.is_success() {
+                        break Ok(response);
+                    } else if attempts < max_attempts && retryable {
+                        attempts += 1;
+                        std::thread::sleep(retry_config.full_jitter_delay(attempts));
+                        continue;
+                    } else {
+                        break Err(ModelError::ProviderError(format!(
+                            "HTTP error: {} - {}",
+                            status,
+                            response.text().unwrap_or_default()
+                        )));
+                    }
+                }
+                Err(e) if attempts < max_attempts => {
+                    attempts += 1;
+                    std::thread::sleep(retry_config.full_jitter_delay(attempts));
+                    continue;
+                }
+                Err(e) => break Err(ModelError::ProviderError(format!("Request failed: {}", e))),
+            }
+        };
+
+        self.update_stats(result.is_ok(), started_at.elapsed().as_millis() as u64);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::provider::{AuthConfig, ConnectionConfig, RequestLoggingConfig};
+    use std::collections::HashMap;
+
+    fn test_provider() -> BaseProvider {
+        let config = ProviderConfig {
+            endpoint: "https://api.example.com".to_string(),
+            auth: AuthConfig::None,
+            connection: ConnectionConfig::default(),
+            provider_specific: HashMap::new(),
+            request_logging: RequestLoggingConfig::default(),
+        };
+
+        BaseProvider::new(
+            ProviderInfo {
+                id: "test-provider".to_string(),
+                name: "Test Provider".to_string(),
+                description: String::new(),
+                version: "1.0.0".to_string(),
+                supported_models: vec![],
+                default_config: config.clone(),
+                capabilities: ProviderCapabilities::default(),
+                metadata: HashMap::new(),
+            },
+            config,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn update_stats_tracks_success_and_failure_counts() {
+        let provider = test_provider();
+
+        provider.update_stats(true, 10);
+        provider.update_stats(false, 20);
+
+        let stats = provider.usage_stats.lock().unwrap();
+        assert_eq!(stats.total_requests, 2);
+        assert_eq!(stats.successful_requests, 1);
+        assert_eq!(stats.failed_requests, 1);
+    }
+}
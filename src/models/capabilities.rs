@@ -19,7 +19,18 @@ pub struct ModelCapabilities {
     
     /// Context window limits
     pub context_limits: ContextLimits,
-    
+
+    /// Constrained/structured decoding support, if any. `None` means the
+    /// model can't enforce a grammar at all, which is distinct from
+    /// `Some(GrammarSupport { json_schema: false, regex: false })`.
+    pub grammar: Option<GrammarSupport>,
+
+    /// Tools this model is allowed to call, keyed by name via
+    /// [`Self::register_tool`]/[`Self::remove_tool`]. Empty unless the
+    /// model has [`CapabilityType::FunctionCalling`] and tools have
+    /// actually been registered.
+    pub tools: Vec<ToolDefinition>,
+
     /// Rate limits
     pub rate_limits: Option<RateLimits>,
     
@@ -95,7 +106,10 @@ pub enum CapabilityType {
     
     /// Conversational AI
     ConversationalAI,
-    
+
+    /// Constrained/structured decoding against a JSON Schema or regex
+    GrammarConstrainedGeneration,
+
     /// Custom capability (with name)
     Custom(String),
 }
@@ -125,10 +139,11 @@ impl CapabilityType {
             CapabilityType::MathematicalReasoning => "mathematical_reasoning",
             CapabilityType::LogicalReasoning => "logical_reasoning",
             CapabilityType::ConversationalAI => "conversational_ai",
+            CapabilityType::GrammarConstrainedGeneration => "grammar_constrained_generation",
             CapabilityType::Custom(name) => name,
         }
     }
-    
+
     /// Parse capability from string
     pub fn from_str(s: &str) -> Self {
         match s {
@@ -153,6 +168,7 @@ impl CapabilityType {
             "mathematical_reasoning" => CapabilityType::MathematicalReasoning,
             "logical_reasoning" => CapabilityType::LogicalReasoning,
             "conversational_ai" => CapabilityType::ConversationalAI,
+            "grammar_constrained_generation" => CapabilityType::GrammarConstrainedGeneration,
             custom => CapabilityType::Custom(custom.to_string()),
         }
     }
@@ -203,22 +219,87 @@ where
                 return false;
             }
         }
-        
+
         if let Some(max) = self.max {
             if value > max {
                 return false;
             }
         }
-        
+
         true
     }
-    
+
     /// Get the default value or a reasonable fallback
     pub fn get_default(&self) -> Option<T> {
         self.default
     }
 }
 
+impl ParameterRange<f64> {
+    /// Snap `value` into `[min, max]`, then to the nearest multiple of
+    /// `step` (if set), clamping once more in case the step snapped it
+    /// back out of range.
+    fn clamp_to_range(&self, value: f64) -> f64 {
+        let mut snapped = value;
+        if let Some(min) = self.min {
+            snapped = snapped.max(min);
+        }
+        if let Some(max) = self.max {
+            snapped = snapped.min(max);
+        }
+        if let Some(step) = self.step {
+            if step > 0.0 {
+                snapped = (snapped / step).round() * step;
+                if let Some(min) = self.min {
+                    snapped = snapped.max(min);
+                }
+                if let Some(max) = self.max {
+                    snapped = snapped.min(max);
+                }
+            }
+        }
+        snapped
+    }
+}
+
+impl ParameterRange<u32> {
+    /// Snap `value` into `[min, max]`, then to the nearest multiple of
+    /// `step` (if set), clamping once more in case the step snapped it
+    /// back out of range.
+    fn clamp_to_range(&self, value: u32) -> u32 {
+        let mut snapped = value;
+        if let Some(min) = self.min {
+            snapped = snapped.max(min);
+        }
+        if let Some(max) = self.max {
+            snapped = snapped.min(max);
+        }
+        if let Some(step) = self.step {
+            if step > 0 {
+                snapped = ((snapped + step / 2) / step) * step;
+                if let Some(min) = self.min {
+                    snapped = snapped.max(min);
+                }
+                if let Some(max) = self.max {
+                    snapped = snapped.min(max);
+                }
+            }
+        }
+        snapped
+    }
+}
+
+/// How [`ModelCapabilities::validate_config_with`] treats an out-of-range
+/// parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// The existing behavior: any out-of-range value is an error.
+    Strict,
+    /// Snap out-of-range values to the nearest bound (and to the nearest
+    /// valid `step`, when one is set) instead of failing.
+    Clamp,
+}
+
 /// Context window limits
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextLimits {
@@ -238,6 +319,241 @@ pub struct ContextLimits {
     pub unlimited_context: bool,
 }
 
+/// Counts tokens for a prompt against whatever tokenizer a provider
+/// actually uses. [`ModelCapabilities::validate_request`] only needs a
+/// count to check against [`ContextLimits`], plus `encode`/`decode` to
+/// truncate to an exact token count when asked to.
+pub trait TokenCounter: Send + Sync {
+    /// Number of tokens `text` encodes to.
+    fn count(&self, text: &str) -> usize;
+
+    /// Encode `text` into token ids, so it can be truncated by count and
+    /// decoded back into text.
+    fn encode(&self, text: &str) -> Vec<u32>;
+
+    /// Reconstruct text from a slice of token ids.
+    fn decode(&self, tokens: &[u32]) -> String;
+}
+
+/// Which end of the input to drop tokens from when it doesn't fit
+/// `max_input_tokens`, mirroring text-generation-inference's truncation
+/// modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationDirection {
+    /// Drop tokens from the start, keeping the most recent content.
+    Left,
+    /// Drop tokens from the end, keeping the earliest content.
+    Right,
+}
+
+/// The result of [`ModelCapabilities::validate_request`]: how many input
+/// tokens were counted, how many output tokens remain in the model's
+/// budget, and whether `text` differs from the original input because it
+/// had to be truncated to fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestValidation {
+    pub input_tokens: usize,
+    pub allowed_output_tokens: u32,
+    pub was_truncated: bool,
+    /// The input to actually send: the original text, or its truncated
+    /// form when `was_truncated` is true.
+    pub text: String,
+}
+
+/// Counts `text`'s tokens on a scoped worker thread (the same
+/// `std::thread::scope` idiom used elsewhere in this crate to fan work
+/// off the caller's thread) so a large prompt's tokenization doesn't
+/// block whoever called `validate_request`.
+fn count_tokens(tokenizer: &impl TokenCounter, text: &str) -> usize {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| tokenizer.count(text))
+            .join()
+            .expect("token-counting worker thread panicked")
+    })
+}
+
+/// Like [`count_tokens`], but encodes `text`, drops tokens from `direction`
+/// until at most `max_tokens` remain, and decodes the result back to text.
+fn truncate_to_tokens(tokenizer: &impl TokenCounter, text: &str, max_tokens: u32, direction: TruncationDirection) -> String {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let tokens = tokenizer.encode(text);
+                let max_tokens = max_tokens as usize;
+                let kept: Vec<u32> = match direction {
+                    TruncationDirection::Right => tokens.into_iter().take(max_tokens).collect(),
+                    TruncationDirection::Left => {
+                        let start = tokens.len().saturating_sub(max_tokens);
+                        tokens[start..].to_vec()
+                    }
+                };
+                tokenizer.decode(&kept)
+            })
+            .join()
+            .expect("token-truncation worker thread panicked")
+    })
+}
+
+/// Which grammar mechanisms a model can enforce during decoding,
+/// paralleling text-generation-inference's grammar support.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrammarSupport {
+    /// The model can constrain output to match a JSON Schema.
+    pub json_schema: bool,
+    /// The model can constrain output to match a regex.
+    pub regex: bool,
+}
+
+/// A constrained-decoding request: either a JSON Schema (Draft 2020-12)
+/// the output must validate against, or a regex the output must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GrammarRequest {
+    Json(serde_json::Value),
+    Regex(String),
+}
+
+/// A compiled grammar artifact, ready to validate a model's output
+/// against. Produced by [`ModelCapabilities::validate_grammar`] and
+/// reused via [`GrammarCache`] instead of being recompiled per response.
+pub enum CompiledGrammar {
+    Json(jsonschema::Validator),
+    Regex(regex::Regex),
+}
+
+impl std::fmt::Debug for CompiledGrammar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompiledGrammar::Json(_) => write!(f, "CompiledGrammar::Json(..)"),
+            CompiledGrammar::Regex(pattern) => write!(f, "CompiledGrammar::Regex({pattern})"),
+        }
+    }
+}
+
+/// Memoizes [`CompiledGrammar`]s keyed on the grammar request's own
+/// content, so a schema or regex shared across a conversation's responses
+/// is compiled once and reused, not recompiled on every validation call.
+#[derive(Default)]
+pub struct GrammarCache {
+    entries: std::sync::Mutex<HashMap<String, std::sync::Arc<CompiledGrammar>>>,
+}
+
+impl GrammarCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached compiled grammar for `grammar`, compiling and
+    /// caching it against `capabilities` first on a miss.
+    pub fn get_or_compile(&self, capabilities: &ModelCapabilities, grammar: &GrammarRequest) -> ModelResult<std::sync::Arc<CompiledGrammar>> {
+        let key = Self::key(grammar);
+        if let Some(cached) = self.entries.lock().unwrap().get(&key) {
+            return Ok(cached.clone());
+        }
+        let compiled = std::sync::Arc::new(capabilities.validate_grammar(grammar)?);
+        self.entries.lock().unwrap().insert(key, compiled.clone());
+        Ok(compiled)
+    }
+
+    fn key(grammar: &GrammarRequest) -> String {
+        match grammar {
+            GrammarRequest::Json(schema) => format!("json:{schema}"),
+            GrammarRequest::Regex(pattern) => format!("regex:{pattern}"),
+        }
+    }
+}
+
+/// Whether invoking a tool can change state (`Execute`) or only reads it
+/// (`Retrieve`). Mirrors the `Pure`/`Mutates` split `functions::Effect`
+/// uses to gate the agent loop's tool execution, scoped here to a
+/// model's own registered tool set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    /// Only reads state; safe to run without confirmation.
+    Retrieve,
+    /// Changes state; should require confirmation before running.
+    Execute,
+}
+
+/// A tool a model can call: its name, a human-readable description, and
+/// its parameters as a JSON Schema used to validate call arguments in
+/// [`ModelCapabilities::validate_tool_call`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+    pub kind: ToolKind,
+}
+
+/// One entry in a [`ToolCallSession`]: a call and, once it's run, its
+/// result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub name: String,
+    pub args: serde_json::Value,
+    pub result: Option<serde_json::Value>,
+}
+
+/// Tracks the sequence of tool calls and results across a multi-step
+/// function-calling conversation, following aichat's multi-step design: a
+/// repeated call can reuse an earlier result via [`Self::previous_result`]
+/// instead of running the tool again, and [`Self::requires_confirmation`]
+/// tells a caller which calls are `Execute` tools that need approval
+/// before they run.
+#[derive(Debug, Default)]
+pub struct ToolCallSession {
+    calls: Vec<ToolCallRecord>,
+}
+
+impl ToolCallSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new call (with no result yet) and return its index.
+    pub fn push_call(&mut self, name: impl Into<String>, args: serde_json::Value) -> usize {
+        self.calls.push(ToolCallRecord { name: name.into(), args, result: None });
+        self.calls.len() - 1
+    }
+
+    /// Attach a call's result once it's run.
+    pub fn record_result(&mut self, index: usize, result: serde_json::Value) {
+        if let Some(call) = self.calls.get_mut(index) {
+            call.result = Some(result);
+        }
+    }
+
+    /// The most recent completed call to `name` with exactly these `args`,
+    /// if any, so a repeated call can reuse its result instead of running
+    /// the tool again.
+    pub fn previous_result(&self, name: &str, args: &serde_json::Value) -> Option<&serde_json::Value> {
+        self.calls
+            .iter()
+            .rev()
+            .find(|call| call.name == name && &call.args == args)
+            .and_then(|call| call.result.as_ref())
+    }
+
+    /// Whether this tool call must be confirmed before it runs: true for
+    /// an `Execute` tool, or for a tool `capabilities` doesn't know about
+    /// (fails safe).
+    pub fn requires_confirmation(capabilities: &ModelCapabilities, name: &str) -> bool {
+        capabilities
+            .tools
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.kind == ToolKind::Execute)
+            .unwrap_or(true)
+    }
+
+    /// Every call recorded so far, in call order.
+    pub fn calls(&self) -> &[ToolCallRecord] {
+        &self.calls
+    }
+}
+
 /// Rate limit information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimits {
@@ -263,12 +579,12 @@ pub struct RateLimits {
 /// Cost information for model usage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostInfo {
-    /// Cost per input token (in USD)
-    pub cost_per_input_token: Option<f64>,
-    
-    /// Cost per output token (in USD)
-    pub cost_per_output_token: Option<f64>,
-    
+    /// Price per 1,000 input tokens (in USD), aichat-style.
+    pub input_price_per_1k: Option<f64>,
+
+    /// Price per 1,000 output tokens (in USD), aichat-style.
+    pub output_price_per_1k: Option<f64>,
+
     /// Fixed cost per request (in USD)
     pub cost_per_request: Option<f64>,
     
@@ -305,6 +621,8 @@ impl ModelCapabilities {
             supported_capabilities: capabilities,
             parameter_constraints: ParameterConstraints::default(),
             context_limits: ContextLimits::default(),
+            grammar: None,
+            tools: Vec::new(),
             rate_limits: None,
             cost_info: None,
             metadata: HashMap::new(),
@@ -357,7 +675,44 @@ impl ModelCapabilities {
     pub fn remove_capability(&mut self, capability: &CapabilityType) {
         self.supported_capabilities.retain(|cap| cap != capability);
     }
-    
+
+    /// Register a tool this model can call, replacing any existing
+    /// registration with the same name.
+    pub fn register_tool(&mut self, tool: ToolDefinition) {
+        self.remove_tool(&tool.name);
+        self.tools.push(tool);
+    }
+
+    /// Remove a tool by name, if registered.
+    pub fn remove_tool(&mut self, name: &str) {
+        self.tools.retain(|tool| tool.name != name);
+    }
+
+    /// Validate a tool call before dispatch: the model must have
+    /// [`CapabilityType::FunctionCalling`], `name` must be a registered
+    /// tool, and `args` must validate against that tool's parameters
+    /// schema.
+    pub fn validate_tool_call(&self, name: &str, args: &serde_json::Value) -> ModelResult<()> {
+        if !self.has_capability_type(&CapabilityType::FunctionCalling) {
+            return Err(ModelError::InvalidParameter(
+                "model does not support function calling".to_string(),
+            ));
+        }
+
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or_else(|| ModelError::InvalidParameter(format!("unknown tool '{name}'")))?;
+
+        let validator = jsonschema::validator_for(&tool.parameters)
+            .map_err(|e| ModelError::InvalidParameter(format!("tool '{name}' has an invalid parameters schema: {e}")))?;
+
+        validator
+            .validate(args)
+            .map_err(|e| ModelError::InvalidParameter(format!("arguments for '{name}' failed schema validation: {e}")))
+    }
+
     /// Validate a model configuration against these capabilities
     pub fn validate_config(&self, config: &ModelConfig) -> ModelResult<()> {
         // Validate temperature
@@ -423,10 +778,236 @@ impl ModelCapabilities {
                 }
             }
         }
-        
+
+        // Validate the grammar request, if any. A grammar the model can't
+        // enforce or can't compile is always an error here, never a
+        // silently-ignored no-op; the compiled artifact itself isn't kept
+        // around by this call — route through `GrammarCache` for reuse.
+        if let Some(grammar) = &config.grammar {
+            self.validate_grammar(grammar)?;
+        }
+
         Ok(())
     }
-    
+
+    /// Fill in any of `temperature`/`top_p`/`top_k`/`max_tokens`/
+    /// `presence_penalty`/`frequency_penalty` that `config` leaves unset,
+    /// using each [`ParameterRange::default`]. `max_tokens` falls back to
+    /// `min(max_output_tokens, context_window / 2)` when neither the
+    /// constraint nor the caller supplies one, mirroring rust-bert making
+    /// `max_length` optional: callers can submit a partial config and get
+    /// something usable back.
+    pub fn apply_defaults(&self, config: &mut ModelConfig) {
+        if config.temperature.is_none() {
+            config.temperature = self.parameter_constraints.temperature.as_ref().and_then(|r| r.default);
+        }
+        if config.top_p.is_none() {
+            config.top_p = self.parameter_constraints.top_p.as_ref().and_then(|r| r.default);
+        }
+        if config.top_k.is_none() {
+            config.top_k = self.parameter_constraints.top_k.as_ref().and_then(|r| r.default);
+        }
+        if config.presence_penalty.is_none() {
+            config.presence_penalty = self.parameter_constraints.presence_penalty.as_ref().and_then(|r| r.default);
+        }
+        if config.frequency_penalty.is_none() {
+            config.frequency_penalty = self.parameter_constraints.frequency_penalty.as_ref().and_then(|r| r.default);
+        }
+        if config.max_tokens.is_none() {
+            config.max_tokens = self.parameter_constraints.max_tokens.as_ref().and_then(|r| r.default).or_else(|| {
+                match (self.context_limits.max_output_tokens, self.context_limits.context_window) {
+                    (Some(max_output), Some(window)) => Some(max_output.min(window / 2)),
+                    (Some(max_output), None) => Some(max_output),
+                    (None, Some(window)) => Some(window / 2),
+                    (None, None) => None,
+                }
+            });
+        }
+    }
+
+    /// Like [`Self::validate_config`], but under [`ValidationMode::Clamp`]
+    /// snaps out-of-range values into bounds instead of failing, returning
+    /// a human-readable description of every adjustment it made.
+    /// [`ValidationMode::Strict`] delegates straight to `validate_config`.
+    pub fn validate_config_with(&self, config: &mut ModelConfig, mode: ValidationMode) -> ModelResult<Vec<String>> {
+        if mode == ValidationMode::Strict {
+            self.validate_config(config)?;
+            return Ok(Vec::new());
+        }
+
+        let mut adjustments = Vec::new();
+
+        if let (Some(temp), Some(range)) = (config.temperature, &self.parameter_constraints.temperature) {
+            if !range.contains(temp) {
+                let clamped = range.clamp_to_range(temp);
+                adjustments.push(format!("temperature clamped from {temp} to {clamped}"));
+                config.temperature = Some(clamped);
+            }
+        }
+
+        if let (Some(top_p), Some(range)) = (config.top_p, &self.parameter_constraints.top_p) {
+            if !range.contains(top_p) {
+                let clamped = range.clamp_to_range(top_p);
+                adjustments.push(format!("top_p clamped from {top_p} to {clamped}"));
+                config.top_p = Some(clamped);
+            }
+        }
+
+        if let (Some(top_k), Some(range)) = (config.top_k, &self.parameter_constraints.top_k) {
+            if !range.contains(top_k) {
+                let clamped = range.clamp_to_range(top_k);
+                adjustments.push(format!("top_k clamped from {top_k} to {clamped}"));
+                config.top_k = Some(clamped);
+            }
+        }
+
+        if let Some(max_tokens) = config.max_tokens {
+            if let Some(range) = &self.parameter_constraints.max_tokens {
+                if !range.contains(max_tokens) {
+                    let clamped = range.clamp_to_range(max_tokens);
+                    adjustments.push(format!("max_tokens clamped from {max_tokens} to {clamped}"));
+                    config.max_tokens = Some(clamped);
+                }
+            }
+        }
+        if let (Some(current), Some(max_output)) = (config.max_tokens, self.context_limits.max_output_tokens) {
+            if current > max_output {
+                adjustments.push(format!("max_tokens clamped from {current} to model limit {max_output}"));
+                config.max_tokens = Some(max_output);
+            }
+        }
+
+        if let (Some(penalty), Some(range)) = (config.presence_penalty, &self.parameter_constraints.presence_penalty) {
+            if !range.contains(penalty) {
+                let clamped = range.clamp_to_range(penalty);
+                adjustments.push(format!("presence_penalty clamped from {penalty} to {clamped}"));
+                config.presence_penalty = Some(clamped);
+            }
+        }
+
+        if let (Some(penalty), Some(range)) = (config.frequency_penalty, &self.parameter_constraints.frequency_penalty) {
+            if !range.contains(penalty) {
+                let clamped = range.clamp_to_range(penalty);
+                adjustments.push(format!("frequency_penalty clamped from {penalty} to {clamped}"));
+                config.frequency_penalty = Some(clamped);
+            }
+        }
+
+        if let Some(grammar) = &config.grammar {
+            self.validate_grammar(grammar)?;
+        }
+
+        Ok(adjustments)
+    }
+
+    /// Compile `grammar` and check it against what this model advertises
+    /// in [`Self::grammar`]. An unsupported grammar mechanism, or one that
+    /// fails to compile, is always an error.
+    pub fn validate_grammar(&self, grammar: &GrammarRequest) -> ModelResult<CompiledGrammar> {
+        let support = self.grammar.as_ref().ok_or_else(|| {
+            ModelError::InvalidParameter("model does not advertise grammar-constrained generation".to_string())
+        })?;
+
+        match grammar {
+            GrammarRequest::Json(schema) => {
+                if !support.json_schema {
+                    return Err(ModelError::InvalidParameter(
+                        "model does not support JSON Schema grammars".to_string(),
+                    ));
+                }
+                let validator = jsonschema::validator_for(schema)
+                    .map_err(|e| ModelError::InvalidParameter(format!("invalid JSON schema: {e}")))?;
+                Ok(CompiledGrammar::Json(validator))
+            }
+            GrammarRequest::Regex(pattern) => {
+                if !support.regex {
+                    return Err(ModelError::InvalidParameter(
+                        "model does not support regex grammars".to_string(),
+                    ));
+                }
+                let compiled = regex::Regex::new(pattern)
+                    .map_err(|e| ModelError::InvalidParameter(format!("invalid grammar regex: {e}")))?;
+                Ok(CompiledGrammar::Regex(compiled))
+            }
+        }
+    }
+
+    /// Validate `input` against [`ContextLimits`] before a request is
+    /// sent: counts input tokens (offloaded to a worker thread via
+    /// [`count_tokens`]), rejects an input that overflows `max_input_tokens` unless
+    /// `truncate` is set, and computes the output budget remaining in
+    /// `max_total_tokens` after `input`'s tokens are spent.
+    ///
+    /// When [`ContextLimits::unlimited_context`] is set, every window
+    /// check is skipped, but `config.max_tokens` is still checked against
+    /// `max_output_tokens` if one is configured.
+    pub fn validate_request(
+        &self,
+        input: &str,
+        tokenizer: &impl TokenCounter,
+        config: &ModelConfig,
+        truncate: Option<TruncationDirection>,
+    ) -> ModelResult<RequestValidation> {
+        let limits = &self.context_limits;
+
+        if limits.unlimited_context {
+            let allowed_output_tokens = limits.max_output_tokens.unwrap_or(u32::MAX);
+            if let Some(max_tokens) = config.max_tokens {
+                if max_tokens > allowed_output_tokens {
+                    return Err(ModelError::InvalidParameter(format!(
+                        "Max tokens {} exceeds model limit of {}", max_tokens, allowed_output_tokens
+                    )));
+                }
+            }
+            return Ok(RequestValidation {
+                input_tokens: count_tokens(tokenizer, input),
+                allowed_output_tokens: config.max_tokens.unwrap_or(allowed_output_tokens),
+                was_truncated: false,
+                text: input.to_string(),
+            });
+        }
+
+        let max_input_tokens = limits.max_input_tokens.ok_or_else(|| {
+            ModelError::InvalidParameter("model has no max_input_tokens configured".to_string())
+        })?;
+        let max_total_tokens = limits.max_total_tokens.ok_or_else(|| {
+            ModelError::InvalidParameter("model has no max_total_tokens configured".to_string())
+        })?;
+        if max_input_tokens >= max_total_tokens {
+            return Err(ModelError::InvalidParameter(format!(
+                "max_input_tokens ({}) must be less than max_total_tokens ({})", max_input_tokens, max_total_tokens
+            )));
+        }
+
+        let mut text = input.to_string();
+        let mut input_tokens = count_tokens(tokenizer, &text);
+        let mut was_truncated = false;
+
+        if input_tokens as u32 > max_input_tokens {
+            let Some(direction) = truncate else {
+                return Err(ModelError::InvalidParameter(format!(
+                    "input has {} tokens, exceeding max_input_tokens of {}", input_tokens, max_input_tokens
+                )));
+            };
+            text = truncate_to_tokens(tokenizer, &text, max_input_tokens, direction);
+            input_tokens = count_tokens(tokenizer, &text);
+            was_truncated = true;
+        }
+
+        let remaining_output_budget = max_total_tokens.saturating_sub(input_tokens as u32);
+        let allowed_output_tokens = match config.max_tokens {
+            Some(max_tokens) if max_tokens > remaining_output_budget => {
+                return Err(ModelError::InvalidParameter(format!(
+                    "Max tokens {} exceeds remaining output budget of {}", max_tokens, remaining_output_budget
+                )));
+            }
+            Some(max_tokens) => max_tokens,
+            None => remaining_output_budget,
+        };
+
+        Ok(RequestValidation { input_tokens, allowed_output_tokens, was_truncated, text })
+    }
+
     /// Get capability compatibility score with another set of capabilities
     pub fn compatibility_score(&self, required_capabilities: &[CapabilityType]) -> f64 {
         if required_capabilities.is_empty() {
@@ -443,13 +1024,29 @@ impl ModelCapabilities {
     /// Estimate cost for a given usage
     pub fn estimate_cost(&self, input_tokens: u32, output_tokens: u32) -> Option<f64> {
         self.cost_info.as_ref().map(|cost| {
-            let input_cost = cost.cost_per_input_token.unwrap_or(0.0) * input_tokens as f64;
-            let output_cost = cost.cost_per_output_token.unwrap_or(0.0) * output_tokens as f64;
+            let input_cost = cost.input_price_per_1k.unwrap_or(0.0) * input_tokens as f64 / 1000.0;
+            let output_cost = cost.output_price_per_1k.unwrap_or(0.0) * output_tokens as f64 / 1000.0;
             let request_cost = cost.cost_per_request.unwrap_or(0.0);
-            
+
             input_cost + output_cost + request_cost
         })
     }
+
+    /// Render a one-line summary for REPL/`list_models`-style display,
+    /// e.g. `gpt-4 | ctx=8192 | in=$0.0001/1K out=$0.0002/1K`, the way
+    /// aichat surfaces each model's context window and per-token pricing.
+    pub fn describe_model(&self, name: &str) -> String {
+        let ctx = match self.context_limits.context_window {
+            Some(window) => window.to_string(),
+            None => "unlimited".to_string(),
+        };
+        let price = |p: Option<f64>| p.map(|p| format!("${p}")).unwrap_or_else(|| "?".to_string());
+        let (input_price, output_price) = match &self.cost_info {
+            Some(cost) => (price(cost.input_price_per_1k), price(cost.output_price_per_1k)),
+            None => ("?".to_string(), "?".to_string()),
+        };
+        format!("{name} | ctx={ctx} | in={input_price}/1K out={output_price}/1K")
+    }
 }
 
 impl Default for ParameterConstraints {
@@ -574,14 +1171,316 @@ mod tests {
     fn test_cost_estimation() {
         let mut capabilities = ModelCapabilities::text_generation();
         capabilities.cost_info = Some(CostInfo {
-            cost_per_input_token: Some(0.0001),
-            cost_per_output_token: Some(0.0002),
+            input_price_per_1k: Some(0.0001),
+            output_price_per_1k: Some(0.0002),
             cost_per_request: Some(0.001),
             currency: "USD".to_string(),
             billing_model: BillingModel::PayPerToken,
         });
-        
+
         let cost = capabilities.estimate_cost(1000, 500);
-        assert_eq!(cost, Some(0.001 + 0.1 + 0.1)); // request + input + output
+        assert_eq!(cost, Some(0.001 + 0.0001 + 0.0001)); // request + input + output
+    }
+
+    #[test]
+    fn describe_model_renders_context_and_price() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.context_limits.context_window = Some(8192);
+        capabilities.cost_info = Some(CostInfo {
+            input_price_per_1k: Some(0.0001),
+            output_price_per_1k: Some(0.0002),
+            cost_per_request: None,
+            currency: "USD".to_string(),
+            billing_model: BillingModel::PayPerToken,
+        });
+
+        assert_eq!(
+            capabilities.describe_model("gpt-4"),
+            "gpt-4 | ctx=8192 | in=$0.0001/1K out=$0.0002/1K"
+        );
+    }
+
+    #[test]
+    fn describe_model_handles_missing_cost_info() {
+        let capabilities = ModelCapabilities::text_generation();
+        assert_eq!(capabilities.describe_model("mystery"), "mystery | ctx=unlimited | in=?/1K out=?/1K");
+    }
+
+    /// One token per character, so tests can reason about exact counts and
+    /// invert `encode`/`decode` without pulling in a real tokenizer.
+    struct CharTokenCounter;
+
+    impl TokenCounter for CharTokenCounter {
+        fn count(&self, text: &str) -> usize {
+            text.chars().count()
+        }
+
+        fn encode(&self, text: &str) -> Vec<u32> {
+            text.chars().map(|c| c as u32).collect()
+        }
+
+        fn decode(&self, tokens: &[u32]) -> String {
+            tokens.iter().filter_map(|&t| char::from_u32(t)).collect()
+        }
+    }
+
+    #[test]
+    fn validate_request_computes_remaining_output_budget() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.context_limits = ContextLimits {
+            max_input_tokens: Some(10),
+            max_output_tokens: Some(10),
+            max_total_tokens: Some(20),
+            context_window: Some(20),
+            unlimited_context: false,
+        };
+        let config = ModelConfig::default();
+
+        let result = capabilities.validate_request("0123456789", &CharTokenCounter, &config, None).unwrap();
+        assert_eq!(result.input_tokens, 10);
+        assert_eq!(result.allowed_output_tokens, 10);
+        assert!(!result.was_truncated);
+    }
+
+    #[test]
+    fn validate_request_errors_when_input_overflows_without_truncation() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.context_limits = ContextLimits {
+            max_input_tokens: Some(5),
+            max_output_tokens: Some(10),
+            max_total_tokens: Some(20),
+            context_window: Some(20),
+            unlimited_context: false,
+        };
+        let config = ModelConfig::default();
+
+        let result = capabilities.validate_request("0123456789", &CharTokenCounter, &config, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_request_truncates_from_the_requested_direction() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.context_limits = ContextLimits {
+            max_input_tokens: Some(5),
+            max_output_tokens: Some(10),
+            max_total_tokens: Some(20),
+            context_window: Some(20),
+            unlimited_context: false,
+        };
+        let config = ModelConfig::default();
+
+        let right = capabilities
+            .validate_request("0123456789", &CharTokenCounter, &config, Some(TruncationDirection::Right))
+            .unwrap();
+        assert!(right.was_truncated);
+        assert_eq!(right.text, "01234");
+
+        let left = capabilities
+            .validate_request("0123456789", &CharTokenCounter, &config, Some(TruncationDirection::Left))
+            .unwrap();
+        assert!(left.was_truncated);
+        assert_eq!(left.text, "56789");
+    }
+
+    #[test]
+    fn validate_request_skips_window_checks_when_unlimited() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.context_limits = ContextLimits {
+            max_input_tokens: Some(1),
+            max_output_tokens: Some(10),
+            max_total_tokens: Some(1),
+            context_window: None,
+            unlimited_context: true,
+        };
+        let mut config = ModelConfig::default();
+        config.max_tokens = Some(5);
+
+        let result = capabilities.validate_request("a very long prompt indeed", &CharTokenCounter, &config, None).unwrap();
+        assert!(!result.was_truncated);
+        assert_eq!(result.allowed_output_tokens, 5);
+
+        config.max_tokens = Some(20);
+        let result = capabilities.validate_request("a very long prompt indeed", &CharTokenCounter, &config, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_grammar_rejects_when_model_lacks_support() {
+        let capabilities = ModelCapabilities::text_generation();
+        let result = capabilities.validate_grammar(&GrammarRequest::Json(serde_json::json!({"type": "object"})));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_grammar_rejects_malformed_schema() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.grammar = Some(GrammarSupport { json_schema: true, regex: false });
+
+        let result = capabilities.validate_grammar(&GrammarRequest::Json(serde_json::json!({"type": "not-a-real-type"})));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_grammar_compiles_supported_requests() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.grammar = Some(GrammarSupport { json_schema: true, regex: true });
+
+        assert!(capabilities.validate_grammar(&GrammarRequest::Json(serde_json::json!({"type": "object"}))).is_ok());
+        assert!(capabilities.validate_grammar(&GrammarRequest::Regex(r"^\d+$".to_string())).is_ok());
+        assert!(capabilities.validate_grammar(&GrammarRequest::Regex("(".to_string())).is_err());
+    }
+
+    #[test]
+    fn grammar_cache_reuses_compiled_artifact() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.grammar = Some(GrammarSupport { json_schema: true, regex: false });
+        let cache = GrammarCache::new();
+        let request = GrammarRequest::Json(serde_json::json!({"type": "string"}));
+
+        let first = cache.get_or_compile(&capabilities, &request).unwrap();
+        let second = cache.get_or_compile(&capabilities, &request).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+    }
+
+    fn echo_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "echo".to_string(),
+            description: "Echo back a message".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": { "message": { "type": "string" } },
+                "required": ["message"],
+            }),
+            kind: ToolKind::Retrieve,
+        }
+    }
+
+    #[test]
+    fn validate_tool_call_requires_function_calling_capability() {
+        let mut capabilities = ModelCapabilities::new(vec![]);
+        capabilities.register_tool(echo_tool());
+
+        let result = capabilities.validate_tool_call("echo", &serde_json::json!({"message": "hi"}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_tool_call_rejects_unknown_tool() {
+        let capabilities = ModelCapabilities::new(vec![CapabilityType::FunctionCalling]);
+        let result = capabilities.validate_tool_call("missing", &serde_json::json!({}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_tool_call_checks_args_against_schema() {
+        let mut capabilities = ModelCapabilities::new(vec![CapabilityType::FunctionCalling]);
+        capabilities.register_tool(echo_tool());
+
+        assert!(capabilities.validate_tool_call("echo", &serde_json::json!({"message": "hi"})).is_ok());
+        assert!(capabilities.validate_tool_call("echo", &serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn register_tool_replaces_same_named_tool() {
+        let mut capabilities = ModelCapabilities::new(vec![CapabilityType::FunctionCalling]);
+        capabilities.register_tool(echo_tool());
+        capabilities.register_tool(echo_tool());
+        assert_eq!(capabilities.tools.len(), 1);
+
+        capabilities.remove_tool("echo");
+        assert!(capabilities.tools.is_empty());
+    }
+
+    #[test]
+    fn tool_call_session_reuses_previous_result() {
+        let mut session = ToolCallSession::new();
+        let args = serde_json::json!({"message": "hi"});
+        let index = session.push_call("echo", args.clone());
+        assert!(session.previous_result("echo", &args).is_none());
+
+        session.record_result(index, serde_json::json!({"echoed": "hi"}));
+        assert_eq!(session.previous_result("echo", &args), Some(&serde_json::json!({"echoed": "hi"})));
+    }
+
+    #[test]
+    fn tool_call_session_gates_execute_tools_behind_confirmation() {
+        let mut capabilities = ModelCapabilities::new(vec![CapabilityType::FunctionCalling]);
+        capabilities.register_tool(echo_tool());
+        capabilities.register_tool(ToolDefinition {
+            name: "delete_file".to_string(),
+            description: "Delete a file".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+            kind: ToolKind::Execute,
+        });
+
+        assert!(!ToolCallSession::requires_confirmation(&capabilities, "echo"));
+        assert!(ToolCallSession::requires_confirmation(&capabilities, "delete_file"));
+        assert!(ToolCallSession::requires_confirmation(&capabilities, "unknown_tool"));
+    }
+
+    #[test]
+    fn apply_defaults_fills_unset_parameters() {
+        let capabilities = ModelCapabilities::text_generation();
+        let mut config = ModelConfig::default();
+
+        capabilities.apply_defaults(&mut config);
+
+        assert_eq!(config.temperature, capabilities.parameter_constraints.temperature.as_ref().unwrap().default);
+        assert_eq!(config.top_p, capabilities.parameter_constraints.top_p.as_ref().unwrap().default);
+        assert_eq!(config.top_k, capabilities.parameter_constraints.top_k.as_ref().unwrap().default);
+    }
+
+    #[test]
+    fn apply_defaults_derives_max_tokens_from_context_window_when_unconstrained() {
+        let mut capabilities = ModelCapabilities::text_generation();
+        capabilities.parameter_constraints.max_tokens = None;
+        capabilities.context_limits.max_output_tokens = Some(4096);
+        capabilities.context_limits.context_window = Some(8192);
+        let mut config = ModelConfig::default();
+        config.max_tokens = None;
+
+        capabilities.apply_defaults(&mut config);
+
+        assert_eq!(config.max_tokens, Some(4096)); // min(4096, 8192 / 2)
+    }
+
+    #[test]
+    fn validate_config_with_strict_matches_validate_config() {
+        let capabilities = ModelCapabilities::text_generation();
+        let mut config = ModelConfig::default();
+        config.temperature = Some(10.0);
+
+        assert!(capabilities.validate_config_with(&mut config, ValidationMode::Strict).is_err());
+        assert!(capabilities.validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn validate_config_with_clamp_snaps_out_of_range_values_and_reports_them() {
+        let capabilities = ModelCapabilities::text_generation();
+        let mut config = ModelConfig::default();
+        config.temperature = Some(10.0);
+
+        let adjustments = capabilities.validate_config_with(&mut config, ValidationMode::Clamp).unwrap();
+
+        assert_eq!(config.temperature, Some(2.0)); // clamped to the range's max
+        assert_eq!(adjustments.len(), 1);
+        assert!(adjustments[0].contains("temperature"));
+    }
+
+    #[test]
+    fn validate_config_with_clamp_snaps_to_nearest_step() {
+        let capabilities = ModelCapabilities::text_generation();
+        let mut config = ModelConfig::default();
+        config.top_k = Some(57); // step is 1, so this is already on-step and valid
+
+        let adjustments = capabilities.validate_config_with(&mut config, ValidationMode::Clamp).unwrap();
+        assert!(adjustments.is_empty());
+        assert_eq!(config.top_k, Some(57));
+
+        config.top_k = Some(500); // above max(100), should clamp down
+        let adjustments = capabilities.validate_config_with(&mut config, ValidationMode::Clamp).unwrap();
+        assert_eq!(config.top_k, Some(100));
+        assert_eq!(adjustments.len(), 1);
     }
 }
\ No newline at end of file
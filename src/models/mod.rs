@@ -4,48 +4,88 @@
 //! model configurations, capabilities, and lifecycle.
 
 pub mod provider;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod registry;
 pub mod capabilities;
 pub mod config;
 pub mod errors;
+pub mod admin;
 
-pub use provider::{ModelProvider, ProviderInfo};
+pub use provider::{Authenticator, ModelProvider, ProviderInfo};
 pub use registry::{ModelRegistry, RegisteredModel};
 pub use capabilities::{ModelCapabilities, CapabilityType};
 pub use config::{ModelConfig, ProviderConfig};
 pub use errors::{ModelError, ModelResult};
+pub use admin::{RegistryCommand, RegistryResponse};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Caller on whose behalf a model operation is requested, threaded through
+/// every [`ModelService`] call so [`AccessControl`] has someone to check
+/// permissions for and audit.
+#[derive(Debug, Clone)]
+pub enum Principal {
+    User(String),
+    Service(String),
+    System,
+}
+
+/// The permission a model operation requires. Kept local to this module
+/// (rather than a project-wide RBAC enum) since `ModelService` is the
+/// first subsystem in this crate to need authorization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelPermission {
+    /// Required by `generate`/`generate_stream`, scoped to the target model.
+    UseApiKey,
+    /// Required by `register_provider`/`unregister_provider`.
+    ManagePermissions,
+    /// Required by `health_check`.
+    ViewAuditLogs,
+}
+
+/// Authorization hook [`DefaultModelService`] consults before acting, so a
+/// caller holding a service handle can't invoke any registered model
+/// regardless of role. A real deployment backs this with a full RBAC
+/// engine; the trait keeps `ModelService` decoupled from any one
+/// implementation.
+#[async_trait]
+pub trait AccessControl: Send + Sync {
+    /// Whether `principal` may exercise `permission` against `model_id`
+    /// (or the provider id, for provider-management permissions).
+    async fn check_permission(&self, principal: &Principal, permission: ModelPermission, model_id: &str) -> bool;
+}
+
 /// Core model service interface
 #[async_trait]
 pub trait ModelService: Send + Sync {
     /// Generate text using the specified model
-    async fn generate(&self, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<String>;
-    
+    async fn generate(&self, principal: &Principal, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<String>;
+
     /// Stream text generation (for real-time responses)
-    async fn generate_stream(&self, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>>;
-    
+    async fn generate_stream(&self, principal: &Principal, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>>;
+
     /// List available models
     fn list_models(&self) -> Vec<RegisteredModel>;
-    
+
     /// Get model information
     fn get_model_info(&self, model_id: &str) -> Option<&RegisteredModel>;
-    
+
     /// Register a new model provider
-    async fn register_provider(&mut self, provider: Box<dyn ModelProvider>) -> ModelResult<()>;
-    
+    async fn register_provider(&mut self, principal: &Principal, provider: Box<dyn ModelProvider>) -> ModelResult<()>;
+
     /// Unregister a model provider
-    fn unregister_provider(&mut self, provider_id: &str) -> ModelResult<()>;
-    
+    async fn unregister_provider(&mut self, principal: &Principal, provider_id: &str) -> ModelResult<()>;
+
     /// Validate model configuration
     fn validate_config(&self, model_id: &str, config: &ModelConfig) -> ModelResult<()>;
-    
+
     /// Get provider health status
-    async fn health_check(&self, provider_id: &str) -> ModelResult<ProviderHealth>;
+    async fn health_check(&self, principal: &Principal, provider_id: &str) -> ModelResult<ProviderHealth>;
 }
 
 /// Provider health information
@@ -71,6 +111,9 @@ pub struct DefaultModelService {
     registry: ModelRegistry,
     providers: HashMap<String, Box<dyn ModelProvider>>,
     config: ServiceConfig,
+    /// Authorizes every operation below when set; `None` runs unauthorized
+    /// (e.g. for tests and tools that haven't wired up RBAC yet).
+    access_control: Option<Arc<dyn AccessControl>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,38 +141,60 @@ impl DefaultModelService {
             registry: ModelRegistry::new(),
             providers: HashMap::new(),
             config,
+            access_control: None,
         }
     }
-    
+
     pub fn with_default_config() -> Self {
         Self::new(ServiceConfig::default())
     }
+
+    /// Authorize every subsequent operation through `access_control`.
+    pub fn with_access_control(mut self, access_control: Arc<dyn AccessControl>) -> Self {
+        self.access_control = Some(access_control);
+        self
+    }
+
+    /// Check `permission` against `resource_id` when an [`AccessControl`]
+    /// is configured; unconditionally allowed otherwise.
+    async fn authorize(&self, principal: &Principal, permission: ModelPermission, resource_id: &str) -> ModelResult<()> {
+        if let Some(access_control) = &self.access_control {
+            if !access_control.check_permission(principal, permission, resource_id).await {
+                return Err(ModelError::PermissionDenied(resource_id.to_string()));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl ModelService for DefaultModelService {
-    async fn generate(&self, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<String> {
+    async fn generate(&self, principal: &Principal, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<String> {
+        self.authorize(principal, ModelPermission::UseApiKey, model_id).await?;
+
         let model = self.registry.get_model(model_id)
             .ok_or_else(|| ModelError::ModelNotFound(model_id.to_string()))?;
-            
+
         let provider = self.providers.get(&model.provider_id)
             .ok_or_else(|| ModelError::ProviderNotFound(model.provider_id.clone()))?;
-            
+
         let effective_config = config.unwrap_or_else(|| model.default_config.clone());
-        
-        provider.generate(prompt, &effective_config).await
+
+        provider.generate(prompt, &effective_config, None).await
     }
-    
-    async fn generate_stream(&self, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>> {
+
+    async fn generate_stream(&self, principal: &Principal, model_id: &str, prompt: &str, config: Option<ModelConfig>) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>> {
+        self.authorize(principal, ModelPermission::UseApiKey, model_id).await?;
+
         let model = self.registry.get_model(model_id)
             .ok_or_else(|| ModelError::ModelNotFound(model_id.to_string()))?;
-            
+
         let provider = self.providers.get(&model.provider_id)
             .ok_or_else(|| ModelError::ProviderNotFound(model.provider_id.clone()))?;
-            
+
         let effective_config = config.unwrap_or_else(|| model.default_config.clone());
-        
-        provider.generate_stream(prompt, &effective_config).await
+
+        provider.generate_stream(prompt, &effective_config, None).await
     }
     
     fn list_models(&self) -> Vec<RegisteredModel> {
@@ -140,10 +205,12 @@ impl ModelService for DefaultModelService {
         self.registry.get_model(model_id)
     }
     
-    async fn register_provider(&mut self, provider: Box<dyn ModelProvider>) -> ModelResult<()> {
+    async fn register_provider(&mut self, principal: &Principal, provider: Box<dyn ModelProvider>) -> ModelResult<()> {
         let provider_info = provider.get_info();
         let provider_id = provider_info.id.clone();
-        
+
+        self.authorize(principal, ModelPermission::ManagePermissions, &provider_id).await?;
+
         // Validate provider
         provider.validate_config(&provider_info.default_config).await?;
         
@@ -156,6 +223,8 @@ impl ModelService for DefaultModelService {
                 capabilities: model_info.capabilities,
                 default_config: model_info.default_config,
                 created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                version: 1,
                 metadata: model_info.metadata,
             };
             
@@ -168,14 +237,16 @@ impl ModelService for DefaultModelService {
         Ok(())
     }
     
-    fn unregister_provider(&mut self, provider_id: &str) -> ModelResult<()> {
+    async fn unregister_provider(&mut self, principal: &Principal, provider_id: &str) -> ModelResult<()> {
+        self.authorize(principal, ModelPermission::ManagePermissions, provider_id).await?;
+
         // Remove all models from this provider
         self.registry.unregister_provider_models(provider_id)?;
-        
+
         // Remove provider
         self.providers.remove(provider_id)
             .ok_or_else(|| ModelError::ProviderNotFound(provider_id.to_string()))?;
-            
+
         Ok(())
     }
     
@@ -192,10 +263,12 @@ impl ModelService for DefaultModelService {
         Ok(())
     }
     
-    async fn health_check(&self, provider_id: &str) -> ModelResult<ProviderHealth> {
+    async fn health_check(&self, principal: &Principal, provider_id: &str) -> ModelResult<ProviderHealth> {
+        self.authorize(principal, ModelPermission::ViewAuditLogs, provider_id).await?;
+
         let provider = self.providers.get(provider_id)
             .ok_or_else(|| ModelError::ProviderNotFound(provider_id.to_string()))?;
-            
+
         let start_time = std::time::Instant::now();
         
         match provider.health_check().await {
@@ -220,18 +293,35 @@ impl ModelService for DefaultModelService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[tokio::test]
     async fn test_model_service_creation() {
         let service = DefaultModelService::with_default_config();
         assert_eq!(service.list_models().len(), 0);
     }
-    
+
     #[tokio::test]
     async fn test_model_not_found_error() {
         let service = DefaultModelService::with_default_config();
-        let result = service.generate("nonexistent", "test prompt", None).await;
-        
+        let result = service.generate(&Principal::System, "nonexistent", "test prompt", None).await;
+
         assert!(matches!(result, Err(ModelError::ModelNotFound(_))));
     }
+
+    struct DenyAll;
+
+    #[async_trait]
+    impl AccessControl for DenyAll {
+        async fn check_permission(&self, _principal: &Principal, _permission: ModelPermission, _model_id: &str) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_is_denied_without_use_api_key_permission() {
+        let service = DefaultModelService::with_default_config().with_access_control(Arc::new(DenyAll));
+        let result = service.generate(&Principal::User("someone".to_string()), "any-model", "test prompt", None).await;
+
+        assert!(matches!(result, Err(ModelError::PermissionDenied(model_id)) if model_id == "any-model"));
+    }
 }
\ No newline at end of file
@@ -4,23 +4,69 @@
 //! along with supporting types for provider information and model specifications.
 
 use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::logging::ApiLogger;
 use super::{ModelCapabilities, ModelConfig, ModelResult, ModelError};
 
+/// Smallest timeout a [`RequestConfig`] can request, so a caller passing
+/// `Duration::from_millis(1)` by mistake doesn't fail every request.
+const MIN_REQUEST_TIMEOUT_MS: u64 = 1_000;
+/// Largest timeout a [`RequestConfig`] can request, so a misconfigured
+/// caller can't hold a connection (and a retry loop) open indefinitely.
+const MAX_REQUEST_TIMEOUT_MS: u64 = 300_000;
+
+/// Per-request override of the provider's [`ConnectionConfig::timeout_ms`]
+/// and [`RetryConfig`], for callers that need one slow request to get
+/// extra time, or one best-effort request to skip retries entirely,
+/// without changing the provider's defaults for every other caller.
+#[derive(Debug, Clone, Default)]
+pub struct RequestConfig {
+    /// Overrides `ConnectionConfig::timeout_ms` for this request only.
+    /// Clamped to `[1s, 300s]` regardless of what's requested.
+    pub timeout: Option<std::time::Duration>,
+    /// Overrides the provider's `RetryConfig` for this request only.
+    pub retry: Option<RetryConfig>,
+    /// Skip retries entirely for this request, regardless of `retry`.
+    pub disable_retry: bool,
+    /// Known token count for this request (e.g. from a tokenizer the caller
+    /// already ran), charged against `RateLimitConfig::tokens_per_minute`.
+    /// Falls back to a byte-size estimate when not given.
+    pub estimated_tokens: Option<u64>,
+}
+
+impl RequestConfig {
+    /// The timeout this request should use, in milliseconds: `self.timeout`
+    /// if set (clamped to `[MIN_REQUEST_TIMEOUT_MS, MAX_REQUEST_TIMEOUT_MS]`),
+    /// otherwise `default_ms` unclamped (the provider's own default is
+    /// trusted as-is).
+    fn effective_timeout_ms(&self, default_ms: u64) -> u64 {
+        match self.timeout {
+            Some(timeout) => (timeout.as_millis() as u64).clamp(MIN_REQUEST_TIMEOUT_MS, MAX_REQUEST_TIMEOUT_MS),
+            None => default_ms,
+        }
+    }
+}
+
 /// Core interface that all model providers must implement
 #[async_trait]
 pub trait ModelProvider: Send + Sync {
     /// Get provider information and supported models
     fn get_info(&self) -> ProviderInfo;
-    
-    /// Generate text using this provider
-    async fn generate(&self, prompt: &str, config: &ModelConfig) -> ModelResult<String>;
-    
+
+    /// Generate text using this provider. `request_config`, if given,
+    /// overrides the provider's default timeout/retry behavior for this
+    /// call only.
+    async fn generate(&self, prompt: &str, config: &ModelConfig, request_config: Option<&RequestConfig>) -> ModelResult<String>;
+
     /// Stream text generation (optional, default implementation returns error)
-    async fn generate_stream(&self, prompt: &str, config: &ModelConfig) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>> {
+    async fn generate_stream(&self, prompt: &str, config: &ModelConfig, request_config: Option<&RequestConfig>) -> ModelResult<Box<dyn futures::Stream<Item = ModelResult<String>> + Unpin + Send>> {
         Err(ModelError::StreamingNotSupported)
     }
     
@@ -108,6 +154,25 @@ pub struct ProviderConfig {
     
     /// Provider-specific settings
     pub provider_specific: HashMap<String, serde_json::Value>,
+
+    /// Opt-in request/response capture for debugging, reusing
+    /// [`crate::logging::ApiLogger`]'s host/path-keyed JSONL layout.
+    pub request_logging: RequestLoggingConfig,
+}
+
+/// Opt-in request/response capture for a [`BaseProvider`]. Disabled by
+/// default — this writes every request and response body to disk, which
+/// is a debugging aid, not something to leave on in production.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestLoggingConfig {
+    pub enabled: bool,
+    /// Where captured JSONL logs are written. Defaults to `logs/providers`
+    /// when `enabled` and unset.
+    pub log_dir: Option<PathBuf>,
+    /// Header names (case-insensitive, in addition to `Authorization` and
+    /// whatever header `self.auth` sends credentials in) whose values get
+    /// redacted before being written.
+    pub redact_headers: Vec<String>,
 }
 
 /// Authentication configuration
@@ -135,6 +200,173 @@ pub enum AuthConfig {
     None,
 }
 
+impl AuthConfig {
+    /// The HTTP header name this auth method sends its credential in, so
+    /// request logging can always redact it even if the caller didn't
+    /// think to list it in `RequestLoggingConfig::redact_headers`.
+    fn credential_header_name(&self) -> Option<&str> {
+        match self {
+            AuthConfig::ApiKey { header_name, .. } => Some(header_name.as_deref().unwrap_or("x-api-key")),
+            AuthConfig::BearerToken { .. } => Some("authorization"),
+            AuthConfig::OAuth2 { .. } => Some("authorization"),
+            AuthConfig::None => None,
+        }
+    }
+}
+
+/// Grace period subtracted from a fetched OAuth2 token's `expires_in`, so
+/// a request doesn't start using a token that dies moments later.
+const OAUTH2_EXPIRY_SKEW_SECS: i64 = 30;
+
+/// A cached OAuth2 access token obtained via the client-credentials grant.
+#[derive(Debug, Clone)]
+struct OAuth2Token {
+    access_token: String,
+    /// When this token should be treated as expired and refreshed.
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl OAuth2Token {
+    fn is_expired(&self) -> bool {
+        chrono::Utc::now() >= self.expires_at
+    }
+}
+
+/// Strategy for attaching credentials to an outgoing request, decoupling
+/// `BaseProvider::make_request` from any one `AuthConfig` variant. The
+/// built-in variants below cover `AuthConfig`; a provider needing a
+/// scheme these don't model can implement this directly and construct a
+/// `BaseProvider` with [`BaseProvider::with_authenticator`].
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    /// Attach credentials to `request` in place.
+    async fn apply(&self, request: &mut reqwest::Request) -> ModelResult<()>;
+
+    /// Discard any cached credential so the next `apply` fetches a fresh
+    /// one. Called after a 401, in case the server revoked a credential
+    /// before this authenticator's own expiry check caught it. Schemes
+    /// with nothing to cache (API key, bearer token, none) are no-ops.
+    async fn refresh(&self) -> ModelResult<()> {
+        Ok(())
+    }
+}
+
+/// No credentials sent. Backs `AuthConfig::None`.
+struct NoAuthenticator;
+
+#[async_trait]
+impl Authenticator for NoAuthenticator {
+    async fn apply(&self, _request: &mut reqwest::Request) -> ModelResult<()> {
+        Ok(())
+    }
+}
+
+/// Sends a static API key in a configurable header. Backs `AuthConfig::ApiKey`.
+struct ApiKeyAuthenticator {
+    key: String,
+    header_name: String,
+}
+
+#[async_trait]
+impl Authenticator for ApiKeyAuthenticator {
+    async fn apply(&self, request: &mut reqwest::Request) -> ModelResult<()> {
+        let header_name = reqwest::header::HeaderName::from_bytes(self.header_name.as_bytes())
+            .map_err(|e| ModelError::ProviderError(format!("invalid API key header name: {}", e)))?;
+        let header_value = reqwest::header::HeaderValue::from_str(&self.key)
+            .map_err(|e| ModelError::ProviderError(format!("invalid API key value: {}", e)))?;
+        request.headers_mut().insert(header_name, header_value);
+        Ok(())
+    }
+}
+
+/// Sends a static bearer token. Backs `AuthConfig::BearerToken`.
+struct BearerTokenAuthenticator {
+    token: String,
+}
+
+#[async_trait]
+impl Authenticator for BearerTokenAuthenticator {
+    async fn apply(&self, request: &mut reqwest::Request) -> ModelResult<()> {
+        apply_bearer_token(request, &self.token)
+    }
+}
+
+/// Acquires, caches, and refreshes an OAuth2 client-credentials token.
+/// Backs `AuthConfig::OAuth2`.
+struct OAuth2Authenticator {
+    client_id: String,
+    client_secret: String,
+    token_url: String,
+    client: reqwest::Client,
+    cached: tokio::sync::Mutex<Option<OAuth2Token>>,
+}
+
+impl OAuth2Authenticator {
+    fn new(client: reqwest::Client, client_id: String, client_secret: String, token_url: String) -> Self {
+        Self { client_id, client_secret, token_url, client, cached: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Fetch a fresh token via the OAuth2 client-credentials grant.
+    async fn fetch_token(&self) -> ModelResult<OAuth2Token> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            expires_in: i64,
+        }
+
+        let response = self
+            .client
+            .post(&self.token_url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| ModelError::ProviderError(format!("OAuth2 token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ModelError::ProviderError(format!("OAuth2 token request returned {}: {}", status, body)));
+        }
+
+        let parsed: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| ModelError::ProviderError(format!("OAuth2 token response was not valid JSON: {}", e)))?;
+
+        Ok(OAuth2Token {
+            access_token: parsed.access_token,
+            expires_at: chrono::Utc::now() + chrono::Duration::seconds((parsed.expires_in - OAUTH2_EXPIRY_SKEW_SECS).max(0)),
+        })
+    }
+
+    /// The cached token, refreshing it first if it's missing, expired, or
+    /// `force_refresh` is set. Concurrent callers share one in-flight
+    /// fetch since they all hold the same mutex while fetching.
+    async fn token(&self, force_refresh: bool) -> ModelResult<String> {
+        let mut cached = self.cached.lock().await;
+        if force_refresh || cached.as_ref().map(OAuth2Token::is_expired).unwrap_or(true) {
+            *cached = Some(self.fetch_token().await?);
+        }
+        Ok(cached.as_ref().expect("just populated above").access_token.clone())
+    }
+}
+
+#[async_trait]
+impl Authenticator for OAuth2Authenticator {
+    async fn apply(&self, request: &mut reqwest::Request) -> ModelResult<()> {
+        let token = self.token(false).await?;
+        apply_bearer_token(request, &token)
+    }
+
+    async fn refresh(&self) -> ModelResult<()> {
+        self.token(true).await.map(|_| ())
+    }
+}
+
 /// Connection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionConfig {
@@ -149,6 +381,17 @@ pub struct ConnectionConfig {
     
     /// Connection pool settings
     pub pool_size: Option<usize>,
+
+    /// Client-side request-rate limiting, enforced by [`BaseProvider::make_request`].
+    pub rate_limits: RateLimitConfig,
+
+    /// Log a warning when a request (including time spent on retries)
+    /// takes at least this long. `None` disables the check.
+    pub slow_request_warn_ms: Option<u64>,
+
+    /// Response/request compression, only applied when the provider's
+    /// `ProviderCapabilities::compression` also opts in.
+    pub compression: CompressionConfig,
 }
 
 impl Default for ConnectionConfig {
@@ -158,10 +401,35 @@ impl Default for ConnectionConfig {
             max_concurrent: 5,
             retry: RetryConfig::default(),
             pool_size: Some(10),
+            rate_limits: RateLimitConfig::default(),
+            slow_request_warn_ms: Some(10_000),
+            compression: CompressionConfig::default(),
         }
     }
 }
 
+/// Compression negotiated with a provider. Gated, as a whole, behind
+/// `ProviderCapabilities::compression` — see that field's doc comment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Send `Accept-Encoding: gzip, deflate` and transparently decompress
+    /// a compressed response body. Handled by the underlying HTTP client,
+    /// so it costs nothing beyond the `Client` construction in `BaseProvider::new`.
+    pub accept_encoding: bool,
+
+    /// Gzip-compress the request body and send `Content-Encoding: gzip`.
+    /// Off by default: unlike response decompression, most APIs don't
+    /// expect a compressed request body, so this needs an explicit opt-in
+    /// on top of `ProviderCapabilities::compression`.
+    pub compress_requests: bool,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self { accept_encoding: true, compress_requests: false }
+    }
+}
+
 /// Retry configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryConfig {
@@ -178,6 +446,22 @@ pub struct RetryConfig {
     pub backoff_multiplier: f64,
 }
 
+impl RetryConfig {
+    /// Full-jitter exponential backoff (AWS's "Exponential Backoff and
+    /// Jitter" algorithm): `cap = min(max_delay_ms, base_delay_ms *
+    /// backoff_multiplier^(attempt - 1))`, then sleep a uniformly random
+    /// duration in `[0, cap]`. Spreads retries out instead of every
+    /// client retrying at exactly the same instant after a shared outage.
+    pub(crate) fn full_jitter_delay(&self, attempt: u32) -> std::time::Duration {
+        let cap = std::cmp::min(
+            self.max_delay_ms,
+            (self.base_delay_ms as f64 * self.backoff_multiplier.powi(attempt as i32 - 1)) as u64,
+        );
+        let jittered_ms = rand::thread_rng().gen_range(0..=cap.max(1));
+        std::time::Duration::from_millis(jittered_ms)
+    }
+}
+
 impl Default for RetryConfig {
     fn default() -> Self {
         Self {
@@ -212,12 +496,18 @@ pub struct ProviderCapabilities {
     
     /// Supports batch processing
     pub batch_processing: bool,
-    
+
     /// Maximum batch size (if batch processing is supported)
     pub max_batch_size: Option<usize>,
-    
+
     /// Supported formats
     pub supported_formats: Vec<String>,
+
+    /// Accepts a compressed request body and/or returns a compressed
+    /// response. `ConnectionConfig::compression` only takes effect when
+    /// this is `true` — some APIs reject an unexpected `Content-Encoding`,
+    /// so compression is never attempted unless the provider opts in.
+    pub compression: bool,
 }
 
 impl Default for ProviderCapabilities {
@@ -232,6 +522,7 @@ impl Default for ProviderCapabilities {
             batch_processing: false,
             max_batch_size: None,
             supported_formats: vec!["text".to_string()],
+            compression: false,
         }
     }
 }
@@ -256,7 +547,15 @@ pub struct UsageStats {
     
     /// Total cost (if applicable)
     pub total_cost: Option<f64>,
-    
+
+    /// Total request-body bytes sent over the wire, after compression
+    /// (if any) was applied.
+    pub bytes_sent: u64,
+
+    /// Total response-body bytes received over the wire, before
+    /// decompression (if any) was applied.
+    pub bytes_received: u64,
+
     /// Last updated timestamp
     pub last_updated: chrono::DateTime<chrono::Utc>,
 }
@@ -286,21 +585,185 @@ pub struct RateLimits {
     pub reset_time: chrono::DateTime<chrono::Utc>,
 }
 
+/// What [`BaseProvider::make_request`] does when a [`RateLimitConfig`]
+/// window is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateLimitBehavior {
+    /// Sleep until the exhausted window resets, then proceed.
+    Wait,
+    /// Return `ModelError::RateLimited` immediately instead of waiting.
+    FailFast,
+}
+
+impl Default for RateLimitBehavior {
+    fn default() -> Self {
+        RateLimitBehavior::Wait
+    }
+}
+
+/// Client-side rate limits [`BaseProvider::make_request`] enforces before
+/// sending a request, independent of (and resynced from) whatever the
+/// server reports via `X-RateLimit-*` response headers. A `None` limit is
+/// unbounded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub requests_per_minute: Option<u32>,
+    pub requests_per_hour: Option<u32>,
+    pub requests_per_day: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+    pub behavior: RateLimitBehavior,
+}
+
+/// A single sliding rate-limit window: a limit, how much of it has been
+/// used, and when it resets.
+#[derive(Debug, Clone, Copy)]
+struct RateWindow {
+    limit: Option<u32>,
+    used: u32,
+    resets_at: chrono::DateTime<chrono::Utc>,
+    duration: chrono::Duration,
+}
+
+impl RateWindow {
+    fn new(limit: Option<u32>, duration: chrono::Duration) -> Self {
+        Self { limit, used: 0, resets_at: chrono::Utc::now() + duration, duration }
+    }
+
+    fn reset_if_elapsed(&mut self) {
+        let now = chrono::Utc::now();
+        if now >= self.resets_at {
+            self.used = 0;
+            self.resets_at = now + self.duration;
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.limit.map(|limit| self.used >= limit).unwrap_or(false)
+    }
+
+    fn retry_after(&self) -> std::time::Duration {
+        (self.resets_at - chrono::Utc::now()).to_std().unwrap_or(std::time::Duration::ZERO)
+    }
+
+    /// Overwrite this window's state from server-reported truth (an
+    /// `X-RateLimit-*` header trio), which always wins over our own count.
+    fn resync(&mut self, limit: Option<u32>, remaining: Option<u32>, resets_at: Option<chrono::DateTime<chrono::Utc>>) {
+        if let Some(limit) = limit {
+            self.limit = Some(limit);
+        }
+        if let (Some(limit), Some(remaining)) = (self.limit, remaining) {
+            self.used = limit.saturating_sub(remaining);
+        }
+        if let Some(resets_at) = resets_at {
+            self.resets_at = resets_at;
+        }
+    }
+}
+
+/// Live enforcement state for a [`RateLimitConfig`]: one window per limit.
+struct RateLimitState {
+    requests_per_minute: RateWindow,
+    requests_per_hour: RateWindow,
+    requests_per_day: RateWindow,
+    tokens_per_minute: RateWindow,
+}
+
+impl RateLimitState {
+    fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            requests_per_minute: RateWindow::new(config.requests_per_minute, chrono::Duration::minutes(1)),
+            requests_per_hour: RateWindow::new(config.requests_per_hour, chrono::Duration::hours(1)),
+            requests_per_day: RateWindow::new(config.requests_per_day, chrono::Duration::days(1)),
+            tokens_per_minute: RateWindow::new(config.tokens_per_minute, chrono::Duration::minutes(1)),
+        }
+    }
+
+    fn windows_mut(&mut self) -> [&mut RateWindow; 4] {
+        [&mut self.requests_per_minute, &mut self.requests_per_hour, &mut self.requests_per_day, &mut self.tokens_per_minute]
+    }
+
+    /// The longest a caller would need to wait for every currently
+    /// exhausted window to reset, or `None` if none are exhausted.
+    fn exhausted_retry_after(&mut self) -> Option<std::time::Duration> {
+        self.windows_mut()
+            .into_iter()
+            .map(|window| {
+                window.reset_if_elapsed();
+                window
+            })
+            .filter(|window| window.is_exhausted())
+            .map(|window| window.retry_after())
+            .max()
+    }
+
+    fn record_request(&mut self) {
+        for window in [&mut self.requests_per_minute, &mut self.requests_per_hour, &mut self.requests_per_day] {
+            window.reset_if_elapsed();
+            window.used += 1;
+        }
+    }
+
+    /// Charge `tokens` against the `tokens_per_minute` window. Separate from
+    /// `record_request` since the amount varies per call instead of always
+    /// being 1.
+    fn record_tokens(&mut self, tokens: u64) {
+        self.tokens_per_minute.reset_if_elapsed();
+        self.tokens_per_minute.used = self.tokens_per_minute.used.saturating_add(tokens.min(u32::MAX as u64) as u32);
+    }
+}
+
+/// Rough fallback estimate of a request's token count from its serialized
+/// body size, used when a caller doesn't supply
+/// `RequestConfig::estimated_tokens` directly. ~4 bytes per token is the
+/// commonly cited rule of thumb for English text; once a response comes
+/// back, `resync_rate_limit_from_headers` reconciles with whatever the
+/// server actually reports.
+fn estimate_tokens_from_bytes(bytes: u64) -> u64 {
+    (bytes / 4).max(1)
+}
+
+/// Build the `Authenticator` a `BaseProvider` should use for `auth`,
+/// sharing `client` with `OAuth2Authenticator` so token fetches go
+/// through the same connection pool and timeout settings as everything
+/// else the provider sends.
+fn authenticator_for(auth: &AuthConfig, client: reqwest::Client) -> Box<dyn Authenticator> {
+    match auth {
+        AuthConfig::ApiKey { key, header_name } => Box::new(ApiKeyAuthenticator {
+            key: key.clone(),
+            header_name: header_name.clone().unwrap_or_else(|| "x-api-key".to_string()),
+        }),
+        AuthConfig::BearerToken { token } => Box::new(BearerTokenAuthenticator { token: token.clone() }),
+        AuthConfig::OAuth2 { client_id, client_secret, token_url } => {
+            Box::new(OAuth2Authenticator::new(client, client_id.clone(), client_secret.clone(), token_url.clone()))
+        }
+        AuthConfig::None => Box::new(NoAuthenticator),
+    }
+}
+
 /// Base provider implementation with common functionality
 pub struct BaseProvider {
     pub info: ProviderInfo,
     pub config: ProviderConfig,
     pub client: reqwest::Client,
     pub usage_stats: UsageStats,
+    rate_limit_state: tokio::sync::Mutex<RateLimitState>,
+    request_logger: Option<ApiLogger>,
+    authenticator: Box<dyn Authenticator>,
 }
 
 impl BaseProvider {
     pub fn new(info: ProviderInfo, config: ProviderConfig) -> ModelResult<Self> {
+        // Only advertise Accept-Encoding (and auto-decompress) when the
+        // provider itself opts in — some providers reject, or behave
+        // differently for, a request that negotiates compression.
+        let accept_compression = info.capabilities.compression && config.connection.compression.accept_encoding;
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_millis(config.connection.timeout_ms))
+            .gzip(accept_compression)
+            .deflate(accept_compression)
             .build()
             .map_err(|e| ModelError::ProviderError(format!("Failed to create HTTP client: {}", e)))?;
-            
+
         let usage_stats = UsageStats {
             total_requests: 0,
             successful_requests: 0,
@@ -308,84 +771,346 @@ impl BaseProvider {
             avg_response_time_ms: 0.0,
             total_tokens: None,
             total_cost: None,
+            bytes_sent: 0,
+            bytes_received: 0,
             last_updated: chrono::Utc::now(),
         };
-        
+
+        let rate_limit_state = tokio::sync::Mutex::new(RateLimitState::new(&config.connection.rate_limits));
+
+        let request_logger = if config.request_logging.enabled {
+            let log_dir = config.request_logging.log_dir.clone().unwrap_or_else(|| PathBuf::from("logs/providers"));
+            let mut redact_headers = config.request_logging.redact_headers.clone();
+            redact_headers.extend(config.auth.credential_header_name().map(str::to_string));
+
+            Some(
+                ApiLogger::new(log_dir, true)
+                    .map_err(|e| ModelError::ProviderError(format!("Failed to initialize request logger: {}", e)))?
+                    .with_redaction(redact_headers),
+            )
+        } else {
+            None
+        };
+
+        let authenticator = authenticator_for(&config.auth, client.clone());
+
         Ok(Self {
             info,
             config,
             client,
             usage_stats,
+            rate_limit_state,
+            request_logger,
+            authenticator,
         })
     }
+
+    /// Authenticate with a custom scheme instead of the one `config.auth`
+    /// would build, for providers whose credentials don't fit `AuthConfig`.
+    pub fn with_authenticator(mut self, authenticator: Box<dyn Authenticator>) -> Self {
+        self.authenticator = authenticator;
+        self
+    }
+
+    /// Block (or fail fast, per `self.config.connection.rate_limits.behavior`)
+    /// until every configured rate-limit window has room, then records the
+    /// request against the request-count windows and `estimated_tokens`
+    /// against `tokens_per_minute`.
+    async fn acquire_rate_limit(&self, estimated_tokens: u64) -> ModelResult<()> {
+        loop {
+            let retry_after = {
+                let mut state = self.rate_limit_state.lock().await;
+                state.exhausted_retry_after()
+            };
+
+            let Some(retry_after) = retry_after else { break };
+
+            match self.config.connection.rate_limits.behavior {
+                RateLimitBehavior::FailFast => return Err(ModelError::RateLimited { retry_after }),
+                RateLimitBehavior::Wait => tokio::time::sleep(retry_after).await,
+            }
+        }
+
+        let mut state = self.rate_limit_state.lock().await;
+        state.record_request();
+        state.record_tokens(estimated_tokens);
+        Ok(())
+    }
+
+    /// Resync rate-limit state from a response's `X-RateLimit-Remaining`,
+    /// `X-RateLimit-Limit`, and `X-RateLimit-Reset` headers (reset as Unix
+    /// seconds), plus their `-Tokens` counterparts for `tokens_per_minute`,
+    /// when the server sends them. Server-reported state always overrides
+    /// our own count.
+    async fn resync_rate_limit_from_headers(&self, headers: &reqwest::header::HeaderMap) {
+        let header_u32 = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).and_then(|v| v.parse::<u32>().ok());
+        let header_reset = |name: &str| {
+            header_u32(name).and_then(|secs| chrono::DateTime::<chrono::Utc>::from_timestamp(secs as i64, 0))
+        };
+
+        let limit = header_u32("x-ratelimit-limit");
+        let remaining = header_u32("x-ratelimit-remaining");
+        let resets_at = header_reset("x-ratelimit-reset");
+
+        let token_limit = header_u32("x-ratelimit-limit-tokens");
+        let token_remaining = header_u32("x-ratelimit-remaining-tokens");
+        let token_resets_at = header_reset("x-ratelimit-reset-tokens");
+
+        if limit.is_none() && remaining.is_none() && resets_at.is_none()
+            && token_limit.is_none() && token_remaining.is_none() && token_resets_at.is_none()
+        {
+            return;
+        }
+
+        let mut state = self.rate_limit_state.lock().await;
+        state.requests_per_minute.resync(limit, remaining, resets_at);
+        state.tokens_per_minute.resync(token_limit, token_remaining, token_resets_at);
+    }
+
+    /// Gzip-compress `request`'s body in place and set `Content-Encoding:
+    /// gzip`, if the provider's capabilities and config both opt in.
+    /// No-ops for bodies reqwest can't read back out (streaming bodies),
+    /// since there's nothing here to recompress.
+    fn maybe_compress_request(&self, request: &mut reqwest::Request) -> ModelResult<()> {
+        if !self.info.capabilities.compression || !self.config.connection.compression.compress_requests {
+            return Ok(());
+        }
+        let Some(body_bytes) = request.body().and_then(|b| b.as_bytes()) else { return Ok(()) };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(body_bytes)
+            .map_err(|e| ModelError::ProviderError(format!("failed to gzip request body: {}", e)))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| ModelError::ProviderError(format!("failed to gzip request body: {}", e)))?;
+
+        *request.body_mut() = Some(compressed.into());
+        request.headers_mut().insert(reqwest::header::CONTENT_ENCODING, reqwest::header::HeaderValue::from_static("gzip"));
+        Ok(())
+    }
+
+    /// Capture `request` to the configured [`ApiLogger`], if request
+    /// logging is enabled. Failures to log are a warning, not an error —
+    /// a broken log sink shouldn't break the actual request.
+    async fn log_outgoing_request(&self, request: &reqwest::Request) {
+        let Some(logger) = &self.request_logger else { return };
+
+        let url = request.url();
+        let host = url.host_str().unwrap_or("unknown").to_string();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+            .collect();
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok())
+            .unwrap_or(serde_json::Value::Null);
+
+        if let Err(e) = logger.log_request(&host, &path, request.method().as_str(), &headers, &body) {
+            tracing::warn!("failed to log outgoing provider request: {}", e);
+        }
+    }
+
+    /// Capture `response` to the configured [`ApiLogger`], if request
+    /// logging is enabled, then hand back an equivalent [`reqwest::Response`]
+    /// so the caller still sees the original status, headers, and body —
+    /// reading the body to log it would otherwise consume it.
+    async fn log_and_rewrap_response(&self, request: &reqwest::Request, response: reqwest::Response, duration_ms: u64) -> reqwest::Response {
+        let Some(logger) = &self.request_logger else { return response };
+
+        let url = request.url();
+        let host = url.host_str().unwrap_or("unknown").to_string();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = match response.bytes().await {
+            Ok(bytes) => bytes,
+            Err(_) => return http_response_from_parts(status, headers, Vec::new()),
+        };
+
+        let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        if let Err(e) = logger.log_response(&host, &path, status.as_u16(), &body, duration_ms) {
+            tracing::warn!("failed to log provider response: {}", e);
+        }
+
+        http_response_from_parts(status, headers, bytes.to_vec())
+    }
     
     /// Update usage statistics
-    pub fn update_stats(&mut self, success: bool, response_time_ms: u64, tokens: Option<u64>) {
+    pub fn update_stats(&mut self, success: bool, response_time_ms: u64, tokens: Option<u64>, bytes_sent: u64, bytes_received: u64) {
         self.usage_stats.total_requests += 1;
-        
+
         if success {
             self.usage_stats.successful_requests += 1;
         } else {
             self.usage_stats.failed_requests += 1;
         }
-        
+
         // Update average response time
         let total_time = self.usage_stats.avg_response_time_ms * (self.usage_stats.total_requests - 1) as f64;
         self.usage_stats.avg_response_time_ms = (total_time + response_time_ms as f64) / self.usage_stats.total_requests as f64;
-        
+
         if let Some(tokens) = tokens {
             self.usage_stats.total_tokens = Some(
                 self.usage_stats.total_tokens.unwrap_or(0) + tokens
             );
         }
-        
+
+        self.usage_stats.bytes_sent += bytes_sent;
+        self.usage_stats.bytes_received += bytes_received;
+
         self.usage_stats.last_updated = chrono::Utc::now();
     }
     
-    /// Perform HTTP request with retry logic
-    pub async fn make_request(&self, request: reqwest::Request) -> ModelResult<reqwest::Response> {
+    /// Perform HTTP request with retry logic. `request_config`, if given,
+    /// overrides the provider's default timeout and retry behavior for
+    /// this call only.
+    pub async fn make_request(&self, request: reqwest::Request, request_config: Option<&RequestConfig>) -> ModelResult<reqwest::Response> {
         let mut attempts = 0;
-        let retry_config = &self.config.connection.retry;
-        
-        loop {
-            let request_clone = request.try_clone()
-                .ok_or_else(|| ModelError::ProviderError("Failed to clone request".to_string()))?;
-                
-            match self.client.execute(request_clone).await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        return Ok(response);
-                    } else if attempts < retry_config.max_attempts && response.status().is_server_error() {
-                        // Retry on server errors
+        let mut auth_retried = false;
+        let default_retry_config = self.config.connection.retry.clone();
+        let retry_config = request_config.and_then(|rc| rc.retry.as_ref()).unwrap_or(&default_retry_config);
+        let max_attempts = if request_config.map(|rc| rc.disable_retry).unwrap_or(false) { 0 } else { retry_config.max_attempts };
+        let timeout_ms = request_config
+            .map(|rc| rc.effective_timeout_ms(self.config.connection.timeout_ms))
+            .unwrap_or(self.config.connection.timeout_ms);
+        let timeout = std::time::Duration::from_millis(timeout_ms);
+        let started_at = std::time::Instant::now();
+
+        let estimated_tokens = request_config
+            .and_then(|rc| rc.estimated_tokens)
+            .unwrap_or_else(|| estimate_tokens_from_bytes(request.body().and_then(|b| b.as_bytes()).map(|b| b.len() as u64).unwrap_or(0)));
+        self.acquire_rate_limit(estimated_tokens).await?;
+
+        let mut request = request;
+        self.authenticator.apply(&mut request).await?;
+        self.log_outgoing_request(&request).await;
+        self.maybe_compress_request(&mut request)?;
+
+        let bytes_sent = request.body().and_then(|b| b.as_bytes()).map(|b| b.len() as u64).unwrap_or(0);
+
+        let result = loop {
+            // `try_clone` fails on a streaming/non-repeatable body; surface
+            // that before any sleep rather than retrying something that
+            // can never succeed.
+            let request_clone = match request.try_clone() {
+                Some(r) => r,
+                None => break Err(ModelError::ProviderError("Failed to clone request".to_string())),
+            };
+
+            let outcome = tokio::time::timeout(timeout, self.client.execute(request_clone)).await;
+
+            match outcome {
+                Err(_) if attempts < max_attempts => {
+                    attempts += 1;
+                    tokio::time::sleep(retry_config.full_jitter_delay(attempts)).await;
+                    continue;
+                }
+                Err(_) => break Err(ModelError::ProviderError(format!("Request timed out after {}ms", timeout_ms))),
+                Ok(Ok(response)) => {
+                    self.resync_rate_limit_from_headers(response.headers()).await;
+
+                    let status = response.status();
+                    let retryable = status.is_server_error() || status.as_u16() == 429;
+
+                    if status.is_success() {
+                        let duration_ms = started_at.elapsed().as_millis() as u64;
+                        let bytes_received = response.content_length().unwrap_or(0);
+                        tracing::debug!(bytes_sent, bytes_received, "model provider request completed");
+                        break Ok(self.log_and_rewrap_response(&request, response, duration_ms).await);
+                    } else if status.as_u16() == 401 && !auth_retried {
+                        // The credential may have been revoked or expired
+                        // server-side before the authenticator's own check
+                        // caught it. Refresh and retry exactly once,
+                        // independent of the normal retry budget; a no-op
+                        // for schemes (API key, bearer token, none) with
+                        // nothing to refresh.
+                        auth_retried = true;
+                        self.authenticator.refresh().await?;
+                        self.authenticator.apply(&mut request).await?;
+                        continue;
+                    } else if attempts < max_attempts && retryable {
                         attempts += 1;
-                        let delay = std::cmp::min(
-                            retry_config.base_delay_ms * (retry_config.backoff_multiplier.powi(attempts as i32 - 1) as u64),
-                            retry_config.max_delay_ms
-                        );
-                        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                        let delay = retry_after_header(&response).unwrap_or_else(|| retry_config.full_jitter_delay(attempts));
+                        tokio::time::sleep(delay).await;
                         continue;
                     } else {
-                        return Err(ModelError::ProviderError(
-                            format!("HTTP error: {} - {}", response.status(), 
-                                   response.text().await.unwrap_or_default())
+                        break Err(ModelError::ProviderError(
+                            format!("HTTP error: {} - {}", status, response.text().await.unwrap_or_default())
                         ));
                     }
                 }
-                Err(e) if attempts < retry_config.max_attempts => {
+                Ok(Err(e)) if attempts < max_attempts => {
                     attempts += 1;
-                    let delay = std::cmp::min(
-                        retry_config.base_delay_ms * (retry_config.backoff_multiplier.powi(attempts as i32 - 1) as u64),
-                        retry_config.max_delay_ms
-                    );
-                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                    tokio::time::sleep(retry_config.full_jitter_delay(attempts)).await;
                     continue;
                 }
-                Err(e) => {
-                    return Err(ModelError::ProviderError(format!("Request failed: {}", e)));
-                }
+                Ok(Err(e)) => break Err(ModelError::ProviderError(format!("Request failed: {}", e))),
+            }
+        };
+
+        if let Some(threshold_ms) = self.config.connection.slow_request_warn_ms {
+            let elapsed = started_at.elapsed();
+            if elapsed.as_millis() as u64 >= threshold_ms {
+                tracing::warn!(
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    attempts,
+                    "model provider request exceeded slow-request threshold of {}ms",
+                    threshold_ms
+                );
             }
         }
+
+        result
+    }
+}
+
+/// Parse a retryable response's `Retry-After` header, either a delta in
+/// seconds or an HTTP-date, per RFC 9110 §10.2.3. Returns `None` if the
+/// header is absent or unparseable, so the caller falls back to computed
+/// backoff.
+fn retry_after_header(response: &reqwest::Response) -> Option<std::time::Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let when = chrono::DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&chrono::Utc);
+    (when - chrono::Utc::now()).to_std().ok()
+}
+
+/// Set the `Authorization: Bearer <token>` header on `request`, replacing
+/// whatever was there before (e.g. a stale cached token being refreshed).
+fn apply_bearer_token(request: &mut reqwest::Request, token: &str) -> ModelResult<()> {
+    let header_value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|e| ModelError::ProviderError(format!("invalid OAuth2 bearer token: {}", e)))?;
+    request.headers_mut().insert(reqwest::header::AUTHORIZATION, header_value);
+    Ok(())
+}
+
+/// Rebuild a [`reqwest::Response`] from its parts, for when the original
+/// response's body has already been consumed (e.g. by request logging) and
+/// the caller still needs a response with the original status, headers,
+/// and body.
+fn http_response_from_parts(status: reqwest::StatusCode, headers: reqwest::header::HeaderMap, body: Vec<u8>) -> reqwest::Response {
+    let mut builder = http::Response::builder().status(status);
+    if let Some(response_headers) = builder.headers_mut() {
+        *response_headers = headers;
     }
+    let http_response = builder.body(body).expect("status and headers were already valid");
+    reqwest::Response::from(http_response)
 }
 
 #[cfg(test)]
@@ -405,6 +1130,7 @@ mod tests {
                 auth: AuthConfig::None,
                 connection: ConnectionConfig::default(),
                 provider_specific: HashMap::new(),
+                request_logging: RequestLoggingConfig::default(),
             },
             capabilities: ProviderCapabilities::default(),
             metadata: HashMap::new(),
@@ -424,4 +1150,180 @@ mod tests {
         assert_eq!(config.base_delay_ms, 1000);
         assert_eq!(config.backoff_multiplier, 2.0);
     }
+
+    #[test]
+    fn request_config_falls_back_to_the_provider_default_when_unset() {
+        let config = RequestConfig::default();
+        assert_eq!(config.effective_timeout_ms(5_000), 5_000);
+    }
+
+    #[test]
+    fn request_config_clamps_an_out_of_range_timeout() {
+        let too_short = RequestConfig { timeout: Some(std::time::Duration::from_millis(10)), ..Default::default() };
+        assert_eq!(too_short.effective_timeout_ms(5_000), MIN_REQUEST_TIMEOUT_MS);
+
+        let too_long = RequestConfig { timeout: Some(std::time::Duration::from_secs(3600)), ..Default::default() };
+        assert_eq!(too_long.effective_timeout_ms(5_000), MAX_REQUEST_TIMEOUT_MS);
+    }
+
+    fn test_provider(rate_limits: RateLimitConfig) -> BaseProvider {
+        let config = ProviderConfig {
+            endpoint: "https://api.example.com".to_string(),
+            auth: AuthConfig::None,
+            connection: ConnectionConfig { rate_limits, ..ConnectionConfig::default() },
+            provider_specific: HashMap::new(),
+            request_logging: RequestLoggingConfig::default(),
+        };
+
+        BaseProvider::new(
+            ProviderInfo {
+                id: "test-provider".to_string(),
+                name: "Test Provider".to_string(),
+                description: String::new(),
+                version: "1.0.0".to_string(),
+                supported_models: vec![],
+                default_config: config.clone(),
+                capabilities: ProviderCapabilities::default(),
+                metadata: HashMap::new(),
+            },
+            config,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn acquire_rate_limit_fails_fast_once_the_request_window_is_exhausted() {
+        let provider = test_provider(RateLimitConfig {
+            requests_per_minute: Some(1),
+            behavior: RateLimitBehavior::FailFast,
+            ..Default::default()
+        });
+
+        provider.acquire_rate_limit(0).await.unwrap();
+        let result = provider.acquire_rate_limit(0).await;
+        assert!(matches!(result, Err(ModelError::RateLimited { .. })));
+    }
+
+    #[tokio::test]
+    async fn acquire_rate_limit_is_unbounded_with_no_limits_configured() {
+        let provider = test_provider(RateLimitConfig::default());
+
+        for _ in 0..5 {
+            provider.acquire_rate_limit(0).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn acquire_rate_limit_fails_fast_once_the_token_window_is_exhausted() {
+        let provider = test_provider(RateLimitConfig {
+            tokens_per_minute: Some(100),
+            behavior: RateLimitBehavior::FailFast,
+            ..Default::default()
+        });
+
+        provider.acquire_rate_limit(80).await.unwrap();
+        let result = provider.acquire_rate_limit(80).await;
+        assert!(matches!(result, Err(ModelError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn full_jitter_delay_never_exceeds_the_computed_cap() {
+        let retry_config = RetryConfig { base_delay_ms: 100, max_delay_ms: 1_000, backoff_multiplier: 2.0, max_attempts: 5 };
+
+        for attempt in 1..=5 {
+            let cap_ms = std::cmp::min(1_000, (100.0 * 2f64.powi(attempt - 1)) as u64);
+            for _ in 0..20 {
+                let delay = retry_config.full_jitter_delay(attempt as u32);
+                assert!(delay.as_millis() as u64 <= cap_ms);
+            }
+        }
+    }
+
+    #[test]
+    fn oauth2_token_is_expired_once_past_its_deadline() {
+        let token = OAuth2Token { access_token: "t".to_string(), expires_at: chrono::Utc::now() - chrono::Duration::seconds(1) };
+        assert!(token.is_expired());
+
+        let token = OAuth2Token { access_token: "t".to_string(), expires_at: chrono::Utc::now() + chrono::Duration::seconds(60) };
+        assert!(!token.is_expired());
+    }
+
+    #[tokio::test]
+    async fn no_authenticator_leaves_a_request_unmodified() {
+        let provider = test_provider(RateLimitConfig::default());
+        let mut request = reqwest::Request::new(reqwest::Method::GET, "https://api.example.com".parse().unwrap());
+
+        provider.authenticator.apply(&mut request).await.unwrap();
+
+        assert!(!request.headers().contains_key(reqwest::header::AUTHORIZATION));
+    }
+
+    #[tokio::test]
+    async fn api_key_authenticator_sets_the_configured_header() {
+        let authenticator = ApiKeyAuthenticator { key: "secret".to_string(), header_name: "x-api-key".to_string() };
+        let mut request = reqwest::Request::new(reqwest::Method::GET, "https://api.example.com".parse().unwrap());
+
+        authenticator.apply(&mut request).await.unwrap();
+
+        assert_eq!(request.headers().get("x-api-key").unwrap(), "secret");
+    }
+
+    #[tokio::test]
+    async fn bearer_token_authenticator_sets_the_authorization_header() {
+        let authenticator = BearerTokenAuthenticator { token: "tok".to_string() };
+        let mut request = reqwest::Request::new(reqwest::Method::GET, "https://api.example.com".parse().unwrap());
+
+        authenticator.apply(&mut request).await.unwrap();
+
+        assert_eq!(request.headers().get(reqwest::header::AUTHORIZATION).unwrap(), "Bearer tok");
+    }
+
+    fn provider_with_compression(compress_requests: bool) -> BaseProvider {
+        let config = ProviderConfig {
+            endpoint: "https://api.example.com".to_string(),
+            auth: AuthConfig::None,
+            connection: ConnectionConfig { compression: CompressionConfig { accept_encoding: true, compress_requests }, ..ConnectionConfig::default() },
+            provider_specific: HashMap::new(),
+            request_logging: RequestLoggingConfig::default(),
+        };
+
+        BaseProvider::new(
+            ProviderInfo {
+                id: "test-provider".to_string(),
+                name: "Test Provider".to_string(),
+                description: String::new(),
+                version: "1.0.0".to_string(),
+                supported_models: vec![],
+                default_config: config.clone(),
+                capabilities: ProviderCapabilities { compression: true, ..ProviderCapabilities::default() },
+                metadata: HashMap::new(),
+            },
+            config,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn maybe_compress_request_gzips_the_body_when_opted_in() {
+        let provider = provider_with_compression(true);
+        let mut request = reqwest::Request::new(reqwest::Method::POST, "https://api.example.com".parse().unwrap());
+        *request.body_mut() = Some(reqwest::Body::from("hello world"));
+
+        provider.maybe_compress_request(&mut request).unwrap();
+
+        assert_eq!(request.headers().get(reqwest::header::CONTENT_ENCODING).unwrap(), "gzip");
+        assert_ne!(request.body().unwrap().as_bytes().unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn maybe_compress_request_is_a_no_op_when_not_opted_in() {
+        let provider = provider_with_compression(false);
+        let mut request = reqwest::Request::new(reqwest::Method::POST, "https://api.example.com".parse().unwrap());
+        *request.body_mut() = Some(reqwest::Body::from("hello world"));
+
+        provider.maybe_compress_request(&mut request).unwrap();
+
+        assert!(request.headers().get(reqwest::header::CONTENT_ENCODING).is_none());
+        assert_eq!(request.body().unwrap().as_bytes().unwrap(), b"hello world");
+    }
 }
\ No newline at end of file
@@ -3,17 +3,42 @@
 //! Maintains a registry of available models and their configurations.
 //! Handles model registration, lookup, and lifecycle management.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use uuid::Uuid;
 
-use super::{ModelCapabilities, ModelConfig, ModelError, ModelResult};
+use super::{CapabilityType, ModelCapabilities, ModelConfig, ModelError, ModelResult};
+
+/// On-disk schema version for [`ModelRegistry::save_snapshot`]. Bump this
+/// whenever [`RegistrySnapshot`]'s shape changes, and add the
+/// corresponding step to [`ModelRegistry::migrate_step`] so older
+/// snapshots keep loading.
+const SCHEMA_VERSION: u32 = 1;
+
+/// The envelope written by [`ModelRegistry::save_snapshot`]: a
+/// `schema_version` tag plus enough of the registry's state to
+/// reconstruct it (`provider_models` is derived, not stored).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistrySnapshot {
+    schema_version: u32,
+    models: Vec<RegisteredModel>,
+    tombstones: HashMap<String, DateTime<Utc>>,
+    aliases: HashMap<String, String>,
+}
 
 /// Registry for managing available models
 #[derive(Debug, Clone)]
 pub struct ModelRegistry {
     models: HashMap<String, RegisteredModel>,
     provider_models: HashMap<String, Vec<String>>, // provider_id -> model_ids
+    /// Model IDs that were unregistered, and when. Kept (rather than just
+    /// dropping the model) so [`Self::merge`] can tell a deletion apart
+    /// from a peer that simply never learned about the model.
+    tombstones: HashMap<String, DateTime<Utc>>,
+    /// Human-friendly name -> model ID, e.g. "latest-gpt" -> "gpt-4-turbo-2024-04-09".
+    aliases: HashMap<String, String>,
 }
 
 /// A model registered in the system
@@ -36,18 +61,142 @@ pub struct RegisteredModel {
     
     /// When this model was registered
     pub created_at: chrono::DateTime<chrono::Utc>,
-    
+
+    /// When this model's entry last changed (registration, config/metadata
+    /// update). Compared against [`ModelRegistry::tombstones`] entries and
+    /// peers' `updated_at`/`version` during [`ModelRegistry::merge`] to
+    /// decide which side's copy wins.
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+
+    /// Monotonically increasing per-model edit counter, bumped on every
+    /// change. Breaks ties between two entries with the same `updated_at`
+    /// during [`ModelRegistry::merge`].
+    pub version: u64,
+
     /// Model metadata
     pub metadata: HashMap<String, String>,
 }
 
+impl RegisteredModel {
+    /// Render a one-line summary for REPL/`list_models`-style display,
+    /// e.g. `gpt-4 | ctx=8192 | in=$0.0001/1K out=$0.0002/1K`.
+    pub fn describe_model(&self) -> String {
+        self.capabilities.describe_model(&self.name)
+    }
+}
+
+/// A spending ceiling for [`ModelRegistry::select_best`]: the estimated
+/// cost of the caller's workload must not exceed `max_cost`, in the same
+/// currency [`super::capabilities::CostInfo`] was priced in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub max_cost: f64,
+}
+
+/// What to rank surviving candidates by in [`ModelRegistry::select_models`],
+/// highest value first. A missing value (e.g. no `context_window` set)
+/// ranks lowest rather than excluding the model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankingKey {
+    /// `created_at`, as milliseconds since the epoch — prefers the most
+    /// recently registered model.
+    NewestRegistration,
+    /// `capabilities.context_limits.context_window`.
+    ContextWindow,
+    /// A numeric value parsed out of `metadata[key]`.
+    MetadataNumeric(String),
+}
+
+impl RankingKey {
+    fn value(&self, model: &RegisteredModel) -> f64 {
+        match self {
+            RankingKey::NewestRegistration => model.created_at.timestamp_millis() as f64,
+            RankingKey::ContextWindow => model
+                .capabilities
+                .context_limits
+                .context_window
+                .map(|w| w as f64)
+                .unwrap_or(f64::NEG_INFINITY),
+            RankingKey::MetadataNumeric(key) => model
+                .metadata
+                .get(key)
+                .and_then(|v| v.parse::<f64>().ok())
+                .unwrap_or(f64::NEG_INFINITY),
+        }
+    }
+}
+
+/// Criteria for [`ModelRegistry::select_model`]/[`ModelRegistry::select_models`]:
+/// which capabilities a candidate must have, which providers to prefer
+/// when candidates tie, and how to rank the rest.
+#[derive(Debug, Clone)]
+pub struct SelectionCriteria {
+    /// Every one of these must be present (via `has_capability_type`) for
+    /// a model to be considered at all.
+    pub required_capabilities: Vec<CapabilityType>,
+    /// Preference order for tiebreaking; a provider not listed here sorts
+    /// after every provider that is.
+    pub preferred_providers: Vec<String>,
+    pub ranking: RankingKey,
+}
+
+impl Default for SelectionCriteria {
+    fn default() -> Self {
+        Self {
+            required_capabilities: Vec::new(),
+            preferred_providers: Vec::new(),
+            ranking: RankingKey::NewestRegistration,
+        }
+    }
+}
+
 impl ModelRegistry {
     /// Create a new empty model registry
     pub fn new() -> Self {
         Self {
             models: HashMap::new(),
             provider_models: HashMap::new(),
+            tombstones: HashMap::new(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Point `alias` at `model_id`, e.g. so callers can ask for "latest"
+    /// instead of a dated model ID. Rejects an alias that collides with a
+    /// real model ID (to keep lookups unambiguous) and a target that
+    /// doesn't exist yet; re-aliasing an existing alias to a new target is
+    /// allowed and simply overwrites it.
+    pub fn alias_model(&mut self, alias: &str, model_id: &str) -> ModelResult<()> {
+        if self.models.contains_key(alias) {
+            return Err(ModelError::ModelAlreadyExists(alias.to_string()));
         }
+
+        if !self.models.contains_key(model_id) {
+            return Err(ModelError::ModelNotFound(model_id.to_string()));
+        }
+
+        self.aliases.insert(alias.to_string(), model_id.to_string());
+        Ok(())
+    }
+
+    /// Resolve `alias` to its target model, if the alias is registered and
+    /// the target still exists.
+    pub fn resolve_alias(&self, alias: &str) -> Option<&RegisteredModel> {
+        self.aliases.get(alias).and_then(|model_id| self.models.get(model_id))
+    }
+
+    /// List all alias -> model ID mappings.
+    pub fn list_aliases(&self) -> Vec<(String, String)> {
+        self.aliases.iter().map(|(alias, model_id)| (alias.clone(), model_id.clone())).collect()
+    }
+
+    /// Remove an alias. Unlike [`Self::unregister_model`], this doesn't
+    /// leave anything behind for [`Self::merge`] to reconcile: aliases are
+    /// a convenience index, not independently-edited state.
+    pub fn remove_alias(&mut self, alias: &str) -> ModelResult<()> {
+        self.aliases.remove(alias)
+            .map(|_| ())
+            .ok_or_else(|| ModelError::ModelNotFound(alias.to_string()))
     }
     
     /// Register a new model
@@ -63,27 +212,33 @@ impl ModelRegistry {
             .or_insert_with(Vec::new)
             .push(model.id.clone());
         
+        // A re-registration after a prior delete supersedes the tombstone.
+        self.tombstones.remove(&model.id);
+
         // Register the model
         self.models.insert(model.id.clone(), model);
-        
+
         Ok(())
     }
-    
+
     /// Unregister a model
     pub fn unregister_model(&mut self, model_id: &str) -> ModelResult<RegisteredModel> {
         let model = self.models.remove(model_id)
             .ok_or_else(|| ModelError::ModelNotFound(model_id.to_string()))?;
-        
+
         // Remove from provider mapping
         if let Some(provider_models) = self.provider_models.get_mut(&model.provider_id) {
             provider_models.retain(|id| id != model_id);
-            
+
             // Remove provider entry if no models left
             if provider_models.is_empty() {
                 self.provider_models.remove(&model.provider_id);
             }
         }
-        
+
+        self.tombstones.insert(model_id.to_string(), Utc::now());
+        self.aliases.retain(|_, target| target != model_id);
+
         Ok(model)
     }
     
@@ -138,6 +293,96 @@ impl ModelRegistry {
             .collect()
     }
     
+    /// Pick the cheapest model that supports every capability in
+    /// `required`, for a workload of `(input_tokens, output_tokens)`,
+    /// staying within `budget` if one is given.
+    ///
+    /// Only models with a perfect [`ModelCapabilities::compatibility_score`]
+    /// (every required capability present) are considered. Survivors are
+    /// ranked by [`ModelCapabilities::estimate_cost`]: a model with no
+    /// `cost_info` sorts last (unknown cost is worse than any known
+    /// price), and ties break in favor of the larger `context_window`.
+    pub fn select_best(
+        &self,
+        required: &[CapabilityType],
+        workload: (u32, u32),
+        budget: Option<Budget>,
+    ) -> Option<RegisteredModel> {
+        let (input_tokens, output_tokens) = workload;
+
+        let mut candidates: Vec<(&RegisteredModel, Option<f64>)> = self
+            .models
+            .values()
+            .filter(|model| model.capabilities.compatibility_score(required) >= 1.0)
+            .map(|model| (model, model.capabilities.estimate_cost(input_tokens, output_tokens)))
+            .filter(|(_, cost)| match (cost, budget) {
+                (Some(cost), Some(budget)) => *cost <= budget.max_cost,
+                _ => true,
+            })
+            .collect();
+
+        candidates.sort_by(|(model_a, cost_a), (model_b, cost_b)| {
+            match (cost_a, cost_b) {
+                (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal).then_with(|| {
+                    model_b.capabilities.context_limits.context_window.cmp(&model_a.capabilities.context_limits.context_window)
+                }),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => {
+                    model_b.capabilities.context_limits.context_window.cmp(&model_a.capabilities.context_limits.context_window)
+                }
+            }
+        });
+
+        candidates.into_iter().next().map(|(model, _)| model.clone())
+    }
+
+    /// Pick the best model matching `criteria`: every model with all of
+    /// `criteria.required_capabilities` survives, ties in
+    /// `criteria.ranking` are broken by `criteria.preferred_providers`
+    /// order (earlier entries win; a provider not listed sorts last), and
+    /// [`Self::select_models`] exposes the full ranked list if a caller
+    /// wants fallbacks.
+    pub fn select_model(&self, criteria: &SelectionCriteria) -> Option<&RegisteredModel> {
+        self.select_models(criteria).into_iter().next()
+    }
+
+    /// Same as [`Self::select_model`] but returns every matching model,
+    /// ranked best-first.
+    pub fn select_models(&self, criteria: &SelectionCriteria) -> Vec<&RegisteredModel> {
+        let mut candidates: Vec<&RegisteredModel> = self
+            .models
+            .values()
+            .filter(|model| {
+                criteria
+                    .required_capabilities
+                    .iter()
+                    .all(|cap| model.capabilities.has_capability_type(cap))
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            let provider_rank = |model: &RegisteredModel| {
+                criteria
+                    .preferred_providers
+                    .iter()
+                    .position(|p| p == &model.provider_id)
+                    .unwrap_or(criteria.preferred_providers.len())
+            };
+
+            provider_rank(a).cmp(&provider_rank(b)).then_with(|| {
+                // Descending by ranking value, so the best candidate sorts first.
+                criteria
+                    .ranking
+                    .value(b)
+                    .partial_cmp(&criteria.ranking.value(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+
+        candidates
+    }
+
     /// Get registry statistics
     pub fn get_stats(&self) -> RegistryStats {
         let total_models = self.models.len();
@@ -153,6 +398,7 @@ impl ModelRegistry {
         RegistryStats {
             total_models,
             total_providers,
+            total_aliases: self.aliases.len(),
             capabilities_distribution: capabilities_count,
             oldest_registration: self.models.values()
                 .map(|m| m.created_at)
@@ -167,20 +413,27 @@ impl ModelRegistry {
     pub fn update_model_config(&mut self, model_id: &str, config: ModelConfig) -> ModelResult<()> {
         let model = self.models.get_mut(model_id)
             .ok_or_else(|| ModelError::ModelNotFound(model_id.to_string()))?;
-        
+
         // Validate new configuration against capabilities
         model.capabilities.validate_config(&config)?;
-        
+
         model.default_config = config;
+        // `merge` picks a winner by (updated_at, version); without bumping
+        // these, this edit is invisible to it and a peer's stale copy of
+        // the same model would silently win the next merge.
+        model.updated_at = Utc::now();
+        model.version += 1;
         Ok(())
     }
-    
+
     /// Update model metadata
     pub fn update_model_metadata(&mut self, model_id: &str, metadata: HashMap<String, String>) -> ModelResult<()> {
         let model = self.models.get_mut(model_id)
             .ok_or_else(|| ModelError::ModelNotFound(model_id.to_string()))?;
-        
+
         model.metadata = metadata;
+        model.updated_at = Utc::now();
+        model.version += 1;
         Ok(())
     }
     
@@ -192,10 +445,12 @@ impl ModelRegistry {
         let mut removed_models = Vec::new();
         for model_id in model_ids {
             if let Some(model) = self.models.remove(&model_id) {
+                self.tombstones.insert(model_id.clone(), Utc::now());
+                self.aliases.retain(|_, target| *target != model_id);
                 removed_models.push(model);
             }
         }
-        
+
         Ok(removed_models)
     }
     
@@ -247,9 +502,238 @@ impl ModelRegistry {
                 ));
             }
         }
-        
+
+        // Check that every alias points at a model that actually exists
+        for (alias, model_id) in &self.aliases {
+            if !self.models.contains_key(model_id) {
+                return Err(ModelError::RegistryInconsistent(
+                    format!("Alias {} points at missing model {}", alias, model_id)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fix the inconsistencies [`Self::validate`] would otherwise only
+    /// report: drops `provider_models` entries whose model is gone, re-files
+    /// models that ended up listed under the wrong provider, and drops
+    /// aliases pointing at a model that no longer exists. `provider_models`
+    /// is rebuilt from `models` (the source of truth) rather than patched
+    /// in place, so the result is internally consistent by construction.
+    /// Always leaves the registry in a state [`Self::validate`] accepts.
+    pub fn repair(&mut self) -> ModelResult<RepairReport> {
+        let mut report = RepairReport::default();
+
+        for (provider_id, model_ids) in &self.provider_models {
+            for model_id in model_ids {
+                match self.models.get(model_id) {
+                    None => report.dangling_provider_references_dropped.push(format!("{}/{}", provider_id, model_id)),
+                    Some(model) if model.provider_id != *provider_id => report.models_refiled.push(model_id.clone()),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let mut rebuilt: HashMap<String, Vec<String>> = HashMap::new();
+        for model in self.models.values() {
+            rebuilt
+                .entry(model.provider_id.clone())
+                .or_insert_with(Vec::new)
+                .push(model.id.clone());
+        }
+        self.provider_models = rebuilt;
+
+        let models = &self.models;
+        self.aliases.retain(|alias, model_id| {
+            let keep = models.contains_key(model_id);
+            if !keep {
+                report.dangling_aliases_removed.push(alias.clone());
+            }
+            keep
+        });
+
+        self.validate()?;
+        Ok(report)
+    }
+
+    /// Conflict-free merge of `other` into `self`, so two independently
+    /// edited registries converge to the same state regardless of which
+    /// side calls `merge` or in what order a series of merges happens.
+    ///
+    /// For every model ID appearing in either registry's `models` or
+    /// `tombstones`, the surviving entry is whichever side has the greater
+    /// `(updated_at, version)` pair; if the winning side is a tombstone
+    /// newer than the surviving model's `updated_at`, the model stays
+    /// deleted, otherwise a later re-registration wins over an older
+    /// deletion. `provider_models` is then rebuilt from scratch from the
+    /// merged `models` map, and the result is validated before returning.
+    pub fn merge(&mut self, other: &ModelRegistry) -> ModelResult<()> {
+        let ids: HashSet<&String> = self
+            .models
+            .keys()
+            .chain(other.models.keys())
+            .chain(self.tombstones.keys())
+            .chain(other.tombstones.keys())
+            .collect();
+
+        let mut merged_models = HashMap::new();
+        let mut merged_tombstones = HashMap::new();
+
+        for id in ids {
+            let newest_model = match (self.models.get(id), other.models.get(id)) {
+                (Some(a), Some(b)) => Some(if (a.updated_at, a.version) >= (b.updated_at, b.version) { a } else { b }),
+                (Some(a), None) => Some(a),
+                (None, Some(b)) => Some(b),
+                (None, None) => None,
+            };
+
+            let newest_tombstone = match (self.tombstones.get(id), other.tombstones.get(id)) {
+                (Some(a), Some(b)) => Some(*a.max(b)),
+                (Some(a), None) => Some(*a),
+                (None, Some(b)) => Some(*b),
+                (None, None) => None,
+            };
+
+            match (newest_model, newest_tombstone) {
+                (Some(model), Some(tombstone_at)) if tombstone_at > model.updated_at => {
+                    merged_tombstones.insert(id.clone(), tombstone_at);
+                }
+                (Some(model), _) => {
+                    merged_models.insert(id.clone(), model.clone());
+                }
+                (None, Some(tombstone_at)) => {
+                    merged_tombstones.insert(id.clone(), tombstone_at);
+                }
+                (None, None) => {}
+            }
+        }
+
+        self.models = merged_models;
+        self.tombstones = merged_tombstones;
+
+        self.provider_models = HashMap::new();
+        for model in self.models.values() {
+            self.provider_models
+                .entry(model.provider_id.clone())
+                .or_insert_with(Vec::new)
+                .push(model.id.clone());
+        }
+
+        // A model that lost the merge may have taken an alias down with it.
+        self.aliases.retain(|_, model_id| self.models.contains_key(model_id));
+
+        self.validate()
+    }
+
+    /// Write the registry's current state to `path` as a versioned JSON
+    /// snapshot, so it can be restored later with [`Self::load_snapshot`].
+    pub fn save_snapshot(&self, path: &Path) -> ModelResult<()> {
+        let snapshot = RegistrySnapshot {
+            schema_version: SCHEMA_VERSION,
+            models: self.models.values().cloned().collect(),
+            tombstones: self.tombstones.clone(),
+            aliases: self.aliases.clone(),
+        };
+
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| ModelError::RegistryInconsistent(format!("failed to serialize snapshot: {}", e)))?;
+
+        std::fs::write(path, json)
+            .map_err(|e| ModelError::RegistryInconsistent(format!("failed to write snapshot to {}: {}", path.display(), e)))?;
+
         Ok(())
     }
+
+    /// Load a registry previously written by [`Self::save_snapshot`],
+    /// migrating it forward to [`SCHEMA_VERSION`] if it's older, and
+    /// validating the result before returning it.
+    pub fn load_snapshot(path: &Path) -> ModelResult<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| ModelError::RegistryInconsistent(format!("failed to read snapshot from {}: {}", path.display(), e)))?;
+
+        let value: serde_json::Value = serde_json::from_str(&raw)
+            .map_err(|e| ModelError::RegistryInconsistent(format!("failed to parse snapshot: {}", e)))?;
+
+        Self::migrate_snapshot(value)
+    }
+
+    /// Bring a raw snapshot up to [`SCHEMA_VERSION`] by chaining per-version
+    /// transform steps via [`Self::migrate_step`], then deserialize and
+    /// validate it. Rejects a snapshot whose `schema_version` is newer than
+    /// this binary understands, since there's no way to safely downgrade
+    /// data it hasn't seen yet.
+    pub fn migrate_snapshot(raw: serde_json::Value) -> ModelResult<Self> {
+        let version = raw
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| ModelError::RegistryInconsistent("snapshot is missing schema_version".to_string()))?
+            as u32;
+
+        if version > SCHEMA_VERSION {
+            return Err(ModelError::RegistryInconsistent(format!(
+                "snapshot schema_version {} is newer than this binary's {}",
+                version, SCHEMA_VERSION
+            )));
+        }
+
+        let mut data = raw;
+        for from_version in version..SCHEMA_VERSION {
+            data = Self::migrate_step(from_version, data)?;
+        }
+
+        let snapshot: RegistrySnapshot = serde_json::from_value(data)
+            .map_err(|e| ModelError::RegistryInconsistent(format!("failed to deserialize migrated snapshot: {}", e)))?;
+
+        let mut registry = ModelRegistry::new();
+        for model in snapshot.models {
+            registry
+                .provider_models
+                .entry(model.provider_id.clone())
+                .or_insert_with(Vec::new)
+                .push(model.id.clone());
+            registry.models.insert(model.id.clone(), model);
+        }
+        registry.tombstones = snapshot.tombstones;
+        registry.aliases = snapshot.aliases;
+
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    /// Apply the single migration step that brings a snapshot from
+    /// `from_version` to `from_version + 1`. No migrations exist yet since
+    /// [`SCHEMA_VERSION`] is still 1 — this is where a `1 => ...` arm lands
+    /// the day `RegistrySnapshot`'s shape changes.
+    fn migrate_step(from_version: u32, _data: serde_json::Value) -> ModelResult<serde_json::Value> {
+        Err(ModelError::RegistryInconsistent(format!(
+            "no migration defined from schema_version {}",
+            from_version
+        )))
+    }
+}
+
+/// What [`ModelRegistry::repair`] actually changed, so a caller can log or
+/// alert on an otherwise-silent self-heal.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// `"provider_id/model_id"` entries dropped because the model no
+    /// longer exists.
+    pub dangling_provider_references_dropped: Vec<String>,
+    /// Model IDs that were listed under the wrong provider and got
+    /// re-filed under their actual `provider_id`.
+    pub models_refiled: Vec<String>,
+    /// Aliases removed because their target model no longer exists.
+    pub dangling_aliases_removed: Vec<String>,
+}
+
+impl RepairReport {
+    /// Whether `repair` found (and fixed) anything at all.
+    pub fn is_clean(&self) -> bool {
+        self.dangling_provider_references_dropped.is_empty()
+            && self.models_refiled.is_empty()
+            && self.dangling_aliases_removed.is_empty()
+    }
 }
 
 /// Registry statistics
@@ -257,6 +741,7 @@ impl ModelRegistry {
 pub struct RegistryStats {
     pub total_models: usize,
     pub total_providers: usize,
+    pub total_aliases: usize,
     pub capabilities_distribution: HashMap<String, usize>,
     pub oldest_registration: Option<chrono::DateTime<chrono::Utc>>,
     pub newest_registration: Option<chrono::DateTime<chrono::Utc>>,
@@ -271,7 +756,7 @@ impl Default for ModelRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::capabilities::{ModelCapabilities, CapabilityType};
+    use crate::models::capabilities::{BillingModel, CapabilityType, CostInfo, ModelCapabilities};
     
     fn create_test_model(id: &str, provider_id: &str) -> RegisteredModel {
         RegisteredModel {
@@ -281,6 +766,8 @@ mod tests {
             capabilities: ModelCapabilities::new(vec![CapabilityType::TextGeneration]),
             default_config: ModelConfig::default(),
             created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: 1,
             metadata: HashMap::new(),
         }
     }
@@ -392,4 +879,313 @@ mod tests {
         assert!(!registry.has_provider("provider-1"));
         assert!(registry.has_provider("provider-2"));
     }
+
+    fn model_with_cost(id: &str, provider_id: &str, price_per_1k: f64, context_window: u32) -> RegisteredModel {
+        let mut model = create_test_model(id, provider_id);
+        model.capabilities.context_limits.context_window = Some(context_window);
+        model.capabilities.cost_info = Some(CostInfo {
+            input_price_per_1k: Some(price_per_1k),
+            output_price_per_1k: Some(price_per_1k),
+            cost_per_request: None,
+            currency: "USD".to_string(),
+            billing_model: BillingModel::PayPerToken,
+        });
+        model
+    }
+
+    #[test]
+    fn select_best_picks_the_cheapest_compatible_model() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(model_with_cost("cheap", "provider-1", 0.0001, 4096)).unwrap();
+        registry.register_model(model_with_cost("pricey", "provider-1", 0.01, 4096)).unwrap();
+
+        let required = vec![CapabilityType::TextGeneration];
+        let best = registry.select_best(&required, (1000, 500), None).unwrap();
+        assert_eq!(best.id, "cheap");
+    }
+
+    #[test]
+    fn select_best_excludes_models_missing_a_required_capability() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(model_with_cost("text-only", "provider-1", 0.0001, 4096)).unwrap();
+
+        let required = vec![CapabilityType::TextGeneration, CapabilityType::ImageGeneration];
+        assert!(registry.select_best(&required, (1000, 500), None).is_none());
+    }
+
+    #[test]
+    fn select_best_respects_budget() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(model_with_cost("affordable", "provider-1", 0.0001, 4096)).unwrap();
+        registry.register_model(model_with_cost("expensive", "provider-1", 10.0, 4096)).unwrap();
+
+        let required = vec![CapabilityType::TextGeneration];
+        let budget = Some(Budget { max_cost: 0.01 });
+        let best = registry.select_best(&required, (1000, 500), budget).unwrap();
+        assert_eq!(best.id, "affordable");
+    }
+
+    #[test]
+    fn select_best_sends_unknown_cost_to_the_back() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("unpriced", "provider-1")).unwrap();
+        registry.register_model(model_with_cost("priced", "provider-1", 0.5, 4096)).unwrap();
+
+        let required = vec![CapabilityType::TextGeneration];
+        let best = registry.select_best(&required, (1000, 500), None).unwrap();
+        assert_eq!(best.id, "priced");
+    }
+
+    #[test]
+    fn select_best_breaks_ties_with_larger_context_window() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(model_with_cost("small-window", "provider-1", 0.001, 4096)).unwrap();
+        registry.register_model(model_with_cost("large-window", "provider-1", 0.001, 32768)).unwrap();
+
+        let required = vec![CapabilityType::TextGeneration];
+        let best = registry.select_best(&required, (1000, 500), None).unwrap();
+        assert_eq!(best.id, "large-window");
+    }
+
+    #[test]
+    fn merge_keeps_the_model_with_the_later_update() {
+        let mut a = ModelRegistry::new();
+        let mut b = ModelRegistry::new();
+
+        let mut stale = create_test_model("shared", "provider-1");
+        stale.name = "Stale Name".to_string();
+        a.register_model(stale).unwrap();
+
+        let mut fresh = create_test_model("shared", "provider-1");
+        fresh.name = "Fresh Name".to_string();
+        fresh.updated_at = chrono::Utc::now() + chrono::Duration::seconds(1);
+        fresh.version = 2;
+        b.register_model(fresh).unwrap();
+
+        a.merge(&b).unwrap();
+        assert_eq!(a.get_model("shared").unwrap().name, "Fresh Name");
+    }
+
+    #[test]
+    fn merge_honors_a_tombstone_newer_than_the_surviving_copy() {
+        let mut a = ModelRegistry::new();
+        let mut b = ModelRegistry::new();
+
+        a.register_model(create_test_model("deleted-elsewhere", "provider-1")).unwrap();
+
+        b.register_model(create_test_model("deleted-elsewhere", "provider-1")).unwrap();
+        b.unregister_model("deleted-elsewhere").unwrap();
+
+        a.merge(&b).unwrap();
+        assert!(a.get_model("deleted-elsewhere").is_none());
+    }
+
+    #[test]
+    fn merge_lets_a_later_reregistration_win_over_an_older_tombstone() {
+        let mut a = ModelRegistry::new();
+        let mut b = ModelRegistry::new();
+
+        a.register_model(create_test_model("resurrected", "provider-1")).unwrap();
+        a.unregister_model("resurrected").unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let mut reregistered = create_test_model("resurrected", "provider-1");
+        reregistered.updated_at = chrono::Utc::now();
+        b.register_model(reregistered).unwrap();
+
+        a.merge(&b).unwrap();
+        assert!(a.get_model("resurrected").is_some());
+    }
+
+    #[test]
+    fn merge_keeps_a_config_edit_against_a_stale_peer_copy() {
+        let mut a = ModelRegistry::new();
+        let mut b = ModelRegistry::new();
+
+        a.register_model(create_test_model("shared", "provider-1")).unwrap();
+        // `b` still has the stale, never-edited copy of the same model.
+        b.register_model(create_test_model("shared", "provider-1")).unwrap();
+
+        let mut edited_config = ModelConfig::default();
+        edited_config.temperature = Some(0.9);
+        a.update_model_config("shared", edited_config).unwrap();
+
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.get_model("shared").unwrap().default_config.temperature, Some(0.9));
+    }
+
+    #[test]
+    fn alias_resolves_to_its_target_model() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4-turbo-2024-04-09", "openai")).unwrap();
+
+        registry.alias_model("latest-gpt", "gpt-4-turbo-2024-04-09").unwrap();
+
+        assert_eq!(registry.resolve_alias("latest-gpt").unwrap().id, "gpt-4-turbo-2024-04-09");
+        assert_eq!(registry.list_aliases(), vec![("latest-gpt".to_string(), "gpt-4-turbo-2024-04-09".to_string())]);
+    }
+
+    #[test]
+    fn alias_cannot_collide_with_a_model_id_or_target_a_missing_model() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+
+        let missing_target = registry.alias_model("latest", "does-not-exist");
+        assert!(matches!(missing_target, Err(ModelError::ModelNotFound(_))));
+
+        let colliding_alias = registry.alias_model("gpt-4", "gpt-4");
+        assert!(matches!(colliding_alias, Err(ModelError::ModelAlreadyExists(_))));
+    }
+
+    #[test]
+    fn unregistering_a_model_drops_aliases_that_pointed_at_it() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+        registry.alias_model("latest", "gpt-4").unwrap();
+
+        registry.unregister_model("gpt-4").unwrap();
+
+        assert!(registry.resolve_alias("latest").is_none());
+        assert!(registry.validate().is_ok());
+    }
+
+    #[test]
+    fn snapshot_round_trips_models_tombstones_and_aliases() {
+        let dir = std::env::temp_dir().join(format!("registry-snapshot-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshot.json");
+
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+        registry.alias_model("latest", "gpt-4").unwrap();
+        registry.register_model(create_test_model("temp-model", "openai")).unwrap();
+        registry.unregister_model("temp-model").unwrap();
+
+        registry.save_snapshot(&path).unwrap();
+        let restored = ModelRegistry::load_snapshot(&path).unwrap();
+
+        assert_eq!(restored.get_model("gpt-4").unwrap().id, "gpt-4");
+        assert_eq!(restored.resolve_alias("latest").unwrap().id, "gpt-4");
+        assert!(restored.get_model("temp-model").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_snapshot_rejects_a_schema_version_newer_than_this_binary() {
+        let future = serde_json::json!({
+            "schema_version": SCHEMA_VERSION + 1,
+            "models": [],
+            "tombstones": {},
+            "aliases": {},
+        });
+
+        let result = ModelRegistry::migrate_snapshot(future);
+        assert!(matches!(result, Err(ModelError::RegistryInconsistent(_))));
+    }
+
+    #[test]
+    fn repair_drops_dangling_provider_references_and_aliases() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+        registry.alias_model("latest", "gpt-4").unwrap();
+
+        // Simulate corruption: the model is gone but the indexes weren't swept.
+        registry.models.remove("gpt-4");
+
+        let report = registry.repair().unwrap();
+        assert_eq!(report.dangling_provider_references_dropped, vec!["openai/gpt-4".to_string()]);
+        assert_eq!(report.dangling_aliases_removed, vec!["latest".to_string()]);
+        assert!(registry.validate().is_ok());
+    }
+
+    #[test]
+    fn repair_refiles_a_model_listed_under_the_wrong_provider() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+
+        // Simulate corruption: provider_models still lists it under the old provider.
+        registry.provider_models.get_mut("openai").unwrap().clear();
+        registry.provider_models.entry("wrong-provider".to_string()).or_default().push("gpt-4".to_string());
+
+        let report = registry.repair().unwrap();
+        assert_eq!(report.models_refiled, vec!["gpt-4".to_string()]);
+        assert_eq!(registry.list_models_by_provider("openai").len(), 1);
+        assert!(registry.list_models_by_provider("wrong-provider").is_empty());
+        assert!(registry.validate().is_ok());
+    }
+
+    #[test]
+    fn repair_is_a_no_op_on_an_already_consistent_registry() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("gpt-4", "openai")).unwrap();
+
+        let report = registry.repair().unwrap();
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn select_model_filters_out_models_missing_a_required_capability() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("text-only", "openai")).unwrap();
+
+        let mut coder = create_test_model("coder", "openai");
+        coder.capabilities.add_capability(CapabilityType::CodeGeneration);
+        registry.register_model(coder).unwrap();
+
+        let criteria = SelectionCriteria {
+            required_capabilities: vec![CapabilityType::CodeGeneration],
+            ..Default::default()
+        };
+
+        let selected = registry.select_model(&criteria).unwrap();
+        assert_eq!(selected.id, "coder");
+    }
+
+    #[test]
+    fn select_model_breaks_ties_by_preferred_provider_order() {
+        let mut registry = ModelRegistry::new();
+        registry.register_model(create_test_model("model-a", "provider-b")).unwrap();
+        registry.register_model(create_test_model("model-b", "provider-a")).unwrap();
+
+        let criteria = SelectionCriteria {
+            preferred_providers: vec!["provider-a".to_string(), "provider-b".to_string()],
+            ..Default::default()
+        };
+
+        let selected = registry.select_model(&criteria).unwrap();
+        assert_eq!(selected.provider_id, "provider-a");
+    }
+
+    #[test]
+    fn select_model_ranks_by_context_window_with_missing_values_last() {
+        let mut registry = ModelRegistry::new();
+
+        let mut small = create_test_model("small-ctx", "openai");
+        small.capabilities.context_limits.context_window = Some(4096);
+        registry.register_model(small).unwrap();
+
+        let mut large = create_test_model("large-ctx", "openai");
+        large.capabilities.context_limits.context_window = Some(128_000);
+        registry.register_model(large).unwrap();
+
+        let unset = create_test_model("no-ctx", "openai");
+        registry.register_model(unset).unwrap();
+
+        let criteria = SelectionCriteria {
+            ranking: RankingKey::ContextWindow,
+            ..Default::default()
+        };
+
+        let ranked: Vec<&str> = registry.select_models(&criteria).into_iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ranked, vec!["large-ctx", "small-ctx", "no-ctx"]);
+    }
+
+    #[test]
+    fn describe_model_delegates_to_capabilities() {
+        let model = model_with_cost("gpt-4", "openai", 0.0001, 8192);
+        assert_eq!(model.describe_model(), "Test Model gpt-4 | ctx=8192 | in=$0.0001/1K out=$0.0001/1K");
+    }
 }
\ No newline at end of file
@@ -3,6 +3,10 @@
 use async_trait::async_trait;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use futures::stream::{self, Stream};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
 
 pub mod gemini;
 pub mod ollama;
@@ -43,31 +47,98 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+/// One incrementally-delivered piece of a streamed generation: zero or more
+/// chunks of `text`, a partial `FunctionCall` under construction, and a
+/// final `Usage` once the provider knows the total token count. A provider
+/// without native streaming emits exactly one chunk carrying the whole
+/// response, via the `generate_stream` default implementation below.
+#[derive(Debug, Clone, Default)]
+pub struct StreamChunk {
+    pub text: Option<String>,
+    pub function_call_delta: Option<FunctionCallDelta>,
+    pub usage: Option<Usage>,
+}
+
+/// An incremental update to a `FunctionCall` being streamed: the name may
+/// arrive before the arguments are fully buffered, so `arguments_delta` is
+/// the raw JSON text streamed so far rather than a parsed `Value`.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionCallDelta {
+    pub name: Option<String>,
+    pub arguments_delta: Option<String>,
+}
+
 /// Common interface for LLM providers
 #[async_trait]
 pub trait LLMProvider: Send + Sync {
     /// Generate a response from messages
     async fn generate(&self, messages: Vec<Message>) -> Result<ProviderResponse>;
-    
+
     /// Generate with tool/function calling support
     async fn generate_with_tools(
         &self,
         messages: Vec<Message>,
         tools: Vec<Tool>,
     ) -> Result<ProviderResponse>;
-    
+
+    /// Generate incrementally. The default implementation buffers the full
+    /// `generate_with_tools` response and emits it as a single terminal
+    /// chunk; providers with native streaming support should override this.
+    async fn generate_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<Tool>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        let response = self.generate_with_tools(messages, tools).await?;
+        let chunk = StreamChunk {
+            text: response.text,
+            function_call_delta: response.function_call.map(|call| FunctionCallDelta {
+                name: Some(call.name),
+                arguments_delta: Some(call.arguments.to_string()),
+            }),
+            usage: response.usage,
+        };
+        Ok(Box::pin(stream::once(async move { Ok(chunk) })))
+    }
+
     /// Check if provider is available
     async fn health_check(&self) -> Result<bool>;
-    
+
     /// Get provider name
     fn name(&self) -> &str;
 }
 
+/// Constructs a fresh boxed provider instance; stored in the registry under
+/// the provider's name so `create_provider` doesn't need to know every
+/// backend ahead of time.
+pub type ProviderConstructor = fn() -> Result<Box<dyn LLMProvider>>;
+
+fn registry() -> &'static Mutex<HashMap<String, ProviderConstructor>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, ProviderConstructor>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut providers: HashMap<String, ProviderConstructor> = HashMap::new();
+        providers.insert("gemini".to_string(), (|| {
+            Ok(Box::new(gemini::GeminiProvider::new()?) as Box<dyn LLMProvider>)
+        }) as ProviderConstructor);
+        providers.insert("ollama".to_string(), (|| {
+            Ok(Box::new(ollama::OllamaProvider::new()?) as Box<dyn LLMProvider>)
+        }) as ProviderConstructor);
+        Mutex::new(providers)
+    })
+}
+
+/// Register `constructor` under `name`, so downstream crates can add
+/// backends to `create_provider` without editing this file. Re-registering
+/// an existing name overwrites it.
+pub fn register_provider(name: &str, constructor: ProviderConstructor) {
+    registry().lock().unwrap().insert(name.to_lowercase(), constructor);
+}
+
 /// Provider factory
 pub fn create_provider(provider_type: &str) -> Result<Box<dyn LLMProvider>> {
-    match provider_type.to_lowercase().as_str() {
-        "gemini" => Ok(Box::new(gemini::GeminiProvider::new()?)),
-        "ollama" => Ok(Box::new(ollama::OllamaProvider::new()?)),
-        _ => Err(anyhow::anyhow!("Unknown provider: {}", provider_type)),
-    }
+    let constructor = registry().lock().unwrap()
+        .get(&provider_type.to_lowercase())
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider_type))?;
+    constructor()
 }
\ No newline at end of file
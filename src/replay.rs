@@ -0,0 +1,261 @@
+//! Offline replay of previously captured request/response logs.
+//!
+//! [`crate::logging::ApiLogger`] already writes paired `reqs.jsonl`/
+//! `resps.jsonl` files for every request. [`ReplayClient`] reads those
+//! files back and answers `send_message`/`send_message_with_tools` calls
+//! from the recording instead of the network, so integration tests and
+//! demos can run deterministically and without an API key.
+
+use crate::api::Content;
+use crate::backends::{Backend, GeminiBackend};
+use crate::logging::ApiLogEntry;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// How [`ReplayClient`] behaves when it can't find a recorded response for
+/// an outgoing request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Error out — useful for tests that should fail loudly if the
+    /// recording doesn't cover a code path they exercise.
+    Strict,
+    /// Return the nearest recorded response (the last one recorded for
+    /// this model) rather than erroring, for demos that don't need every
+    /// turn to match exactly.
+    Loose,
+}
+
+struct RecordedPair {
+    body: Value,
+    response_body: Value,
+}
+
+/// Replays a conversation against a directory of logs an [`ApiLogger`]
+/// previously captured, implementing the same `send_message`/
+/// `send_message_with_tools` shape [`crate::api::GeminiClient`] does.
+///
+/// [`ApiLogger`]: crate::logging::ApiLogger
+pub struct ReplayClient {
+    model: String,
+    recorded: Vec<RecordedPair>,
+    cursor: Mutex<usize>,
+    mode: ReplayMode,
+}
+
+impl ReplayClient {
+    /// Load every recorded request/response pair under `base_dir` whose
+    /// logged path mentions `model`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base_dir` can't be read, or a log file
+    /// contains invalid JSON.
+    pub fn new(base_dir: impl AsRef<Path>, model: String, mode: ReplayMode) -> Result<Self> {
+        let recorded = load_recorded_pairs(base_dir.as_ref(), &model)?;
+        Ok(Self { model, recorded, cursor: Mutex::new(0), mode })
+    }
+
+    /// Send a message without tools.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no recorded response can be found (see
+    /// [`ReplayMode`]).
+    pub async fn send_message(&self, conversation: &[Content]) -> Result<String> {
+        self.send_message_with_tools(conversation, None).await
+    }
+
+    /// Send a message with tool definitions, returning just the first text
+    /// part of the matched recorded reply.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no recorded response can be found.
+    pub async fn send_message_with_tools(&self, conversation: &[Content], tools: Option<Vec<Value>>) -> Result<String> {
+        let content = self.send_turn(conversation, tools).await?;
+        Ok(content
+            .parts
+            .first()
+            .and_then(|p| p.text.clone())
+            .unwrap_or_else(|| "No text in response".to_string()))
+    }
+
+    /// Match `conversation` against the recording and return the full
+    /// model `Content` the recorded response carried.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no recorded response can be found, or the
+    /// matched response body can't be parsed as a Gemini response.
+    pub async fn send_turn(&self, conversation: &[Content], _tools: Option<Vec<Value>>) -> Result<Content> {
+        let response_body = self.find_response(conversation)?;
+        let parts = GeminiBackend.parse_response(&response_body)?;
+        if parts.is_empty() {
+            bail!("recorded response for model '{}' has no parts", self.model);
+        }
+        Ok(Content { role: "model".to_string(), parts })
+    }
+
+    /// Find the recorded response for `conversation`: first by an exact
+    /// match on the recorded request's `contents`, falling back to the
+    /// next recorded response in sequence for this model.
+    fn find_response(&self, conversation: &[Content]) -> Result<Value> {
+        let wanted = serde_json::to_value(conversation)?;
+
+        if let Some(pair) = self.recorded.iter().find(|p| p.body.get("contents") == Some(&wanted)) {
+            return Ok(pair.response_body.clone());
+        }
+
+        let mut cursor = self.cursor.lock().unwrap();
+        if let Some(pair) = self.recorded.get(*cursor) {
+            *cursor += 1;
+            return Ok(pair.response_body.clone());
+        }
+
+        match self.mode {
+            ReplayMode::Strict => {
+                bail!("no recorded response for model '{}' (cache miss at position {cursor})", self.model)
+            }
+            ReplayMode::Loose => self
+                .recorded
+                .last()
+                .map(|p| p.response_body.clone())
+                .ok_or_else(|| anyhow::anyhow!("no recorded responses available for model '{}'", self.model)),
+        }
+    }
+}
+
+/// Walk `base_dir` for `reqs.jsonl`/`resps.jsonl` pairs (the layout
+/// [`crate::logging::ApiLogger`] writes), keeping only entries whose
+/// logged path mentions `model`, and zip each directory's request lines
+/// with its response lines by position.
+fn load_recorded_pairs(base_dir: &Path, model: &str) -> Result<Vec<RecordedPair>> {
+    let mut pairs = Vec::new();
+    visit_log_dirs(base_dir, model, &mut pairs)?;
+    Ok(pairs)
+}
+
+fn visit_log_dirs(dir: &Path, model: &str, pairs: &mut Vec<RecordedPair>) -> Result<()> {
+    let reqs_path = dir.join("reqs.jsonl");
+    let resps_path = dir.join("resps.jsonl");
+
+    if reqs_path.is_file() && resps_path.is_file() {
+        let requests: Vec<ApiLogEntry> = fs::read_to_string(&reqs_path)?
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+        let responses: Vec<Value> = fs::read_to_string(&resps_path)?
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        for (request, response) in requests.into_iter().zip(responses) {
+            if request.path.contains(model) {
+                let response_body = response.get("body").cloned().unwrap_or(response);
+                pairs.push(RecordedPair { body: request.body, response_body });
+            }
+        }
+    }
+
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                visit_log_dirs(&entry.path(), model, pairs)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::ApiLogger;
+    use serde_json::json;
+
+    fn sample_content(text: &str) -> Content {
+        Content {
+            role: "user".to_string(),
+            parts: vec![crate::api::Part { text: Some(text.to_string()), function_call: None, function_response: None }],
+        }
+    }
+
+    fn record(dir: &Path, model: &str, conversation: &[Content], reply_text: &str) {
+        let logger = ApiLogger::new(dir, true).unwrap();
+        logger
+            .log_request(
+                "generativelanguage.googleapis.com",
+                &format!("/v1beta/models/{model}:generateContent"),
+                "POST",
+                &[],
+                &json!({"contents": conversation}),
+            )
+            .unwrap();
+        logger
+            .log_response(
+                "generativelanguage.googleapis.com",
+                &format!("/v1beta/models/{model}:generateContent"),
+                200,
+                &json!({"candidates": [{"content": {"role": "model", "parts": [{"text": reply_text}]}}]}),
+                5,
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn replays_an_exact_body_match() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-replay-test-{}", uuid::Uuid::new_v4()));
+        let conversation = vec![sample_content("hello")];
+        record(&dir, "gemini-2.0-flash", &conversation, "hi there");
+
+        let client = ReplayClient::new(&dir, "gemini-2.0-flash".to_string(), ReplayMode::Strict).unwrap();
+        let reply = client.send_message(&conversation).await.unwrap();
+        assert_eq!(reply, "hi there");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_sequential_order_when_the_body_does_not_match() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-replay-test-{}", uuid::Uuid::new_v4()));
+        record(&dir, "gemini-2.0-flash", &[sample_content("first turn")], "first reply");
+
+        let client = ReplayClient::new(&dir, "gemini-2.0-flash".to_string(), ReplayMode::Strict).unwrap();
+        let reply = client.send_message(&[sample_content("an unrecorded turn")]).await.unwrap();
+        assert_eq!(reply, "first reply");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn strict_mode_errors_on_a_cache_miss() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-replay-test-{}", uuid::Uuid::new_v4()));
+        record(&dir, "gemini-2.0-flash", &[sample_content("only turn")], "only reply");
+
+        let client = ReplayClient::new(&dir, "gemini-2.0-flash".to_string(), ReplayMode::Strict).unwrap();
+        client.send_message(&[sample_content("turn one")]).await.unwrap();
+        assert!(client.send_message(&[sample_content("turn two")]).await.is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn loose_mode_returns_the_nearest_response_on_a_cache_miss() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-replay-test-{}", uuid::Uuid::new_v4()));
+        record(&dir, "gemini-2.0-flash", &[sample_content("only turn")], "only reply");
+
+        let client = ReplayClient::new(&dir, "gemini-2.0-flash".to_string(), ReplayMode::Loose).unwrap();
+        client.send_message(&[sample_content("turn one")]).await.unwrap();
+        let reply = client.send_message(&[sample_content("turn two")]).await.unwrap();
+        assert_eq!(reply, "only reply");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
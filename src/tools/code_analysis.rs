@@ -1,13 +1,94 @@
 //! Code analysis tools for understanding Rust code structure
+//!
+//! Source locations below rely on `proc-macro2`'s `span-locations` feature
+//! (enabled transitively through `syn`'s `"full"` feature set in most setups
+//! building from source text); without it every span resolves to line 1,
+//! column 0.
 
 use super::Tool;
 use anyhow::Result;
 use async_trait::async_trait;
+use proc_macro2::Span;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::fs;
 use std::path::{Path, PathBuf};
-use syn::{parse_file, Item, ItemFn, ItemStruct, ItemEnum, ItemImpl, ItemTrait, Visibility};
+use std::sync::Mutex;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{parse_file, Item, ItemFn, ItemStruct, ItemEnum, ItemImpl, ItemTrait, Visibility, ExprCall, ExprMethodCall};
+
+/// Converts a 1-indexed `line`/0-indexed `column` (proc-macro2's convention,
+/// `column` counting chars not bytes) into a byte offset into `source`, so
+/// callers get a byte range alongside the line/column pair without needing
+/// the nightly-only `Span::byte_range`.
+fn line_col_to_byte(source: &str, line: usize, column: usize) -> usize {
+    let mut offset = 0usize;
+    for (idx, line_text) in source.split('\n').enumerate() {
+        if idx + 1 == line {
+            let col_bytes: usize = line_text.chars().take(column).map(char::len_utf8).sum();
+            return offset + col_bytes;
+        }
+        offset += line_text.len() + 1;
+    }
+    offset
+}
+
+/// Resolves `span`'s start/end line+column against `source` and derives the
+/// matching byte range.
+fn span_parts(source: &str, span: Span) -> (usize, usize, usize, usize, [usize; 2]) {
+    let start = span.start();
+    let end = span.end();
+    let byte_start = line_col_to_byte(source, start.line, start.column);
+    let byte_end = line_col_to_byte(source, end.line, end.column);
+    (start.line, start.column, end.line, end.column, [byte_start, byte_end])
+}
+
+/// Attaches `start_line`/`start_col`/`end_line`/`end_col` and a `byte_range`
+/// to `value` (expected to serialize as a JSON object), resolved from
+/// `span`'s locations against `source`.
+fn with_span(mut value: Value, source: &str, span: Span) -> Value {
+    let (start_line, start_col, end_line, end_col, byte_range) = span_parts(source, span);
+
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("start_line".to_string(), json!(start_line));
+        obj.insert("start_col".to_string(), json!(start_col));
+        obj.insert("end_line".to_string(), json!(end_line));
+        obj.insert("end_col".to_string(), json!(end_col));
+        obj.insert("byte_range".to_string(), json!(byte_range));
+    }
+    value
+}
+
+/// Reconstructs the doc comment attached to `attrs` (the `#[doc = "..."]`
+/// attributes `///` lines desugar to, joined back into a single string) and
+/// renders every other attribute back to source text, e.g. `#[derive(Debug)]`
+/// or `#[cfg(test)]`.
+fn docs_and_attrs(attrs: &[syn::Attribute]) -> (Option<String>, Vec<String>) {
+    let mut doc_lines = Vec::new();
+    let mut attributes = Vec::new();
+
+    for attr in attrs {
+        if attr.path().is_ident("doc") {
+            if let syn::Meta::NameValue(meta) = &attr.meta {
+                if let syn::Expr::Lit(expr_lit) = &meta.value {
+                    if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                        doc_lines.push(lit_str.value().trim().to_string());
+                        continue;
+                    }
+                }
+            }
+        }
+        attributes.push(quote::quote!(#attr).to_string());
+    }
+
+    let docs = if doc_lines.is_empty() {
+        None
+    } else {
+        Some(doc_lines.join("\n"))
+    };
+    (docs, attributes)
+}
 
 /// Tool for analyzing Rust code
 pub struct AnalyzeRustCodeTool;
@@ -70,10 +151,12 @@ impl Tool for AnalyzeRustCodeTool {
         for item in syntax_tree.items {
             match item {
                 Item::Fn(item_fn) => {
-                    functions.push(analyze_function(&item_fn));
+                    functions.push(analyze_function(&item_fn, &params.code));
                 }
                 Item::Struct(item_struct) => {
-                    structs.push(json!({
+                    let span = item_struct.span();
+                    let (docs, attributes) = docs_and_attrs(&item_struct.attrs);
+                    structs.push(with_span(json!({
                         "name": item_struct.ident.to_string(),
                         "visibility": visibility_to_string(&item_struct.vis),
                         "generics": item_struct.generics.params.len(),
@@ -81,34 +164,48 @@ impl Tool for AnalyzeRustCodeTool {
                             syn::Fields::Named(fields) => fields.named.len(),
                             syn::Fields::Unnamed(fields) => fields.unnamed.len(),
                             syn::Fields::Unit => 0,
-                        }
-                    }));
+                        },
+                        "docs": docs,
+                        "attributes": attributes,
+                    }), &params.code, span));
                 }
                 Item::Enum(item_enum) => {
-                    enums.push(json!({
+                    let span = item_enum.span();
+                    let (docs, attributes) = docs_and_attrs(&item_enum.attrs);
+                    enums.push(with_span(json!({
                         "name": item_enum.ident.to_string(),
                         "visibility": visibility_to_string(&item_enum.vis),
                         "variants": item_enum.variants.len(),
-                    }));
+                        "docs": docs,
+                        "attributes": attributes,
+                    }), &params.code, span));
                 }
                 Item::Trait(item_trait) => {
-                    traits.push(json!({
+                    let span = item_trait.span();
+                    let (docs, attributes) = docs_and_attrs(&item_trait.attrs);
+                    traits.push(with_span(json!({
                         "name": item_trait.ident.to_string(),
                         "visibility": visibility_to_string(&item_trait.vis),
                         "methods": item_trait.items.len(),
-                    }));
+                        "docs": docs,
+                        "attributes": attributes,
+                    }), &params.code, span));
                 }
                 Item::Impl(item_impl) => {
+                    let span = item_impl.span();
+                    let (docs, attributes) = docs_and_attrs(&item_impl.attrs);
                     let type_name = if let Some((_, path, _)) = &item_impl.trait_ {
                         format!("{} for {}", quote::quote!(#path), quote::quote!(#item_impl.self_ty))
                     } else {
                         format!("{}", quote::quote!(#item_impl.self_ty))
                     };
-                    
-                    impls.push(json!({
+
+                    impls.push(with_span(json!({
                         "type": type_name,
                         "methods": item_impl.items.len(),
-                    }));
+                        "docs": docs,
+                        "attributes": attributes,
+                    }), &params.code, span));
                 }
                 _ => {}
             }
@@ -134,7 +231,7 @@ impl Tool for AnalyzeRustCodeTool {
     }
 }
 
-fn analyze_function(item_fn: &ItemFn) -> Value {
+fn analyze_function(item_fn: &ItemFn, source: &str) -> Value {
     let mut params = Vec::new();
     for input in &item_fn.sig.inputs {
         match input {
@@ -144,8 +241,10 @@ fn analyze_function(item_fn: &ItemFn) -> Value {
             }
         }
     }
-    
-    json!({
+
+    let (docs, attributes) = docs_and_attrs(&item_fn.attrs);
+
+    with_span(json!({
         "name": item_fn.sig.ident.to_string(),
         "visibility": visibility_to_string(&item_fn.vis),
         "async": item_fn.sig.asyncness.is_some(),
@@ -153,8 +252,10 @@ fn analyze_function(item_fn: &ItemFn) -> Value {
         "return_type": match &item_fn.sig.output {
             syn::ReturnType::Default => "()".to_string(),
             syn::ReturnType::Type(_, ty) => quote::quote!(#ty).to_string(),
-        }
-    })
+        },
+        "docs": docs,
+        "attributes": attributes,
+    }), source, item_fn.span())
 }
 
 /// Tool for finding functions in Rust code
@@ -206,25 +307,25 @@ impl Tool for FindFunctionTool {
         let search_path = params.path.unwrap_or_else(|| "src".to_string());
         let full_path = self.workspace.join(&search_path);
         
-        let mut results = Vec::new();
-        search_rust_files(&full_path, |file_path, content| {
-            if let Ok(syntax_tree) = parse_file(&content) {
+        let results = search_rust_files(&full_path, |file_path, content| {
+            let mut matches = Vec::new();
+            if let Ok(syntax_tree) = parse_file(content) {
                 for item in syntax_tree.items {
                     if let Item::Fn(item_fn) = item {
                         if item_fn.sig.ident == params.name {
-                            results.push(json!({
+                            matches.push(json!({
                                 "file": file_path.strip_prefix(&self.workspace)
                                     .unwrap_or(file_path)
                                     .to_string_lossy(),
-                                "function": analyze_function(&item_fn),
-                                "line": 0, // TODO: Add line number tracking
+                                "function": analyze_function(&item_fn, content),
                             }));
                         }
                     }
                 }
             }
+            matches
         })?;
-        
+
         Ok(json!({
             "success": true,
             "query": params.name,
@@ -283,53 +384,68 @@ impl Tool for FindStructTool {
         let search_path = params.path.unwrap_or_else(|| "src".to_string());
         let full_path = self.workspace.join(&search_path);
         
-        let mut results = Vec::new();
-        search_rust_files(&full_path, |file_path, content| {
-            if let Ok(syntax_tree) = parse_file(&content) {
+        let results = search_rust_files(&full_path, |file_path, content| {
+            let mut matches = Vec::new();
+            if let Ok(syntax_tree) = parse_file(content) {
                 for item in syntax_tree.items {
                     if let Item::Struct(item_struct) = item {
                         if item_struct.ident == params.name {
                             let fields = match &item_struct.fields {
                                 syn::Fields::Named(fields) => {
                                     fields.named.iter()
-                                        .map(|f| json!({
-                                            "name": f.ident.as_ref().map(|i| i.to_string()),
-                                            "type": quote::quote!(#f.ty).to_string(),
-                                            "visibility": visibility_to_string(&f.vis),
-                                        }))
+                                        .map(|f| {
+                                            let (docs, attributes) = docs_and_attrs(&f.attrs);
+                                            json!({
+                                                "name": f.ident.as_ref().map(|i| i.to_string()),
+                                                "type": quote::quote!(#f.ty).to_string(),
+                                                "visibility": visibility_to_string(&f.vis),
+                                                "docs": docs,
+                                                "attributes": attributes,
+                                            })
+                                        })
                                         .collect::<Vec<_>>()
                                 }
                                 syn::Fields::Unnamed(fields) => {
                                     fields.unnamed.iter()
                                         .enumerate()
-                                        .map(|(i, f)| json!({
-                                            "index": i,
-                                            "type": quote::quote!(#f.ty).to_string(),
-                                            "visibility": visibility_to_string(&f.vis),
-                                        }))
+                                        .map(|(i, f)| {
+                                            let (docs, attributes) = docs_and_attrs(&f.attrs);
+                                            json!({
+                                                "index": i,
+                                                "type": quote::quote!(#f.ty).to_string(),
+                                                "visibility": visibility_to_string(&f.vis),
+                                                "docs": docs,
+                                                "attributes": attributes,
+                                            })
+                                        })
                                         .collect::<Vec<_>>()
                                 }
                                 syn::Fields::Unit => vec![],
                             };
-                            
-                            results.push(json!({
+
+                            let (docs, attributes) = docs_and_attrs(&item_struct.attrs);
+                            let struct_info = with_span(json!({
+                                "name": item_struct.ident.to_string(),
+                                "visibility": visibility_to_string(&item_struct.vis),
+                                "generics": item_struct.generics.params.len(),
+                                "fields": fields,
+                                "docs": docs,
+                                "attributes": attributes,
+                            }), content, item_struct.span());
+
+                            matches.push(json!({
                                 "file": file_path.strip_prefix(&self.workspace)
                                     .unwrap_or(file_path)
                                     .to_string_lossy(),
-                                "struct": {
-                                    "name": item_struct.ident.to_string(),
-                                    "visibility": visibility_to_string(&item_struct.vis),
-                                    "generics": item_struct.generics.params.len(),
-                                    "fields": fields,
-                                },
-                                "line": 0, // TODO: Add line number tracking
+                                "struct": struct_info,
                             }));
                         }
                     }
                 }
             }
+            matches
         })?;
-        
+
         Ok(json!({
             "success": true,
             "query": params.name,
@@ -339,28 +455,409 @@ impl Tool for FindStructTool {
     }
 }
 
-fn search_rust_files<F>(path: &Path, mut callback: F) -> Result<()>
-where
-    F: FnMut(&Path, &str),
-{
-    if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rs") {
-        let content = fs::read_to_string(path)?;
-        callback(path, &content);
-    } else if path.is_dir() {
-        for entry in walkdir::WalkDir::new(path)
-            .follow_links(false) // Don't follow symlinks for security
-            .into_iter()
-            .filter_map(Result::ok)
-        {
-            let path = entry.path();
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("rs") {
-                if let Ok(content) = fs::read_to_string(path) {
-                    callback(path, &content);
+/// One edge in a workspace call graph: `caller` invokes `callee` at the given
+/// location within `file`. `caller` is `"<module>"` for calls made outside
+/// any function body (e.g. in a `const` initializer).
+struct CallEdge {
+    file: PathBuf,
+    caller: String,
+    callee: String,
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+    byte_range: [usize; 2],
+}
+
+impl CallEdge {
+    fn to_json(&self, workspace: &Path) -> Value {
+        json!({
+            "file": self.file.strip_prefix(workspace).unwrap_or(&self.file).to_string_lossy(),
+            "caller": self.caller,
+            "callee": self.callee,
+            "start_line": self.start_line,
+            "start_col": self.start_col,
+            "end_line": self.end_line,
+            "end_col": self.end_col,
+            "byte_range": self.byte_range,
+        })
+    }
+}
+
+/// Walks a parsed file's expression bodies recording every `ExprCall` (by its
+/// path's final segment) and `ExprMethodCall` (by method name) against the
+/// function it was found inside, building up `CallEdge`s for the workspace
+/// call graph.
+struct CallVisitor<'a> {
+    file: &'a Path,
+    source: &'a str,
+    current_fn: Vec<String>,
+    edges: Vec<CallEdge>,
+}
+
+impl<'a> CallVisitor<'a> {
+    fn push_edge(&mut self, callee: String, span: Span) {
+        let (start_line, start_col, end_line, end_col, byte_range) = span_parts(self.source, span);
+        self.edges.push(CallEdge {
+            file: self.file.to_path_buf(),
+            caller: self.current_fn.last().cloned().unwrap_or_else(|| "<module>".to_string()),
+            callee,
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+            byte_range,
+        });
+    }
+}
+
+impl<'a> Visit<'a> for CallVisitor<'a> {
+    fn visit_item_fn(&mut self, item_fn: &'a ItemFn) {
+        self.current_fn.push(item_fn.sig.ident.to_string());
+        visit::visit_item_fn(self, item_fn);
+        self.current_fn.pop();
+    }
+
+    fn visit_expr_call(&mut self, expr_call: &'a ExprCall) {
+        if let syn::Expr::Path(expr_path) = &*expr_call.func {
+            if let Some(segment) = expr_path.path.segments.last() {
+                let callee = segment.ident.to_string();
+                self.push_edge(callee, expr_call.span());
+            }
+        }
+        visit::visit_expr_call(self, expr_call);
+    }
+
+    fn visit_expr_method_call(&mut self, expr_method_call: &'a ExprMethodCall) {
+        let callee = expr_method_call.method.to_string();
+        self.push_edge(callee, expr_method_call.span());
+        visit::visit_expr_method_call(self, expr_method_call);
+    }
+}
+
+/// Tool that builds a workspace-wide call-hierarchy index and answers "who
+/// calls X" / "what does X call" queries against it.
+pub struct FindReferencesTool {
+    workspace: PathBuf,
+}
+
+impl FindReferencesTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for FindReferencesTool {
+    fn name(&self) -> &str {
+        "find_references"
+    }
+
+    fn description(&self) -> &str {
+        "Find callers and callees of a function across the workspace (call-hierarchy index)"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Function name to look up in the call graph"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Optional path to search in (defaults to src/)"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            name: String,
+            path: Option<String>,
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+        let search_path = params.path.unwrap_or_else(|| "src".to_string());
+        let full_path = self.workspace.join(&search_path);
+
+        let edges = search_rust_files(&full_path, |file_path, content| {
+            if let Ok(syntax_tree) = parse_file(content) {
+                let mut visitor = CallVisitor {
+                    file: file_path,
+                    source: content,
+                    current_fn: Vec::new(),
+                    edges: Vec::new(),
+                };
+                visitor.visit_file(&syntax_tree);
+                visitor.edges
+            } else {
+                Vec::new()
+            }
+        })?;
+
+        let incoming_calls: Vec<Value> = edges
+            .iter()
+            .filter(|edge| edge.callee == params.name)
+            .map(|edge| edge.to_json(&self.workspace))
+            .collect();
+        let outgoing_calls: Vec<Value> = edges
+            .iter()
+            .filter(|edge| edge.caller == params.name)
+            .map(|edge| edge.to_json(&self.workspace))
+            .collect();
+
+        Ok(json!({
+            "success": true,
+            "query": params.name,
+            "incoming_calls": incoming_calls,
+            "outgoing_calls": outgoing_calls,
+        }))
+    }
+}
+
+/// One indexed `impl` block: `impl Trait for Type` (`trait_name: Some(..)`)
+/// or an inherent `impl Type` block (`trait_name: None`).
+struct ImplRecord {
+    file: PathBuf,
+    trait_name: Option<String>,
+    self_type: String,
+    methods: Vec<Value>,
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+    byte_range: [usize; 2],
+}
+
+impl ImplRecord {
+    fn to_json(&self, workspace: &Path) -> Value {
+        json!({
+            "file": self.file.strip_prefix(workspace).unwrap_or(&self.file).to_string_lossy(),
+            "trait": self.trait_name,
+            "self_type": self.self_type,
+            "methods": self.methods,
+            "start_line": self.start_line,
+            "start_col": self.start_col,
+            "end_line": self.end_line,
+            "end_col": self.end_col,
+            "byte_range": self.byte_range,
+        })
+    }
+}
+
+/// `analyze_function`-style summary of a single method inside an `impl`
+/// block.
+fn analyze_impl_method(method: &syn::ImplItemFn, source: &str) -> Value {
+    let mut params = Vec::new();
+    for input in &method.sig.inputs {
+        match input {
+            syn::FnArg::Receiver(_) => params.push("self".to_string()),
+            syn::FnArg::Typed(pat_type) => {
+                params.push(quote::quote!(#pat_type.pat).to_string());
+            }
+        }
+    }
+
+    let (docs, attributes) = docs_and_attrs(&method.attrs);
+
+    with_span(json!({
+        "name": method.sig.ident.to_string(),
+        "visibility": visibility_to_string(&method.vis),
+        "async": method.sig.asyncness.is_some(),
+        "params": params,
+        "return_type": match &method.sig.output {
+            syn::ReturnType::Default => "()".to_string(),
+            syn::ReturnType::Type(_, ty) => quote::quote!(#ty).to_string(),
+        },
+        "docs": docs,
+        "attributes": attributes,
+    }), source, method.span())
+}
+
+/// Builds the `ImplRecord` for one `impl` block: its trait (if any), the
+/// type it's implemented for, and a summary of each method it defines.
+fn index_impl(file_path: &Path, content: &str, item_impl: &ItemImpl) -> ImplRecord {
+    let trait_name = item_impl
+        .trait_
+        .as_ref()
+        .map(|(_, path, _)| quote::quote!(#path).to_string());
+    let self_ty = &*item_impl.self_ty;
+    let self_type = quote::quote!(#self_ty).to_string();
+    let methods = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::ImplItem::Fn(method) => Some(analyze_impl_method(method, content)),
+            _ => None,
+        })
+        .collect();
+    let (start_line, start_col, end_line, end_col, byte_range) = span_parts(content, item_impl.span());
+
+    ImplRecord {
+        file: file_path.to_path_buf(),
+        trait_name,
+        self_type,
+        methods,
+        start_line,
+        start_col,
+        end_line,
+        end_col,
+        byte_range,
+    }
+}
+
+/// Tool that indexes every `impl Trait for Type` and inherent `impl Type`
+/// block across the workspace, answering "what implements Trait" / "what
+/// does Type implement" queries with each match's methods and source span.
+pub struct FindImplsTool {
+    workspace: PathBuf,
+}
+
+impl FindImplsTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for FindImplsTool {
+    fn name(&self) -> &str {
+        "find_impls"
+    }
+
+    fn description(&self) -> &str {
+        "Find impl blocks across the workspace by trait name and/or implementing type"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "trait_name": {
+                    "type": "string",
+                    "description": "Only return impls of this trait (e.g. \"Display\")"
+                },
+                "type_name": {
+                    "type": "string",
+                    "description": "Only return impls for this type (e.g. \"Config\")"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Optional path to search in (defaults to src/)"
                 }
             }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            trait_name: Option<String>,
+            type_name: Option<String>,
+            path: Option<String>,
         }
+
+        let params: Params = serde_json::from_value(params)?;
+        let search_path = params.path.unwrap_or_else(|| "src".to_string());
+        let full_path = self.workspace.join(&search_path);
+
+        let records = search_rust_files(&full_path, |file_path, content| {
+            let mut found = Vec::new();
+            if let Ok(syntax_tree) = parse_file(content) {
+                for item in syntax_tree.items {
+                    if let Item::Impl(item_impl) = item {
+                        found.push(index_impl(file_path, content, &item_impl));
+                    }
+                }
+            }
+            found
+        })?;
+
+        let results: Vec<Value> = records
+            .iter()
+            .filter(|record| {
+                let trait_ok = params
+                    .trait_name
+                    .as_ref()
+                    .map_or(true, |wanted| record.trait_name.as_deref() == Some(wanted.as_str()));
+                let type_ok = params
+                    .type_name
+                    .as_ref()
+                    .map_or(true, |wanted| record.self_type == *wanted);
+                trait_ok && type_ok
+            })
+            .map(|record| record.to_json(&self.workspace))
+            .collect();
+
+        Ok(json!({
+            "success": true,
+            "trait_name": params.trait_name,
+            "type_name": params.type_name,
+            "results": results,
+            "count": results.len(),
+        }))
     }
-    Ok(())
+}
+
+/// Collects every `.rs` file under `path` (recursing through directories,
+/// never following symlinks) without reading any of them.
+fn collect_rust_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return if path.extension().and_then(|s| s.to_str()) == Some("rs") {
+            vec![path.to_path_buf()]
+        } else {
+            Vec::new()
+        };
+    }
+
+    walkdir::WalkDir::new(path)
+        .follow_links(false) // Don't follow symlinks for security
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("rs"))
+        .collect()
+}
+
+/// Reads and parses every `.rs` file under `path`, fanning the work out
+/// across `num_cpus::get()` worker threads so large workspaces don't
+/// bottleneck on a single thread. `extract` runs once per successfully-read
+/// file (on whichever worker picked it up) and its per-file results are
+/// aggregated back into a single `Vec`, in no particular order.
+fn search_rust_files<F, T>(path: &Path, extract: F) -> Result<Vec<T>>
+where
+    F: Fn(&Path, &str) -> Vec<T> + Sync,
+    T: Send,
+{
+    let files = collect_rust_files(path);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let worker_count = num_cpus::get().max(1).min(files.len());
+    let chunk_size = (files.len() + worker_count - 1) / worker_count;
+    let results = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for chunk in files.chunks(chunk_size) {
+            scope.spawn(|| {
+                let mut local = Vec::new();
+                for file in chunk {
+                    if let Ok(content) = fs::read_to_string(file) {
+                        local.extend(extract(file, &content));
+                    }
+                }
+                results.lock().unwrap().extend(local);
+            });
+        }
+    });
+
+    Ok(results.into_inner().unwrap())
 }
 
 /// Convert a Visibility to a string representation
@@ -372,4 +869,78 @@ fn visibility_to_string(vis: &Visibility) -> String {
         }
         Visibility::Inherited => "private".to_string(),
     }
+}
+
+/// Tool that dumps the complete `syn` syntax tree of a file as JSON via
+/// `syn-serde`, rather than the lossy hand-rolled summaries the other tools
+/// in this module produce. Round-trips item kinds those summaries ignore
+/// (`use`, `mod`, `macro_rules!`, consts, type aliases, generic bounds,
+/// where-clauses, function bodies) so a caller can reconstruct the source
+/// from the tree.
+pub struct DumpAstTool;
+
+impl Default for DumpAstTool {
+    fn default() -> Self {
+        Self
+    }
+}
+
+impl DumpAstTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Tool for DumpAstTool {
+    fn name(&self) -> &str {
+        "dump_ast"
+    }
+
+    fn description(&self) -> &str {
+        "Dump the full syn AST of Rust code as structured JSON"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Rust code to parse"
+                },
+                "pretty": {
+                    "type": "boolean",
+                    "description": "Indent the JSON output (defaults to false)"
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            code: String,
+            #[serde(default)]
+            pretty: bool,
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+
+        let syntax_tree = parse_file(&params.code)
+            .map_err(|e| anyhow::anyhow!("Failed to parse Rust code: {}", e))?;
+
+        let ast_json = if params.pretty {
+            syn_serde::json::to_string_pretty(&syntax_tree)
+        } else {
+            syn_serde::json::to_string(&syntax_tree)
+        };
+        let ast: Value = serde_json::from_str(&ast_json)?;
+
+        Ok(json!({
+            "success": true,
+            "ast": ast,
+        }))
+    }
 }
\ No newline at end of file
@@ -1,30 +1,152 @@
 //! Ed-based file manipulation tools
-//! 
+//!
 //! This module implements file operations using ed(1) semantics,
 //! providing a line-oriented approach to text manipulation.
 
 use super::Tool;
-use anyhow::{bail, Result};
 use async_trait::async_trait;
+use nom::branch::alt;
+use nom::bytes::complete::{escaped, is_not};
+use nom::character::complete::{char as nom_char, digit1, one_of};
+use nom::combinator::{map, map_res, opt, value};
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
+use regex::Regex;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-/// Represents a line in the ed buffer
+/// Represents a line in the ed buffer.
+///
+/// `id` is a monotonically increasing, never-reused identifier distinct
+/// from the line's position in `EdBuffer::lines`. `g`/`v` use it as a
+/// stable marker: they record which lines matched *before* running any
+/// command, then re-resolve each marked `id` back to a live index right
+/// before acting on it, so deletions or moves earlier in the same global
+/// pass can't shift a later mark onto the wrong line.
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Line {
     content: String,
+    id: u64,
 }
 
 impl Line {
-    fn new(content: impl Into<String>) -> Self {
+    fn new(content: impl Into<String>, id: u64) -> Self {
         Self {
             content: content.into(),
+            id,
         }
     }
 }
 
+/// An address as written by the user, not yet resolved against a buffer.
+///
+/// Resolution happens at execution time (see [`EdBuffer::resolve`]), never
+/// at parse time, because earlier commands in the same batch can change the
+/// line count or `current` that a later address (e.g. `.` or `+2`) depends
+/// on.
+#[derive(Debug, Clone, PartialEq)]
+enum Addr {
+    Current,
+    Last,
+    Line(usize),
+    Offset(i32),
+    Mark(char),
+    Fwd(String),
+    Bwd(String),
+}
+
+/// A parsed `start,end` address range.
+#[derive(Debug, Clone, PartialEq)]
+struct Range(Addr, Addr);
+
+/// The command letter and its arguments, still holding unresolved
+/// [`Addr`]/[`Range`] values.
+#[derive(Debug, Clone, PartialEq)]
+enum CommandKind {
+    Append,
+    Insert,
+    Change,
+    Delete,
+    Print,
+    Number,
+    Write(Option<String>),
+    Quit,
+    Substitute(String),
+    Move(String),
+    Join,
+    LineNumber,
+    /// `g/re/cmd` (or `v` for the inverted match) — run `cmd` (raw, parsed
+    /// again when each marked line is reached; defaults to `p`) on every
+    /// line matching (or not matching) `re`.
+    Global { invert: bool, pattern: String, command: String },
+    /// `G`/`V` — just collect the matching (or non-matching) lines.
+    GlobalList { invert: bool, pattern: String },
+    /// `e filename` — switch a [`Session`]'s active buffer. Meaningless
+    /// against a lone `EdBuffer`.
+    Edit(String),
+    /// `f [filename]` — get (no argument) or set the current buffer's
+    /// associated filename.
+    File(Option<String>),
+    /// `W` — flush every modified buffer in a [`Session`].
+    WriteAll,
+    /// `u` — undo the most recent mutating command.
+    Undo,
+    /// `R` — redo the most recently undone command.
+    Redo,
+    /// `r [filename]` — read a file's contents in after the addressed line.
+    /// Defaults to the buffer's associated filename. Meaningless against a
+    /// lone `EdBuffer`, which has no filesystem access of its own.
+    Read(Option<String>),
+    /// `t addr,dest` — copy (not move) the addressed lines to after `dest`.
+    Transfer(String),
+    /// `k x` — set mark `x` to the addressed line.
+    Mark(char),
+}
+
+/// A fully parsed command line: an optional address/range plus its kind.
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedCommand {
+    range: Option<Range>,
+    kind: CommandKind,
+}
+
+/// Errors raised by [`EdBuffer::execute_command`]. `Parse` carries enough of
+/// the offending token and its byte position for a caller to build a
+/// diagnostic; `Exec` covers everything that can only fail once addresses
+/// are resolved against the live buffer (out-of-range lines, bad destinations).
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdError {
+    Parse {
+        message: String,
+        token: String,
+        position: usize,
+    },
+    Exec(String),
+}
+
+impl EdError {
+    fn exec(message: impl Into<String>) -> Self {
+        EdError::Exec(message.into())
+    }
+}
+
+impl std::fmt::Display for EdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdError::Parse { message, token, position } => {
+                write!(f, "{message} (at '{token}', position {position})")
+            }
+            EdError::Exec(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for EdError {}
+
+type EdOutcome = Result<EdResult, EdError>;
+
 /// Ed buffer implementation with line-oriented operations
 pub struct EdBuffer {
     lines: Vec<Line>,
@@ -32,6 +154,59 @@ pub struct EdBuffer {
     modified: bool,
     marks: HashMap<char, usize>,
     filename: Option<String>,
+    /// The most recently compiled pattern, reused when `s` or a `/re/`/`?re?`
+    /// address is given an empty regex.
+    last_regex: Option<Regex>,
+    /// Next id to hand out via [`Self::fresh_line`].
+    next_line_id: u64,
+    /// Snapshots taken before each mutating command, most recent last, for
+    /// `u` to pop and restore. Bounded by `undo_limit`: the oldest snapshot
+    /// is dropped once the stack grows past it.
+    undo_stack: Vec<UndoSnapshot>,
+    /// Snapshots popped by `u`, restored by `R`. Cleared whenever a new
+    /// mutating command runs, since redoing past it would no longer make
+    /// sense.
+    redo_stack: Vec<UndoSnapshot>,
+    /// Maximum depth of `undo_stack`/`redo_stack`.
+    undo_limit: usize,
+}
+
+/// How many undo steps an [`EdBuffer`] keeps by default.
+const DEFAULT_UNDO_LIMIT: usize = 100;
+
+/// A pre-mutation copy of everything `u`/`R` need to restore: the line
+/// table (including each `Line`'s stable `id`, so global marks taken before
+/// an undo remain meaningful after it), the cursor, the dirty flag, and
+/// marks.
+#[derive(Clone)]
+struct UndoSnapshot {
+    lines: Vec<Line>,
+    current: usize,
+    modified: bool,
+    marks: HashMap<char, usize>,
+}
+
+/// Does `kind` mutate the buffer, and so need an undo snapshot taken before
+/// it runs? `Global` is included even though its own edits happen through
+/// nested (untracked) `EdBuffer::run` calls, so the whole `g` command undoes
+/// as one step rather than one step per matched line. `Read` is left out on
+/// purpose: POSIX ed doesn't make `r` undoable either, and since it only
+/// runs through a [`Session`] (see [`Session::cmd_read`]) it never reaches
+/// this check in the first place.
+fn is_mutating(kind: &CommandKind) -> bool {
+    matches!(
+        kind,
+        CommandKind::Append
+            | CommandKind::Insert
+            | CommandKind::Change
+            | CommandKind::Delete
+            | CommandKind::Substitute(_)
+            | CommandKind::Move(_)
+            | CommandKind::Join
+            | CommandKind::Global { .. }
+            | CommandKind::Transfer(_)
+            | CommandKind::Mark(_)
+    )
 }
 
 /// Result of executing an ed command
@@ -53,10 +228,308 @@ impl Default for EdBuffer {
             modified: false,
             marks: HashMap::new(),
             filename: None,
+            last_regex: None,
+            next_line_id: 0,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_limit: DEFAULT_UNDO_LIMIT,
+        }
+    }
+}
+
+// --- Parsing -----------------------------------------------------------
+//
+// A small nom grammar turns a trimmed command line into a `ParsedCommand`.
+// Addresses are collected as `Addr` values, not resolved line numbers, so
+// resolution can happen later against whatever buffer state exists when the
+// command actually runs.
+
+fn addr_mark(input: &str) -> IResult<&str, Addr> {
+    map(preceded(nom_char('\''), one_of("abcdefghijklmnopqrstuvwxyz")), Addr::Mark)(input)
+}
+
+fn addr_search(input: &str) -> IResult<&str, Addr> {
+    alt((
+        map(
+            preceded(nom_char('/'), escaped(is_not("\\/"), '\\', one_of("/\\"))),
+            |s: &str| Addr::Fwd(s.to_string()),
+        ),
+        value(Addr::Fwd(String::new()), nom_char('/')),
+        map(
+            preceded(nom_char('?'), escaped(is_not("\\?"), '\\', one_of("?\\"))),
+            |s: &str| Addr::Bwd(s.to_string()),
+        ),
+        value(Addr::Bwd(String::new()), nom_char('?')),
+    ))(input)
+}
+
+fn addr_offset(input: &str) -> IResult<&str, Addr> {
+    map(
+        nom::sequence::pair(one_of("+-"), opt(digit1)),
+        |(sign, digits): (char, Option<&str>)| {
+            let magnitude: i32 = digits.map(|d| d.parse().unwrap_or(1)).unwrap_or(1);
+            Addr::Offset(if sign == '-' { -magnitude } else { magnitude })
+        },
+    )(input)
+}
+
+fn addr_line(input: &str) -> IResult<&str, Addr> {
+    map_res(digit1, |d: &str| d.parse::<usize>().map(Addr::Line))(input)
+}
+
+/// A single address: `.`, `$`, a line number, `+N`/`-N`, `'x`, `/re/`, `?re?`.
+fn addr(input: &str) -> IResult<&str, Addr> {
+    alt((
+        value(Addr::Current, nom_char('.')),
+        value(Addr::Last, nom_char('$')),
+        addr_mark,
+        addr_search,
+        addr_offset,
+        addr_line,
+    ))(input)
+}
+
+/// `%` is sugar for the whole-buffer range `1,$`.
+fn range_all(input: &str) -> IResult<&str, Range> {
+    value(Range(Addr::Line(1), Addr::Last), nom_char('%'))(input)
+}
+
+fn range_pair(input: &str) -> IResult<&str, Range> {
+    map(separated_pair(addr, nom_char(','), addr), |(a, b)| Range(a, b))(input)
+}
+
+fn range_single(input: &str) -> IResult<&str, Range> {
+    map(addr, |a| Range(a.clone(), a))(input)
+}
+
+fn range(input: &str) -> IResult<&str, Range> {
+    alt((range_all, range_pair, range_single))(input)
+}
+
+/// Parses a trimmed ed command line into a [`ParsedCommand`]. Only the
+/// command letter and its address(es) are understood here; `a`/`i`/`c`'s
+/// trailing text block is collected separately by the caller.
+fn parse_command_line(input: &str) -> Result<ParsedCommand, EdError> {
+    let (rest, maybe_range) = opt(range)(input).map_err(|_| EdError::Parse {
+        message: "invalid address".to_string(),
+        token: input.to_string(),
+        position: 0,
+    })?;
+
+    let rest = rest.trim_start();
+    let Some(cmd_char) = rest.chars().next() else {
+        return Err(EdError::Parse {
+            message: "empty command".to_string(),
+            token: input.to_string(),
+            position: input.len(),
+        });
+    };
+    let position = input.len() - rest.len();
+    let args = &rest[cmd_char.len_utf8()..];
+
+    let kind = match cmd_char {
+        'a' => CommandKind::Append,
+        'i' => CommandKind::Insert,
+        'c' => CommandKind::Change,
+        'd' => CommandKind::Delete,
+        'p' => CommandKind::Print,
+        'n' => CommandKind::Number,
+        'w' => CommandKind::Write(non_empty(args.trim())),
+        'W' => CommandKind::WriteAll,
+        'e' => CommandKind::Edit(args.trim().to_string()),
+        'f' => CommandKind::File(non_empty(args.trim())),
+        'q' => CommandKind::Quit,
+        's' => CommandKind::Substitute(args.to_string()),
+        'm' => CommandKind::Move(args.trim().to_string()),
+        'j' => CommandKind::Join,
+        '=' => CommandKind::LineNumber,
+        'u' => CommandKind::Undo,
+        'R' => CommandKind::Redo,
+        'r' => CommandKind::Read(non_empty(args.trim())),
+        't' => CommandKind::Transfer(args.trim().to_string()),
+        'k' => {
+            let Some(name) = args.trim().chars().next() else {
+                return Err(EdError::Parse {
+                    message: "'k' requires a single-letter mark name".to_string(),
+                    token: args.to_string(),
+                    position,
+                });
+            };
+            CommandKind::Mark(name)
         }
+        'g' | 'v' | 'G' | 'V' => {
+            let Some((pattern, trailing)) = parse_global_args(args) else {
+                return Err(EdError::Parse {
+                    message: "invalid global command: missing regex delimiter".to_string(),
+                    token: args.to_string(),
+                    position,
+                });
+            };
+            let invert = cmd_char == 'v' || cmd_char == 'V';
+            if cmd_char == 'G' || cmd_char == 'V' {
+                CommandKind::GlobalList { invert, pattern }
+            } else {
+                CommandKind::Global { invert, pattern, command: trailing }
+            }
+        }
+        other => {
+            return Err(EdError::Parse {
+                message: format!("unknown command '{other}'"),
+                token: other.to_string(),
+                position,
+            });
+        }
+    };
+
+    Ok(ParsedCommand { range: maybe_range, kind })
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
     }
 }
 
+/// Splits `s` on unescaped occurrences of `delim`. A `\<delim>` sequence is
+/// unescaped to a literal `delim` and does not terminate the field; any
+/// other backslash sequence (e.g. `\1`, `\\`) passes through untouched so
+/// later regex/replacement translation can still see it.
+fn split_fields(s: &str, delim: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if chars.peek() == Some(&delim) {
+                current.push(delim);
+                chars.next();
+            } else {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            continue;
+        }
+        if c == delim {
+            fields.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+    }
+    fields.push(current);
+    fields
+}
+
+/// Splits `s` at the first unescaped occurrence of `delim`, honoring the
+/// same `\<delim>` escaping as [`split_fields`]. Unlike `split_fields`,
+/// everything after that first delimiter is returned verbatim as the second
+/// element — used for `g/re/cmd`, where `cmd` may itself contain `delim`.
+fn split_first_field(s: &str, delim: char) -> (String, String) {
+    let mut field = String::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            if chars.peek().map(|&(_, d)| d) == Some(delim) {
+                field.push(delim);
+                chars.next();
+            } else {
+                field.push(c);
+                if let Some((_, next)) = chars.next() {
+                    field.push(next);
+                }
+            }
+            continue;
+        }
+        if c == delim {
+            return (field, s[i + c.len_utf8()..].to_string());
+        }
+        field.push(c);
+    }
+    (field, String::new())
+}
+
+/// Parses `g`/`v`/`G`/`V`'s arguments (`<delim>pattern<delim>[command]`)
+/// into `(pattern, trailing_command)`.
+fn parse_global_args(args: &str) -> Option<(String, String)> {
+    let delim = args.chars().next()?;
+    Some(split_first_field(&args[delim.len_utf8()..], delim))
+}
+
+/// Translates ed's substitution backreference syntax (`&` for the whole
+/// match, `\1`..`\9` for capture groups) into the `regex` crate's
+/// `$0`/`$N` template syntax, escaping any literal `$` so it survives
+/// `Captures::expand` unchanged.
+fn translate_replacement(raw: &str) -> String {
+    let mut out = String::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => out.push_str("$$"),
+            '&' => out.push_str("$0"),
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() && d != '0' => {
+                    out.push('$');
+                    out.push(d);
+                }
+                Some('&') => out.push('&'),
+                Some('\\') => out.push('\\'),
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            },
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Parses `s///`'s trailing flags: `g` (all occurrences), a numeric count
+/// (only the Nth match), and `p` (print the changed line).
+fn parse_flags(flags: &str) -> (bool, Option<usize>, bool) {
+    let global = flags.contains('g');
+    let print = flags.contains('p');
+    let digits: String = flags.chars().filter(char::is_ascii_digit).collect();
+    let count = if digits.is_empty() { None } else { digits.parse().ok() };
+    (global, count, print)
+}
+
+/// Applies `template` (already translated to `$N` form) to whichever
+/// matches of `re` in `line` are selected by `global`/`count`: all of them
+/// if `global`, else only the `count`th (default: the first). Returns the
+/// new line content and whether anything actually changed.
+fn substitute_line(re: &Regex, line: &str, template: &str, global: bool, count: Option<usize>) -> (String, bool) {
+    let target = count.unwrap_or(1);
+    let mut result = String::new();
+    let mut last_end = 0;
+    let mut changed = false;
+
+    for (i, caps) in re.captures_iter(line).enumerate() {
+        let m = caps.get(0).unwrap();
+        if global || i + 1 == target {
+            result.push_str(&line[last_end..m.start()]);
+            caps.expand(template, &mut result);
+            last_end = m.end();
+            changed = true;
+        }
+    }
+    result.push_str(&line[last_end..]);
+
+    (result, changed)
+}
+
+/// Does `trimmed` start a text-collecting command (`a`, `i`, `c`) that needs
+/// its trailing lines gathered up to a lone `.` before it can run? Used by
+/// [`EdTool::execute`] to know when to buffer subsequent array entries
+/// instead of executing them as commands.
+fn starts_text_block(trimmed: &str) -> bool {
+    matches!(parse_command_line(trimmed), Ok(ParsedCommand { kind: CommandKind::Append | CommandKind::Insert | CommandKind::Change, .. }))
+}
+
 impl EdBuffer {
     /// Create a new empty buffer
     pub fn new() -> Self {
@@ -67,19 +540,38 @@ impl EdBuffer {
     pub fn from_string(content: &str) -> Self {
         let lines: Vec<Line> = content
             .lines()
-            .map(Line::new)
+            .enumerate()
+            .map(|(id, content)| Line::new(content, id as u64))
             .collect();
-        
+
         let line_count = lines.len();
         Self {
+            next_line_id: line_count as u64,
             lines,
             current: line_count,
             modified: false,
             marks: HashMap::new(),
             filename: None,
+            last_regex: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_limit: DEFAULT_UNDO_LIMIT,
         }
     }
 
+    /// Overrides the default undo/redo depth (see [`DEFAULT_UNDO_LIMIT`]).
+    pub fn with_undo_limit(mut self, limit: usize) -> Self {
+        self.undo_limit = limit;
+        self
+    }
+
+    /// Builds a [`Line`] tagged with a fresh, buffer-unique id.
+    fn fresh_line(&mut self, content: impl Into<String>) -> Line {
+        let id = self.next_line_id;
+        self.next_line_id += 1;
+        Line::new(content, id)
+    }
+
     /// Get current line number (1-indexed)
     pub fn current_line(&self) -> usize {
         self.current
@@ -90,154 +582,324 @@ impl EdBuffer {
         self.lines.len()
     }
 
-    /// Parse line address from string
-    fn parse_address(&self, addr: &str) -> Result<usize> {
+    /// Resolve an [`Addr`] against the buffer's *current* state.
+    fn resolve(&self, addr: &Addr) -> Result<usize, EdError> {
         match addr {
-            "." => Ok(self.current),
-            "$" => Ok(self.lines.len()),
-            "0" => Ok(0),
-            _ => {
-                if let Ok(n) = addr.parse::<usize>() {
-                    if n <= self.lines.len() {
-                        Ok(n)
-                    } else {
-                        bail!("Invalid address")
-                    }
-                } else if addr.starts_with('+') || addr.starts_with('-') {
-                    let offset: i32 = addr.parse()?;
-                    let new_addr = (self.current as i32) + offset;
-                    if new_addr >= 0 && new_addr as usize <= self.lines.len() {
-                        Ok(new_addr as usize)
-                    } else {
-                        bail!("Invalid address")
-                    }
+            Addr::Current => Ok(self.current),
+            Addr::Last => Ok(self.lines.len()),
+            Addr::Line(n) => {
+                if *n <= self.lines.len() {
+                    Ok(*n)
+                } else {
+                    Err(EdError::exec("Invalid address"))
+                }
+            }
+            Addr::Offset(delta) => {
+                let resolved = self.current as i32 + delta;
+                if resolved >= 0 && resolved as usize <= self.lines.len() {
+                    Ok(resolved as usize)
                 } else {
-                    bail!("Invalid address")
+                    Err(EdError::exec("Invalid address"))
                 }
             }
+            Addr::Mark(name) => self
+                .marks
+                .get(name)
+                .copied()
+                .ok_or_else(|| EdError::exec(format!("No mark '{name}'"))),
+            Addr::Fwd(pattern) => {
+                let re = self.resolve_regex(pattern)?;
+                self.search_forward(&re)
+            }
+            Addr::Bwd(pattern) => {
+                let re = self.resolve_regex(pattern)?;
+                self.search_backward(&re)
+            }
         }
     }
 
-    /// Parse address range (e.g., "1,5" or "%" for all lines)
-    fn parse_range(&self, range: &str) -> Result<(usize, usize)> {
-        if range == "%" {
-            return Ok((1, self.lines.len()));
+    /// Compiles `pattern`, or falls back to the last compiled pattern if
+    /// `pattern` is empty (ed's "reuse the last regex" convention).
+    fn resolve_regex(&self, pattern: &str) -> Result<Regex, EdError> {
+        if pattern.is_empty() {
+            self.last_regex.clone().ok_or_else(|| EdError::exec("No previous regular expression"))
+        } else {
+            Regex::new(pattern).map_err(|e| EdError::exec(format!("Invalid regex: {e}")))
         }
+    }
 
-        let parts: Vec<&str> = range.split(',').collect();
-        match parts.len() {
-            1 => {
-                let addr = self.parse_address(parts[0])?;
-                Ok((addr, addr))
-            }
-            2 => {
-                let start = if parts[0].is_empty() {
-                    self.current
-                } else {
-                    self.parse_address(parts[0])?
-                };
-                let end = if parts[1].is_empty() {
-                    self.lines.len()
-                } else {
-                    self.parse_address(parts[1])?
-                };
+    /// Finds the next line matching `re`, searching forward from just after
+    /// `current` and wrapping around to `current` itself last.
+    fn search_forward(&self, re: &Regex) -> Result<usize, EdError> {
+        let n = self.lines.len();
+        if n == 0 {
+            return Err(EdError::exec("No match"));
+        }
+        (1..=n)
+            .map(|offset| (self.current + offset - 1) % n + 1)
+            .find(|&idx| re.is_match(&self.lines[idx - 1].content))
+            .ok_or_else(|| EdError::exec("No match"))
+    }
+
+    /// Like [`Self::search_forward`] but searches backward.
+    fn search_backward(&self, re: &Regex) -> Result<usize, EdError> {
+        let n = self.lines.len();
+        if n == 0 {
+            return Err(EdError::exec("No match"));
+        }
+        (1..=n)
+            .map(|offset| (self.current + n - offset - 1) % n + 1)
+            .find(|&idx| re.is_match(&self.lines[idx - 1].content))
+            .ok_or_else(|| EdError::exec("No match"))
+    }
+
+    /// Resolve an optional [`Range`], defaulting to `current,current`.
+    fn resolve_range(&self, range: &Option<Range>) -> Result<(usize, usize), EdError> {
+        self.resolve_range_or(range, (self.current, self.current))
+    }
+
+    /// Resolve an optional [`Range`], falling back to `default` rather than
+    /// `current,current` when no range was given (used by `g`/`v`/`G`/`V`,
+    /// which default to the whole buffer).
+    fn resolve_range_or(&self, range: &Option<Range>, default: (usize, usize)) -> Result<(usize, usize), EdError> {
+        match range {
+            None => Ok(default),
+            Some(Range(a, b)) => {
+                let start = self.resolve(a)?;
+                let end = self.resolve(b)?;
                 if start <= end && start > 0 {
                     Ok((start, end))
                 } else {
-                    bail!("Invalid range")
+                    Err(EdError::exec("Invalid range"))
                 }
             }
-            _ => bail!("Invalid range"),
         }
     }
 
-    /// Execute an ed command
-    pub fn execute_command(&mut self, command: &str) -> Result<EdResult> {
-        if command.is_empty() {
+    /// Execute an ed command that needs no trailing text block.
+    pub fn execute_command(&mut self, command: &str) -> EdOutcome {
+        let trimmed = command.trim();
+        if trimmed.is_empty() {
             return Ok(EdResult::Success);
         }
 
-        // Parse address and command
+        let parsed = parse_command_line(trimmed)?;
+        self.run_tracked(parsed, Vec::new())
+    }
+
+    /// Execute an `a`/`i`/`c` command together with the text block the
+    /// caller already collected (terminated by a lone `.`).
+    pub fn execute_command_with_text(&mut self, command: &str, text: Vec<String>) -> EdOutcome {
         let trimmed = command.trim();
-        
-        // Handle simple address (just a number or special address)
-        if let Ok(addr) = self.parse_address(trimmed) {
-            if addr <= self.lines.len() {
-                self.current = addr;
-                return Ok(EdResult::CurrentLine(addr));
-            }
+        let parsed = parse_command_line(trimmed)?;
+        self.run_tracked(parsed, text)
+    }
+
+    /// Takes an undo snapshot before a mutating command and runs it,
+    /// dropping the snapshot again if the command failed (a failed command
+    /// must leave undo history untouched) and otherwise clearing `redo_stack`
+    /// (redoing past a fresh edit would replay stale state). Non-mutating
+    /// commands, and `u`/`R` themselves, skip snapshotting entirely.
+    fn run_tracked(&mut self, parsed: ParsedCommand, text: Vec<String>) -> EdOutcome {
+        if !is_mutating(&parsed.kind) {
+            return self.run(parsed, text);
         }
+        self.with_undo_tracking(|buf| buf.run(parsed, text))
+    }
 
-        // Extract command character and address
-        let (addr_part, cmd_part) = self.split_command(trimmed)?;
-        
-        match cmd_part.chars().next() {
-            Some('a') => self.cmd_append(addr_part),
-            Some('i') => self.cmd_insert(addr_part),
-            Some('d') => self.cmd_delete(addr_part),
-            Some('c') => self.cmd_change(addr_part),
-            Some('p') => self.cmd_print(addr_part),
-            Some('n') => self.cmd_number(addr_part),
-            Some('w') => self.cmd_write(&cmd_part[1..].trim()),
-            Some('q') => self.cmd_quit(),
-            Some('s') => self.cmd_substitute(addr_part, &cmd_part[1..]),
-            Some('m') => self.cmd_move(addr_part, &cmd_part[1..]),
-            Some('j') => self.cmd_join(addr_part),
-            Some('=') => self.cmd_line_number(addr_part),
-            _ => bail!("Unknown command"),
-        }
-    }
-
-    /// Split command into address and command parts
-    fn split_command<'a>(&self, cmd: &'a str) -> Result<(&'a str, &'a str)> {
-        // Find where the command letter starts
-        for (i, ch) in cmd.char_indices() {
-            if ch.is_alphabetic() || ch == '=' {
-                return Ok((&cmd[..i], &cmd[i..]));
+    /// Takes an undo snapshot, runs `edit`, and either commits it (clearing
+    /// `redo_stack`) or discards the snapshot if `edit` failed. Shared by
+    /// [`Self::run_tracked`] and [`Session::cmd_read`], whose `r` splicing
+    /// lives outside `run` since it needs filesystem access `EdBuffer` alone
+    /// doesn't have.
+    fn with_undo_tracking(&mut self, edit: impl FnOnce(&mut Self) -> EdOutcome) -> EdOutcome {
+        self.push_undo_snapshot();
+        let outcome = edit(self);
+        match &outcome {
+            Ok(_) => self.redo_stack.clear(),
+            Err(_) => {
+                self.undo_stack.pop();
             }
         }
-        Ok((cmd, ""))
+        outcome
     }
 
-    /// Append lines after the given address
-    fn cmd_append(&mut self, addr: &str) -> Result<EdResult> {
-        let line_num = if addr.is_empty() {
-            self.current
-        } else {
-            self.parse_address(addr)?
+    fn snapshot(&self) -> UndoSnapshot {
+        UndoSnapshot {
+            lines: self.lines.clone(),
+            current: self.current,
+            modified: self.modified,
+            marks: self.marks.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: UndoSnapshot) {
+        self.lines = snapshot.lines;
+        self.current = snapshot.current;
+        self.modified = snapshot.modified;
+        self.marks = snapshot.marks;
+    }
+
+    fn push_undo_snapshot(&mut self) {
+        self.undo_stack.push(self.snapshot());
+        if self.undo_stack.len() > self.undo_limit {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// `u`: restore the state captured just before the most recent mutating
+    /// command, pushing the (now-superseded) current state onto
+    /// `redo_stack` first.
+    fn cmd_undo(&mut self) -> EdOutcome {
+        let Some(previous) = self.undo_stack.pop() else {
+            return Err(EdError::exec("nothing to undo"));
         };
-        
-        // In a real implementation, this would read from input
-        // For now, we'll just mark as ready for append
-        self.current = line_num;
+        self.redo_stack.push(self.snapshot());
+        if self.redo_stack.len() > self.undo_limit {
+            self.redo_stack.remove(0);
+        }
+        self.restore(previous);
         Ok(EdResult::Success)
     }
 
-    /// Insert lines before the given address
-    fn cmd_insert(&mut self, addr: &str) -> Result<EdResult> {
-        let line_num = if addr.is_empty() {
-            self.current
-        } else {
-            self.parse_address(addr)?
+    /// `R`: reapply the most recently undone command, pushing the current
+    /// (pre-redo) state back onto `undo_stack` so `u` can undo it again.
+    fn cmd_redo(&mut self) -> EdOutcome {
+        let Some(next) = self.redo_stack.pop() else {
+            return Err(EdError::exec("nothing to redo"));
         };
-        
-        self.current = if line_num > 0 { line_num - 1 } else { 0 };
+        self.undo_stack.push(self.snapshot());
+        if self.undo_stack.len() > self.undo_limit {
+            self.undo_stack.remove(0);
+        }
+        self.restore(next);
+        Ok(EdResult::Success)
+    }
+
+    fn run(&mut self, parsed: ParsedCommand, text: Vec<String>) -> EdOutcome {
+        match parsed.kind {
+            CommandKind::Append => self.cmd_append(&parsed.range, text),
+            CommandKind::Insert => self.cmd_insert(&parsed.range, text),
+            CommandKind::Change => self.cmd_change(&parsed.range, text),
+            CommandKind::Delete => self.cmd_delete(&parsed.range),
+            CommandKind::Print => self.cmd_print(&parsed.range),
+            CommandKind::Number => self.cmd_number(&parsed.range),
+            CommandKind::Write(filename) => self.cmd_write(filename.as_deref().unwrap_or("")),
+            CommandKind::Quit => self.cmd_quit(),
+            CommandKind::Substitute(args) => self.cmd_substitute(&parsed.range, &args),
+            CommandKind::Move(dest) => self.cmd_move(&parsed.range, &dest),
+            CommandKind::Join => self.cmd_join(&parsed.range),
+            CommandKind::LineNumber => self.cmd_line_number(&parsed.range),
+            CommandKind::Global { invert, pattern, command } => {
+                self.cmd_global(&parsed.range, invert, &pattern, &command)
+            }
+            CommandKind::GlobalList { invert, pattern } => {
+                self.cmd_global_list(&parsed.range, invert, &pattern)
+            }
+            CommandKind::File(name) => self.cmd_file(name),
+            CommandKind::Edit(_) => Err(EdError::exec("'e' requires a multi-buffer session")),
+            CommandKind::WriteAll => Err(EdError::exec("'W' requires a multi-buffer session")),
+            CommandKind::Undo => self.cmd_undo(),
+            CommandKind::Redo => self.cmd_redo(),
+            CommandKind::Read(_) => Err(EdError::exec("'r' requires a multi-buffer session")),
+            CommandKind::Transfer(dest) => self.cmd_transfer(&parsed.range, &dest),
+            CommandKind::Mark(name) => self.cmd_mark(&parsed.range, name),
+        }
+    }
+
+    /// `f [filename]`: sets the buffer's associated filename if one is
+    /// given, and always reports the (possibly just-updated) filename.
+    fn cmd_file(&mut self, name: Option<String>) -> EdOutcome {
+        if let Some(name) = name {
+            self.filename = Some(name);
+        }
+        Ok(EdResult::Lines(vec![self.filename.clone().unwrap_or_default()]))
+    }
+
+    /// `g/re/cmd` (or `v` for `invert`): mark every line in `range` (default
+    /// the whole buffer) matching `re`, then re-resolve each mark against
+    /// the live buffer and run `cmd` on it, skipping marks whose line has
+    /// since been removed.
+    fn cmd_global(&mut self, range: &Option<Range>, invert: bool, pattern: &str, command: &str) -> EdOutcome {
+        let (start, end) = self.resolve_range_or(range, (1, self.lines.len()))?;
+        let regex = self.resolve_regex(pattern)?;
+        self.last_regex = Some(regex.clone());
+
+        let marked: Vec<u64> = (start..=end)
+            .filter(|&i| i >= 1 && i <= self.lines.len())
+            .filter(|&i| regex.is_match(&self.lines[i - 1].content) != invert)
+            .map(|i| self.lines[i - 1].id)
+            .collect();
+
+        let trailing = command.trim();
+        let trailing = if trailing.is_empty() { "p" } else { trailing };
+        let parsed_trailing = parse_command_line(trailing)?;
+
+        let mut outputs = Vec::new();
+        for id in marked {
+            let Some(idx) = self.lines.iter().position(|line| line.id == id) else {
+                continue; // the line was removed by an earlier iteration
+            };
+            self.current = idx + 1;
+            if let EdResult::Lines(lines) = self.run(parsed_trailing.clone(), Vec::new())? {
+                outputs.extend(lines);
+            }
+        }
+
+        Ok(if outputs.is_empty() { EdResult::Success } else { EdResult::Lines(outputs) })
+    }
+
+    /// `G`/`V`: collect the matching (or, with `invert`, non-matching)
+    /// lines without running anything on them.
+    fn cmd_global_list(&self, range: &Option<Range>, invert: bool, pattern: &str) -> EdOutcome {
+        let (start, end) = self.resolve_range_or(range, (1, self.lines.len()))?;
+        let regex = self.resolve_regex(pattern)?;
+
+        let matches = (start..=end)
+            .filter(|&i| i >= 1 && i <= self.lines.len())
+            .filter(|&i| regex.is_match(&self.lines[i - 1].content) != invert)
+            .map(|i| self.lines[i - 1].content.clone())
+            .collect();
+
+        Ok(EdResult::Lines(matches))
+    }
+
+    /// Append `text` after the addressed line.
+    fn cmd_append(&mut self, range: &Option<Range>, text: Vec<String>) -> EdOutcome {
+        let (_, line_num) = self.resolve_range(range)?;
+        let mut insert_at = line_num;
+        for line in text {
+            let fresh = self.fresh_line(line);
+            self.lines.insert(insert_at, fresh);
+            insert_at += 1;
+        }
+        self.current = insert_at;
+        self.modified = true;
+        Ok(EdResult::Success)
+    }
+
+    /// Insert `text` before the addressed line.
+    fn cmd_insert(&mut self, range: &Option<Range>, text: Vec<String>) -> EdOutcome {
+        let (_, line_num) = self.resolve_range(range)?;
+        let mut insert_at = if line_num > 0 { line_num - 1 } else { 0 };
+        for line in text {
+            let fresh = self.fresh_line(line);
+            self.lines.insert(insert_at, fresh);
+            insert_at += 1;
+        }
+        self.current = insert_at;
+        self.modified = true;
         Ok(EdResult::Success)
     }
 
     /// Delete lines in range
-    fn cmd_delete(&mut self, addr: &str) -> Result<EdResult> {
-        let (start, end) = if addr.is_empty() {
-            (self.current, self.current)
-        } else {
-            self.parse_range(addr)?
-        };
+    fn cmd_delete(&mut self, range: &Option<Range>) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
 
         if start == 0 || start > self.lines.len() {
-            bail!("Invalid address");
+            return Err(EdError::exec("Invalid address"));
         }
 
-        // Remove lines (convert to 0-indexed)
         for _ in start..=end {
             if start - 1 < self.lines.len() {
                 self.lines.remove(start - 1);
@@ -246,26 +908,24 @@ impl EdBuffer {
 
         self.modified = true;
         self.current = if start > 1 { start - 1 } else { 0 };
-        
+
         Ok(EdResult::Success)
     }
 
-    /// Change (replace) lines
-    fn cmd_change(&mut self, addr: &str) -> Result<EdResult> {
-        self.cmd_delete(addr)?;
-        Ok(EdResult::Success)
+    /// Change (replace) lines with `text`
+    fn cmd_change(&mut self, range: &Option<Range>, text: Vec<String>) -> EdOutcome {
+        let (start, _) = self.resolve_range(range)?;
+        self.cmd_delete(range)?;
+        let insert_range = Some(Range(Addr::Line(start.saturating_sub(1)), Addr::Line(start.saturating_sub(1))));
+        self.cmd_insert(&insert_range, text)
     }
 
     /// Print lines
-    fn cmd_print(&self, addr: &str) -> Result<EdResult> {
-        let (start, end) = if addr.is_empty() {
-            (self.current, self.current)
-        } else {
-            self.parse_range(addr)?
-        };
+    fn cmd_print(&self, range: &Option<Range>) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
 
         if start == 0 || start > self.lines.len() {
-            bail!("Invalid address");
+            return Err(EdError::exec("Invalid address"));
         }
 
         let mut output = Vec::new();
@@ -279,15 +939,11 @@ impl EdBuffer {
     }
 
     /// Print lines with line numbers
-    fn cmd_number(&self, addr: &str) -> Result<EdResult> {
-        let (start, end) = if addr.is_empty() {
-            (self.current, self.current)
-        } else {
-            self.parse_range(addr)?
-        };
+    fn cmd_number(&self, range: &Option<Range>) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
 
         if start == 0 || start > self.lines.len() {
-            bail!("Invalid address");
+            return Err(EdError::exec("Invalid address"));
         }
 
         let mut output = Vec::new();
@@ -301,11 +957,11 @@ impl EdBuffer {
     }
 
     /// Write buffer to file
-    fn cmd_write(&mut self, filename: &str) -> Result<EdResult> {
+    fn cmd_write(&mut self, filename: &str) -> EdOutcome {
         let fname = if filename.is_empty() {
-            self.filename.as_ref().ok_or_else(|| anyhow::anyhow!("No filename"))?
+            self.filename.clone().ok_or_else(|| EdError::exec("No filename"))?
         } else {
-            filename
+            filename.to_string()
         };
 
         let content: Vec<String> = self.lines.iter()
@@ -315,75 +971,79 @@ impl EdBuffer {
         let bytes = text.len();
 
         // In real implementation, would write to file
-        self.filename = Some(fname.to_string());
+        self.filename = Some(fname);
         self.modified = false;
 
         Ok(EdResult::Written(bytes))
     }
 
     /// Quit editor
-    fn cmd_quit(&self) -> Result<EdResult> {
+    fn cmd_quit(&self) -> EdOutcome {
         if self.modified {
-            bail!("Warning: buffer modified");
+            return Err(EdError::exec("Warning: buffer modified"));
         }
         Ok(EdResult::Success)
     }
 
-    /// Substitute text
-    fn cmd_substitute(&mut self, addr: &str, args: &str) -> Result<EdResult> {
-        let (start, end) = if addr.is_empty() {
-            (self.current, self.current)
-        } else {
-            self.parse_range(addr)?
-        };
+    /// Substitute text: `s<delim>pattern<delim>replacement<delim>flags`,
+    /// where `<delim>` is whatever character immediately follows `s`.
+    fn cmd_substitute(&mut self, range: &Option<Range>, args: &str) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
 
-        // Parse s/pattern/replacement/flags
-        if args.is_empty() || !args.starts_with('/') {
-            bail!("Invalid substitute command");
-        }
+        let Some(delim) = args.chars().next() else {
+            return Err(EdError::exec("Invalid substitute command"));
+        };
 
-        let parts: Vec<&str> = args[1..].split('/').collect();
-        if parts.len() < 2 {
-            bail!("Invalid substitute command");
+        let fields = split_fields(&args[delim.len_utf8()..], delim);
+        if fields.len() < 2 {
+            return Err(EdError::exec("Invalid substitute command"));
         }
 
-        let pattern = parts[0];
-        let replacement = parts[1];
-        let global = parts.get(2).map(|f| f.contains('g')).unwrap_or(false);
+        let regex = self.resolve_regex(&fields[0])?;
+        self.last_regex = Some(regex.clone());
+        let template = translate_replacement(&fields[1]);
+        let (global, count, print) = parse_flags(fields.get(2).map(String::as_str).unwrap_or(""));
 
         let mut changed = false;
+        let mut last_changed_line: Option<usize> = None;
         for i in start..=end {
-            if i <= self.lines.len() {
-                let new_content = if global {
-                    self.lines[i - 1].content.replace(pattern, replacement)
-                } else {
-                    self.lines[i - 1].content.replacen(pattern, replacement, 1)
-                };
-                
-                if new_content != self.lines[i - 1].content {
-                    self.lines[i - 1].content = new_content;
-                    changed = true;
-                }
+            if i > self.lines.len() {
+                continue;
+            }
+            let (new_content, line_changed) =
+                substitute_line(&regex, &self.lines[i - 1].content, &template, global, count);
+            if line_changed {
+                self.lines[i - 1].content = new_content;
+                changed = true;
+                last_changed_line = Some(i);
             }
         }
 
         if changed {
             self.modified = true;
+            self.current = last_changed_line.unwrap();
+        }
+
+        if print {
+            if let Some(line) = last_changed_line {
+                return Ok(EdResult::Lines(vec![self.lines[line - 1].content.clone()]));
+            }
         }
 
         Ok(EdResult::Success)
     }
 
     /// Move lines to destination
-    fn cmd_move(&mut self, addr: &str, dest: &str) -> Result<EdResult> {
-        let (start, end) = self.parse_range(addr)?;
-        let dest_addr = self.parse_address(dest.trim())?;
+    fn cmd_move(&mut self, range: &Option<Range>, dest: &str) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
+        let dest_parsed = parse_command_line(&format!("{dest}p"))
+            .map_err(|_| EdError::exec("Invalid destination"))?;
+        let dest_addr = self.resolve_range(&dest_parsed.range)?.1;
 
         if dest_addr >= start && dest_addr <= end {
-            bail!("Invalid destination");
+            return Err(EdError::exec("Invalid destination"));
         }
 
-        // Extract lines to move
         let mut to_move = Vec::new();
         for _ in start..=end {
             if start - 1 < self.lines.len() {
@@ -391,7 +1051,6 @@ impl EdBuffer {
             }
         }
 
-        // Insert at destination
         let insert_pos = if dest_addr < start {
             dest_addr
         } else {
@@ -408,29 +1067,79 @@ impl EdBuffer {
         Ok(EdResult::Success)
     }
 
+    /// Copy the addressed lines to after `dest`, leaving the originals in
+    /// place. Unlike `cmd_move`, the source is never removed, so the copy
+    /// can be computed against the original indices and inserted without
+    /// any of `cmd_move`'s shift arithmetic.
+    fn cmd_transfer(&mut self, range: &Option<Range>, dest: &str) -> EdOutcome {
+        let (start, end) = self.resolve_range(range)?;
+        let dest_parsed = parse_command_line(&format!("{dest}p"))
+            .map_err(|_| EdError::exec("Invalid destination"))?;
+        let dest_addr = self.resolve_range(&dest_parsed.range)?.1;
+
+        let to_copy: Vec<String> = (start..=end)
+            .filter(|&i| i >= 1 && i <= self.lines.len())
+            .map(|i| self.lines[i - 1].content.clone())
+            .collect();
+
+        let mut insert_at = dest_addr;
+        for content in to_copy {
+            let fresh = self.fresh_line(content);
+            self.lines.insert(insert_at, fresh);
+            insert_at += 1;
+        }
+
+        self.modified = true;
+        self.current = insert_at;
+
+        Ok(EdResult::Success)
+    }
+
+    /// Set mark `name` to the addressed line.
+    fn cmd_mark(&mut self, range: &Option<Range>, name: char) -> EdOutcome {
+        let (_, line_num) = self.resolve_range(range)?;
+        self.marks.insert(name, line_num);
+        Ok(EdResult::Success)
+    }
+
+    /// Splices `content`'s lines in after the addressed line. Called only by
+    /// [`Session::cmd_read`], which is what actually reads the file — a bare
+    /// `EdBuffer` has no filesystem access.
+    fn insert_content(&mut self, range: &Option<Range>, content: &str) -> EdOutcome {
+        let (_, line_num) = self.resolve_range(range)?;
+        let mut insert_at = line_num;
+        for line in content.lines() {
+            let fresh = self.fresh_line(line);
+            self.lines.insert(insert_at, fresh);
+            insert_at += 1;
+        }
+        self.current = insert_at;
+        self.modified = true;
+        Ok(EdResult::Read(content.len()))
+    }
+
     /// Join lines
-    fn cmd_join(&mut self, addr: &str) -> Result<EdResult> {
-        let (start, end) = if addr.is_empty() {
-            if self.current < self.lines.len() {
-                (self.current, self.current + 1)
-            } else {
-                bail!("Invalid address");
+    fn cmd_join(&mut self, range: &Option<Range>) -> EdOutcome {
+        let (start, end) = match range {
+            None => {
+                if self.current < self.lines.len() {
+                    (self.current, self.current + 1)
+                } else {
+                    return Err(EdError::exec("Invalid address"));
+                }
             }
-        } else {
-            self.parse_range(addr)?
+            Some(_) => self.resolve_range(range)?,
         };
 
         if start >= end || end > self.lines.len() {
             return Ok(EdResult::Success);
         }
 
-        // Join lines
         let mut joined = self.lines[start - 1].content.clone();
         for i in start..end {
             joined.push_str(&self.lines[i].content);
         }
 
-        // Remove joined lines and replace with combined line
         for _ in start..end {
             self.lines.remove(start);
         }
@@ -443,26 +1152,15 @@ impl EdBuffer {
     }
 
     /// Print line number
-    fn cmd_line_number(&self, addr: &str) -> Result<EdResult> {
-        let line_num = if addr.is_empty() {
-            self.lines.len()
-        } else if addr == "." {
-            self.current
-        } else {
-            self.parse_address(addr)?
+    fn cmd_line_number(&self, range: &Option<Range>) -> EdOutcome {
+        let line_num = match range {
+            None => self.lines.len(),
+            Some(_) => self.resolve_range(range)?.1,
         };
 
         Ok(EdResult::Lines(vec![line_num.to_string()]))
     }
 
-    /// Append a line to the buffer (used after 'a' or 'i' command)
-    pub fn append_line(&mut self, line: &str) -> Result<()> {
-        self.lines.insert(self.current, Line::new(line));
-        self.current += 1;
-        self.modified = true;
-        Ok(())
-    }
-
     /// Get buffer contents as string
     pub fn to_string(&self) -> String {
         if self.lines.is_empty() {
@@ -472,7 +1170,7 @@ impl EdBuffer {
                 .map(|l| l.content.len() + 1) // +1 for newline
                 .sum::<usize>()
                 .saturating_sub(1); // Remove last newline
-            
+
             let mut result = String::with_capacity(capacity);
             for (i, line) in self.lines.iter().enumerate() {
                 if i > 0 {
@@ -485,6 +1183,178 @@ impl EdBuffer {
     }
 }
 
+/// One command's outcome from a [`Session`] batch. `command` borrows
+/// straight from the caller's command list rather than being cloned, so a
+/// caller can see exactly which command on which file failed without the
+/// session paying to copy every command string it runs.
+#[derive(Debug)]
+pub struct CommandReport<'a> {
+    pub file: String,
+    pub command: &'a str,
+    pub outcome: EdOutcome,
+}
+
+/// Owns every buffer opened for one [`EdTool::execute`] call, keyed by
+/// workspace-relative path, and tracks which one ed commands currently
+/// target. Mirrors the multi-source `Loader` pattern used for command-line
+/// parsing elsewhere in this codebase, applied here to ed buffers instead
+/// of parser input strings.
+pub struct Session {
+    workspace: PathBuf,
+    buffers: HashMap<String, EdBuffer>,
+    active: String,
+}
+
+impl Session {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            buffers: HashMap::new(),
+            active: String::new(),
+        }
+    }
+
+    /// Registers `buffer` under `name`. The first buffer loaded becomes
+    /// active.
+    pub fn load(&mut self, name: impl Into<String>, buffer: EdBuffer) {
+        let name = name.into();
+        if self.active.is_empty() {
+            self.active = name.clone();
+        }
+        self.buffers.insert(name, buffer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    pub fn buffer(&self, name: &str) -> Option<&EdBuffer> {
+        self.buffers.get(name)
+    }
+
+    pub fn buffer_names(&self) -> impl Iterator<Item = &String> {
+        self.buffers.keys()
+    }
+
+    fn switch(&mut self, name: &str) -> Result<(), EdError> {
+        if self.buffers.contains_key(name) {
+            self.active = name.to_string();
+            Ok(())
+        } else {
+            Err(EdError::exec(format!("No such buffer: {name}")))
+        }
+    }
+
+    fn active_buffer(&mut self) -> Result<&mut EdBuffer, EdError> {
+        self.buffers
+            .get_mut(&self.active)
+            .ok_or_else(|| EdError::exec("No active buffer"))
+    }
+
+    /// Execute a command that needs no trailing text block, intercepting
+    /// `e`/`W` (which act on the whole session) before delegating everything
+    /// else to the active buffer.
+    pub fn execute_command(&mut self, command: &str) -> EdOutcome {
+        let trimmed = command.trim();
+        if trimmed.is_empty() {
+            return Ok(EdResult::Success);
+        }
+        let parsed = parse_command_line(trimmed)?;
+        match &parsed.kind {
+            CommandKind::Edit(name) => self.switch(name).map(|_| EdResult::Success),
+            CommandKind::WriteAll => self.flush_modified().map(EdResult::Written),
+            CommandKind::Read(filename) => {
+                let filename = filename.clone();
+                let range = parsed.range.clone();
+                self.cmd_read(filename, &range)
+            }
+            _ => self.active_buffer()?.run_tracked(parsed, Vec::new()),
+        }
+    }
+
+    /// `r [filename]`: reads `filename` (or, if omitted, the active
+    /// buffer's associated filename) from the workspace and splices its
+    /// lines in after the addressed line. The disk read happens here
+    /// rather than in `EdBuffer` because a bare buffer has no workspace to
+    /// read against.
+    fn cmd_read(&mut self, filename: Option<String>, range: &Option<Range>) -> EdOutcome {
+        let name = filename
+            .or_else(|| self.buffers.get(&self.active).and_then(|b| b.filename.clone()))
+            .ok_or_else(|| EdError::exec("No filename"))?;
+        let path = self.workspace.join(&name);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| EdError::exec(format!("Failed to read {name}: {e}")))?;
+        self.active_buffer()?.insert_content(range, &content)
+    }
+
+    /// Execute an `a`/`i`/`c` command together with its already-collected
+    /// text block, against the active buffer.
+    pub fn execute_command_with_text(&mut self, command: &str, text: Vec<String>) -> EdOutcome {
+        let trimmed = command.trim();
+        let parsed = parse_command_line(trimmed)?;
+        self.active_buffer()?.run_tracked(parsed, text)
+    }
+
+    /// Runs every command in `commands` against whichever buffer is active
+    /// at the time, collecting `a`/`i`/`c` text blocks (terminated by a
+    /// lone `.`) the same way [`EdTool::execute`] used to do inline.
+    pub fn run_batch<'a>(&mut self, commands: &'a [String]) -> Vec<CommandReport<'a>> {
+        let mut reports = Vec::new();
+        let mut iter = commands.iter().peekable();
+        while let Some(command) = iter.next() {
+            let file = self.active.clone();
+            let outcome = if starts_text_block(command.trim()) {
+                let mut text = Vec::new();
+                for line in iter.by_ref() {
+                    if line == "." {
+                        break;
+                    }
+                    text.push(line.clone());
+                }
+                self.execute_command_with_text(command, text)
+            } else {
+                self.execute_command(command)
+            };
+            reports.push(CommandReport { file, command, outcome });
+        }
+        reports
+    }
+
+    /// Writes every buffer whose `modified` flag is set back to its file in
+    /// the workspace, in a deterministic (sorted by name) order. Buffers
+    /// are only marked clean once *every* write in the batch has
+    /// succeeded — if one fails partway through, none of them are, so a
+    /// retry re-attempts the whole batch rather than quietly treating a
+    /// half-flushed session as done.
+    pub fn flush_modified(&mut self) -> Result<usize, EdError> {
+        let mut pending: Vec<String> = self
+            .buffers
+            .iter()
+            .filter(|(_, buffer)| buffer.modified)
+            .map(|(name, _)| name.clone())
+            .collect();
+        pending.sort();
+
+        let mut total_bytes = 0;
+        for name in &pending {
+            let buffer = &self.buffers[name];
+            let content = buffer.to_string();
+            let path = self.workspace.join(name);
+            std::fs::write(&path, &content)
+                .map_err(|e| EdError::exec(format!("Failed to write {name}: {e}")))?;
+            total_bytes += content.len();
+        }
+
+        for name in &pending {
+            if let Some(buffer) = self.buffers.get_mut(name) {
+                buffer.modified = false;
+            }
+        }
+
+        Ok(total_bytes)
+    }
+}
+
 /// Ed-style line editor tool
 pub struct EdTool {
     workspace: PathBuf,
@@ -501,129 +1371,120 @@ impl Tool for EdTool {
     fn name(&self) -> &str {
         "ed_editor"
     }
-    
+
     fn description(&self) -> &str {
         "Ed-style line editor for precise text manipulation"
     }
-    
+
     fn parameters_schema(&self) -> Value {
         json!({
             "type": "object",
             "properties": {
-                "file": {
-                    "type": "string",
-                    "description": "File to edit (relative to workspace)"
+                "files": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Files to edit (relative to workspace). The first one becomes the active buffer; switch between them with 'e filename'."
                 },
                 "commands": {
                     "type": "array",
                     "items": {"type": "string"},
-                    "description": "Ed commands to execute"
+                    "description": "Ed commands to execute against whichever buffer is active. For a/i/c, follow the command with its text lines and terminate the block with a line containing only '.'"
                 },
                 "content": {
                     "type": "string",
-                    "description": "Initial content for new file"
+                    "description": "Initial content for a scratch buffer, used when no files are given"
+                },
+                "undo_limit": {
+                    "type": "integer",
+                    "description": "Maximum number of 'u'/'R' steps kept per buffer (default 100)"
                 }
             },
             "required": ["commands"]
         })
     }
-    
-    async fn execute(&self, params: Value) -> Result<Value> {
+
+    async fn execute(&self, params: Value) -> anyhow::Result<Value> {
         #[derive(Deserialize)]
         struct Params {
-            file: Option<String>,
+            files: Option<Vec<String>>,
             commands: Vec<String>,
             content: Option<String>,
+            undo_limit: Option<usize>,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
-        // Create buffer
-        let mut buffer = if let Some(content) = params.content {
-            EdBuffer::from_string(&content)
-        } else if let Some(file) = &params.file {
+        let undo_limit = params.undo_limit.unwrap_or(DEFAULT_UNDO_LIMIT);
+
+        let mut session = Session::new(self.workspace.clone());
+        for file in params.files.iter().flatten() {
             let file_path = self.workspace.join(file);
-            if file_path.exists() {
-                let content = std::fs::read_to_string(&file_path)?;
-                EdBuffer::from_string(&content)
+            let buffer = if file_path.exists() {
+                EdBuffer::from_string(&std::fs::read_to_string(&file_path)?)
             } else {
                 EdBuffer::new()
-            }
-        } else {
-            EdBuffer::new()
-        };
-        
-        // Execute commands
+            };
+            session.load(file.clone(), buffer.with_undo_limit(undo_limit));
+        }
+        if session.is_empty() {
+            session.load(
+                "<scratch>",
+                EdBuffer::from_string(&params.content.unwrap_or_default()).with_undo_limit(undo_limit),
+            );
+        }
+
+        let reports = session.run_batch(&params.commands);
         let mut results = Vec::new();
-        for command in params.commands {
-            match buffer.execute_command(&command) {
-                Ok(result) => {
-                    match result {
-                        EdResult::Lines(lines) => {
-                            results.push(json!({
-                                "command": command,
-                                "output": lines
-                            }));
-                        }
-                        EdResult::Success => {
-                            results.push(json!({
-                                "command": command,
-                                "status": "success"
-                            }));
-                        }
-                        EdResult::Written(bytes) => {
-                            results.push(json!({
-                                "command": command,
-                                "status": "written",
-                                "bytes": bytes
-                            }));
-                        }
-                        EdResult::Read(bytes) => {
-                            results.push(json!({
-                                "command": command,
-                                "status": "read",
-                                "bytes": bytes
-                            }));
-                        }
-                        EdResult::CurrentLine(line) => {
-                            results.push(json!({
-                                "command": command,
-                                "current_line": line
-                            }));
-                        }
-                        EdResult::Error(msg) => {
-                            results.push(json!({
-                                "command": command,
-                                "error": msg
-                            }));
-                        }
-                    }
-                }
-                Err(e) => {
-                    results.push(json!({
-                        "command": command,
-                        "error": e.to_string()
-                    }));
-                }
-            }
+        for report in reports {
+            let entry = match report.outcome {
+                Ok(EdResult::Lines(lines)) => json!({
+                    "file": report.file, "command": report.command, "output": lines
+                }),
+                Ok(EdResult::Success) => json!({
+                    "file": report.file, "command": report.command, "status": "success"
+                }),
+                Ok(EdResult::Written(bytes)) => json!({
+                    "file": report.file, "command": report.command, "status": "written", "bytes": bytes
+                }),
+                Ok(EdResult::Read(bytes)) => json!({
+                    "file": report.file, "command": report.command, "status": "read", "bytes": bytes
+                }),
+                Ok(EdResult::CurrentLine(line)) => json!({
+                    "file": report.file, "command": report.command, "current_line": line
+                }),
+                Ok(EdResult::Error(msg)) => json!({
+                    "file": report.file, "command": report.command, "error": msg
+                }),
+                Err(EdError::Parse { message, token, position }) => json!({
+                    "file": report.file, "command": report.command,
+                    "error": message, "token": token, "position": position
+                }),
+                Err(EdError::Exec(message)) => json!({
+                    "file": report.file, "command": report.command, "error": message
+                }),
+            };
+            results.push(entry);
         }
-        
-        // Save to file if specified
-        let file_ref = if let Some(ref file) = params.file {
-            let file_path = self.workspace.join(file);
-            std::fs::write(&file_path, buffer.to_string())?;
-            Some(file.clone())
-        } else {
-            None
-        };
-        
+
+        // Write back every buffer left modified after the batch. If one
+        // write fails, `flush_modified` leaves all of them marked modified
+        // rather than reporting a partial success.
+        let flush_error = session.flush_modified().err().map(|e| e.to_string());
+
+        let mut buffers = json!({});
+        for name in session.buffer_names().cloned().collect::<Vec<_>>() {
+            let buffer = session.buffer(&name).expect("name came from buffer_names");
+            buffers[name.as_str()] = json!({
+                "final_content": buffer.to_string(),
+                "line_count": buffer.line_count(),
+                "current_line": buffer.current_line(),
+            });
+        }
+
         Ok(json!({
-            "success": true,
-            "file": file_ref,
+            "success": flush_error.is_none(),
+            "flush_error": flush_error,
             "results": results,
-            "final_content": buffer.to_string(),
-            "line_count": buffer.line_count(),
-            "current_line": buffer.current_line()
+            "buffers": buffers,
         }))
     }
 }
@@ -635,7 +1496,7 @@ mod tests {
     #[test]
     fn test_basic_operations() {
         let mut ed = EdBuffer::from_string("line1\nline2\nline3");
-        
+
         // Test print
         match ed.execute_command("2p").unwrap() {
             EdResult::Lines(lines) => assert_eq!(lines, vec!["line2"]),
@@ -657,7 +1518,7 @@ mod tests {
     #[test]
     fn test_range_operations() {
         let mut ed = EdBuffer::from_string("a\nb\nc\nd\ne");
-        
+
         // Test range print
         match ed.execute_command("2,4p").unwrap() {
             EdResult::Lines(lines) => assert_eq!(lines, vec!["b", "c", "d"]),
@@ -670,4 +1531,234 @@ mod tests {
             _ => panic!("Expected Lines result"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_append_collects_text_block() {
+        let mut ed = EdBuffer::from_string("one\ntwo");
+        ed.execute_command_with_text("1a", vec!["inserted".to_string()]).unwrap();
+        match ed.execute_command("%p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["one", "inserted", "two"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_substitute_with_backreference() {
+        let mut ed = EdBuffer::from_string("hello world");
+        ed.execute_command(r"1s/(\w+) (\w+)/\2 \1/").unwrap();
+        match ed.execute_command("1p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["world hello"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_substitute_global_flag_replaces_every_match() {
+        let mut ed = EdBuffer::from_string("a-a-a");
+        ed.execute_command("1s/a/b/g").unwrap();
+        match ed.execute_command("1p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["b-b-b"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_regex_address_searches_forward() {
+        let mut ed = EdBuffer::from_string("one\ntwo\nthree");
+        match ed.execute_command("/three/p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["three"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_regex_address_no_match_is_an_error() {
+        let mut ed = EdBuffer::from_string("one\ntwo");
+        assert!(matches!(ed.execute_command("/nope/p"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_global_deletes_matching_lines() {
+        let mut ed = EdBuffer::from_string("keep\ndrop\nkeep\ndrop");
+        ed.execute_command("g/drop/d").unwrap();
+        match ed.execute_command("%p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["keep", "keep"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_global_inverted_defaults_to_print() {
+        let mut ed = EdBuffer::from_string("a\nb\na");
+        match ed.execute_command("v/a/").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["b"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_global_list_collects_without_executing() {
+        let mut ed = EdBuffer::from_string("x\ny\nx");
+        match ed.execute_command("G/x/").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["x", "x"]),
+            _ => panic!("Expected Lines result"),
+        }
+        // G must not have modified the buffer.
+        assert_eq!(ed.line_count(), 3);
+    }
+
+    #[test]
+    fn test_unknown_command_is_a_structured_parse_error() {
+        let mut ed = EdBuffer::from_string("one");
+        match ed.execute_command("z") {
+            Err(EdError::Parse { token, .. }) => assert_eq!(token, "z"),
+            other => panic!("Expected Parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_deleted_line() {
+        let mut ed = EdBuffer::from_string("one\ntwo\nthree");
+        ed.execute_command("2d").unwrap();
+        assert_eq!(ed.to_string(), "one\nthree");
+
+        ed.execute_command("u").unwrap();
+        assert_eq!(ed.to_string(), "one\ntwo\nthree");
+    }
+
+    #[test]
+    fn test_redo_reapplies_undone_command() {
+        let mut ed = EdBuffer::from_string("one\ntwo\nthree");
+        ed.execute_command("2d").unwrap();
+        ed.execute_command("u").unwrap();
+
+        ed.execute_command("R").unwrap();
+        assert_eq!(ed.to_string(), "one\nthree");
+    }
+
+    #[test]
+    fn test_undo_with_empty_history_is_an_error() {
+        let mut ed = EdBuffer::from_string("one");
+        assert!(matches!(ed.execute_command("u"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo_history() {
+        let mut ed = EdBuffer::from_string("one\ntwo");
+        ed.execute_command("2d").unwrap();
+        ed.execute_command("u").unwrap();
+
+        // A fresh mutating command clears the redo stack built up by `u`.
+        ed.execute_command_with_text("1a", vec!["inserted".to_string()]).unwrap();
+        assert!(matches!(ed.execute_command("R"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_failed_command_leaves_undo_history_untouched() {
+        let mut ed = EdBuffer::from_string("one");
+        ed.execute_command("5d").unwrap_err();
+        assert!(matches!(ed.execute_command("u"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_undo_depth_is_bounded() {
+        let mut ed = EdBuffer::from_string("start").with_undo_limit(2);
+        ed.execute_command_with_text("a", vec!["one".to_string()]).unwrap();
+        ed.execute_command_with_text("a", vec!["two".to_string()]).unwrap();
+        ed.execute_command_with_text("a", vec!["three".to_string()]).unwrap();
+
+        // Only the last 2 snapshots survive, so undo can run twice but not
+        // a third time.
+        ed.execute_command("u").unwrap();
+        ed.execute_command("u").unwrap();
+        assert!(matches!(ed.execute_command("u"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_transfer_copies_without_removing_source() {
+        let mut ed = EdBuffer::from_string("one\ntwo\nthree");
+        ed.execute_command("1t3").unwrap();
+        assert_eq!(ed.to_string(), "one\ntwo\nthree\none");
+    }
+
+    #[test]
+    fn test_transfer_is_undoable() {
+        let mut ed = EdBuffer::from_string("one\ntwo");
+        ed.execute_command("1t2").unwrap();
+        ed.execute_command("u").unwrap();
+        assert_eq!(ed.to_string(), "one\ntwo");
+    }
+
+    #[test]
+    fn test_mark_sets_addressable_mark() {
+        let mut ed = EdBuffer::from_string("one\ntwo\nthree");
+        ed.execute_command("2k a").unwrap();
+        match ed.execute_command("'ap").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["two"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_read_requires_a_session() {
+        let mut ed = EdBuffer::from_string("one");
+        assert!(matches!(ed.execute_command("r other.txt"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_session_read_splices_file_contents_after_the_addressed_line() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-ed-read-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("snippet.txt"), "middle1\nmiddle2").unwrap();
+
+        let mut session = Session::new(dir.clone());
+        session.load("main.txt", EdBuffer::from_string("first\nlast"));
+
+        match session.execute_command("1r snippet.txt").unwrap() {
+            EdResult::Read(bytes) => assert_eq!(bytes, "middle1\nmiddle2".len()),
+            other => panic!("Expected Read result, got {other:?}"),
+        }
+        assert_eq!(
+            session.buffer("main.txt").unwrap().to_string(),
+            "first\nmiddle1\nmiddle2\nlast"
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_session_edit_switches_active_buffer() {
+        let mut session = Session::new(PathBuf::from("."));
+        session.load("a.txt", EdBuffer::from_string("from a"));
+        session.load("b.txt", EdBuffer::from_string("from b"));
+
+        session.execute_command("e b.txt").unwrap();
+        match session.execute_command("%p").unwrap() {
+            EdResult::Lines(lines) => assert_eq!(lines, vec!["from b"]),
+            _ => panic!("Expected Lines result"),
+        }
+    }
+
+    #[test]
+    fn test_session_edit_unknown_buffer_is_an_error() {
+        let mut session = Session::new(PathBuf::from("."));
+        session.load("a.txt", EdBuffer::from_string("from a"));
+        assert!(matches!(session.execute_command("e missing.txt"), Err(EdError::Exec(_))));
+    }
+
+    #[test]
+    fn test_session_run_batch_tags_reports_with_the_active_file() {
+        let mut session = Session::new(PathBuf::from("."));
+        session.load("a.txt", EdBuffer::from_string("one"));
+        session.load("b.txt", EdBuffer::from_string("two"));
+
+        let commands = vec!["e b.txt".to_string(), "1p".to_string()];
+        let reports = session.run_batch(&commands);
+
+        assert_eq!(reports[1].file, "b.txt");
+        match &reports[1].outcome {
+            Ok(EdResult::Lines(lines)) => assert_eq!(lines, &vec!["two".to_string()]),
+            other => panic!("Expected Lines result, got {other:?}"),
+        }
+    }
+}
@@ -1,21 +1,33 @@
 //! File operation tools with security sandboxing
 
+use super::cache::FileCache;
+use super::glob::Pattern as GlobPattern;
+use super::permissions::{PathSet, Permissions};
 use super::{Tool, security};
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Tool for reading files
 pub struct ReadFileTool {
     workspace: PathBuf,
+    cache: Option<Arc<FileCache>>,
 }
 
 impl ReadFileTool {
     pub fn new(workspace: PathBuf) -> Self {
-        Self { workspace }
+        Self { workspace, cache: None }
+    }
+
+    /// Serve reads through `cache`, shared with the other file tools so a
+    /// file read once isn't re-read from disk until it changes.
+    pub fn with_cache(workspace: PathBuf, cache: Arc<FileCache>) -> Self {
+        Self { workspace, cache: Some(cache) }
     }
 }
 
@@ -36,6 +48,11 @@ impl Tool for ReadFileTool {
                 "path": {
                     "type": "string",
                     "description": "Path to the file to read (relative to workspace)"
+                },
+                "with_git_info": {
+                    "type": "boolean",
+                    "description": "Also return the file's HEAD version and a working-tree diff, if the workspace is a git repo",
+                    "default": false
                 }
             },
             "required": ["path"]
@@ -46,27 +63,141 @@ impl Tool for ReadFileTool {
         #[derive(Deserialize)]
         struct Params {
             path: String,
+            #[serde(default)]
+            with_git_info: bool,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
         let path = Path::new(&params.path);
-        
+
         // Security validation
         if !security::is_path_safe(path) {
             bail!("Access denied: unsafe path");
         }
-        
+
         let full_path = self.workspace.join(path);
         let validated_path = security::validate_path(&full_path, &self.workspace)?;
-        
-        // Read file
-        let content = fs::read_to_string(&validated_path)?;
-        
-        Ok(json!({
+
+        let content = if let Some(cache) = &self.cache {
+            if let Some(cached) = cache.get(&validated_path) {
+                cached
+            } else {
+                let content = fs::read_to_string(&validated_path)?;
+                cache.insert(validated_path.clone(), content.clone());
+                content
+            }
+        } else {
+            fs::read_to_string(&validated_path)?
+        };
+
+        let mut result = json!({
             "success": true,
             "path": params.path,
             "content": content,
             "size": content.len(),
+        });
+
+        if params.with_git_info {
+            if let Some(git_info) = git_file_info(&self.workspace, path) {
+                result["git"] = git_info;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// `git show HEAD:<path>` and `git diff -- <path>` for a workspace-relative
+/// file, for tools that want to show a model what changed on disk versus
+/// the last commit. Returns `None` if the workspace isn't a git repo, the
+/// file isn't tracked, or `git` isn't available.
+fn git_file_info(workspace: &Path, relative_path: &Path) -> Option<Value> {
+    let rel = relative_path.to_string_lossy().to_string();
+
+    let head_version = std::process::Command::new("git")
+        .args(["show", &format!("HEAD:{rel}")])
+        .current_dir(workspace)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string());
+
+    let diff = std::process::Command::new("git")
+        .args(["diff", "--", &rel])
+        .current_dir(workspace)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string());
+
+    if head_version.is_none() && diff.is_none() {
+        return None;
+    }
+
+    Some(json!({
+        "head_version": head_version,
+        "working_tree_diff": diff.unwrap_or_default(),
+    }))
+}
+
+/// Tool for classifying a file's programming language
+pub struct DetectLanguageTool {
+    workspace: PathBuf,
+}
+
+impl DetectLanguageTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for DetectLanguageTool {
+    fn name(&self) -> &str {
+        "detect_language"
+    }
+
+    fn description(&self) -> &str {
+        "Classify a file's programming language by extension, falling back to its shebang line for extensionless scripts"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file to classify (relative to workspace)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            path: String,
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+        let path = Path::new(&params.path);
+
+        if !security::is_path_safe(path) {
+            bail!("Access denied: unsafe path");
+        }
+
+        let full_path = self.workspace.join(path);
+        let validated_path = security::validate_path(&full_path, &self.workspace)?;
+
+        let first_line = fs::read_to_string(&validated_path).ok().and_then(|content| content.lines().next().map(str::to_string));
+        let classification = super::language::classify(path, first_line.as_deref());
+
+        Ok(json!({
+            "success": true,
+            "path": params.path,
+            "language": classification.language,
+            "confidence": classification.confidence,
         }))
     }
 }
@@ -108,12 +239,30 @@ impl Tool for WriteFileTool {
                     "type": "boolean",
                     "description": "Create parent directories if they don't exist",
                     "default": true
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["overwrite", "create_new", "append"],
+                    "description": "overwrite: replace existing contents (default); create_new: fail if the file already exists; append: add to the end of the file",
+                    "default": "overwrite"
                 }
             },
             "required": ["path", "content"]
         })
     }
-    
+
+    fn required_permissions(&self, params: &Value) -> Permissions {
+        let path = params
+            .get("path")
+            .and_then(Value::as_str)
+            .map(|path| self.workspace.join(path))
+            .unwrap_or_else(|| self.workspace.clone());
+        Permissions {
+            write: PathSet::single(path),
+            ..Permissions::default()
+        }
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
@@ -121,39 +270,107 @@ impl Tool for WriteFileTool {
             content: String,
             #[serde(default = "default_true")]
             create_dirs: bool,
+            #[serde(default = "default_mode")]
+            mode: String,
         }
-        
+
         fn default_true() -> bool { true }
-        
+        fn default_mode() -> String { "overwrite".to_string() }
+
         let params: Params = serde_json::from_value(params)?;
         let path = Path::new(&params.path);
-        
+
         // Security validation
         if !security::is_path_safe(path) {
             bail!("Access denied: unsafe path");
         }
-        
+
         let full_path = self.workspace.join(path);
         let validated_path = security::validate_path(&full_path, &self.workspace)?;
-        
+
         // Create parent directories if requested
         if params.create_dirs {
             if let Some(parent) = validated_path.parent() {
                 fs::create_dir_all(parent)?;
             }
         }
-        
-        // Write file
-        fs::write(&validated_path, &params.content)?;
-        
+
+        // Whether the file is already there, independent of whether its
+        // content happens to be readable as UTF-8 — a binary/non-UTF-8
+        // file still exists and must not be silently clobbered.
+        let already_exists = validated_path.exists();
+        let existing = fs::read_to_string(&validated_path).ok();
+
+        if params.mode == "create_new" && already_exists {
+            bail!("{} already exists (mode is create_new)", validated_path.display());
+        }
+
+        if params.mode == "append" && already_exists && existing.is_none() {
+            bail!("{} exists but is not valid UTF-8; refusing to append to it", validated_path.display());
+        }
+
+        // Preserve the file's existing line-ending convention (CRLF vs LF)
+        // rather than silently normalizing it to whatever the caller sent.
+        let line_ending = existing
+            .as_deref()
+            .map(detect_line_ending)
+            .unwrap_or(LineEnding::Lf);
+        let normalized = normalize_line_endings(&params.content, line_ending);
+
+        let final_content = match (params.mode.as_str(), existing) {
+            ("append", Some(existing)) => format!("{existing}{normalized}"),
+            _ => normalized,
+        };
+
+        atomic_write(&validated_path, &final_content)?;
+
         Ok(json!({
             "success": true,
             "path": params.path,
-            "bytes_written": params.content.len(),
+            "bytes_written": final_content.len(),
+            "mode": params.mode,
         }))
     }
 }
 
+/// Line-ending convention detected in (or to use for) a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+fn detect_line_ending(content: &str) -> LineEnding {
+    if content.contains("\r\n") {
+        LineEnding::CrLf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+fn normalize_line_endings(content: &str, ending: LineEnding) -> String {
+    let lf_normalized = content.replace("\r\n", "\n");
+    match ending {
+        LineEnding::Lf => lf_normalized,
+        LineEnding::CrLf => lf_normalized.replace('\n', "\r\n"),
+    }
+}
+
+/// Write `content` to `path` atomically: write to a sibling temp file, then
+/// rename it into place, so readers never observe a partially-written file
+/// and a crash mid-write can't corrupt the original.
+fn atomic_write(path: &Path, content: &str) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let tmp_path = dir.join(format!(
+        ".{}.tmp-{}",
+        path.file_name().and_then(|n| n.to_str()).unwrap_or("gemini-repl"),
+        std::process::id()
+    ));
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
 /// Tool for editing files
 pub struct EditFileTool {
     workspace: PathBuf,
@@ -231,19 +448,38 @@ impl Tool for EditFileTool {
                             }
                         }
                     }
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Compute the result without writing it to disk; returns a preview diff instead",
+                    "default": false
                 }
             },
             "required": ["path", "operations"]
         })
     }
-    
+
+    fn required_permissions(&self, params: &Value) -> Permissions {
+        let path = params
+            .get("path")
+            .and_then(Value::as_str)
+            .map(|path| self.workspace.join(path))
+            .unwrap_or_else(|| self.workspace.clone());
+        Permissions {
+            write: PathSet::single(path),
+            ..Permissions::default()
+        }
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
             path: String,
             operations: Vec<EditOperation>,
+            #[serde(default)]
+            dry_run: bool,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
         let path = Path::new(&params.path);
         
@@ -256,10 +492,13 @@ impl Tool for EditFileTool {
         let validated_path = security::validate_path(&full_path, &self.workspace)?;
         
         // Read file
-        let content = fs::read_to_string(&validated_path)?;
-        let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
-        
-        // Apply operations
+        let original_content = fs::read_to_string(&validated_path)?;
+        let mut lines: Vec<String> = original_content.lines().map(|l| l.to_string()).collect();
+
+        // Apply operations to an in-memory copy. Nothing touches disk until
+        // every operation below has succeeded, and dry_run never writes at
+        // all — so a failing operation or a preview both leave the file on
+        // disk exactly as it was (transactional by construction).
         let mut changes = 0;
         for op in params.operations {
             match op.operation.as_str() {
@@ -305,10 +544,22 @@ impl Tool for EditFileTool {
             }
         }
         
-        // Write back
         let new_content = lines.join("\n");
+
+        if params.dry_run {
+            return Ok(json!({
+                "success": true,
+                "path": params.path,
+                "dry_run": true,
+                "changes_applied": changes,
+                "diff": unified_diff(&original_content, &new_content),
+            }));
+        }
+
+        // Write back. Since `new_content` was built entirely in memory
+        // above, a failure here can't leave the file partially edited.
         fs::write(&validated_path, &new_content)?;
-        
+
         Ok(json!({
             "success": true,
             "path": params.path,
@@ -318,6 +569,29 @@ impl Tool for EditFileTool {
     }
 }
 
+/// A minimal line-level diff between `old` and `new`, good enough for a
+/// human or model to preview a dry-run edit without pulling in a full diff
+/// algorithm crate.
+fn unified_diff(old: &str, new: &str) -> Vec<Value> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let max_len = old_lines.len().max(new_lines.len());
+
+    let mut hunks = Vec::new();
+    for i in 0..max_len {
+        let before = old_lines.get(i).copied();
+        let after = new_lines.get(i).copied();
+        if before != after {
+            hunks.push(json!({
+                "line": i + 1,
+                "before": before,
+                "after": after,
+            }));
+        }
+    }
+    hunks
+}
+
 /// Tool for listing files
 pub struct ListFilesTool {
     workspace: PathBuf,
@@ -356,11 +630,24 @@ impl Tool for ListFilesTool {
                     "type": "boolean",
                     "description": "List recursively",
                     "default": false
+                },
+                "respect_ignore": {
+                    "type": "boolean",
+                    "description": "Skip files and directories matched by the workspace's .gitignore/.ignore files",
+                    "default": true
+                },
+                "include_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); only paths matching at least one are kept"
+                },
+                "exclude_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); paths matching any are dropped"
                 }
             }
         })
     }
-    
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
@@ -369,29 +656,37 @@ impl Tool for ListFilesTool {
             pattern: Option<String>,
             #[serde(default)]
             recursive: bool,
+            #[serde(default = "default_true")]
+            respect_ignore: bool,
+            include_from: Option<String>,
+            exclude_from: Option<String>,
         }
-        
+
         fn default_dot() -> String { ".".to_string() }
-        
+        fn default_true() -> bool { true }
+
         let params: Params = serde_json::from_value(params)?;
         let path = Path::new(&params.path);
-        
+
         // Security validation
         if !security::is_path_safe(path) {
             bail!("Access denied: unsafe path");
         }
-        
+
         let full_path = self.workspace.join(path);
         let validated_path = security::validate_path(&full_path, &self.workspace)?;
-        
+
+        let ignore = params.respect_ignore.then(|| GitignoreMatcher::load(&self.workspace));
+        let filter = PathFilter::load(params.include_from.as_deref(), params.exclude_from.as_deref())?;
+
         let mut files = Vec::new();
-        
+
         if params.recursive {
-            walk_directory(&validated_path, &self.workspace, &mut files, params.pattern.as_deref())?;
+            walk_directory(&validated_path, &self.workspace, &mut files, params.pattern.as_deref(), ignore.as_ref(), &filter)?;
         } else {
-            list_directory(&validated_path, &self.workspace, &mut files, params.pattern.as_deref())?;
+            list_directory(&validated_path, &self.workspace, &mut files, params.pattern.as_deref(), ignore.as_ref(), &filter)?;
         }
-        
+
         Ok(json!({
             "success": true,
             "path": params.path,
@@ -401,20 +696,112 @@ impl Tool for ListFilesTool {
     }
 }
 
-fn list_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern: Option<&str>) -> Result<()> {
+/// Minimal `.gitignore`/`.ignore` matcher: supports the common subset used
+/// by this workspace (plain patterns and directory patterns ending in
+/// `/`), matched against the workspace-relative path. Good enough to keep
+/// build output and VCS internals out of tool results without shelling
+/// out to git.
+///
+/// Patterns are collected from every `.gitignore`/`.ignore` file from the
+/// workspace root up to the filesystem root, mirroring how `rg`/`fd`
+/// honor ignore files above the search root, not just within it.
+pub(crate) struct GitignoreMatcher {
+    patterns: Vec<String>,
+}
+
+impl GitignoreMatcher {
+    pub(crate) fn load(workspace: &Path) -> Self {
+        let mut patterns = vec![".git".to_string()];
+        for dir in workspace.ancestors() {
+            for file in [".gitignore", ".ignore"] {
+                let Ok(content) = fs::read_to_string(dir.join(file)) else {
+                    continue;
+                };
+                for line in content.lines() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    patterns.push(line.trim_end_matches('/').to_string());
+                }
+            }
+        }
+        Self { patterns }
+    }
+
+    /// Whether `relative_path` (workspace-relative, with `/` separators)
+    /// should be excluded from listings.
+    pub(crate) fn is_ignored(&self, relative_path: &str) -> bool {
+        self.patterns.iter().any(|pattern| {
+            relative_path
+                .split('/')
+                .any(|component| glob::Pattern::new(pattern).map(|p| p.matches(component)).unwrap_or(false))
+        })
+    }
+}
+
+/// Include/exclude path filtering compiled from line-delimited pattern
+/// files (`include_from`/`exclude_from`), following the blocklist/
+/// allowlist pattern documented for the search aggregator: a path is kept
+/// only if it matches the include set (when present) and matches none of
+/// the exclude set.
+pub(crate) struct PathFilter {
+    include: Option<regex::RegexSet>,
+    exclude: Option<regex::RegexSet>,
+}
+
+impl PathFilter {
+    pub(crate) fn load(include_from: Option<&str>, exclude_from: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            include: include_from.map(Self::compile).transpose()?,
+            exclude: exclude_from.map(Self::compile).transpose()?,
+        })
+    }
+
+    fn compile(path: &str) -> Result<regex::RegexSet> {
+        let content = fs::read_to_string(path).with_context(|| format!("failed to read '{path}'"))?;
+        let patterns: Vec<&str> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        regex::RegexSetBuilder::new(patterns)
+            .build()
+            .with_context(|| format!("invalid pattern in '{path}'"))
+    }
+
+    /// Whether `relative_path` survives the include/exclude sets.
+    pub(crate) fn keep(&self, relative_path: &str) -> bool {
+        let included = self.include.as_ref().is_none_or(|set| set.is_match(relative_path));
+        let excluded = self.exclude.as_ref().is_some_and(|set| set.is_match(relative_path));
+        included && !excluded
+    }
+}
+
+fn list_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern: Option<&str>, ignore: Option<&GitignoreMatcher>, filter: &PathFilter) -> Result<()> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        
+
         let relative = path.strip_prefix(workspace).unwrap_or(&path);
         let name = relative.to_string_lossy().to_string();
-        
+
+        if let Some(ignore) = ignore {
+            if ignore.is_ignored(&name) {
+                continue;
+            }
+        }
+
+        if !filter.keep(&name) {
+            continue;
+        }
+
         if let Some(pattern) = pattern {
-            if !glob::Pattern::new(pattern)?.matches(&name) {
+            if !GlobPattern::new(pattern)?.matches(&name) {
                 continue;
             }
         }
-        
+
         let metadata = entry.metadata()?;
         files.push(json!({
             "name": name,
@@ -425,11 +812,11 @@ fn list_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern:
     Ok(())
 }
 
-fn walk_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern: Option<&str>) -> Result<()> {
+fn walk_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern: Option<&str>, ignore: Option<&GitignoreMatcher>, filter: &PathFilter) -> Result<()> {
     for entry in walkdir::WalkDir::new(dir) {
         let entry = entry?;
         let path = entry.path();
-        
+
         // Skip hidden directories
         if path.file_name()
             .and_then(|n| n.to_str())
@@ -437,16 +824,26 @@ fn walk_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern:
             .unwrap_or(false) {
             continue;
         }
-        
+
         let relative = path.strip_prefix(workspace).unwrap_or(path);
         let name = relative.to_string_lossy().to_string();
-        
+
+        if let Some(ignore) = ignore {
+            if ignore.is_ignored(&name) {
+                continue;
+            }
+        }
+
+        if !filter.keep(&name) {
+            continue;
+        }
+
         if let Some(pattern) = pattern {
-            if !glob::Pattern::new(pattern)?.matches(&name) {
+            if !GlobPattern::new(pattern)?.matches(&name) {
                 continue;
             }
         }
-        
+
         let metadata = entry.metadata()?;
         files.push(json!({
             "name": name,
@@ -455,4 +852,333 @@ fn walk_directory(dir: &Path, workspace: &Path, files: &mut Vec<Value>, pattern:
         }));
     }
     Ok(())
+}
+
+/// How `search_code` decides case-sensitivity, mirroring `fd`/`rg`'s
+/// `--case` flag.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum CaseMode {
+    /// Case-insensitive unless `pattern` contains an uppercase letter.
+    #[default]
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseMode {
+    /// Whether matching should ignore case for this `pattern`.
+    fn is_insensitive(self, pattern: &str) -> bool {
+        match self {
+            CaseMode::Smart => !pattern.chars().any(char::is_uppercase),
+            CaseMode::Sensitive => false,
+            CaseMode::Insensitive => true,
+        }
+    }
+}
+
+/// Tool for searching file contents (a sandboxed grep)
+pub struct SearchCodeTool {
+    workspace: PathBuf,
+}
+
+impl SearchCodeTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for SearchCodeTool {
+    fn name(&self) -> &str {
+        "search_code"
+    }
+
+    fn description(&self) -> &str {
+        "Search for a text or regex pattern across files in the workspace"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regex pattern to search for"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory to search within (relative to workspace)",
+                    "default": "."
+                },
+                "file_pattern": {
+                    "type": "string",
+                    "description": "Optional glob pattern to limit which files are searched (e.g. '*.rs')"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Optional language name (e.g. 'Python') to limit which files are searched, instead of a file_pattern glob"
+                },
+                "case_mode": {
+                    "type": "string",
+                    "enum": ["smart", "sensitive", "insensitive"],
+                    "description": "'smart' (default) matches case-insensitively unless pattern contains an uppercase letter, like fd/rg's smart case",
+                    "default": "smart"
+                },
+                "literal": {
+                    "type": "boolean",
+                    "description": "Treat pattern as literal text rather than a regex, escaping any special characters",
+                    "default": false
+                },
+                "respect_ignore": {
+                    "type": "boolean",
+                    "description": "Skip files matched by the workspace's .gitignore/.ignore files",
+                    "default": true
+                },
+                "include_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); only files matching at least one are searched"
+                },
+                "exclude_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); files matching any are skipped"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matching lines to return",
+                    "default": 200
+                }
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            pattern: String,
+            #[serde(default = "default_dot")]
+            path: String,
+            file_pattern: Option<String>,
+            language: Option<String>,
+            #[serde(default)]
+            case_mode: CaseMode,
+            #[serde(default)]
+            literal: bool,
+            #[serde(default = "default_true")]
+            respect_ignore: bool,
+            include_from: Option<String>,
+            exclude_from: Option<String>,
+            #[serde(default = "default_max_results")]
+            max_results: usize,
+        }
+
+        fn default_dot() -> String { ".".to_string() }
+        fn default_true() -> bool { true }
+        fn default_max_results() -> usize { 200 }
+
+        let params: Params = serde_json::from_value(params)?;
+        let path = Path::new(&params.path);
+
+        if !security::is_path_safe(path) {
+            bail!("Access denied: unsafe path");
+        }
+
+        let full_path = self.workspace.join(path);
+        let validated_path = security::validate_path(&full_path, &self.workspace)?;
+        let pattern = if params.literal { regex::escape(&params.pattern) } else { params.pattern.clone() };
+        let regex = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(params.case_mode.is_insensitive(&params.pattern))
+            .build()?;
+        let ignore = params.respect_ignore.then(|| GitignoreMatcher::load(&self.workspace));
+        let filter = PathFilter::load(params.include_from.as_deref(), params.exclude_from.as_deref())?;
+
+        let mut matches = Vec::new();
+        'walk: for entry in walkdir::WalkDir::new(&validated_path).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry
+                .path()
+                .strip_prefix(&self.workspace)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .to_string();
+
+            if let Some(ignore) = &ignore {
+                if ignore.is_ignored(&relative) {
+                    continue;
+                }
+            }
+
+            if !filter.keep(&relative) {
+                continue;
+            }
+
+            if let Some(file_pattern) = &params.file_pattern {
+                let name = entry.file_name().to_string_lossy();
+                if !GlobPattern::new(file_pattern)?.matches(&name) {
+                    continue;
+                }
+            }
+
+            let Ok(content) = fs::read_to_string(entry.path()) else {
+                continue; // skip binary/unreadable files
+            };
+
+            if let Some(language) = &params.language {
+                let classification = super::language::classify(entry.path(), content.lines().next());
+                if !classification.language.eq_ignore_ascii_case(language) {
+                    continue;
+                }
+            }
+
+            for (line_number, line) in content.lines().enumerate() {
+                if let Some(m) = regex.find(line) {
+                    matches.push(json!({
+                        "path": relative,
+                        "line": line_number + 1,
+                        "column": line[..m.start()].chars().count() + 1,
+                        "text": line,
+                    }));
+                    if matches.len() >= params.max_results {
+                        break 'walk;
+                    }
+                }
+            }
+        }
+
+        Ok(json!({
+            "success": true,
+            "pattern": params.pattern,
+            "matches": matches,
+            "count": matches.len(),
+        }))
+    }
+}
+
+/// Tool for reporting on-disk space usage, like `du`.
+pub struct DiskUsageTool {
+    workspace: PathBuf,
+}
+
+impl DiskUsageTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for DiskUsageTool {
+    fn name(&self) -> &str {
+        "disk_usage"
+    }
+
+    fn description(&self) -> &str {
+        "Report on-disk size of files and directories under a path, like `du`"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to measure (relative to workspace)",
+                    "default": "."
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "How many directory levels below path to report individually; deeper entries are folded into their ancestor's total",
+                    "default": 1
+                },
+                "min_size_bytes": {
+                    "type": "integer",
+                    "description": "Omit entries smaller than this many bytes",
+                    "default": 0
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default = "default_dot")]
+            path: String,
+            #[serde(default = "default_depth")]
+            max_depth: usize,
+            #[serde(default)]
+            min_size_bytes: u64,
+        }
+
+        fn default_dot() -> String { ".".to_string() }
+        fn default_depth() -> usize { 1 }
+
+        let params: Params = serde_json::from_value(params)?;
+        let path = Path::new(&params.path);
+
+        if !security::is_path_safe(path) {
+            bail!("Access denied: unsafe path");
+        }
+
+        let full_path = self.workspace.join(path);
+        let validated_path = security::validate_path(&full_path, &self.workspace)?;
+
+        // Real on-disk size (blocks * 512), not the logical file length,
+        // so sparse files and filesystem overhead are reflected accurately.
+        let mut totals: HashMap<PathBuf, u64> = HashMap::new();
+        let mut total_size = 0u64;
+
+        for entry in walkdir::WalkDir::new(&validated_path).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let size = on_disk_size(entry.path());
+            total_size += size;
+
+            let relative = entry.path().strip_prefix(&validated_path).unwrap_or(entry.path());
+            let bucket = bucket_at_depth(relative, params.max_depth);
+            *totals.entry(bucket).or_insert(0) += size;
+        }
+
+        let mut entries: Vec<Value> = totals
+            .into_iter()
+            .filter(|(_, size)| *size >= params.min_size_bytes)
+            .map(|(rel_path, size)| {
+                json!({
+                    "path": rel_path.to_string_lossy(),
+                    "size_bytes": size,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| b["size_bytes"].as_u64().cmp(&a["size_bytes"].as_u64()));
+
+        Ok(json!({
+            "success": true,
+            "path": params.path,
+            "total_size_bytes": total_size,
+            "entries": entries,
+        }))
+    }
+}
+
+#[cfg(unix)]
+fn on_disk_size(path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).map(|m| m.blocks() * 512).unwrap_or(0)
+}
+
+#[cfg(not(unix))]
+fn on_disk_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Folds `relative` down to its ancestor at `max_depth` path components, so
+/// entries deeper than that are grouped under a single directory total.
+fn bucket_at_depth(relative: &Path, max_depth: usize) -> PathBuf {
+    if max_depth == 0 {
+        return PathBuf::from(".");
+    }
+    relative.components().take(max_depth).collect()
 }
\ No newline at end of file
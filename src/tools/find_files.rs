@@ -0,0 +1,343 @@
+//! `find_files`: fd-like file discovery with composable size/time/type
+//! filters, as opposed to [`super::file_tools::ListFilesTool`]'s plain
+//! glob filter.
+
+use super::file_tools::{GitignoreMatcher, PathFilter};
+use super::glob::Pattern as GlobPattern;
+use super::{security, Tool};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A size bound parsed from an fd-style expression: `+10k` (at least),
+/// `-1M` (at most), or `500b` (exactly).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SizeFilter {
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+impl SizeFilter {
+    fn parse(expr: &str) -> Result<Self> {
+        let (sign, rest) = match expr.as_bytes().first() {
+            Some(b'+') => (Some('+'), &expr[1..]),
+            Some(b'-') => (Some('-'), &expr[1..]),
+            _ => (None, expr),
+        };
+
+        let bytes = parse_byte_count(rest)
+            .with_context(|| format!("invalid size expression '{expr}'"))?;
+
+        Ok(match sign {
+            Some('+') => SizeFilter { min: Some(bytes), max: None },
+            Some('-') => SizeFilter { min: None, max: Some(bytes) },
+            _ => SizeFilter { min: Some(bytes), max: Some(bytes) },
+        })
+    }
+
+    fn matches(&self, size: u64) -> bool {
+        self.min.is_none_or(|min| size >= min) && self.max.is_none_or(|max| size <= max)
+    }
+}
+
+/// Parse a byte count like `500b`, `10k`, `1M`, `2G` (binary multiples, as
+/// fd does for its `k`/`M`/`G`/`T` suffixes).
+fn parse_byte_count(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let lower = s.to_lowercase();
+    let (digits, multiplier) = match lower.chars().last() {
+        Some('b') => (&s[..s.len() - 1], 1),
+        Some('k') => (&s[..s.len() - 1], 1024),
+        Some('m') => (&s[..s.len() - 1], 1024 * 1024),
+        Some('g') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        Some('t') => (&s[..s.len() - 1], 1024u64.pow(4)),
+        _ => (s, 1),
+    };
+    let value: u64 = digits.trim().parse().with_context(|| format!("not a number: '{digits}'"))?;
+    Ok(value * multiplier)
+}
+
+/// Parse an fd-style duration (`2d`, `30min`, `1h`, `45s`, `2w`) into a
+/// [`Duration`].
+fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split_at = s.find(|c: char| c.is_alphabetic()).unwrap_or(s.len());
+    let (digits, unit) = s.split_at(split_at);
+    let value: u64 = digits.parse().with_context(|| format!("not a number: '{digits}'"))?;
+
+    let seconds = match unit {
+        "s" | "sec" | "secs" => value,
+        "min" | "mins" | "m" => value * 60,
+        "h" | "hr" | "hrs" => value * 3600,
+        "d" | "day" | "days" => value * 86400,
+        "w" | "week" | "weeks" => value * 86400 * 7,
+        other => bail!("unknown duration unit '{other}' in '{s}'"),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// A `modified_within`/`modified_before` bound: either relative to now (a
+/// duration) or an absolute RFC 3339 timestamp.
+fn parse_time_bound(s: &str) -> Result<SystemTime> {
+    if let Ok(duration) = parse_duration(s) {
+        return Ok(SystemTime::now() - duration);
+    }
+    let parsed = chrono::DateTime::parse_from_rfc3339(s)
+        .with_context(|| format!("'{s}' is neither a duration (e.g. '2d') nor an RFC 3339 timestamp"))?;
+    Ok(UNIX_EPOCH + Duration::from_secs(parsed.timestamp().max(0) as u64))
+}
+
+/// fd's `--type` filter.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum FileTypeFilter {
+    File,
+    Directory,
+    Symlink,
+    Executable,
+    Empty,
+}
+
+impl FileTypeFilter {
+    fn matches(self, metadata: &std::fs::Metadata) -> bool {
+        match self {
+            FileTypeFilter::File => metadata.is_file(),
+            FileTypeFilter::Directory => metadata.is_dir(),
+            FileTypeFilter::Symlink => metadata.file_type().is_symlink(),
+            FileTypeFilter::Executable => is_executable(metadata),
+            FileTypeFilter::Empty => metadata.len() == 0,
+        }
+    }
+}
+
+/// Whether `path` classifies as `language`, trying the extension first and
+/// falling back to the file's shebang line (read from disk) only when the
+/// extension alone doesn't settle it.
+fn matches_language(path: &Path, metadata: &std::fs::Metadata, language: &str) -> bool {
+    let by_extension = super::language::classify(path, None);
+    if by_extension.language.eq_ignore_ascii_case(language) {
+        return true;
+    }
+    if !metadata.is_file() {
+        return false;
+    }
+    let first_line = std::fs::read_to_string(path).ok().and_then(|content| content.lines().next().map(str::to_string));
+    super::language::classify(path, first_line.as_deref()).language.eq_ignore_ascii_case(language)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    metadata.is_file()
+}
+
+/// Walk the workspace applying a `pattern`/`size`/`modified_within`/
+/// `modified_before`/`file_type` filter set as an AND, mirroring `fd`.
+pub struct FindFilesTool {
+    workspace: PathBuf,
+}
+
+impl FindFilesTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for FindFilesTool {
+    fn name(&self) -> &str {
+        "find_files"
+    }
+
+    fn description(&self) -> &str {
+        "Find files under the workspace matching composable filters: a glob pattern, a size range, a modification-time range, and/or a file type (file/directory/symlink/executable/empty), like `fd`"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to search under (relative to workspace)",
+                    "default": "."
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Optional glob pattern the file's workspace-relative path must match (e.g. 'src/**/*.rs')"
+                },
+                "size": {
+                    "type": "string",
+                    "description": "Size filter: '+10k' (at least), '-1M' (at most), or '500b' (exactly). Units: b, k, M, G, T."
+                },
+                "modified_within": {
+                    "type": "string",
+                    "description": "Only files modified within this long ago: a duration ('2d', '30min') or an RFC 3339 timestamp"
+                },
+                "modified_before": {
+                    "type": "string",
+                    "description": "Only files modified longer ago than this: a duration ('2d', '30min') or an RFC 3339 timestamp"
+                },
+                "file_type": {
+                    "type": "string",
+                    "enum": ["file", "directory", "symlink", "executable", "empty"],
+                    "description": "Only entries of this type"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Optional language name (e.g. 'Python') to limit results to, instead of a pattern glob"
+                },
+                "respect_ignore": {
+                    "type": "boolean",
+                    "description": "Skip entries matched by the workspace's .gitignore/.ignore files",
+                    "default": true
+                },
+                "include_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); only paths matching at least one are kept"
+                },
+                "exclude_from": {
+                    "type": "string",
+                    "description": "Path to a file of regex patterns (one per line); paths matching any are dropped"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default = "default_dot")]
+            path: String,
+            pattern: Option<String>,
+            size: Option<String>,
+            modified_within: Option<String>,
+            modified_before: Option<String>,
+            file_type: Option<FileTypeFilter>,
+            language: Option<String>,
+            #[serde(default = "default_true")]
+            respect_ignore: bool,
+            include_from: Option<String>,
+            exclude_from: Option<String>,
+        }
+
+        fn default_dot() -> String {
+            ".".to_string()
+        }
+
+        fn default_true() -> bool {
+            true
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+        let path = Path::new(&params.path);
+
+        if !security::is_path_safe(path) {
+            bail!("Access denied: unsafe path");
+        }
+
+        let full_path = self.workspace.join(path);
+        let validated_path = security::validate_path(&full_path, &self.workspace)?;
+
+        let size_filter = params.size.as_deref().map(SizeFilter::parse).transpose()?;
+        let modified_after = params.modified_within.as_deref().map(parse_time_bound).transpose()?;
+        let modified_before = params.modified_before.as_deref().map(parse_time_bound).transpose()?;
+        let glob_pattern = params.pattern.as_deref().map(GlobPattern::new).transpose()?;
+        let ignore = params.respect_ignore.then(|| GitignoreMatcher::load(&self.workspace));
+        let filter = PathFilter::load(params.include_from.as_deref(), params.exclude_from.as_deref())?;
+
+        let mut results = Vec::new();
+        for entry in walkdir::WalkDir::new(&validated_path).into_iter().filter_map(|e| e.ok()) {
+            let relative = entry
+                .path()
+                .strip_prefix(&self.workspace)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .to_string();
+
+            if let Some(ignore) = &ignore {
+                if ignore.is_ignored(&relative) {
+                    continue;
+                }
+            }
+
+            if !filter.keep(&relative) {
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else { continue };
+
+            if let Some(pattern) = &glob_pattern {
+                if !pattern.matches(&relative) {
+                    continue;
+                }
+            }
+            if let Some(filter) = size_filter {
+                if !filter.matches(metadata.len()) {
+                    continue;
+                }
+            }
+            if let Ok(modified) = metadata.modified() {
+                if let Some(after) = modified_after {
+                    if modified < after {
+                        continue;
+                    }
+                }
+                if let Some(before) = modified_before {
+                    if modified > before {
+                        continue;
+                    }
+                }
+            }
+            if let Some(file_type) = params.file_type {
+                if !file_type.matches(&metadata) {
+                    continue;
+                }
+            }
+            if let Some(language) = &params.language {
+                if !matches_language(entry.path(), &metadata, language) {
+                    continue;
+                }
+            }
+
+            results.push(json!({
+                "path": relative,
+                "type": if metadata.is_dir() { "directory" } else if metadata.file_type().is_symlink() { "symlink" } else { "file" },
+                "size": metadata.len(),
+            }));
+        }
+
+        Ok(json!({
+            "success": true,
+            "path": params.path,
+            "files": results,
+            "count": results.len(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_size_filters() {
+        assert_eq!(SizeFilter::parse("+10k").unwrap(), SizeFilter { min: Some(10 * 1024), max: None });
+        assert_eq!(SizeFilter::parse("-1M").unwrap(), SizeFilter { min: None, max: Some(1024 * 1024) });
+        assert_eq!(SizeFilter::parse("500b").unwrap(), SizeFilter { min: Some(500), max: Some(500) });
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(2 * 86400));
+        assert_eq!(parse_duration("30min").unwrap(), Duration::from_secs(30 * 60));
+    }
+}
@@ -0,0 +1,145 @@
+//! Self-contained glob-to-regex compiler for the `pattern`/`file_pattern`
+//! parameters shared by [`super::file_tools::ListFilesTool`],
+//! [`super::file_tools::SearchCodeTool`], and [`super::find_files::FindFilesTool`].
+//!
+//! Patterns are compiled following Mercurial's `_globre` algorithm: scan
+//! left-to-right, escaping ordinary bytes for the regex engine and
+//! translating `**/`, `**`, `*`, `?`, and `[...]` into their anchored
+//! regex equivalents. This gives `*` well-defined semantics (never
+//! crosses a `/`) and `**`/`**/ ` the usual "any number of path
+//! components" meaning, instead of leaning on a general-purpose glob
+//! crate's own (differently-opinionated) rules.
+
+use anyhow::Result;
+use regex::Regex;
+
+/// Regex metacharacters that need a leading backslash to be matched
+/// literally, plus the backslash itself.
+const SPECIAL: &str = "()[]{}?*+-|^$.\\&~#";
+
+fn escape_char(c: char) -> String {
+    if SPECIAL.contains(c) || (c as u32) < 0x20 {
+        format!("\\{c}")
+    } else {
+        c.to_string()
+    }
+}
+
+/// Translate one glob pattern into the body of an anchored regex (the
+/// caller wraps it in `^...$`).
+fn compile(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') && chars.get(i + 2) == Some(&'/') => {
+                out.push_str("(?:.*/)?");
+                i += 3;
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                out.push_str(".*");
+                i += 2;
+            }
+            '*' => {
+                out.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                out.push('.');
+                i += 1;
+            }
+            '[' => {
+                let mut j = i + 1;
+                if matches!(chars.get(j), Some('!') | Some(']')) {
+                    j += 1;
+                }
+                while j < chars.len() && chars[j] != ']' {
+                    j += 1;
+                }
+                if j >= chars.len() {
+                    // No closing bracket: treat '[' as a literal.
+                    out.push_str("\\[");
+                    i += 1;
+                } else {
+                    let class: String = chars[i + 1..j].iter().collect();
+                    let class = class.strip_prefix('!').map(|rest| format!("^{rest}")).unwrap_or(class);
+                    out.push('[');
+                    out.push_str(&class);
+                    out.push(']');
+                    i = j + 1;
+                }
+            }
+            c => {
+                out.push_str(&escape_char(c));
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// A compiled glob pattern, matched against forward-slash-normalized,
+/// workspace-relative paths.
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let body = compile(pattern);
+        let regex = Regex::new(&format!("^{body}$"))?;
+        Ok(Self { regex })
+    }
+
+    /// Whether `path` matches this pattern. `path` is normalized to
+    /// forward slashes first, so callers can pass platform paths as-is.
+    pub fn matches(&self, path: &str) -> bool {
+        let normalized = path.replace('\\', "/");
+        self.regex.is_match(&normalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_does_not_cross_directories() {
+        let pattern = Pattern::new("src/*.rs").unwrap();
+        assert!(pattern.matches("src/main.rs"));
+        assert!(!pattern.matches("src/tools/mod.rs"));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        let pattern = Pattern::new("src/**/*.rs").unwrap();
+        assert!(pattern.matches("src/main.rs"));
+        assert!(pattern.matches("src/tools/mod.rs"));
+        assert!(pattern.matches("src/tools/deep/nested.rs"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_char() {
+        let pattern = Pattern::new("file?.txt").unwrap();
+        assert!(pattern.matches("file1.txt"));
+        assert!(!pattern.matches("file12.txt"));
+    }
+
+    #[test]
+    fn character_class_and_negation() {
+        assert!(Pattern::new("[abc].txt").unwrap().matches("a.txt"));
+        assert!(!Pattern::new("[abc].txt").unwrap().matches("d.txt"));
+        assert!(Pattern::new("[!abc].txt").unwrap().matches("d.txt"));
+        assert!(!Pattern::new("[!abc].txt").unwrap().matches("a.txt"));
+    }
+
+    #[test]
+    fn special_regex_chars_are_escaped() {
+        let pattern = Pattern::new("file(1).txt").unwrap();
+        assert!(pattern.matches("file(1).txt"));
+        assert!(!pattern.matches("fileX1X.txt"));
+    }
+}
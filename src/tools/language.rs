@@ -0,0 +1,133 @@
+//! Programming-language classification shared by `detect_language` and the
+//! `language` filter on `search_code`/`find_files`. Extension is checked
+//! first since it's cheap and almost always right; a shebang line is the
+//! fallback for extensionless scripts.
+
+use std::path::Path;
+
+/// `(extension, canonical language name)`. The single source of truth for
+/// both classification and language-scoped filtering, so the two can
+/// never drift apart.
+const EXTENSION_TABLE: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("pyw", "Python"),
+    ("js", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("go", "Go"),
+    ("rb", "Ruby"),
+    ("java", "Java"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("cxx", "C++"),
+    ("hpp", "C++"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("md", "Markdown"),
+    ("json", "JSON"),
+    ("toml", "TOML"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("html", "HTML"),
+    ("css", "CSS"),
+];
+
+/// `(shebang interpreter, canonical language name)`, checked against the
+/// first path component after `#!` (e.g. `#!/usr/bin/env python3` → `python3`).
+const SHEBANG_TABLE: &[(&str, &str)] = &[
+    ("python", "Python"),
+    ("python3", "Python"),
+    ("python2", "Python"),
+    ("node", "JavaScript"),
+    ("ruby", "Ruby"),
+    ("bash", "Shell"),
+    ("sh", "Shell"),
+    ("perl", "Perl"),
+];
+
+/// The result of classifying a file: its canonical language name (or
+/// `"unknown"`) and how confident the classification is, in `[0.0, 1.0]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    pub language: String,
+    pub confidence: f64,
+}
+
+/// Classify a file by its extension first, falling back to `first_line`
+/// (the file's first line of content, for shebang detection) when the
+/// extension is missing or unrecognized.
+pub fn classify(path: &Path, first_line: Option<&str>) -> Classification {
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        let ext = ext.to_lowercase();
+        if let Some((_, language)) = EXTENSION_TABLE.iter().find(|(e, _)| *e == ext) {
+            return Classification { language: language.to_string(), confidence: 0.9 };
+        }
+    }
+
+    if let Some(line) = first_line {
+        if let Some(interpreter) = shebang_interpreter(line) {
+            if let Some((_, language)) = SHEBANG_TABLE.iter().find(|(i, _)| *i == interpreter) {
+                return Classification { language: language.to_string(), confidence: 0.6 };
+            }
+        }
+    }
+
+    Classification { language: "unknown".to_string(), confidence: 0.0 }
+}
+
+/// Extract the interpreter name from a shebang line, e.g.
+/// `#!/usr/bin/env python3` or `#!/bin/bash` both yield their last path
+/// component (`python3`, `bash`).
+fn shebang_interpreter(first_line: &str) -> Option<&str> {
+    let rest = first_line.strip_prefix("#!")?.trim();
+    let program = rest.split_whitespace().last()?;
+    program.rsplit('/').next()
+}
+
+/// Every extension this table associates with `language` (case-insensitive
+/// match on `language`), used to scope `search_code`/`find_files` by
+/// language instead of an extension glob.
+pub fn extensions_for_language(language: &str) -> Vec<&'static str> {
+    EXTENSION_TABLE
+        .iter()
+        .filter(|(_, name)| name.eq_ignore_ascii_case(language))
+        .map(|(ext, _)| *ext)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_by_extension() {
+        let result = classify(Path::new("src/main.rs"), None);
+        assert_eq!(result.language, "Rust");
+        assert_eq!(result.confidence, 0.9);
+    }
+
+    #[test]
+    fn falls_back_to_shebang() {
+        let result = classify(Path::new("build"), Some("#!/usr/bin/env python3"));
+        assert_eq!(result.language, "Python");
+        assert_eq!(result.confidence, 0.6);
+    }
+
+    #[test]
+    fn unknown_when_neither_matches() {
+        let result = classify(Path::new("README"), Some("just some text"));
+        assert_eq!(result.language, "unknown");
+    }
+
+    #[test]
+    fn looks_up_extensions_by_language() {
+        let mut extensions = extensions_for_language("python");
+        extensions.sort_unstable();
+        assert_eq!(extensions, vec!["py", "pyw"]);
+    }
+}
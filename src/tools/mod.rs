@@ -5,17 +5,27 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+pub mod cache;
 pub mod file_tools;
 pub mod code_analysis;
+pub mod find_files;
+pub mod glob;
+pub mod language;
+pub mod permissions;
 pub mod rust_tools;
 pub mod self_awareness;
 
-use file_tools::{ReadFileTool, WriteFileTool, EditFileTool, ListFilesTool};
-use code_analysis::{AnalyzeRustCodeTool, FindFunctionTool, FindStructTool};
-use rust_tools::{CargoBuildTool, CargoTestTool, RustfmtTool, ClippyTool, CargoCheckTool};
-use self_awareness::{ProjectMapTool, GetCurrentCapabilitiesTool, ExplainArchitectureTool};
+use cache::FileCache;
+use file_tools::{ReadFileTool, WriteFileTool, EditFileTool, ListFilesTool, SearchCodeTool, DiskUsageTool, DetectLanguageTool};
+use find_files::FindFilesTool;
+use code_analysis::{AnalyzeRustCodeTool, FindFunctionTool, FindStructTool, DumpAstTool, FindReferencesTool, FindImplsTool};
+use permissions::{PermissionManager, PermissionPrompter, Permissions, PermissionsPolicy};
+use rust_tools::{CargoBenchTool, CargoBuildTool, CargoTestTool, RustfmtTool, ClippyTool, CargoCheckTool};
+use self_awareness::{ProjectMapTool, GetCurrentCapabilitiesTool, ExplainArchitectureTool, LintDiagnosticsTool, ToolManifestTool};
 
 /// Tool trait that all tools must implement
 #[async_trait]
@@ -37,6 +47,24 @@ pub trait Tool: Send + Sync {
         // Default implementation - tools can override
         Ok(())
     }
+
+    /// Capabilities this call needs: filesystem paths, subprocess
+    /// commands, network hosts. `ToolRegistry::execute_tool` checks this
+    /// against its grants before dispatching. Tools that only ever touch
+    /// what the workspace sandbox already allows (read-only, in-process
+    /// analysis) can leave this at the default of "nothing extra needed".
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions::default()
+    }
+
+    /// CLI flags this call would pass to whatever it spawns (e.g.
+    /// `--release`, `--fix`), checked against the configured
+    /// [`permissions::ToolFlagPolicy`] for this tool before dispatch.
+    /// Tools that don't expose policy-relevant flags can leave this at the
+    /// default of "nothing to check".
+    fn requested_flags(&self, _params: &Value) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Tool information for listing
@@ -48,10 +76,16 @@ pub struct ToolInfo {
     pub self_modification: bool,
 }
 
+/// Number of file contents the shared [`FileCache`] holds at once.
+const FILE_CACHE_CAPACITY: usize = 64;
+
 /// Registry for managing available tools
 pub struct ToolRegistry {
     tools: HashMap<String, Box<dyn Tool>>,
     workspace: PathBuf,
+    file_cache: Arc<FileCache>,
+    permissions: PermissionManager,
+    policy: PermissionsPolicy,
 }
 
 impl ToolRegistry {
@@ -61,16 +95,41 @@ impl ToolRegistry {
         Self {
             tools: HashMap::new(),
             workspace,
+            file_cache: Arc::new(FileCache::new(FILE_CACHE_CAPACITY)),
+            permissions: PermissionManager::new(),
+            policy: PermissionsPolicy::default(),
         }
     }
-    
+
+    /// Replace the configured [`PermissionsPolicy`], e.g. after loading the
+    /// `[permissions]` table from `gemini.toml`. Defaults to
+    /// [`PermissionsPolicy::default`] (no additional restriction) so a
+    /// registry that never calls this behaves exactly as before this
+    /// policy layer existed.
+    pub fn set_policy(&mut self, policy: PermissionsPolicy) {
+        self.policy = policy;
+    }
+
+    /// Like [`Self::new`], but unresolved permission requests are routed
+    /// through `prompter` (e.g. a REPL asking the user) instead of being
+    /// denied outright.
+    pub fn with_prompter(prompter: Box<dyn PermissionPrompter>) -> Self {
+        let mut registry = Self::new();
+        registry.permissions = PermissionManager::with_prompter(prompter);
+        registry
+    }
+
     /// Initialize default tools
     pub fn initialize_default_tools(&mut self) -> Result<()> {
         // File operation tools
-        self.register_tool(Box::new(ReadFileTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(ReadFileTool::with_cache(self.workspace.clone(), self.file_cache.clone())))?;
         self.register_tool(Box::new(WriteFileTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(ListFilesTool::new(self.workspace.clone())))?;
-        
+        self.register_tool(Box::new(SearchCodeTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(DiskUsageTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(FindFilesTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(DetectLanguageTool::new(self.workspace.clone())))?;
+
         Ok(())
     }
     
@@ -83,19 +142,25 @@ impl ToolRegistry {
         self.register_tool(Box::new(AnalyzeRustCodeTool::new()))?;
         self.register_tool(Box::new(FindFunctionTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(FindStructTool::new(self.workspace.clone())))?;
-        
+        self.register_tool(Box::new(DumpAstTool::new()))?;
+        self.register_tool(Box::new(FindReferencesTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(FindImplsTool::new(self.workspace.clone())))?;
+
         // Rust-specific tools
         self.register_tool(Box::new(CargoBuildTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(CargoTestTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(CargoCheckTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(ClippyTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(CargoBenchTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(RustfmtTool::new()))?;
         
         // Self-awareness tools
         self.register_tool(Box::new(ProjectMapTool::new(self.workspace.clone())))?;
         self.register_tool(Box::new(GetCurrentCapabilitiesTool::new()))?;
         self.register_tool(Box::new(ExplainArchitectureTool::new(self.workspace.clone())))?;
-        
+        self.register_tool(Box::new(LintDiagnosticsTool::new(self.workspace.clone())))?;
+        self.register_tool(Box::new(ToolManifestTool::new()))?;
+
         Ok(())
     }
     
@@ -120,18 +185,19 @@ impl ToolRegistry {
             .iter()
             .map(|(name, tool)| {
                 let category = match name.as_str() {
-                    "read_file" | "write_file" | "edit_file" | "list_files" => "file_operations",
-                    "analyze_rust_code" | "find_function" | "find_struct" => "code_analysis",
+                    "read_file" | "write_file" | "edit_file" | "list_files" | "search_code" | "disk_usage" | "find_files" | "detect_language" => "file_operations",
+                    "analyze_rust_code" | "find_function" | "find_struct" | "dump_ast" | "find_references" | "find_impls" => "code_analysis",
                     "cargo_build" | "cargo_test" | "cargo_check" | "clippy" | "rustfmt" => "rust_tools",
-                    "project_map" | "get_current_capabilities" | "explain_architecture" => "self_awareness",
+                    "project_map" | "get_current_capabilities" | "explain_architecture" | "lint_diagnostics" | "tool_manifest" => "self_awareness",
                     _ => "other",
                 };
-                
+
                 let self_modification = matches!(
                     name.as_str(),
                     "edit_file" | "analyze_rust_code" | "find_function" | "find_struct" |
-                    "cargo_build" | "cargo_test" | "cargo_check" | "clippy" | "rustfmt" | 
-                    "project_map" | "get_current_capabilities" | "explain_architecture"
+                    "cargo_build" | "cargo_test" | "cargo_check" | "clippy" | "rustfmt" |
+                    "project_map" | "get_current_capabilities" | "explain_architecture" |
+                    "lint_diagnostics" | "tool_manifest"
                 );
                 
                 ToolInfo {
@@ -158,16 +224,78 @@ impl ToolRegistry {
             .collect()
     }
     
+    /// Build a versioned function-declaration manifest: one entry per
+    /// registered tool with its name, description, and JSON-Schema
+    /// parameters (the same shape [`Self::get_tool_definitions`] sends to
+    /// the API), plus a `version` hash over the whole set so a caller that
+    /// cached an earlier manifest can detect when a tool's schema drifted.
+    pub fn function_manifest(&self) -> Value {
+        let mut names: Vec<&String> = self.tools.keys().collect();
+        names.sort();
+
+        let declarations: Vec<Value> = names
+            .into_iter()
+            .map(|name| {
+                let tool = &self.tools[name];
+                serde_json::json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "parameters": tool.parameters_schema(),
+                })
+            })
+            .collect();
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        serde_json::to_string(&declarations).unwrap_or_default().hash(&mut hasher);
+
+        serde_json::json!({
+            "version": format!("{:016x}", hasher.finish()),
+            "tool_count": declarations.len(),
+            "declarations": declarations,
+        })
+    }
+
+    /// Run every pre-execution gate `execute_tool` enforces (parameter
+    /// validation, then policy and permission checks) without actually
+    /// invoking the tool. Lets a caller that's about to reuse a memoized
+    /// result (e.g. the agent loop's tool cache) still be subject to the
+    /// same policy/permission decisions a live execution would face,
+    /// instead of a cache hit silently skipping them.
+    pub fn check_tool(&self, name: &str, params: &Value) -> Result<()> {
+        let tool = self.tools
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Tool '{}' not found", name))?;
+
+        tool.validate_params(params)?;
+
+        // Configured policy (allowed_tools, per-tool flag restrictions,
+        // workspace_paths) gates dispatch before the grant-based prompter
+        // below even sees the request.
+        self.policy.check(name, &tool.required_permissions(params), &tool.requested_flags(params))?;
+
+        // Least-privilege check: deny or prompt for anything this call
+        // needs that hasn't already been granted.
+        self.permissions.check(&tool.required_permissions(params))?;
+
+        Ok(())
+    }
+
     /// Execute a tool by name
     pub async fn execute_tool(&self, name: &str, params: Value) -> Result<Value> {
+        self.check_tool(name, &params)?;
+
+        if self.policy.dry_run {
+            return Ok(serde_json::json!({
+                "dry_run": true,
+                "tool": name,
+                "params": params,
+            }));
+        }
+
+        // Execute tool
         let tool = self.tools
             .get(name)
             .ok_or_else(|| anyhow::anyhow!("Tool '{}' not found", name))?;
-        
-        // Validate parameters
-        tool.validate_params(&params)?;
-        
-        // Execute tool
         tool.execute(params).await
     }
 }
@@ -0,0 +1,326 @@
+//! Per-tool capability/permission system, modeled on Deno's `--allow-*`
+//! flags. Instead of every registered tool sharing the same all-or-nothing
+//! workspace access that [`super::security`] enforces, each tool declares
+//! the specific filesystem paths, subprocess commands, and network hosts
+//! it needs for a given call, and [`super::ToolRegistry::execute_tool`]
+//! checks that request against a running set of grants before dispatch.
+//!
+//! A request that isn't already covered by a grant goes through a
+//! [`PermissionPrompter`]: a REPL front-end can interactively allow, deny,
+//! or allow-and-remember it. Remembered grants make subsequent identical
+//! requests silent, the same way answering "always allow" to a Deno
+//! permission prompt does.
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A set of filesystem paths a tool is allowed to touch. `contains` treats
+/// an allowed entry as a prefix, so granting a directory covers everything
+/// under it (e.g. granting the workspace root covers every file in it).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathSet(HashSet<PathBuf>);
+
+impl PathSet {
+    pub fn single(path: impl Into<PathBuf>) -> Self {
+        Self(HashSet::from([path.into()]))
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.0.iter().any(|allowed| path.starts_with(allowed))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn extend(&mut self, other: &PathSet) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Path> {
+        self.0.iter().map(PathBuf::as_path)
+    }
+}
+
+/// A set of subprocess commands (e.g. `cargo`, `rustfmt`) a tool is
+/// allowed to run. Unlike [`PathSet`], membership is exact: there's no
+/// useful notion of one command being a "prefix" of another.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSet(HashSet<String>);
+
+impl CommandSet {
+    pub fn single(command: impl Into<String>) -> Self {
+        Self(HashSet::from([command.into()]))
+    }
+
+    pub fn contains(&self, command: &str) -> bool {
+        self.0.contains(command)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn extend(&mut self, other: &CommandSet) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+/// A set of network hosts a tool is allowed to connect to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostSet(HashSet<String>);
+
+impl HostSet {
+    pub fn single(host: impl Into<String>) -> Self {
+        Self(HashSet::from([host.into()]))
+    }
+
+    pub fn contains(&self, host: &str) -> bool {
+        self.0.contains(host)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn extend(&mut self, other: &HostSet) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+/// The capabilities a tool call needs, or the ones already granted. Used
+/// both as the *request* a [`super::Tool::required_permissions`] call
+/// returns for one invocation, and as the registry's running record of
+/// what's been granted so far this session.
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    pub read: PathSet,
+    pub write: PathSet,
+    pub run: CommandSet,
+    pub net: HostSet,
+}
+
+impl Permissions {
+    /// Merge `granted` into this set, e.g. after a prompt is answered
+    /// "allow and remember".
+    fn grant(&mut self, granted: &Permissions) {
+        self.read.extend(&granted.read);
+        self.write.extend(&granted.write);
+        self.run.extend(&granted.run);
+        self.net.extend(&granted.net);
+    }
+}
+
+/// One capability a tool is asking permission for, passed to
+/// [`PermissionPrompter::prompt`] so a front-end can render e.g.
+/// `write access to src/main.rs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionKind {
+    Read,
+    Write,
+    Run,
+    Net,
+}
+
+impl PermissionKind {
+    fn label(self) -> &'static str {
+        match self {
+            PermissionKind::Read => "read",
+            PermissionKind::Write => "write",
+            PermissionKind::Run => "run",
+            PermissionKind::Net => "net",
+        }
+    }
+}
+
+/// How a [`PermissionPrompter`] resolved one request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionDecision {
+    /// Allow this one call; ask again next time.
+    Allow,
+    /// Allow this call and remember the grant for the rest of the session.
+    AllowAndRemember,
+    Deny,
+}
+
+/// Front-end hook for resolving a permission request that isn't already
+/// covered by a grant. A REPL implements this to prompt the user
+/// interactively; a non-interactive caller (tests, CI) can supply one that
+/// always denies, which is also what happens with no prompter at all —
+/// Deno's permission model calls this default "prompt or deny": ask if
+/// there's someone to ask, otherwise refuse.
+pub trait PermissionPrompter: Send + Sync {
+    fn prompt(&self, kind: PermissionKind, resource: &str) -> PermissionDecision;
+}
+
+/// Tracks grants accumulated over a session and resolves new requests,
+/// prompting through an optional [`PermissionPrompter`] when a request
+/// isn't already covered.
+pub struct PermissionManager {
+    granted: std::sync::Mutex<Permissions>,
+    prompter: Option<Box<dyn PermissionPrompter>>,
+}
+
+impl PermissionManager {
+    pub fn new() -> Self {
+        Self {
+            granted: std::sync::Mutex::new(Permissions::default()),
+            prompter: None,
+        }
+    }
+
+    pub fn with_prompter(prompter: Box<dyn PermissionPrompter>) -> Self {
+        Self {
+            granted: std::sync::Mutex::new(Permissions::default()),
+            prompter: Some(prompter),
+        }
+    }
+
+    /// Checks `required` against the current grants, prompting for
+    /// anything not yet covered. Returns an error naming the first denied
+    /// resource; a tool with a denied request is never dispatched.
+    pub fn check(&self, required: &Permissions) -> anyhow::Result<()> {
+        for path in required.read.iter() {
+            self.resolve(PermissionKind::Read, &path.display().to_string(), |granted| {
+                granted.read.contains(path)
+            })?;
+        }
+        for path in required.write.iter() {
+            self.resolve(PermissionKind::Write, &path.display().to_string(), |granted| {
+                granted.write.contains(path)
+            })?;
+        }
+        for command in required.run.iter() {
+            self.resolve(PermissionKind::Run, command, |granted| granted.run.contains(command))?;
+        }
+        for host in required.net.iter() {
+            self.resolve(PermissionKind::Net, host, |granted| granted.net.contains(host))?;
+        }
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        kind: PermissionKind,
+        resource: &str,
+        already_granted: impl Fn(&Permissions) -> bool,
+    ) -> anyhow::Result<()> {
+        if already_granted(&self.granted.lock().unwrap()) {
+            return Ok(());
+        }
+
+        let decision = match &self.prompter {
+            Some(prompter) => prompter.prompt(kind, resource),
+            None => PermissionDecision::Deny,
+        };
+
+        match decision {
+            PermissionDecision::Allow => Ok(()),
+            PermissionDecision::AllowAndRemember => {
+                let mut one_off = Permissions::default();
+                match kind {
+                    PermissionKind::Read => one_off.read = PathSet::single(resource),
+                    PermissionKind::Write => one_off.write = PathSet::single(resource),
+                    PermissionKind::Run => one_off.run = CommandSet::single(resource),
+                    PermissionKind::Net => one_off.net = HostSet::single(resource),
+                }
+                self.granted.lock().unwrap().grant(&one_off);
+                Ok(())
+            }
+            PermissionDecision::Deny => {
+                anyhow::bail!("permission denied: {} access to '{}'", kind.label(), resource)
+            }
+        }
+    }
+}
+
+impl Default for PermissionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Allow/deny list for one tool's CLI flags, keyed by tool name in
+/// [`PermissionsPolicy::flags`]. `deny` always wins; if `allow` is set,
+/// any flag not named in it is denied too. Deserialized directly from the
+/// `[permissions.flags.<tool>]` table in `gemini.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ToolFlagPolicy {
+    #[serde(default)]
+    pub allow: Option<Vec<String>>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+/// Declarative policy for which tools may run at all, which CLI flags
+/// they're allowed to pass, and which workspace paths they may touch.
+/// Unlike [`PermissionManager`]'s interactive, per-session grants, this is
+/// configured once up front (from the optional `[permissions]` table in
+/// `gemini.toml`, via `crate::config::PermissionsConfigLayer`) and checked
+/// by [`super::ToolRegistry::execute_tool`] before a tool call is even
+/// offered to the grant-based prompter.
+#[derive(Debug, Clone, Default)]
+pub struct PermissionsPolicy {
+    /// Tool names this agent may invoke. `None` allows every registered
+    /// tool; `Some` restricts to exactly the named set.
+    pub allowed_tools: Option<HashSet<String>>,
+    /// Workspace paths tools may read or write. Empty means no additional
+    /// restriction beyond each tool's own workspace sandbox.
+    pub workspace_paths: Vec<PathBuf>,
+    /// Per-tool CLI flag allow/deny lists, keyed by tool name.
+    pub flags: HashMap<String, ToolFlagPolicy>,
+    /// When true, `execute_tool` reports what it would have run instead of
+    /// dispatching the tool.
+    pub dry_run: bool,
+}
+
+impl PermissionsPolicy {
+    /// Checks whether `tool_name` may run at all, whether each of
+    /// `requested_flags` is permitted by its [`ToolFlagPolicy`] (if any),
+    /// and whether every path `required` would read or write falls under
+    /// `workspace_paths` (when that allowlist is non-empty). Returns an
+    /// error naming the first violation.
+    pub fn check(&self, tool_name: &str, required: &Permissions, requested_flags: &[String]) -> anyhow::Result<()> {
+        if let Some(allowed) = &self.allowed_tools {
+            if !allowed.contains(tool_name) {
+                anyhow::bail!("policy denied: tool '{}' is not in the configured allowed_tools list", tool_name);
+            }
+        }
+
+        if let Some(flag_policy) = self.flags.get(tool_name) {
+            for flag in requested_flags {
+                if flag_policy.deny.iter().any(|denied| denied == flag) {
+                    anyhow::bail!("policy denied: tool '{}' is not allowed to use flag '{}'", tool_name, flag);
+                }
+                if let Some(allow) = &flag_policy.allow {
+                    if !allow.iter().any(|allowed| allowed == flag) {
+                        anyhow::bail!("policy denied: tool '{}' is not allowed to use flag '{}'", tool_name, flag);
+                    }
+                }
+            }
+        }
+
+        if !self.workspace_paths.is_empty() {
+            for path in required.read.iter().chain(required.write.iter()) {
+                if !self.workspace_paths.iter().any(|allowed| path.starts_with(allowed)) {
+                    anyhow::bail!(
+                        "policy denied: tool '{}' would touch '{}', outside the configured workspace_paths",
+                        tool_name,
+                        path.display()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
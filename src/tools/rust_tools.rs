@@ -1,12 +1,140 @@
 //! Rust-specific tools for building, testing, and formatting code
 
+use super::permissions::{CommandSet, Permissions};
 use super::Tool;
+use crate::watch::WatchSession;
 use anyhow::Result;
 use async_trait::async_trait;
+use regex::Regex;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command as AsyncCommand;
+use tokio::sync::mpsc;
+
+/// Default timeout for a single tool invocation, used whenever a call
+/// doesn't set its own `timeout_ms`. Mirrors `config::GeminiConfig`'s
+/// `tool_timeout_ms` default.
+pub const DEFAULT_TOOL_TIMEOUT_MS: u64 = 300_000;
+
+/// Parses `cargo`'s `--message-format=json` output (one JSON object per
+/// line) into structured `{level, message, code, spans}` diagnostics,
+/// keeping only `compiler-message` records and each span's file/line/column
+/// range plus any machine-applicable `suggested_replacement`.
+fn parse_cargo_json_diagnostics(stdout: &str) -> Vec<Value> {
+    let mut diagnostics = Vec::new();
+
+    for line in stdout.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if record["reason"].as_str() != Some("compiler-message") {
+            continue;
+        }
+        let message = &record["message"];
+        let Some(level) = message["level"].as_str() else {
+            continue;
+        };
+
+        let spans = message["spans"]
+            .as_array()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .map(|span| {
+                        json!({
+                            "file_name": span["file_name"].as_str().unwrap_or_default(),
+                            "line_start": span["line_start"].as_u64().unwrap_or(0),
+                            "line_end": span["line_end"].as_u64().unwrap_or(0),
+                            "column_start": span["column_start"].as_u64().unwrap_or(0),
+                            "column_end": span["column_end"].as_u64().unwrap_or(0),
+                            "is_primary": span["is_primary"].as_bool().unwrap_or(false),
+                            "suggested_replacement": span["suggested_replacement"].as_str(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        diagnostics.push(json!({
+            "level": level,
+            "message": message["message"].as_str().unwrap_or_default(),
+            "code": message["code"]["code"].as_str(),
+            "spans": spans,
+        }));
+    }
+
+    diagnostics
+}
+
+/// Counts `error`/`warning`-level entries in a diagnostics list produced by
+/// [`parse_cargo_json_diagnostics`].
+fn count_diagnostics(diagnostics: &[Value]) -> (usize, usize) {
+    let error_count = diagnostics.iter().filter(|d| d["level"] == "error").count();
+    let warning_count = diagnostics.iter().filter(|d| d["level"] == "warning").count();
+    (error_count, warning_count)
+}
+
+/// Result of [`run_with_timeout`]: whatever stdout/stderr had been
+/// captured by the time the command either finished or was killed.
+struct TimedOutput {
+    success: bool,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    timed_out: bool,
+}
+
+/// Runs `cmd` to completion, or kills it once `timeout_ms` elapses —
+/// unlike wrapping `cmd.output()` in a `tokio::time::timeout` and dropping
+/// the future on expiry, this actually terminates the child instead of
+/// leaving it running, and still returns whatever partial stdout/stderr
+/// had already been read before the timeout fired.
+async fn run_with_timeout(cmd: &mut AsyncCommand, timeout_ms: u64) -> Result<TimedOutput> {
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.kill_on_drop(true);
+
+    let mut child = cmd.spawn()?;
+    let mut stdout_pipe = child.stdout.take().expect("stdout was piped");
+    let mut stderr_pipe = child.stderr.take().expect("stderr was piped");
+    let mut stdout_buf = Vec::new();
+    let mut stderr_buf = Vec::new();
+
+    let run_to_completion = async {
+        let (status, _, _) = tokio::join!(
+            child.wait(),
+            stdout_pipe.read_to_end(&mut stdout_buf),
+            stderr_pipe.read_to_end(&mut stderr_buf),
+        );
+        status
+    };
+
+    match tokio::time::timeout(std::time::Duration::from_millis(timeout_ms), run_to_completion).await {
+        Ok(status) => {
+            let status = status?;
+            Ok(TimedOutput {
+                success: status.success(),
+                exit_code: status.code(),
+                stdout: String::from_utf8_lossy(&stdout_buf).into_owned(),
+                stderr: String::from_utf8_lossy(&stderr_buf).into_owned(),
+                timed_out: false,
+            })
+        }
+        Err(_) => {
+            child.kill().await.ok();
+            Ok(TimedOutput {
+                success: false,
+                exit_code: None,
+                stdout: String::from_utf8_lossy(&stdout_buf).into_owned(),
+                stderr: String::from_utf8_lossy(&stderr_buf).into_owned(),
+                timed_out: true,
+            })
+        }
+    }
+}
 
 /// Tool for running cargo build
 pub struct CargoBuildTool {
@@ -46,11 +174,37 @@ impl Tool for CargoBuildTool {
                 "target": {
                     "type": "string",
                     "description": "Target architecture to build for"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["human", "json"],
+                    "description": "Output format; \"json\" passes --message-format=json and returns structured diagnostics",
+                    "default": "human"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill the build and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
                 }
             }
         })
     }
-    
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
+    fn requested_flags(&self, params: &Value) -> Vec<String> {
+        let mut flags = Vec::new();
+        if params.get("release").and_then(Value::as_bool).unwrap_or(false) {
+            flags.push("--release".to_string());
+        }
+        flags
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
@@ -58,41 +212,60 @@ impl Tool for CargoBuildTool {
             release: bool,
             features: Option<Vec<String>>,
             target: Option<String>,
+            #[serde(default)]
+            format: Option<String>,
+            timeout_ms: Option<u64>,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
+        let json_format = params.format.as_deref() == Some("json");
+
         let mut cmd = AsyncCommand::new("cargo");
         cmd.arg("build");
         cmd.current_dir(&self.workspace);
-        
+
         if params.release {
             cmd.arg("--release");
         }
-        
+
         if let Some(features) = params.features {
             if !features.is_empty() {
                 cmd.arg("--features");
                 cmd.arg(features.join(","));
             }
         }
-        
+
         if let Some(target) = params.target {
             cmd.arg("--target");
             cmd.arg(target);
         }
-        
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minutes timeout
-            cmd.output()
-        ).await
-        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))??;
-        
+
+        if json_format {
+            cmd.arg("--message-format=json");
+        }
+
+        let output = run_with_timeout(&mut cmd, params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)).await?;
+
+        if json_format {
+            let diagnostics = parse_cargo_json_diagnostics(&output.stdout);
+            let (error_count, warning_count) = count_diagnostics(&diagnostics);
+            return Ok(json!({
+                "success": output.success,
+                "exit_code": output.exit_code,
+                "diagnostics": diagnostics,
+                "error_count": error_count,
+                "warning_count": warning_count,
+                "stderr": output.stderr,
+                "timed_out": output.timed_out,
+            }));
+        }
+
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code(),
-            "stdout": String::from_utf8_lossy(&output.stdout),
-            "stderr": String::from_utf8_lossy(&output.stderr),
+            "success": output.success,
+            "exit_code": output.exit_code,
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
         }))
     }
 }
@@ -140,11 +313,31 @@ impl Tool for CargoTestTool {
                     "type": "boolean",
                     "description": "Verbose output",
                     "default": false
+                },
+                "jobs": {
+                    "type": "integer",
+                    "description": "Parallel test-thread count (default: available_parallelism)"
+                },
+                "shard": {
+                    "type": "string",
+                    "description": "Run only the k-th of n slices of the test list, as \"k/n\" (1-indexed), so several agents can split a large suite"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill the test run and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
                 }
             }
         })
     }
-    
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
@@ -154,48 +347,361 @@ impl Tool for CargoTestTool {
             features: Option<Vec<String>>,
             #[serde(default)]
             verbose: bool,
+            jobs: Option<usize>,
+            shard: Option<String>,
+            timeout_ms: Option<u64>,
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+        let jobs = params
+            .jobs
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4));
+        let timeout_ms = params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS);
+
+        let base_cmd = || {
+            let mut cmd = AsyncCommand::new("cargo");
+            cmd.arg("test");
+            cmd.current_dir(&self.workspace);
+            if params.release {
+                cmd.arg("--release");
+            }
+            if let Some(features) = &params.features {
+                if !features.is_empty() {
+                    cmd.arg("--features");
+                    cmd.arg(features.join(","));
+                }
+            }
+            if params.verbose {
+                cmd.arg("--verbose");
+            }
+            cmd
+        };
+
+        let mut test_filters: Vec<String> = params.test_name.iter().cloned().collect();
+        let mut exact = false;
+
+        if let Some(shard) = &params.shard {
+            let mut list_cmd = base_cmd();
+            for filter in &test_filters {
+                list_cmd.arg(filter);
+            }
+            list_cmd.arg("--").arg("--list");
+
+            let list_output = run_with_timeout(&mut list_cmd, timeout_ms).await?;
+
+            let names = parse_test_list(&list_output.stdout);
+            test_filters = shard_slice(&names, shard)?;
+            exact = true;
+
+            if test_filters.is_empty() {
+                return Ok(json!({
+                    "success": true,
+                    "events": [],
+                    "summary": {"passed": 0, "failed": 0, "ignored": 0, "filtered": 0, "total": 0, "wall_time_ms": 0.0},
+                }));
+            }
+        }
+
+        let mut cmd = base_cmd();
+        // `--format json` is gated behind `-Z unstable-options` on stable
+        // cargo; RUSTC_BOOTSTRAP forces the gate open the same way CI
+        // tooling does when it needs libtest's JSON event stream without a
+        // nightly toolchain.
+        cmd.env("RUSTC_BOOTSTRAP", "1");
+        cmd.arg("--");
+        cmd.arg("--format").arg("json");
+        cmd.arg("-Z").arg("unstable-options");
+        cmd.arg("--test-threads").arg(jobs.to_string());
+        for filter in &test_filters {
+            cmd.arg(filter);
+        }
+        if exact {
+            cmd.arg("--exact");
+        }
+
+        let output = run_with_timeout(&mut cmd, timeout_ms).await?;
+
+        let (events, summary) = parse_libtest_json_events(&output.stdout);
+
+        Ok(json!({
+            "success": output.success,
+            "exit_code": output.exit_code,
+            "events": events,
+            "summary": summary,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
+        }))
+    }
+}
+
+/// Parses `cargo test -- --list` output into the bare test names (dropping
+/// the trailing `N tests, M benchmarks` summary line).
+fn parse_test_list(raw: &str) -> Vec<String> {
+    raw.lines()
+        .filter_map(|line| line.strip_suffix(": test").map(str::to_string))
+        .collect()
+}
+
+/// Splits sorted test `names` into `n` contiguous-by-index shards and
+/// returns the `k`-th (1-indexed), per the `"k/n"` `shard` parameter.
+fn shard_slice(names: &[String], shard: &str) -> Result<Vec<String>> {
+    let (k, n) = shard
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("shard must be in \"k/n\" form"))?;
+    let k: usize = k.parse().map_err(|_| anyhow::anyhow!("shard must be in \"k/n\" form"))?;
+    let n: usize = n.parse().map_err(|_| anyhow::anyhow!("shard must be in \"k/n\" form"))?;
+    anyhow::ensure!(n > 0 && k >= 1 && k <= n, "shard must be \"k/n\" with 1 <= k <= n");
+
+    let mut sorted = names.to_vec();
+    sorted.sort();
+    Ok(sorted
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % n == k - 1)
+        .map(|(_, name)| name)
+        .collect())
+}
+
+/// Parses libtest's `--format json` event stream (one JSON object per line)
+/// into per-test `{name, result, duration_ms}` events plus a final
+/// `{passed, failed, ignored, filtered, total, wall_time_ms}` summary.
+fn parse_libtest_json_events(raw: &str) -> (Vec<Value>, Value) {
+    let mut events = Vec::new();
+    let mut summary = json!({
+        "passed": 0, "failed": 0, "ignored": 0, "filtered": 0, "total": 0, "wall_time_ms": Value::Null,
+    });
+
+    for line in raw.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+
+        match record["type"].as_str() {
+            Some("test") => {
+                let Some(result) = record["event"].as_str() else {
+                    continue;
+                };
+                if !matches!(result, "ok" | "failed" | "ignored") {
+                    continue;
+                }
+                events.push(json!({
+                    "name": record["name"].as_str().unwrap_or_default(),
+                    "result": result,
+                    "duration_ms": record["exec_time"].as_f64().map(|secs| secs * 1000.0),
+                }));
+            }
+            Some("suite") if record.get("passed").is_some() => {
+                let passed = record["passed"].as_u64().unwrap_or(0);
+                let failed = record["failed"].as_u64().unwrap_or(0);
+                let ignored = record["ignored"].as_u64().unwrap_or(0);
+                let filtered = record["filtered_out"].as_u64().unwrap_or(0);
+                summary = json!({
+                    "passed": passed,
+                    "failed": failed,
+                    "ignored": ignored,
+                    "filtered": filtered,
+                    "total": passed + failed + ignored,
+                    "wall_time_ms": record["exec_time"].as_f64().map(|secs| secs * 1000.0),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (events, summary)
+}
+
+/// Tool for running cargo bench, stamping results with the machine/build
+/// metadata needed to compare numbers across runs.
+pub struct CargoBenchTool {
+    workspace: PathBuf,
+}
+
+impl CargoBenchTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for CargoBenchTool {
+    fn name(&self) -> &str {
+        "cargo_bench"
+    }
+
+    fn description(&self) -> &str {
+        "Run cargo bench and return structured {name, ns_per_iter, throughput} results stamped with environment metadata"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "bench_target": {
+                    "type": "string",
+                    "description": "Specific bench target to run (optional)"
+                },
+                "features": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Features to enable"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill the bench run and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
+                }
+            }
+        })
+    }
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            bench_target: Option<String>,
+            features: Option<Vec<String>>,
+            timeout_ms: Option<u64>,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
+
         let mut cmd = AsyncCommand::new("cargo");
-        cmd.arg("test");
+        cmd.arg("bench");
         cmd.current_dir(&self.workspace);
-        
-        if params.release {
-            cmd.arg("--release");
-        }
-        
-        if let Some(features) = params.features {
+
+        if let Some(features) = &params.features {
             if !features.is_empty() {
                 cmd.arg("--features");
                 cmd.arg(features.join(","));
             }
         }
-        
-        if params.verbose {
-            cmd.arg("--verbose");
-        }
-        
-        if let Some(test_name) = params.test_name {
-            cmd.arg(test_name);
-        }
-        
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minutes timeout
-            cmd.output()
-        ).await
-        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))??;
-        
+
+        if let Some(target) = &params.bench_target {
+            cmd.arg("--bench");
+            cmd.arg(target);
+        }
+
+        let output = run_with_timeout(&mut cmd, params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)).await?;
+
+        let results = parse_bench_output(&output.stdout)?;
+        // cargo bench always compiles with optimizations, same as --release.
+        let env_info = collect_env_info(&self.workspace, true).await;
+
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code(),
-            "stdout": String::from_utf8_lossy(&output.stdout),
-            "stderr": String::from_utf8_lossy(&output.stderr),
+            "success": output.success,
+            "exit_code": output.exit_code,
+            "results": results,
+            "env_info": env_info,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
         }))
     }
 }
 
+/// Parses libtest's built-in `#[bench]` harness output (`test foo ... bench:
+/// 1,234 ns/iter (+/- 56) = 78 MB/s`) and, best-effort, a single-line
+/// Criterion `<name> time: [.. <value> <unit> ..]` summary, into
+/// `{name, ns_per_iter, throughput}` entries.
+fn parse_bench_output(raw: &str) -> Result<Vec<Value>> {
+    let libtest_pattern =
+        Regex::new(r"^test (\S+)\s+\.\.\.\s+bench:\s+([\d,]+) ns/iter \(\+/- [\d,]+\)(?:\s*=\s*([\d,]+) MB/s)?")?;
+    let criterion_pattern = Regex::new(r"^(\S+)\s+time:\s+\[[0-9.]+ \w+ ([0-9.]+) (\w+) [0-9.]+ \w+\]")?;
+
+    let mut entries = Vec::new();
+    for line in raw.lines() {
+        if let Some(caps) = libtest_pattern.captures(line) {
+            let ns_per_iter: f64 = caps[2].replace(',', "").parse()?;
+            let throughput = caps
+                .get(3)
+                .map(|m| m.as_str().replace(',', "").parse::<f64>())
+                .transpose()?;
+            entries.push(json!({
+                "name": &caps[1],
+                "ns_per_iter": ns_per_iter,
+                "throughput": throughput,
+            }));
+        } else if let Some(caps) = criterion_pattern.captures(line) {
+            let value: f64 = caps[2].parse()?;
+            let ns_per_iter = match &caps[3] {
+                "ns" => value,
+                "us" | "µs" => value * 1_000.0,
+                "ms" => value * 1_000_000.0,
+                "s" => value * 1_000_000_000.0,
+                _ => value,
+            };
+            entries.push(json!({
+                "name": &caps[1],
+                "ns_per_iter": ns_per_iter,
+                "throughput": Value::Null,
+            }));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Collects the machine/build context a bare `ns_per_iter` number needs to
+/// be comparable across runs and machines: CPU model/core count, total RAM,
+/// OS/arch, rustc version, and the `workspace`'s current git commit.
+async fn collect_env_info(workspace: &Path, release: bool) -> Value {
+    let rustc_version = AsyncCommand::new("rustc")
+        .arg("--version")
+        .output()
+        .await
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let git_commit = AsyncCommand::new("git")
+        .arg("rev-parse")
+        .arg("HEAD")
+        .current_dir(workspace)
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let (cpu_model, cpu_count) = read_cpu_info();
+
+    json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "cpu_model": cpu_model,
+        "cpu_count": cpu_count,
+        "total_ram_kb": read_total_ram_kb(),
+        "rustc_version": rustc_version,
+        "git_commit": git_commit,
+        "release": release,
+    })
+}
+
+/// Reads the CPU model name from `/proc/cpuinfo` (Linux-only; `None` on
+/// other platforms) and the logical core count via `available_parallelism`.
+fn read_cpu_info() -> (Option<String>, usize) {
+    let cpu_count = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let model = std::fs::read_to_string("/proc/cpuinfo").ok().and_then(|text| {
+        text.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "model name").then(|| value.trim().to_string())
+        })
+    });
+    (model, cpu_count)
+}
+
+/// Reads total system RAM in KB from `/proc/meminfo` (Linux-only).
+fn read_total_ram_kb() -> Option<u64> {
+    let text = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = text.lines().find(|line| line.starts_with("MemTotal:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
 /// Tool for running rustfmt
 pub struct RustfmtTool;
 
@@ -227,50 +733,60 @@ impl Tool for RustfmtTool {
                     "type": "boolean",
                     "description": "Check if code is formatted without making changes",
                     "default": false
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill rustfmt and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
                 }
             },
             "required": ["code"]
         })
     }
-    
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("rustfmt"),
+            ..Permissions::default()
+        }
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
             code: String,
             #[serde(default)]
             check: bool,
+            timeout_ms: Option<u64>,
         }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
+
         // Create a temporary file for the code
         let temp_file = tempfile::NamedTempFile::new()?;
         std::fs::write(temp_file.path(), &params.code)?;
-        
+
         let mut cmd = AsyncCommand::new("rustfmt");
         if params.check {
             cmd.arg("--check");
         }
         cmd.arg(temp_file.path());
-        
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minutes timeout
-            cmd.output()
-        ).await
-        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))??;
-        
-        let formatted_code = if !params.check && output.status.success() {
+
+        let output = run_with_timeout(&mut cmd, params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)).await?;
+
+        let formatted_code = if !params.check && output.success {
             std::fs::read_to_string(temp_file.path())?
         } else {
             params.code.clone()
         };
-        
+
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code(),
+            "success": output.success,
+            "exit_code": output.exit_code,
             "formatted_code": formatted_code,
-            "changes_needed": !output.status.success() && params.check,
-            "stderr": String::from_utf8_lossy(&output.stderr),
+            "changes_needed": !output.success && params.check,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
         }))
     }
 }
@@ -309,11 +825,38 @@ impl Tool for ClippyTool {
                     "type": "boolean",
                     "description": "Check all targets (lib, bin, tests, etc.)",
                     "default": true
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["human", "json"],
+                    "description": "Output format; \"json\" passes --message-format=json and returns structured diagnostics",
+                    "default": "human"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill clippy and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
                 }
             }
         })
     }
-    
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
+    fn requested_flags(&self, params: &Value) -> Vec<String> {
+        let mut flags = Vec::new();
+        if params.get("fix").and_then(Value::as_bool).unwrap_or(false) {
+            flags.push("--fix".to_string());
+            flags.push("--allow-dirty".to_string());
+        }
+        flags
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
@@ -321,40 +864,59 @@ impl Tool for ClippyTool {
             fix: bool,
             #[serde(default = "default_true")]
             all_targets: bool,
+            #[serde(default)]
+            format: Option<String>,
+            timeout_ms: Option<u64>,
         }
-        
+
         fn default_true() -> bool { true }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
+        let json_format = params.format.as_deref() == Some("json");
+
         let mut cmd = AsyncCommand::new("cargo");
         cmd.arg("clippy");
         cmd.current_dir(&self.workspace);
-        
+
         if params.all_targets {
             cmd.arg("--all-targets");
         }
-        
+
         if params.fix {
             cmd.arg("--fix");
             cmd.arg("--allow-dirty");
         }
-        
+
+        if json_format {
+            cmd.arg("--message-format=json");
+        }
+
         cmd.arg("--");
         cmd.arg("-D");
         cmd.arg("warnings");
-        
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minutes timeout
-            cmd.output()
-        ).await
-        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))??;
-        
+
+        let output = run_with_timeout(&mut cmd, params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)).await?;
+
+        if json_format {
+            let diagnostics = parse_cargo_json_diagnostics(&output.stdout);
+            let (error_count, warning_count) = count_diagnostics(&diagnostics);
+            return Ok(json!({
+                "success": output.success,
+                "exit_code": output.exit_code,
+                "diagnostics": diagnostics,
+                "error_count": error_count,
+                "warning_count": warning_count,
+                "stderr": output.stderr,
+                "timed_out": output.timed_out,
+            }));
+        }
+
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code(),
-            "stdout": String::from_utf8_lossy(&output.stdout),
-            "stderr": String::from_utf8_lossy(&output.stderr),
+            "success": output.success,
+            "exit_code": output.exit_code,
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
         }))
     }
 }
@@ -393,49 +955,182 @@ impl Tool for CargoCheckTool {
                     "type": "array",
                     "items": {"type": "string"},
                     "description": "Features to enable"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["human", "json"],
+                    "description": "Output format; \"json\" passes --message-format=json and returns structured diagnostics",
+                    "default": "human"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Kill the check and return partial output after this many milliseconds",
+                    "default": DEFAULT_TOOL_TIMEOUT_MS
                 }
             }
         })
     }
-    
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
     async fn execute(&self, params: Value) -> Result<Value> {
         #[derive(Deserialize)]
         struct Params {
             #[serde(default = "default_true")]
             all_targets: bool,
             features: Option<Vec<String>>,
+            #[serde(default)]
+            format: Option<String>,
+            timeout_ms: Option<u64>,
         }
-        
+
         fn default_true() -> bool { true }
-        
+
         let params: Params = serde_json::from_value(params)?;
-        
+        let json_format = params.format.as_deref() == Some("json");
+
         let mut cmd = AsyncCommand::new("cargo");
         cmd.arg("check");
         cmd.current_dir(&self.workspace);
-        
+
         if params.all_targets {
             cmd.arg("--all-targets");
         }
-        
+
         if let Some(features) = params.features {
             if !features.is_empty() {
                 cmd.arg("--features");
                 cmd.arg(features.join(","));
             }
         }
-        
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minutes timeout
-            cmd.output()
-        ).await
-        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))??;
-        
+
+        if json_format {
+            cmd.arg("--message-format=json");
+        }
+
+        let output = run_with_timeout(&mut cmd, params.timeout_ms.unwrap_or(DEFAULT_TOOL_TIMEOUT_MS)).await?;
+
+        if json_format {
+            let diagnostics = parse_cargo_json_diagnostics(&output.stdout);
+            let (error_count, warning_count) = count_diagnostics(&diagnostics);
+            return Ok(json!({
+                "success": output.success,
+                "exit_code": output.exit_code,
+                "diagnostics": diagnostics,
+                "error_count": error_count,
+                "warning_count": warning_count,
+                "stderr": output.stderr,
+                "timed_out": output.timed_out,
+            }));
+        }
+
         Ok(json!({
+            "success": output.success,
+            "exit_code": output.exit_code,
+            "stdout": output.stdout,
+            "stderr": output.stderr,
+            "timed_out": output.timed_out,
+        }))
+    }
+}
+
+/// True for paths a cargo watch loop should rebuild on: any `.rs` source
+/// file or `Cargo.toml`, excluding build output under a `target/` tree
+/// (cargo's own writes there would otherwise re-trigger itself).
+fn is_relevant_cargo_change(path: &Path) -> bool {
+    if path.components().any(|c| c.as_os_str() == "target") {
+        return false;
+    }
+    path.extension().is_some_and(|ext| ext == "rs") || path.file_name().is_some_and(|name| name == "Cargo.toml")
+}
+
+/// Builds the same `{success, exit_code, ...}` result shape the non-watch
+/// cargo tools return, from a completed [`std::process::Output`].
+fn cargo_output_to_value(output: &std::process::Output, json_format: bool) -> Value {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if json_format {
+        let diagnostics = parse_cargo_json_diagnostics(&stdout);
+        let (error_count, warning_count) = count_diagnostics(&diagnostics);
+        json!({
             "success": output.status.success(),
             "exit_code": output.status.code(),
-            "stdout": String::from_utf8_lossy(&output.stdout),
+            "diagnostics": diagnostics,
+            "error_count": error_count,
+            "warning_count": warning_count,
             "stderr": String::from_utf8_lossy(&output.stderr),
-        }))
+        })
+    } else {
+        json!({
+            "success": output.status.success(),
+            "exit_code": output.status.code(),
+            "stdout": stdout,
+            "stderr": String::from_utf8_lossy(&output.stderr),
+        })
     }
-}
\ No newline at end of file
+}
+
+/// Watch mode for `cargo check`/`cargo test`: re-runs `cargo <subcommand>
+/// --all-targets` in `workspace` every time a debounced batch of filesystem
+/// changes touches a `.rs` file or `Cargo.toml` (ignoring `target/`),
+/// sending one structured result over `results` per completed run.
+///
+/// This isn't a [`Tool`] impl: `Tool::execute` returns a single
+/// `Result<Value>`, which can't represent an open-ended stream of rebuild
+/// results the way this needs to. Callers (a REPL command, a long-running
+/// CI-style session) drive this directly and read `results` for as long as
+/// they want to keep watching.
+///
+/// A run still in flight when a newer relevant change arrives is killed —
+/// via `kill_on_drop` on the child, dropped when `cargo.output()` loses the
+/// `tokio::select!` race — so results always reflect the latest source
+/// tree rather than a stale in-progress build.
+pub async fn run_cargo_watch(
+    workspace: PathBuf,
+    subcommand: &str,
+    json_format: bool,
+    results: mpsc::Sender<Value>,
+) -> Result<()> {
+    let session = WatchSession::start(vec![workspace.clone()])?;
+    let (change_tx, mut change_rx) = mpsc::channel::<()>(1);
+
+    std::thread::spawn(move || {
+        while let Ok(true) = session.wait_for_relevant_change(is_relevant_cargo_change) {
+            if change_tx.blocking_send(()).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let mut cmd = AsyncCommand::new("cargo");
+        cmd.arg(subcommand).arg("--all-targets").current_dir(&workspace).kill_on_drop(true);
+        if json_format {
+            cmd.arg("--message-format=json");
+        }
+
+        tokio::select! {
+            output = cmd.output() => {
+                let value = cargo_output_to_value(&output?, json_format);
+                if results.send(value).await.is_err() {
+                    return Ok(());
+                }
+            }
+            signal = change_rx.recv() => {
+                if signal.is_none() {
+                    return Ok(());
+                }
+                continue;
+            }
+        }
+
+        if change_rx.recv().await.is_none() {
+            return Ok(());
+        }
+    }
+}
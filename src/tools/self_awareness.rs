@@ -1,12 +1,15 @@
 //! Self-awareness tools for understanding project structure and capabilities
 
+use super::permissions::{CommandSet, Permissions};
 use super::Tool;
 use anyhow::Result;
 use async_trait::async_trait;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tokio::process::Command as AsyncCommand;
 
 /// Tool for mapping project structure
 pub struct ProjectMapTool {
@@ -143,7 +146,9 @@ impl Tool for GetCurrentCapabilitiesTool {
             "self_awareness": {
                 "project_map": "Map project structure and dependencies",
                 "get_current_capabilities": "List available capabilities",
-                "explain_architecture": "Explain system architecture"
+                "explain_architecture": "Explain system architecture",
+                "lint_diagnostics": "Run clippy/rustfmt and parse their output into structured diagnostics",
+                "tool_manifest": "Emit a versioned function-declaration manifest for every registered tool"
             },
             "security": {
                 "workspace_sandboxing": "All operations restricted to workspace",
@@ -268,6 +273,224 @@ impl Tool for ExplainArchitectureTool {
     }
 }
 
+/// One diagnostic parsed out of clippy or rustfmt's terminal output.
+#[derive(Debug, Clone, Serialize)]
+pub struct LintDiagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub severity: String,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: &'static str,
+}
+
+/// Tool for turning clippy/rustfmt terminal output into structured diagnostics
+pub struct LintDiagnosticsTool {
+    workspace: PathBuf,
+}
+
+impl LintDiagnosticsTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self { workspace }
+    }
+}
+
+#[async_trait]
+impl Tool for LintDiagnosticsTool {
+    fn name(&self) -> &str {
+        "lint_diagnostics"
+    }
+
+    fn description(&self) -> &str {
+        "Run clippy and rustfmt and parse their output into structured {file, line, column, severity, code, message} diagnostics"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "clippy": {
+                    "type": "boolean",
+                    "description": "Run clippy and include its diagnostics",
+                    "default": true
+                },
+                "rustfmt": {
+                    "type": "boolean",
+                    "description": "Run rustfmt --check and include its diagnostics",
+                    "default": true
+                }
+            }
+        })
+    }
+
+    fn required_permissions(&self, _params: &Value) -> Permissions {
+        Permissions {
+            run: CommandSet::single("cargo"),
+            ..Permissions::default()
+        }
+    }
+
+    async fn execute(&self, params: Value) -> Result<Value> {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default = "default_true")]
+            clippy: bool,
+            #[serde(default = "default_true")]
+            rustfmt: bool,
+        }
+
+        fn default_true() -> bool {
+            true
+        }
+
+        let params: Params = serde_json::from_value(params)?;
+
+        let mut diagnostics = Vec::new();
+
+        if params.clippy {
+            let output = run_with_timeout(
+                AsyncCommand::new("cargo")
+                    .arg("clippy")
+                    .arg("--all-targets")
+                    .arg("--message-format=short")
+                    .current_dir(&self.workspace),
+            )
+            .await?;
+            diagnostics.extend(parse_clippy_diagnostics(&String::from_utf8_lossy(&output.stderr))?);
+        }
+
+        if params.rustfmt {
+            let output = run_with_timeout(
+                AsyncCommand::new("cargo")
+                    .arg("fmt")
+                    .arg("--")
+                    .arg("--check")
+                    .current_dir(&self.workspace),
+            )
+            .await?;
+            diagnostics.extend(parse_rustfmt_diagnostics(&String::from_utf8_lossy(&output.stdout))?);
+        }
+
+        Ok(json!({
+            "success": true,
+            "diagnostic_count": diagnostics.len(),
+            "diagnostics": diagnostics,
+        }))
+    }
+}
+
+/// Runs `cmd` with the same 5-minute timeout the other rust-tooling tools use.
+async fn run_with_timeout(cmd: &mut AsyncCommand) -> Result<std::process::Output> {
+    tokio::time::timeout(std::time::Duration::from_secs(300), cmd.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("Command timed out after 5 minutes"))?
+        .map_err(Into::into)
+}
+
+/// Strips ANSI color escapes (`cargo clippy`'s terminal renderer emits them
+/// even when stderr isn't a tty in some environments) before pattern-matching.
+fn strip_ansi(text: &str) -> Result<String> {
+    let ansi = Regex::new("\x1b\\[[0-9;]*m")?;
+    Ok(ansi.replace_all(text, "").into_owned())
+}
+
+/// Parses clippy's short message format: a `warning|error[code]: message`
+/// line immediately followed by a `--> file:line:col` location line.
+fn parse_clippy_diagnostics(raw: &str) -> Result<Vec<LintDiagnostic>> {
+    let text = strip_ansi(raw)?;
+    let message_pattern = Regex::new(r"^(warning|error)(?:\[([a-zA-Z0-9:_]+)\])?: (.+)$")?;
+    let location_pattern = Regex::new(r"^\s*--> (.+):(\d+):(\d+)$")?;
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut diagnostics = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some(message_captures) = message_pattern.captures(line) else {
+            continue;
+        };
+        let Some(location_line) = lines.get(index + 1) else {
+            continue;
+        };
+        let Some(location_captures) = location_pattern.captures(location_line) else {
+            continue;
+        };
+
+        diagnostics.push(LintDiagnostic {
+            file: location_captures[1].to_string(),
+            line: location_captures[2].parse()?,
+            column: location_captures[3].parse()?,
+            severity: message_captures[1].to_string(),
+            code: message_captures.get(2).map(|m| m.as_str().to_string()),
+            message: message_captures[3].to_string(),
+            source: "clippy",
+        });
+    }
+
+    Ok(diagnostics)
+}
+
+/// Parses rustfmt's check-mode output: a `Diff in <file> at line N:` header
+/// per misformatted location.
+fn parse_rustfmt_diagnostics(raw: &str) -> Result<Vec<LintDiagnostic>> {
+    let text = strip_ansi(raw)?;
+    let diff_pattern = Regex::new(r"^Diff in (.+) at line (\d+):$")?;
+
+    let mut diagnostics = Vec::new();
+    for line in text.lines() {
+        let Some(captures) = diff_pattern.captures(line) else {
+            continue;
+        };
+        diagnostics.push(LintDiagnostic {
+            file: captures[1].to_string(),
+            line: captures[2].parse()?,
+            column: 1,
+            severity: "warning".to_string(),
+            code: None,
+            message: "formatting differs from rustfmt's output".to_string(),
+            source: "rustfmt",
+        });
+    }
+
+    Ok(diagnostics)
+}
+
+/// Tool that emits a versioned function-declaration manifest for every
+/// registered tool, so the REPL (or the model itself) can detect when a
+/// tool's schema has drifted from a manifest cached earlier.
+pub struct ToolManifestTool;
+
+impl ToolManifestTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Tool for ToolManifestTool {
+    fn name(&self) -> &str {
+        "tool_manifest"
+    }
+
+    fn description(&self) -> &str {
+        "Emit a versioned function-declaration manifest (name, description, JSON-Schema parameters, and a drift-detection version hash) for every registered tool"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _params: Value) -> Result<Value> {
+        let mut registry = super::ToolRegistry::new();
+        registry.initialize_default_tools()?;
+        registry.initialize_self_modification_tools()?;
+        Ok(registry.function_manifest())
+    }
+}
+
 fn analyze_cargo_toml(workspace: &Path) -> Result<Value> {
     let cargo_path = workspace.join("Cargo.toml");
     if cargo_path.exists() {
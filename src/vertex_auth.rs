@@ -0,0 +1,217 @@
+//! Application Default Credentials for Vertex AI.
+//!
+//! Vertex AI authenticates with a short-lived OAuth access token rather
+//! than the API key the public Gemini endpoint uses. [`AdcTokenProvider`]
+//! locates the credentials the same way the `gcloud`/Google Cloud client
+//! libraries do — `GOOGLE_APPLICATION_CREDENTIALS` if set, otherwise the
+//! well-known file `gcloud auth application-default login` writes — mints
+//! a token, and caches it until shortly before it expires.
+
+use anyhow::{bail, Context, Result};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const TOKEN_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
+/// Refresh this long before the token's actual expiry so an in-flight
+/// request never races a token that goes stale mid-call.
+const EXPIRY_SLACK: Duration = Duration::from_secs(60);
+
+/// The two ADC shapes `gcloud` writes to disk: a service-account key (from
+/// `GOOGLE_APPLICATION_CREDENTIALS`) or a user's refresh token (from
+/// `gcloud auth application-default login`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AdcFile {
+    ServiceAccount {
+        client_email: String,
+        private_key: String,
+        #[serde(default = "default_token_uri")]
+        token_uri: String,
+    },
+    AuthorizedUser {
+        client_id: String,
+        client_secret: String,
+        refresh_token: String,
+    },
+}
+
+fn default_token_uri() -> String {
+    TOKEN_ENDPOINT.to_string()
+}
+
+/// Find and parse the ADC file: `explicit_path` if given, otherwise
+/// `GOOGLE_APPLICATION_CREDENTIALS` if set, otherwise the well-known path
+/// under the user's gcloud config directory.
+fn load_adc_file(explicit_path: Option<&Path>) -> Result<AdcFile> {
+    let path = match explicit_path {
+        Some(path) => path.to_path_buf(),
+        None => match std::env::var_os("GOOGLE_APPLICATION_CREDENTIALS") {
+            Some(path) => PathBuf::from(path),
+            None => well_known_adc_path()?,
+        },
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("reading Application Default Credentials from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("parsing Application Default Credentials at {}", path.display()))
+}
+
+fn well_known_adc_path() -> Result<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        dirs_next_config_dir()
+    };
+    let base = base.ok_or_else(|| {
+        anyhow::anyhow!(
+            "no GOOGLE_APPLICATION_CREDENTIALS and could not determine the gcloud config directory"
+        )
+    })?;
+    Ok(base.join("gcloud").join("application_default_credentials.json"))
+}
+
+/// Minimal stand-in for `dirs::config_dir()` on Unix: `$XDG_CONFIG_HOME`,
+/// falling back to `~/.config`.
+fn dirs_next_config_dir() -> Option<PathBuf> {
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(xdg));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Mints and caches Vertex AI bearer tokens from Application Default
+/// Credentials, refreshing them once they're within [`EXPIRY_SLACK`] of
+/// expiring.
+pub struct AdcTokenProvider {
+    credentials: AdcFile,
+    http: reqwest::Client,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl AdcTokenProvider {
+    /// Locate and parse ADC from the environment. Does not make a network
+    /// call yet; the first [`Self::access_token`] call does that.
+    pub fn from_env() -> Result<Self> {
+        Self::from_path(None)
+    }
+
+    /// Like [`Self::from_env`], but reads ADC from `adc_file` instead of
+    /// `GOOGLE_APPLICATION_CREDENTIALS`/the well-known gcloud path, for
+    /// callers that keep the credentials file somewhere else.
+    pub fn from_adc_file(adc_file: PathBuf) -> Result<Self> {
+        Self::from_path(Some(&adc_file))
+    }
+
+    fn from_path(explicit_path: Option<&Path>) -> Result<Self> {
+        Ok(Self {
+            credentials: load_adc_file(explicit_path)?,
+            http: reqwest::Client::new(),
+            cached: Mutex::new(None),
+        })
+    }
+
+    /// Return a valid bearer token, minting or refreshing one if the
+    /// cached token is missing or close to expiry.
+    pub async fn access_token(&self) -> Result<String> {
+        if let Some(cached) = self.cached.lock().unwrap().as_ref() {
+            if cached.expires_at > Instant::now() {
+                return Ok(cached.access_token.clone());
+            }
+        }
+
+        let token = match &self.credentials {
+            AdcFile::ServiceAccount { .. } => self.mint_service_account_token().await?,
+            AdcFile::AuthorizedUser { .. } => self.refresh_authorized_user_token().await?,
+        };
+
+        let access_token = token.access_token.clone();
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in).saturating_sub(EXPIRY_SLACK);
+        *self.cached.lock().unwrap() = Some(CachedToken { access_token: access_token.clone(), expires_at });
+
+        Ok(access_token)
+    }
+
+    /// Self-signed JWT-bearer flow (RFC 7523): sign a short-lived JWT with
+    /// the service account's private key and exchange it for an access
+    /// token, rather than a full three-legged OAuth dance.
+    async fn mint_service_account_token(&self) -> Result<TokenResponse> {
+        let AdcFile::ServiceAccount { client_email, private_key, token_uri } = &self.credentials else {
+            unreachable!("caller matched on ServiceAccount");
+        };
+
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        let claims = json!({
+            "iss": client_email,
+            "scope": CLOUD_PLATFORM_SCOPE,
+            "aud": token_uri,
+            "iat": now,
+            "exp": now + 3600,
+        });
+
+        let key = EncodingKey::from_rsa_pem(private_key.as_bytes())
+            .context("parsing service account private key")?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .context("signing service account JWT")?;
+
+        let response = self
+            .http
+            .post(token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", &assertion),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("token exchange failed with status {status}: {}", response.text().await?);
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Refresh-token flow for `gcloud auth application-default login`
+    /// credentials.
+    async fn refresh_authorized_user_token(&self) -> Result<TokenResponse> {
+        let AdcFile::AuthorizedUser { client_id, client_secret, refresh_token } = &self.credentials else {
+            unreachable!("caller matched on AuthorizedUser");
+        };
+
+        let response = self
+            .http
+            .post(TOKEN_ENDPOINT)
+            .form(&[
+                ("client_id", client_id.as_str()),
+                ("client_secret", client_secret.as_str()),
+                ("refresh_token", refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("token refresh failed with status {status}: {}", response.text().await?);
+        }
+
+        Ok(response.json().await?)
+    }
+}
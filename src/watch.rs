@@ -0,0 +1,158 @@
+//! `/watch` mode: re-run a prompt whenever files in the workspace change.
+//!
+//! Built around a debounced filesystem watcher so a burst of saves (editor
+//! autosave, `cargo fmt`, etc.) triggers a single re-run rather than one per
+//! event. Watched roots are resolved once, against the REPL's *initial*
+//! working directory, so a tool call that changes the cwd mid-session can't
+//! pull the watcher out from under itself or re-target paths typed at the
+//! `/watch` prompt.
+
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to wait for more filesystem events before treating a burst of
+/// changes as a single trigger.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A running `/watch` session over a fixed set of root paths.
+pub struct WatchSession {
+    roots: Vec<PathBuf>,
+    _watcher: RecommendedWatcher,
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+}
+
+impl WatchSession {
+    /// Start watching `roots` (each resolved once, up front) for changes.
+    pub fn start(roots: Vec<PathBuf>) -> Result<Self> {
+        let (tx, rx) = channel();
+        let mut watcher = notify::recommended_watcher(tx).context("creating file watcher")?;
+        for root in &roots {
+            watcher
+                .watch(root, RecursiveMode::Recursive)
+                .with_context(|| format!("watching {}", root.display()))?;
+        }
+
+        Ok(Self {
+            roots,
+            _watcher: watcher,
+            events: rx,
+        })
+    }
+
+    /// Block until a debounced batch of changes has settled, then return.
+    /// Returns `Ok(false)` if the watcher channel was closed (the session
+    /// should stop).
+    pub fn wait_for_change(&self) -> Result<bool> {
+        // Block for the first event indefinitely.
+        match self.events.recv() {
+            Ok(_) => {}
+            Err(_) => return Ok(false),
+        }
+
+        // Then drain any further events for DEBOUNCE, coalescing a burst
+        // (e.g. editor save + format-on-save) into one trigger.
+        let deadline = Instant::now() + DEBOUNCE;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match self.events.recv_timeout(remaining) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(false),
+            }
+        }
+
+        Ok(true)
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Like [`wait_for_change`](Self::wait_for_change), but keeps waiting
+    /// through debounced batches that don't contain any path matching
+    /// `is_relevant` (e.g. a `cargo build` run only touching `target/`),
+    /// only returning once a batch has at least one relevant path.
+    pub fn wait_for_relevant_change(&self, is_relevant: impl Fn(&Path) -> bool) -> Result<bool> {
+        loop {
+            let Ok(first) = self.events.recv() else {
+                return Ok(false);
+            };
+            let mut relevant = event_is_relevant(&first, &is_relevant);
+
+            let deadline = Instant::now() + DEBOUNCE;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                match self.events.recv_timeout(remaining) {
+                    Ok(event) => relevant |= event_is_relevant(&event, &is_relevant),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return Ok(false),
+                }
+            }
+
+            if relevant {
+                return Ok(true);
+            }
+            // The whole debounced batch was irrelevant; keep waiting.
+        }
+    }
+}
+
+fn event_is_relevant(event: &notify::Result<notify::Event>, is_relevant: &impl Fn(&Path) -> bool) -> bool {
+    match event {
+        Ok(event) => event.paths.iter().any(|p| is_relevant(p)),
+        Err(_) => false,
+    }
+}
+
+/// Re-run `action` every time one of `roots` changes, until `action` returns
+/// `Ok(false)` (requesting the watch loop stop) or the watcher errors.
+///
+/// On each change, any in-flight invocation should already have been
+/// cancelled by the caller (e.g. by dropping the request future) before the
+/// next call to `action` is issued.
+pub async fn run_watch_loop<F, Fut>(roots: Vec<PathBuf>, mut action: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<bool>>,
+{
+    let session = WatchSession::start(roots)?;
+
+    // Fire once immediately so `/watch <prompt>` shows a result right away.
+    if !action().await? {
+        return Ok(());
+    }
+
+    loop {
+        let changed = tokio::task::block_in_place(|| session.wait_for_change())?;
+        if !changed {
+            return Ok(());
+        }
+        if !action().await? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn watch_session_resolves_roots_once() {
+        let dir = std::env::temp_dir().join(format!("gemini-repl-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let session = WatchSession::start(vec![dir.clone()]).unwrap();
+        assert_eq!(session.roots(), &[dir.clone()]);
+        fs::remove_dir_all(&dir).ok();
+    }
+}